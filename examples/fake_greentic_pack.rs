@@ -0,0 +1,15 @@
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    // `doctor <pack>`: prints `<pack>.doctor.json` when present, and fails with
+    // the contents of `<pack>.fail` on stderr when that exists.
+    let Some(pack) = args.get(2) else {
+        std::process::exit(2);
+    };
+    if let Ok(report) = std::fs::read_to_string(format!("{pack}.doctor.json")) {
+        println!("{report}");
+    }
+    if let Ok(reason) = std::fs::read_to_string(format!("{pack}.fail")) {
+        eprintln!("{}", reason.trim());
+        std::process::exit(1);
+    }
+}
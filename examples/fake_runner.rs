@@ -1,10 +1,21 @@
 use std::io::{self, Write};
 
 fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
     let mut stdout = io::stdout();
-    println!(
-        "{{\"status\":\"ok\",\"argv\":{:?}}}",
-        std::env::args().collect::<Vec<_>>()
-    );
+    println!("{{\"status\":\"ok\",\"argv\":{:?}}}", args);
     let _ = stdout.flush();
+    // A `<pack>.fail` file listing flow names makes those flows exit non-zero.
+    let arg_after = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|index| args.get(index + 1))
+    };
+    if let (Some(pack), Some(flow)) = (arg_after("--pack"), arg_after("--flow"))
+        && let Ok(failing) = std::fs::read_to_string(format!("{pack}.fail"))
+        && failing.lines().any(|line| line.trim() == flow)
+    {
+        eprintln!("fake_runner: flow {flow} failed");
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,144 @@
+//! Minimal in-memory Vault KV v2 server for the http secrets backend tests.
+//!
+//! Prints `fake_vault_kv listening on http://127.0.0.1:<port>` once ready.
+//! Accepts the token in `FAKE_VAULT_TOKEN` (default `root`) and an AppRole
+//! login for `FAKE_VAULT_ROLE_ID`/`FAKE_VAULT_SECRET_ID`. `GET /_fake/stats`
+//! reports how many KV reads were served.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+use serde_json::{Value, json};
+
+#[derive(Default)]
+struct State {
+    secrets: HashMap<String, Value>,
+    tokens: Vec<String>,
+    reads: u64,
+}
+
+fn main() -> io::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let root = std::env::var("FAKE_VAULT_TOKEN").unwrap_or_else(|_| "root".to_string());
+    let state = Arc::new(Mutex::new(State {
+        tokens: vec![root],
+        ..State::default()
+    }));
+    println!(
+        "fake_vault_kv listening on http://{}",
+        listener.local_addr()?
+    );
+    io::stdout().flush()?;
+    for stream in listener.incoming() {
+        let state = state.clone();
+        std::thread::spawn(move || {
+            if let Ok(stream) = stream {
+                let _ = handle(stream, &state);
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle(mut stream: TcpStream, state: &Mutex<State>) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+    let mut token = None;
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            match name.trim().to_ascii_lowercase().as_str() {
+                "x-vault-token" => token = Some(value.trim().to_string()),
+                "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    let body: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    let (status, response) = route(&method, &path, token.as_deref(), &body, state);
+    let payload = response.map(|value| value.to_string()).unwrap_or_default();
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{payload}",
+        payload.len()
+    )?;
+    stream.flush()
+}
+
+fn route(
+    method: &str,
+    path: &str,
+    token: Option<&str>,
+    body: &Value,
+    state: &Mutex<State>,
+) -> (&'static str, Option<Value>) {
+    let mut state = state.lock().unwrap();
+    if path == "/_fake/stats" {
+        return ("200 OK", Some(json!({ "reads": state.reads })));
+    }
+    if method == "POST" && path == "/v1/auth/approle/login" {
+        let expected = (
+            std::env::var("FAKE_VAULT_ROLE_ID").ok(),
+            std::env::var("FAKE_VAULT_SECRET_ID").ok(),
+        );
+        let given = (
+            body["role_id"].as_str().map(str::to_string),
+            body["secret_id"].as_str().map(str::to_string),
+        );
+        if expected.0.is_none() || given != expected {
+            return (
+                "400 Bad Request",
+                Some(json!({ "errors": ["invalid role or secret id"] })),
+            );
+        }
+        let token = format!("approle-{}", state.tokens.len());
+        state.tokens.push(token.clone());
+        return (
+            "200 OK",
+            Some(json!({ "auth": { "client_token": token, "lease_duration": 3600 } })),
+        );
+    }
+    if !token.is_some_and(|token| state.tokens.iter().any(|known| known == token)) {
+        return (
+            "403 Forbidden",
+            Some(json!({ "errors": ["permission denied"] })),
+        );
+    }
+    let Some(rest) = path.strip_prefix("/v1/secret/") else {
+        return ("404 Not Found", Some(json!({ "errors": [] })));
+    };
+    let (kind, key) = rest.split_once('/').unwrap_or((rest, ""));
+    match (method, kind) {
+        ("GET", "data") => {
+            state.reads += 1;
+            match state.secrets.get(key) {
+                Some(data) => (
+                    "200 OK",
+                    Some(json!({ "data": { "data": data, "metadata": { "version": 1 } } })),
+                ),
+                None => ("404 Not Found", Some(json!({ "errors": [] }))),
+            }
+        }
+        ("POST" | "PUT", "data") => {
+            state.secrets.insert(key.to_string(), body["data"].clone());
+            ("200 OK", Some(json!({ "data": { "version": 1 } })))
+        }
+        ("DELETE", "data" | "metadata") => match state.secrets.remove(key) {
+            Some(_) => ("204 No Content", None),
+            None => ("404 Not Found", Some(json!({ "errors": [] }))),
+        },
+        _ => ("405 Method Not Allowed", Some(json!({ "errors": [] }))),
+    }
+}
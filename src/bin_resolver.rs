@@ -59,7 +59,7 @@ pub fn resolve_binary(name: &str, ctx: &ResolveCtx) -> anyhow::Result<PathBuf> {
             message.push_str(&format!("\n  - {}", path.display()));
         }
         message.push_str(&format!(
-            "\nSuggestions:\n  - cargo build -p {repo}\n  - update dev.repo_map for {name}\n  - set binaries.{name} in greentic.yaml"
+            "\nSuggestions:\n  - cargo build -p {repo}\n  - greentic-operator dev up --build\n  - update dev.repo_map for {name}\n  - set binaries.{name} in greentic.yaml"
         ));
         return Err(anyhow::anyhow!(message));
     }
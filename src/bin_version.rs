@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use semver::{Prerelease, Version, VersionReq};
+use serde::Serialize;
+
+use crate::bin_resolver::{self, ResolveCtx};
+use crate::config::{self, OperatorConfig};
+use crate::dev_mode::DevSettingsResolved;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What `<binary> --version` printed.
+#[derive(Clone, Debug)]
+pub struct VersionProbe {
+    /// First non-empty line of the output.
+    pub line: String,
+    pub version: Option<Version>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BinaryCheckStatus {
+    Compatible,
+    Incompatible,
+    /// The binary ran but printed nothing that parses as a version.
+    Unknown,
+    NotFound,
+}
+
+/// One `binary_versions` entry from `greentic.yaml` checked against the
+/// binary the resolver picks.
+#[derive(Clone, Debug, Serialize)]
+pub struct BinaryCheck {
+    pub binary: String,
+    pub requirement: String,
+    pub path: Option<PathBuf>,
+    pub version: Option<String>,
+    pub status: BinaryCheckStatus,
+    pub message: String,
+}
+
+impl BinaryCheck {
+    /// Incompatible and unverifiable versions block service start; a missing
+    /// binary only matters once something tries to run it.
+    pub fn is_blocking(&self) -> bool {
+        matches!(
+            self.status,
+            BinaryCheckStatus::Incompatible | BinaryCheckStatus::Unknown
+        )
+    }
+}
+
+fn probe_cache() -> &'static Mutex<HashMap<PathBuf, VersionProbe>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, VersionProbe>>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// Runs `<path> --version` once per path for the lifetime of the process.
+pub fn probe(path: &Path) -> anyhow::Result<VersionProbe> {
+    let cache = probe_cache();
+    if let Some(probe) = cached_probe(path) {
+        return Ok(probe);
+    }
+    let output = run_version_command(path)?;
+    let line = output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+        .to_string();
+    let probe = VersionProbe {
+        version: parse_version(&output),
+        line,
+    };
+    cache
+        .lock()
+        .unwrap()
+        .insert(path.to_path_buf(), probe.clone());
+    Ok(probe)
+}
+
+/// The result of an earlier [`probe`] of `path`, without running anything.
+pub fn cached_probe(path: &Path) -> Option<VersionProbe> {
+    probe_cache().lock().unwrap().get(path).cloned()
+}
+
+fn run_version_command(path: &Path) -> anyhow::Result<String> {
+    let mut child = Command::new(path)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| anyhow::anyhow!("failed to run {} --version: {err}", path.display()))?;
+    let started = Instant::now();
+    while child.try_wait()?.is_none() {
+        if started.elapsed() > PROBE_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(anyhow::anyhow!(
+                "{} --version did not exit within {}s",
+                path.display(),
+                PROBE_TIMEOUT.as_secs()
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    // Some tools print their version on stderr.
+    let mut output = String::new();
+    if let Some(mut stdout) = child.stdout.take() {
+        stdout.read_to_string(&mut output)?;
+    }
+    if let Some(mut stderr) = child.stderr.take() {
+        stderr.read_to_string(&mut output)?;
+    }
+    Ok(output)
+}
+
+/// Finds the first `MAJOR.MINOR[.PATCH][-PRE]` in free-form version output,
+/// such as `cloudflared version 2024.1.5 (built ...)` or `nats-server: v2.10.1`.
+pub fn parse_version(output: &str) -> Option<Version> {
+    let pattern = regex::Regex::new(r"(\d+)\.(\d+)(?:\.(\d+))?(?:-([0-9A-Za-z.-]+))?").ok()?;
+    let captures = pattern.captures(output)?;
+    let number = |idx: usize| {
+        captures
+            .get(idx)
+            .map_or(Some(0), |value| value.as_str().parse::<u64>().ok())
+    };
+    let mut version = Version::new(number(1)?, number(2)?, number(3)?);
+    if let Some(pre) = captures.get(4) {
+        version.pre = Prerelease::new(pre.as_str()).unwrap_or(Prerelease::EMPTY);
+    }
+    Some(version)
+}
+
+/// Checks every `binary_versions` requirement in `greentic.yaml`, resolving
+/// each binary the same way the services that run it do.
+pub fn check_binaries(
+    config: Option<&OperatorConfig>,
+    config_dir: &Path,
+    dev: Option<&DevSettingsResolved>,
+) -> anyhow::Result<Vec<BinaryCheck>> {
+    let Some(config) = config else {
+        return Ok(Vec::new());
+    };
+    let mut checks = Vec::new();
+    for (binary, requirement) in &config.binary_versions {
+        let req = VersionReq::parse(requirement).map_err(|err| {
+            anyhow::anyhow!(
+                "invalid binary_versions.{binary} `{requirement}` in greentic.yaml: {err}"
+            )
+        })?;
+        let resolved = bin_resolver::resolve_binary(
+            binary,
+            &ResolveCtx {
+                config_dir: config_dir.to_path_buf(),
+                dev: dev.cloned(),
+                explicit_path: config::binary_override(Some(config), binary, config_dir),
+            },
+        );
+        let check = |path, version, status, message| BinaryCheck {
+            binary: binary.clone(),
+            requirement: requirement.clone(),
+            path,
+            version,
+            status,
+            message,
+        };
+        let path = match resolved {
+            Ok(path) => path,
+            Err(_) => {
+                checks.push(check(
+                    None,
+                    None,
+                    BinaryCheckStatus::NotFound,
+                    format!("{binary} not found"),
+                ));
+                continue;
+            }
+        };
+        let probed = match probe(&path) {
+            Ok(probed) => probed,
+            Err(err) => {
+                checks.push(check(
+                    Some(path),
+                    None,
+                    BinaryCheckStatus::Unknown,
+                    format!("{err:#}"),
+                ));
+                continue;
+            }
+        };
+        checks.push(match probed.version {
+            Some(version) if req.matches(&version) => check(
+                Some(path),
+                Some(version.to_string()),
+                BinaryCheckStatus::Compatible,
+                format!("{binary} {version} satisfies {requirement}"),
+            ),
+            Some(version) => check(
+                Some(path.clone()),
+                Some(version.to_string()),
+                BinaryCheckStatus::Incompatible,
+                format!(
+                    "{binary} {version} at {} does not satisfy {requirement}",
+                    path.display()
+                ),
+            ),
+            None => check(
+                Some(path.clone()),
+                None,
+                BinaryCheckStatus::Unknown,
+                format!(
+                    "no version in `{} --version` output: {:?}",
+                    path.display(),
+                    probed.line
+                ),
+            ),
+        });
+    }
+    Ok(checks)
+}
+
+/// One line per check, as printed by `demo doctor` and the text doctor report.
+pub fn render_checks(checks: &[BinaryCheck]) -> String {
+    let mut out = String::new();
+    for check in checks {
+        let status = match check.status {
+            BinaryCheckStatus::Compatible => "ok",
+            BinaryCheckStatus::NotFound => "warning",
+            BinaryCheckStatus::Incompatible | BinaryCheckStatus::Unknown => "FAILED",
+        };
+        out.push_str(&format!("[{status}] binary {}\n", check.message));
+    }
+    out
+}
+
+/// Refuses to go on when a declared binary has an incompatible or unknown
+/// version, unless `allow_incompatible` is set.
+pub fn ensure_compatible(checks: &[BinaryCheck], allow_incompatible: bool) -> anyhow::Result<()> {
+    let blocking = checks
+        .iter()
+        .filter(|check| check.is_blocking())
+        .collect::<Vec<_>>();
+    if blocking.is_empty() {
+        return Ok(());
+    }
+    if allow_incompatible {
+        for check in blocking {
+            eprintln!("Warning: {} (--allow-incompatible-binaries)", check.message);
+        }
+        return Ok(());
+    }
+    let mut message = String::from("binary version requirements not met:");
+    for check in blocking {
+        message.push_str(&format!("\n  - {}", check.message));
+    }
+    message.push_str(
+        "\nupdate the binaries, adjust binary_versions in greentic.yaml, or pass --allow-incompatible-binaries",
+    );
+    Err(anyhow::anyhow!(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_versions_from_tool_output() {
+        let cases = [
+            (
+                "cloudflared version 2024.1.5 (built 2024-01-22)",
+                "2024.1.5",
+            ),
+            ("nats-server: v2.10.1", "2.10.1"),
+            ("greentic-runner 0.4.26-rc.1\n", "0.4.26-rc.1"),
+            ("tool 1.2", "1.2.0"),
+        ];
+        for (output, expected) in cases {
+            assert_eq!(
+                parse_version(output).map(|version| version.to_string()),
+                Some(expected.to_string()),
+                "{output}"
+            );
+        }
+        assert_eq!(parse_version("no version here"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runner_flavor_uses_only_earlier_probes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        use crate::runner_integration::{RunnerFlavor, detect_runner_flavor};
+
+        let temp = tempfile::tempdir().unwrap();
+        let runner = temp.path().join("runner-wrapper");
+        let calls = temp.path().join("calls");
+        std::fs::write(
+            &runner,
+            format!(
+                "#!/bin/sh\necho probed >> {}\necho greentic-runner-cli 0.5.0\n",
+                calls.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&runner, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert_eq!(detect_runner_flavor(&runner), RunnerFlavor::RunSubcommand);
+        assert!(!calls.exists());
+
+        probe(&runner).unwrap();
+        assert_eq!(detect_runner_flavor(&runner), RunnerFlavor::RunnerCli);
+        assert_eq!(std::fs::read_to_string(&calls).unwrap(), "probed\n");
+    }
+}
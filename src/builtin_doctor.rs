@@ -0,0 +1,311 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use greentic_types::validate::{Diagnostic, Severity, ValidationReport};
+use serde_json::Value as JsonValue;
+
+use crate::component_qa_ops;
+use crate::demo::timer_scheduler;
+use crate::domains;
+use crate::secret_requirements;
+use crate::setup_input::{self, SetupSpec};
+
+const SETUP_QUESTION_KINDS: &[&str] = &["string", "number", "choice", "boolean"];
+
+/// Validates a pack in-process, without the external `greentic-pack` binary.
+///
+/// Produces the same [`ValidationReport`] shape as `greentic-pack doctor`.
+/// Covers the manifest encoding, entry flows, secret requirements,
+/// `setup.yaml`, timer extensions and the component QA contract.
+pub fn validate_pack(pack_path: &Path) -> ValidationReport {
+    let mut diagnostics = Vec::new();
+    let pack_id = domains::read_pack_meta(pack_path)
+        .ok()
+        .map(|meta| meta.pack_id);
+
+    match domains::manifest_cbor_issue_detail(pack_path) {
+        Ok(None) => {
+            check_flows(pack_path, &mut diagnostics);
+            check_timers(pack_path, &mut diagnostics);
+            check_qa_contract(pack_path, &mut diagnostics);
+        }
+        Ok(Some(detail)) => diagnostics.push(error(
+            "OP_MANIFEST_CBOR_INVALID",
+            format!("manifest.cbor is malformed: {detail}"),
+            Some("manifest.cbor"),
+        )),
+        Err(err) => diagnostics.push(error(
+            "OP_PACK_ARCHIVE_INVALID",
+            format!("pack is not a readable archive: {err}"),
+            None,
+        )),
+    }
+    check_secret_requirements(pack_path, &mut diagnostics);
+    check_setup_spec(pack_path, &mut diagnostics);
+
+    ValidationReport {
+        pack_id: pack_id.and_then(|id| id.parse().ok()),
+        pack_version: None,
+        diagnostics,
+    }
+}
+
+fn check_flows(pack_path: &Path, diagnostics: &mut Vec<Diagnostic>) {
+    let flows = match domains::read_manifest_flows(pack_path) {
+        Ok(flows) => flows,
+        Err(err) => {
+            diagnostics.push(error(
+                "OP_MANIFEST_CBOR_INVALID",
+                format!("manifest could not be decoded: {err:#}"),
+                Some("manifest.cbor"),
+            ));
+            return;
+        }
+    };
+    if flows.entry_flows.is_empty() && flows.declared.is_empty() {
+        diagnostics.push(warn(
+            "OP_ENTRY_FLOWS_MISSING",
+            "manifest declares no flows or entry flows".to_string(),
+            Some("meta.entry_flows"),
+        ));
+        return;
+    }
+    if flows.declared.is_empty() {
+        return;
+    }
+    for (idx, flow) in flows.entry_flows.iter().enumerate() {
+        if !flows.declared.contains(flow) {
+            diagnostics.push(error(
+                "OP_ENTRY_FLOW_UNDECLARED",
+                format!("entry flow `{flow}` is not declared in the manifest flows"),
+                Some(&format!("meta.entry_flows[{idx}]")),
+            ));
+        }
+    }
+}
+
+fn check_secret_requirements(pack_path: &Path, diagnostics: &mut Vec<Diagnostic>) {
+    let keys = match secret_requirements::load_declared_secret_keys(pack_path) {
+        Ok(keys) => keys,
+        Err(err) => {
+            if err.downcast_ref::<zip::result::ZipError>().is_none() {
+                diagnostics.push(error(
+                    "OP_SECRET_REQUIREMENTS_INVALID",
+                    format!("secret requirements could not be read: {err:#}"),
+                    Some("secret_requirements"),
+                ));
+            }
+            return;
+        }
+    };
+    let mut seen = BTreeSet::new();
+    for (idx, key) in keys.iter().enumerate() {
+        if key.trim().is_empty() {
+            diagnostics.push(error(
+                "OP_SECRET_REQUIREMENT_INVALID",
+                "secret requirement has an empty key".to_string(),
+                Some(&format!("secret_requirements[{idx}].key")),
+            ));
+        } else if !seen.insert(key.as_str()) {
+            diagnostics.push(warn(
+                "OP_SECRET_REQUIREMENT_DUPLICATE",
+                format!("secret `{key}` is declared more than once"),
+                Some(&format!("secret_requirements[{idx}].key")),
+            ));
+        }
+    }
+}
+
+fn check_setup_spec(pack_path: &Path, diagnostics: &mut Vec<Diagnostic>) {
+    let spec = match setup_input::load_setup_spec(pack_path) {
+        Ok(Some(spec)) => spec,
+        Ok(None) => return,
+        Err(err) => {
+            diagnostics.push(error(
+                "OP_SETUP_SPEC_INVALID",
+                format!("{err:#}"),
+                Some("assets/setup.yaml"),
+            ));
+            return;
+        }
+    };
+    diagnostics.extend(setup_spec_diagnostics(&spec));
+}
+
+fn setup_spec_diagnostics(spec: &SetupSpec) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut earlier = BTreeSet::new();
+    for (idx, question) in spec.questions.iter().enumerate() {
+        let path = |field: &str| format!("questions[{idx}]{field}");
+        if question.name.trim().is_empty() {
+            diagnostics.push(error(
+                "OP_SETUP_QUESTION_INVALID",
+                "setup question has no name".to_string(),
+                Some(&path(".name")),
+            ));
+        } else if earlier.contains(question.name.as_str()) {
+            diagnostics.push(error(
+                "OP_SETUP_QUESTION_INVALID",
+                format!(
+                    "setup question `{}` is declared more than once",
+                    question.name
+                ),
+                Some(&path(".name")),
+            ));
+        }
+        let kind = question.kind.to_lowercase();
+        if !SETUP_QUESTION_KINDS.contains(&kind.as_str()) {
+            diagnostics.push(warn(
+                "OP_SETUP_QUESTION_KIND_UNKNOWN",
+                format!(
+                    "unknown kind `{}`; answers are read as strings (expected one of {})",
+                    question.kind,
+                    SETUP_QUESTION_KINDS.join(", ")
+                ),
+                Some(&path(".kind")),
+            ));
+        }
+        let has_enum = question
+            .validate
+            .as_ref()
+            .is_some_and(|rules| !rules.allowed.is_empty());
+        if kind == "choice" && question.choices.is_empty() && !has_enum {
+            diagnostics.push(warn(
+                "OP_SETUP_QUESTION_INVALID",
+                format!("choice question `{}` lists no choices", question.name),
+                Some(&path(".choices")),
+            ));
+        }
+        if let Some(pattern) = question
+            .validate
+            .as_ref()
+            .and_then(|rules| rules.regex.as_deref())
+            && let Err(err) = regex::Regex::new(pattern)
+        {
+            diagnostics.push(error(
+                "OP_SETUP_QUESTION_INVALID",
+                format!("invalid regex for `{}`: {err}", question.name),
+                Some(&path(".validate.regex")),
+            ));
+        } else if let Some(default) = question.default.as_ref()
+            && let Err(err) = question.validate(default)
+        {
+            diagnostics.push(warn(
+                "OP_SETUP_DEFAULT_INVALID",
+                format!("default for `{}` fails validation: {err}", question.name),
+                Some(&path(".default")),
+            ));
+        }
+        if let Some(condition) = question.when.as_ref()
+            && !earlier.contains(condition.question.as_str())
+        {
+            diagnostics.push(error(
+                "OP_SETUP_QUESTION_INVALID",
+                format!(
+                    "`{}` depends on `{}`, which is not an earlier question",
+                    question.name, condition.question
+                ),
+                Some(&path(".when.question")),
+            ));
+        }
+        earlier.insert(question.name.as_str());
+    }
+    diagnostics
+}
+
+fn check_timers(pack_path: &Path, diagnostics: &mut Vec<Diagnostic>) {
+    let Ok(manifest) = read_manifest_json(pack_path) else {
+        return;
+    };
+    for issue in timer_scheduler::timer_handler_issues(&manifest) {
+        diagnostics.push(error(
+            "OP_TIMER_HANDLER_INVALID",
+            issue.message,
+            Some(&issue.path),
+        ));
+    }
+}
+
+fn check_qa_contract(pack_path: &Path, diagnostics: &mut Vec<Diagnostic>) {
+    let Ok(providers) = component_qa_ops::qa_contract_gaps(pack_path) else {
+        return;
+    };
+    for (provider, missing) in providers {
+        diagnostics.push(error(
+            "OP_QA_CONTRACT_INCOMPLETE",
+            format!(
+                "provider `{provider}` implements part of the component QA contract but lacks {}",
+                missing.join(", ")
+            ),
+            Some(&format!("extensions.providers.{provider}.ops")),
+        ));
+    }
+}
+
+fn read_manifest_json(pack_path: &Path) -> anyhow::Result<JsonValue> {
+    let file = std::fs::File::open(pack_path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
+    let mut manifest = archive.by_name("manifest.cbor")?;
+    let mut bytes = Vec::new();
+    std::io::Read::read_to_end(&mut manifest, &mut bytes)?;
+    Ok(serde_cbor::from_slice(&bytes)?)
+}
+
+fn error(code: &str, message: String, path: Option<&str>) -> Diagnostic {
+    diagnostic(Severity::Error, code, message, path)
+}
+
+fn warn(code: &str, message: String, path: Option<&str>) -> Diagnostic {
+    diagnostic(Severity::Warn, code, message, path)
+}
+
+fn diagnostic(severity: Severity, code: &str, message: String, path: Option<&str>) -> Diagnostic {
+    Diagnostic {
+        severity,
+        code: code.to_string(),
+        message,
+        path: path.map(str::to_string),
+        hint: None,
+        data: JsonValue::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn setup_spec_checks_names_conditions_and_regexes() {
+        let spec: SetupSpec = serde_yaml_bw::from_str(
+            r#"
+questions:
+  - name: mode
+    kind: choice
+  - name: token
+    when: { question: later }
+    validate: { regex: "([a-z" }
+  - name: port
+    kind: number
+    default: 99999
+    validate: { max: 65535 }
+  - name: mode
+    kind: colour
+"#,
+        )
+        .unwrap();
+        let found = setup_spec_diagnostics(&spec)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.code, diagnostic.path.unwrap_or_default()))
+            .collect::<Vec<_>>();
+        let expected = [
+            ("OP_SETUP_QUESTION_INVALID", "questions[0].choices"),
+            ("OP_SETUP_QUESTION_INVALID", "questions[1].validate.regex"),
+            ("OP_SETUP_QUESTION_INVALID", "questions[1].when.question"),
+            ("OP_SETUP_DEFAULT_INVALID", "questions[2].default"),
+            ("OP_SETUP_QUESTION_INVALID", "questions[3].name"),
+            ("OP_SETUP_QUESTION_KIND_UNKNOWN", "questions[3].kind"),
+        ]
+        .map(|(code, path)| (code.to_string(), path.to_string()));
+        assert_eq!(found, expected);
+    }
+}
@@ -16,12 +16,14 @@ use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 use tokio::runtime::Runtime;
 
 use crate::bin_resolver::{self, ResolveCtx};
+use crate::bin_version;
 use crate::config;
 use crate::config_gate::{self, ConfigGateItem, ConfigValueSource};
 use crate::demo::{
     self, BuildOptions, DemoRepl, DemoRunner,
     card::{detect_adaptive_card_view, print_card_summary},
     http_ingress::{HttpIngressConfig, HttpIngressServer},
+    ingress_access::IngressAccessPolicy,
     input as demo_input, pack_resolve,
     runner_host::{DemoRunnerHost, FlowOutcome, OperatorContext, primary_provider_type},
     setup::{ProvidersInput, discover_tenants},
@@ -43,30 +45,42 @@ use crate::operator_log;
 use crate::project::{self, ScanFormat};
 use crate::runner_exec;
 use crate::runner_integration;
-use crate::runtime_state::RuntimePaths;
+use crate::runtime_state::{self, RuntimePaths};
+use crate::secret_rotation;
+use crate::secrets_admin::SecretsScope;
 use crate::secrets_gate::{self, DynSecretsManager, SecretsManagerHandle};
 use crate::secrets_manager;
 use crate::secrets_setup::resolve_env;
 use crate::settings;
-use crate::setup_input::{SetupInputAnswers, collect_setup_answers, load_setup_input};
+use crate::setup_input::{
+    SecretAnswerStore, SetupInputAnswers, check_provider_answers, collect_setup_answers,
+    export_provider_answers, load_setup_input, store_secret_answers,
+};
 use crate::state_layout;
 use crate::subscriptions_universal::{
-    build_runner,
-    scheduler::Scheduler,
+    build_runner, reconcile_desired_subscriptions,
+    scheduler::{RenewalLoop, Scheduler},
     service::{SubscriptionEnsureRequest, SubscriptionService},
     state_root,
     store::{AuthUserRefV1, SubscriptionStore},
 };
+use crate::supervisor;
 use greentic_runner_host::secrets::default_manager;
 use greentic_types::{ChannelMessageEnvelope, Destination, EnvId, TeamId, TenantCtx, TenantId};
 use std::time::Duration;
 use uuid::Uuid;
 
+mod config_cmd;
+mod dev_config_cmd;
 mod dev_mode_cmd;
+mod secrets_cmd;
 
+use config_cmd::DemoConfigCommand;
+use dev_config_cmd::DevConfigCommand;
 use dev_mode_cmd::{
     DevModeDetectArgs, DevModeMapCommand, DevModeOffArgs, DevModeOnArgs, DevModeStatusArgs,
 };
+use secrets_cmd::DemoSecretsCommand;
 #[derive(Parser)]
 #[command(name = "greentic-operator")]
 #[command(about = "Greentic operator tooling", version)]
@@ -122,6 +136,7 @@ enum DevSubcommand {
     Diagnostics(DomainDiagnosticsArgs),
     Verify(DomainVerifyArgs),
     Doctor(DevDoctorArgs),
+    Config(DevConfigCommand),
 }
 
 #[derive(Subcommand)]
@@ -144,6 +159,10 @@ enum DemoSubcommand {
     Forbid(DemoPolicyArgs),
     #[command(about = "Manage demo subscriptions via provider components")]
     Subscriptions(DemoSubscriptionsCommand),
+    #[command(about = "Manage bundle secrets through the configured backend")]
+    Secrets(DemoSecretsCommand),
+    #[command(about = "Inspect and roll back provider config envelopes")]
+    Config(DemoConfigCommand),
     #[command(about = "Run a pack/flow with inline input")]
     Run(DemoRunArgs),
     #[command(about = "List resolved packs from a bundle")]
@@ -292,8 +311,8 @@ struct TeamListArgs {
 #[derive(Parser)]
 #[command(
     about = "Start local messaging services (and NATS unless disabled).",
-    long_about = "Uses state/resolved/<tenant>[.<team>].yaml and launches greentic-messaging and optional NATS.",
-    after_help = "Main options:\n  --tenant <TENANT>\n\nOptional options:\n  --team <TEAM>\n  --no-nats\n  --nats-url <URL>\n  --project-root <PATH> (default: current directory)\n  --dev-mode <auto|on|off>\n  --dev-root <PATH>\n  --dev-profile <debug|release>\n  --dev-target-dir <PATH>"
+    long_about = "Uses state/resolved/<tenant>[.<team>].yaml and launches greentic-messaging and optional NATS. With --build, first runs cargo build for dev.repo_map repos whose binaries are older than their sources.",
+    after_help = "Main options:\n  --tenant <TENANT>\n\nOptional options:\n  --team <TEAM>\n  --no-nats\n  --nats-url <URL>\n  --build\n  --allow-incompatible-binaries\n  --project-root <PATH> (default: current directory)\n  --dev-mode <auto|on|off>\n  --dev-root <PATH>\n  --dev-profile <debug|release>\n  --dev-target-dir <PATH>"
 )]
 struct DevUpArgs {
     #[arg(long)]
@@ -305,6 +324,10 @@ struct DevUpArgs {
     #[arg(long)]
     nats_url: Option<String>,
     #[arg(long)]
+    build: bool,
+    #[arg(long)]
+    allow_incompatible_binaries: bool,
+    #[arg(long)]
     project_root: Option<PathBuf>,
     #[command(flatten)]
     dev: DevModeArgs,
@@ -475,7 +498,7 @@ enum DoctorDomainArg {
     All,
 }
 
-#[derive(Clone, Copy, Debug, ValueEnum)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
 enum PlanFormat {
     Text,
     Json,
@@ -485,8 +508,8 @@ enum PlanFormat {
 #[derive(Parser)]
 #[command(
     about = "Run domain doctor validation.",
-    long_about = "Executes greentic-pack doctor with optional validators.",
-    after_help = "Main options:\n  <DOMAIN>\n\nOptional options:\n  --tenant <TENANT>\n  --team <TEAM>\n  --strict\n  --validator-pack <PATH>...\n  --project-root <PATH> (default: current directory)\n  --dev-mode <auto|on|off>\n  --dev-root <PATH>\n  --dev-profile <debug|release>\n  --dev-target-dir <PATH>"
+    long_about = "Executes greentic-pack doctor with optional validators and reports every pack with its status, duration and diagnostics. The report can be emitted as JSON, JUnit XML or SARIF for CI dashboards and code scanning.",
+    after_help = "Main options:\n  <DOMAIN>\n\nOptional options:\n  --tenant <TENANT>\n  --team <TEAM>\n  --strict\n  --validator-pack <PATH>...\n  --builtin\n  --format <text|json|junit|sarif> (default: text)\n  --output <PATH> (default: stdout)\n  --project-root <PATH> (default: current directory)\n  --dev-mode <auto|on|off>\n  --dev-root <PATH>\n  --dev-profile <debug|release>\n  --dev-target-dir <PATH>"
 )]
 struct DevDoctorArgs {
     domain: DoctorDomainArg,
@@ -499,11 +522,36 @@ struct DevDoctorArgs {
     #[arg(long)]
     validator_pack: Vec<PathBuf>,
     #[arg(long)]
+    builtin: bool,
+    #[arg(long, value_enum, default_value_t = DoctorFormatArg::Text)]
+    format: DoctorFormatArg,
+    #[arg(long)]
+    output: Option<PathBuf>,
+    #[arg(long)]
     project_root: Option<PathBuf>,
     #[command(flatten)]
     dev: DevModeArgs,
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum DoctorFormatArg {
+    Text,
+    Json,
+    Junit,
+    Sarif,
+}
+
+impl From<DoctorFormatArg> for crate::doctor_report::DoctorReportFormat {
+    fn from(value: DoctorFormatArg) -> Self {
+        match value {
+            DoctorFormatArg::Text => Self::Text,
+            DoctorFormatArg::Json => Self::Json,
+            DoctorFormatArg::Junit => Self::Junit,
+            DoctorFormatArg::Sarif => Self::Sarif,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, ValueEnum)]
 enum LogService {
     Messaging,
@@ -530,7 +578,7 @@ enum RestartTarget {
 #[command(
     about = "Build a portable demo bundle.",
     long_about = "Copies packs/providers/tenants and writes resolved manifests under the output directory.",
-    after_help = "Main options:\n  --out <DIR>\n\nOptional options:\n  --tenant <TENANT>\n  --team <TEAM>\n  --allow-pack-dirs\n  --only-used-providers\n  --doctor\n  --skip-doctor\n  --project-root <PATH> (default: current directory)\n  --dev-mode <auto|on|off>\n  --dev-root <PATH>\n  --dev-profile <debug|release>\n  --dev-target-dir <PATH>"
+    after_help = "Main options:\n  --out <DIR>\n\nOptional options:\n  --tenant <TENANT>\n  --team <TEAM>\n  --allow-pack-dirs\n  --only-used-providers\n  --doctor\n  --skip-doctor\n  --builtin-doctor\n  --project-root <PATH> (default: current directory)\n  --dev-mode <auto|on|off>\n  --dev-root <PATH>\n  --dev-profile <debug|release>\n  --dev-target-dir <PATH>"
 )]
 struct DemoBuildArgs {
     #[arg(long)]
@@ -548,6 +596,8 @@ struct DemoBuildArgs {
     #[arg(long)]
     skip_doctor: bool,
     #[arg(long)]
+    builtin_doctor: bool,
+    #[arg(long)]
     project_root: Option<PathBuf>,
     #[command(flatten)]
     dev: DevModeArgs,
@@ -590,13 +640,13 @@ struct DemoUpArgs {
     #[arg(
         long,
         help_heading = "Optional options",
-        help = "Tenant to target when running the bundle (defaults to demo)."
+        help = "Tenant(s) to run from the bundle: one tenant, a comma-separated list, or `all` (defaults to every resolved tenant, or demo)."
     )]
     tenant: Option<String>,
     #[arg(
         long,
         help_heading = "Optional options",
-        help = "Team to assign when running demo services."
+        help = "Team to assign when running demo services; in bundle mode, limits the run to that team."
     )]
     team: Option<String>,
     #[arg(
@@ -634,12 +684,18 @@ struct DemoUpArgs {
         help = "Path to a prebuilt config file to use instead of auto-discovery."
     )]
     config: Option<PathBuf>,
-    #[arg(long, value_enum, default_value_t = CloudflaredModeArg::On, help_heading = "Optional options", help = "Whether to start cloudflared for webhook tunneling.")]
+    #[arg(
+        long,
+        help_heading = "Optional options",
+        help = "Merge the greentic.demo.<PROFILE>.yaml overlay (or <config>.<PROFILE>.yaml with --config) over the demo config."
+    )]
+    profile: Option<String>,
+    #[arg(long, value_enum, default_value_t = CloudflaredModeArg::On, help_heading = "Optional options", help = "Whether to start the webhook tunnel (services.tunnel.provider, cloudflared by default).")]
     cloudflared: CloudflaredModeArg,
     #[arg(
         long,
         help_heading = "Optional options",
-        help = "Explicit path to the cloudflared binary used when cloudflared mode is on."
+        help = "Explicit path to the tunnel binary (cloudflared, ngrok, ...) used when the tunnel is on."
     )]
     cloudflared_binary: Option<PathBuf>,
     #[arg(
@@ -669,6 +725,12 @@ struct DemoUpArgs {
         help = "Skip greentic-secrets init during setup."
     )]
     skip_secrets_init: bool,
+    #[arg(
+        long,
+        help_heading = "Optional options",
+        help = "Refuse to start when a provider still has placeholder secrets from setup."
+    )]
+    strict_secrets: bool,
     #[arg(
         long,
         help_heading = "Optional options",
@@ -681,6 +743,24 @@ struct DemoUpArgs {
         help = "Force re-run of setup flows even if records already exist."
     )]
     force_setup: bool,
+    #[arg(
+        long,
+        help_heading = "Optional options",
+        help = "List which providers would re-run setup, and why, then exit without starting services (config mode only)."
+    )]
+    dry_run: bool,
+    #[arg(
+        long,
+        help_heading = "Optional options",
+        help = "Reload packs, providers, tenant gmaps and greentic.demo.yaml (and its --profile overlay) on change, keeping the tunnel and NATS running (bundle mode only)."
+    )]
+    watch: bool,
+    #[arg(
+        long,
+        help_heading = "Optional options",
+        help = "Start even when a binary fails its binary_versions requirement in greentic.yaml."
+    )]
+    allow_incompatible_binaries: bool,
     #[arg(
         long,
         help_heading = "Optional options",
@@ -778,8 +858,8 @@ impl From<NatsModeArg> for demo::NatsMode {
 #[derive(Parser)]
 #[command(
     about = "Run provider setup flows against a demo bundle.",
-    long_about = "Executes setup flows for provider packs included in the bundle.",
-    after_help = "Main options:\n  --bundle <DIR>\n  --tenant <TENANT>\n\nOptional options:\n  --team <TEAM>\n  --domain <messaging|events|secrets|all> (default: all)\n  --provider <FILTER>\n  --dry-run\n  --format <text|json|yaml> (default: text)\n  --parallel <N> (default: 1)\n  --allow-missing-setup\n  --allow-contract-change\n  --backup\n  --online\n  --secrets-env <ENV>\n  --skip-secrets-init\n  --setup-input <PATH>\n  --runner-binary <PATH>\n  --best-effort"
+    long_about = "Executes setup flows for provider packs included in the bundle. With --parallel N up to N flows run at once; each flow's output is buffered and printed in plan order.",
+    after_help = "Main options:\n  --bundle <DIR>\n  --tenant <TENANT>\n\nOptional options:\n  --team <TEAM>\n  --domain <messaging|events|secrets|all> (default: all)\n  --provider <FILTER>\n  --dry-run\n  --format <text|json|yaml> (default: text)\n  --parallel <N> (default: 1)\n  --allow-missing-setup\n  --allow-contract-change\n  --backup\n  --online\n  --secrets-env <ENV>\n  --skip-secrets-init\n  --setup-input <PATH>\n  --export-answers <PATH>\n  --check-answers <PATH>\n  --runner-binary <PATH>\n  --best-effort"
 )]
 struct DemoSetupArgs {
     #[arg(long)]
@@ -817,6 +897,10 @@ struct DemoSetupArgs {
     #[arg(long)]
     setup_input: Option<PathBuf>,
     #[arg(long)]
+    export_answers: Option<PathBuf>,
+    #[arg(long, conflicts_with = "export_answers")]
+    check_answers: Option<PathBuf>,
+    #[arg(long)]
     best_effort: bool,
 }
 
@@ -838,14 +922,14 @@ struct DemoPolicyArgs {
 #[derive(Parser)]
 #[command(
     about = "Show demo service status using runtime state.",
-    long_about = "Lists pidfiles under state/pids for the selected tenant/team.",
-    after_help = "Main options:\n  (none)\n\nOptional options:\n  --tenant <TENANT> (default: demo)\n  --team <TEAM> (default: default)\n  --state-dir <PATH> (default: ./state or <bundle>/state)\n  --bundle <DIR> (legacy mode if --state-dir omitted)\n  --verbose\n  --no-nats"
+    long_about = "Lists pidfiles under state/pids for the selected tenant/team. Without --tenant, a running demo start --bundle is shown per tenant and team, including its in-process services.",
+    after_help = "Main options:\n  (none)\n\nOptional options:\n  --tenant <TENANT> (default: every running target, or demo)\n  --team <TEAM> (default: default)\n  --state-dir <PATH> (default: ./state or <bundle>/state)\n  --bundle <DIR> (legacy mode if --state-dir omitted)\n  --verbose\n  --no-nats"
 )]
 struct DemoStatusArgs {
     #[arg(long)]
     bundle: Option<PathBuf>,
-    #[arg(long, default_value = "demo")]
-    tenant: String,
+    #[arg(long)]
+    tenant: Option<String>,
     #[arg(long, default_value = "default")]
     team: String,
     #[arg(long)]
@@ -884,12 +968,16 @@ struct DemoLogsArgs {
 #[derive(Parser)]
 #[command(
     about = "Run demo doctor validation from a bundle.",
-    long_about = "Runs greentic-pack doctor against packs in the demo bundle.",
-    after_help = "Main options:\n  --bundle <DIR>\n\nOptional options:\n  --dev-mode <auto|on|off>\n  --dev-root <PATH>\n  --dev-profile <debug|release>\n  --dev-target-dir <PATH>"
+    long_about = "Runs greentic-pack doctor against packs in the demo bundle. With --builtin, validates packs in-process instead (manifest encoding, entry flows, secret requirements, setup.yaml, timer extensions and the component QA contract), so no greentic-pack binary is needed.",
+    after_help = "Main options:\n  --bundle <DIR>\n\nOptional options:\n  --builtin\n  --strict-secrets\n  --dev-mode <auto|on|off>\n  --dev-root <PATH>\n  --dev-profile <debug|release>\n  --dev-target-dir <PATH>"
 )]
 struct DemoDoctorArgs {
     #[arg(long)]
     bundle: PathBuf,
+    #[arg(long)]
+    builtin: bool,
+    #[arg(long)]
+    strict_secrets: bool,
     #[command(flatten)]
     dev: DevModeArgs,
 }
@@ -1000,6 +1088,7 @@ enum DemoSubscriptionsSubcommand {
     Status(DemoSubscriptionsStatusArgs),
     Renew(DemoSubscriptionsRenewArgs),
     Delete(DemoSubscriptionsDeleteArgs),
+    Reconcile(DemoSubscriptionsReconcileArgs),
 }
 
 #[derive(Parser)]
@@ -1088,6 +1177,22 @@ struct DemoSubscriptionsDeleteArgs {
     team: String,
 }
 
+#[derive(Parser)]
+#[command(
+    about = "Reconcile stored bindings with the desired subscriptions in greentic.demo.yaml.",
+    long_about = "Creates missing bindings, re-creates bindings whose resource or change types changed, and deletes managed bindings no longer listed under services.subscriptions.universal.desired. Use --plan to print the actions without applying them."
+)]
+struct DemoSubscriptionsReconcileArgs {
+    #[arg(long)]
+    bundle: PathBuf,
+    #[arg(long, default_value = "demo")]
+    tenant: String,
+    #[arg(long, default_value = "default")]
+    team: String,
+    #[arg(long)]
+    plan: bool,
+}
+
 impl DemoSubscriptionsCommand {
     fn run(self) -> anyhow::Result<()> {
         match self.command {
@@ -1095,6 +1200,7 @@ impl DemoSubscriptionsCommand {
             DemoSubscriptionsSubcommand::Status(args) => args.run(),
             DemoSubscriptionsSubcommand::Renew(args) => args.run(),
             DemoSubscriptionsSubcommand::Delete(args) => args.run(),
+            DemoSubscriptionsSubcommand::Reconcile(args) => args.run(),
         }
     }
 }
@@ -1138,7 +1244,7 @@ impl DemoRunArgs {
         let secrets_manager = if let Some(bundle) = &self.bundle {
             let secrets_handle =
                 secrets_gate::resolve_secrets_manager(bundle, &self.tenant, self.team.as_deref())?;
-            secrets_handle.runtime_manager(Some(&pack.pack_id))
+            secrets_handle.runtime_manager(Some(&pack.pack_id), Some(&pack_path), None)
         } else {
             default_manager()?
         };
@@ -1432,6 +1538,21 @@ impl DemoSubscriptionsStatusArgs {
                 team_label,
                 expiry.unwrap_or_else(|| "<unknown>".to_string())
             );
+            if state.consecutive_failures > 0 || state.expired {
+                let format_ms = |ms: Option<i64>| {
+                    ms.and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+                        .map(|value| value.to_rfc3339())
+                        .unwrap_or_else(|| "<never>".to_string())
+                };
+                println!(
+                    "  {}renewal failing: failures={} last_attempt={} next_attempt={} last_error={}",
+                    if state.expired { "EXPIRED " } else { "" },
+                    state.consecutive_failures,
+                    format_ms(state.last_attempt_unix_ms),
+                    format_ms(state.next_attempt_unix_ms),
+                    state.last_error.as_deref().unwrap_or("<none>")
+                );
+            }
         }
         Ok(())
     }
@@ -1510,6 +1631,46 @@ impl DemoSubscriptionsDeleteArgs {
     }
 }
 
+impl DemoSubscriptionsReconcileArgs {
+    fn run(self) -> anyhow::Result<()> {
+        let DemoSubscriptionsReconcileArgs {
+            bundle,
+            tenant,
+            team,
+            plan,
+        } = self;
+        let team_override = if team.trim().is_empty() {
+            None
+        } else {
+            Some(team)
+        };
+        let demo_config = config::load_demo_config(&bundle.join("greentic.demo.yaml"))?;
+        let desired = &demo_config.services.subscriptions.universal.desired;
+        let (runner_host, context) = build_runner(&bundle, &tenant, team_override.clone())?;
+        let scheduler = Scheduler::new(
+            SubscriptionService::new(runner_host, context),
+            SubscriptionStore::new(state_root(&bundle)),
+        );
+        let actions = reconcile_desired_subscriptions(
+            &bundle,
+            &tenant,
+            team_override,
+            desired,
+            &scheduler,
+            plan,
+        )?;
+        if actions.is_empty() {
+            println!("subscriptions up to date");
+            return Ok(());
+        }
+        let prefix = if plan { "plan: " } else { "" };
+        for action in &actions {
+            println!("{prefix}{action}");
+        }
+        Ok(())
+    }
+}
+
 #[derive(Parser)]
 #[command(
     about = "Create a new demo bundle scaffold.",
@@ -1624,6 +1785,7 @@ impl DevCommand {
             DevSubcommand::Diagnostics(args) => args.run(),
             DevSubcommand::Verify(args) => args.run(),
             DevSubcommand::Doctor(args) => args.run(ctx),
+            DevSubcommand::Config(command) => command.run(),
         }
     }
 }
@@ -1657,6 +1819,8 @@ impl DemoCommand {
             DemoSubcommand::Allow(args) => args.run(Policy::Public),
             DemoSubcommand::Forbid(args) => args.run(Policy::Forbidden),
             DemoSubcommand::Subscriptions(args) => args.run(),
+            DemoSubcommand::Secrets(args) => args.run(),
+            DemoSubcommand::Config(args) => args.run(),
             DemoSubcommand::Run(args) => args.run(ctx),
         }
     }
@@ -1776,7 +1940,48 @@ impl DevUpArgs {
     fn run(self, ctx: &AppCtx) -> anyhow::Result<()> {
         let root = project_root(self.project_root)?;
         let config = config::load_operator_config(&root)?;
-        let _ = resolve_dev_settings(&ctx.settings, config.as_ref(), &self.dev, &root)?;
+        let dev_settings = resolve_dev_settings(&ctx.settings, config.as_ref(), &self.dev, &root)?;
+        if self.build {
+            let Some(dev_settings) = dev_settings.as_ref() else {
+                return Err(anyhow::anyhow!(
+                    "dev up --build needs dev mode; set dev.root in greentic.yaml or pass --dev-root"
+                ));
+            };
+            let demo_config_path = root.join("greentic.demo.yaml");
+            let demo_services = if demo_config_path.exists() {
+                config::load_demo_config(&demo_config_path)?.services
+            } else {
+                config::DemoServicesConfig::default()
+            };
+            let binaries = crate::dev_build::dev_binaries(config.as_ref(), &demo_services);
+            let binaries = binaries
+                .iter()
+                .map(String::as_str)
+                .filter(|name| config::binary_override(config.as_ref(), name, &root).is_none())
+                .collect::<Vec<_>>();
+            for build in crate::dev_build::build_repos(&root, dev_settings, &binaries)? {
+                match build.status {
+                    crate::dev_build::RepoBuildStatus::Built => println!(
+                        "dev build: {} built {} (log: {})",
+                        build.repo,
+                        build.binaries.join(", "),
+                        build
+                            .log_path
+                            .as_deref()
+                            .map(|path| path.display().to_string())
+                            .unwrap_or_default()
+                    ),
+                    crate::dev_build::RepoBuildStatus::UpToDate => println!(
+                        "dev build: {} up to date ({})",
+                        build.repo,
+                        build.binaries.join(", ")
+                    ),
+                }
+            }
+        }
+        let binary_checks =
+            bin_version::check_binaries(config.as_ref(), &root, dev_settings.as_ref())?;
+        bin_version::ensure_compatible(&binary_checks, self.allow_incompatible_binaries)?;
         let discovery = discovery::discover(&root)?;
         discovery::persist(&root, &self.tenant, &discovery)?;
         let services = config
@@ -1994,15 +2199,24 @@ impl DevDoctorArgs {
         let root = project_root(self.project_root)?;
         let config = config::load_operator_config(&root)?;
         let dev_settings = resolve_dev_settings(&ctx.settings, config.as_ref(), &self.dev, &root)?;
-        let explicit = config::binary_override(config.as_ref(), "greentic-pack", &root);
-        let pack_command = bin_resolver::resolve_binary(
-            "greentic-pack",
-            &ResolveCtx {
-                config_dir: root.clone(),
-                dev: dev_settings,
-                explicit_path: explicit,
-            },
-        )?;
+        let binaries = bin_version::check_binaries(config.as_ref(), &root, dev_settings.as_ref())?;
+        let pack_command = if self.builtin {
+            None
+        } else {
+            let explicit = config::binary_override(config.as_ref(), "greentic-pack", &root);
+            Some(bin_resolver::resolve_binary(
+                "greentic-pack",
+                &ResolveCtx {
+                    config_dir: root.clone(),
+                    dev: dev_settings,
+                    explicit_path: explicit,
+                },
+            )?)
+        };
+        let backend = match pack_command.as_deref() {
+            Some(pack_command) => crate::doctor::DoctorBackend::External(pack_command),
+            None => crate::doctor::DoctorBackend::Builtin,
+        };
 
         let scope = match self.domain {
             DoctorDomainArg::Messaging => crate::doctor::DoctorScope::One(Domain::Messaging),
@@ -2010,7 +2224,7 @@ impl DevDoctorArgs {
             DoctorDomainArg::Secrets => crate::doctor::DoctorScope::One(Domain::Secrets),
             DoctorDomainArg::All => crate::doctor::DoctorScope::All,
         };
-        crate::doctor::run_doctor(
+        let report = crate::doctor::run_doctor(
             &root,
             scope,
             crate::doctor::DoctorOptions {
@@ -2018,10 +2232,25 @@ impl DevDoctorArgs {
                 team: self.team,
                 strict: self.strict,
                 validator_packs: self.validator_pack,
+                binaries,
             },
-            &pack_command,
+            backend,
         )?;
-        Ok(())
+        let rendered = report.render(self.format.into())?;
+        match self.output {
+            Some(path) => {
+                if let Some(parent) = path
+                    .parent()
+                    .filter(|parent| !parent.as_os_str().is_empty())
+                {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&path, rendered)?;
+                println!("Doctor report written to {}", path.display());
+            }
+            None => print!("{rendered}"),
+        }
+        report.ensure_passed()
     }
 }
 
@@ -2056,7 +2285,7 @@ impl DemoBuildArgs {
         };
         let config = config::load_operator_config(&root)?;
         let dev_settings = resolve_dev_settings(&ctx.settings, config.as_ref(), &self.dev, &root)?;
-        let pack_command = if options.run_doctor {
+        let pack_command = if options.run_doctor && !self.builtin_doctor {
             let explicit = config::binary_override(config.as_ref(), "greentic-pack", &root);
             Some(bin_resolver::resolve_binary(
                 "greentic-pack",
@@ -2069,7 +2298,12 @@ impl DemoBuildArgs {
         } else {
             None
         };
-        demo::build_bundle(&root, options, pack_command.as_deref())
+        let doctor = match pack_command.as_deref() {
+            Some(pack_command) => Some(crate::doctor::DoctorBackend::External(pack_command)),
+            None if self.builtin_doctor => Some(crate::doctor::DoctorBackend::Builtin),
+            None => None,
+        };
+        demo::build_bundle(&root, options, doctor)
     }
 }
 
@@ -2091,12 +2325,18 @@ impl DemoUpArgs {
         let command_label = "demo start";
         let debug_enabled = self.verbose;
         if let Some(bundle) = self.bundle.clone() {
+            if self.dry_run {
+                return Err(anyhow::anyhow!(
+                    "--dry-run lists provider setup from greentic.demo.yaml; use --config instead of --bundle"
+                ));
+            }
             let state_dir = bundle.join("state");
             std::fs::create_dir_all(&state_dir)?;
             let log_dir = self.log_dir.clone().unwrap_or_else(|| bundle.join("logs"));
             let log_dir = operator_log::init(log_dir.clone(), log_level)?;
+            let tenant_selection = TenantSelection::parse(self.tenant.as_deref());
             let run_targets =
-                select_bundle_run_targets(&bundle, self.tenant.as_deref(), self.team.as_deref())?;
+                select_bundle_run_targets(&bundle, &tenant_selection, self.team.as_deref())?;
             let target_summary = format_bundle_targets(&run_targets);
             operator_log::info(
                 module_path!(),
@@ -2128,18 +2368,27 @@ impl DemoUpArgs {
                     self.cloudflared
                 );
             }
-            let tenant = self
-                .tenant
-                .clone()
-                .unwrap_or_else(|| DEMO_DEFAULT_TENANT.to_string());
+            // The first target owns the shared tunnel and NATS. Ingress only
+            // serves the running targets, each with its own secrets.
+            let primary = run_targets[0].clone();
             let config = config::load_operator_config(&bundle)?;
-            let _ = resolve_dev_settings(&ctx.settings, config.as_ref(), &self.dev, &bundle)?;
+            let dev_settings =
+                resolve_dev_settings(&ctx.settings, config.as_ref(), &self.dev, &bundle)?;
+            let binary_checks =
+                bin_version::check_binaries(config.as_ref(), &bundle, dev_settings.as_ref())?;
+            bin_version::ensure_compatible(&binary_checks, self.allow_incompatible_binaries)?;
             domains::ensure_cbor_packs(&bundle)?;
             let discovery = discovery::discover_with_options(
                 &bundle,
                 discovery::DiscoveryOptions { cbor_only: true },
             )?;
-            discovery::persist(&bundle, &tenant, &discovery)?;
+            for tenant in run_targets
+                .iter()
+                .map(|target| target.tenant.as_str())
+                .collect::<BTreeSet<_>>()
+            {
+                discovery::persist(&bundle, tenant, &discovery)?;
+            }
             operator_log::info(
                 module_path!(),
                 format!(
@@ -2151,7 +2400,8 @@ impl DemoUpArgs {
                 ),
             );
             let demo_config_path = bundle.join("greentic.demo.yaml");
-            let demo_config = load_demo_config_or_default(&demo_config_path);
+            let demo_config =
+                load_demo_config_or_default(&demo_config_path, self.profile.as_deref())?;
             let services = config
                 .as_ref()
                 .and_then(|config| config.services.clone())
@@ -2163,55 +2413,32 @@ impl DemoUpArgs {
             let explicit_nats_url = self.nats_url.clone();
             let domains_to_setup = self.domain.resolve_domains(Some(&discovery));
 
-            let mut cloudflared_config = match self.cloudflared {
+            let mut tunnel_config = match self.cloudflared {
                 CloudflaredModeArg::Off => None,
-                CloudflaredModeArg::On => {
-                    let explicit = self.cloudflared_binary.clone();
-                    let binary = bin_resolver::resolve_binary(
-                        "cloudflared",
-                        &ResolveCtx {
-                            config_dir: bundle.clone(),
-                            dev: None,
-                            explicit_path: explicit,
-                        },
-                    )?;
-                    Some(crate::cloudflared::CloudflaredConfig {
-                        binary,
-                        local_port: 8080,
-                        extra_args: Vec::new(),
-                        restart: restart.contains("cloudflared"),
-                    })
-                }
+                CloudflaredModeArg::On => Some(crate::tunnel::resolve_tunnel_config(
+                    &demo_config.services.tunnel,
+                    &bundle,
+                    demo_config.services.gateway.port,
+                    self.cloudflared_binary.clone(),
+                    restart.contains("cloudflared"),
+                )?),
             };
 
             let mut public_base_url = self.public_base_url.clone();
-            let team_id = self
-                .team
-                .clone()
-                .unwrap_or_else(|| DEMO_DEFAULT_TEAM.to_string());
-            let mut started_cloudflared_early = false;
+            let mut started_tunnel_early = false;
             if public_base_url.is_none()
                 && self.setup_input.is_some()
-                && let Some(cfg) = cloudflared_config.as_mut()
+                && let Some(cfg) = tunnel_config.as_mut()
             {
-                let paths = RuntimePaths::new(&state_dir, &tenant, &team_id);
-                let setup_log = operator_log::reserve_service_log(&log_dir, "cloudflared")
-                    .with_context(|| "unable to open cloudflared.log")?;
+                let paths = RuntimePaths::new(&state_dir, &primary.tenant, primary.team_id());
                 operator_log::info(
                     module_path!(),
-                    format!(
-                        "starting setup-mode cloudflared log={}",
-                        setup_log.display()
-                    ),
+                    format!("starting setup-mode tunnel service={}", cfg.service_id()),
                 );
-                let handle = crate::cloudflared::start_quick_tunnel(&paths, cfg, &setup_log)?;
+                let handle = cfg.start(&paths, &log_dir)?;
                 operator_log::info(
                     module_path!(),
-                    format!(
-                        "cloudflared setup mode ready url={} log={}",
-                        handle.url,
-                        setup_log.display()
-                    ),
+                    format!("{} setup mode ready url={}", handle.service_id, handle.url),
                 );
                 let domain_labels = domains_to_setup
                     .iter()
@@ -2219,41 +2446,60 @@ impl DemoUpArgs {
                     .collect::<Vec<_>>()
                     .join(",");
                 println!(
-                    "Public URL (cloudflared setup domains={domain_labels}): {}",
-                    handle.url
+                    "Public URL ({} setup domains={domain_labels}): {}",
+                    handle.service_id, handle.url
                 );
                 public_base_url = Some(handle.url.clone());
-                started_cloudflared_early = true;
+                started_tunnel_early = true;
             }
 
-            if started_cloudflared_early && let Some(cfg) = cloudflared_config.as_mut() {
-                cfg.restart = false;
+            if started_tunnel_early && let Some(cfg) = tunnel_config.as_mut() {
+                cfg.set_restart(false);
             }
 
             if let Some(setup_input) = self.setup_input.as_ref() {
-                let tenant_ref = self.tenant.as_deref().unwrap_or(DEMO_DEFAULT_TENANT);
-                let secrets_handle = secrets_gate::resolve_secrets_manager(
-                    &bundle,
-                    tenant_ref,
-                    self.team.as_deref(),
-                )?;
-                run_demo_up_setup(
-                    &bundle,
-                    &domains_to_setup,
-                    setup_input,
-                    self.tenant.clone(),
-                    self.team.clone(),
-                    &self.env,
-                    self.runner_binary.clone(),
-                    public_base_url.clone(),
-                    Some(secrets_handle.manager()),
-                )?;
+                // One setup pass per target, each with its own secrets.
+                for target in &run_targets {
+                    let secrets_handle = secrets_gate::resolve_secrets_manager(
+                        &bundle,
+                        &target.tenant,
+                        target.team.as_deref(),
+                    )?;
+                    run_demo_up_setup(
+                        &bundle,
+                        &domains_to_setup,
+                        setup_input,
+                        Some(target.tenant.clone()),
+                        target.team.clone(),
+                        &self.env,
+                        self.runner_binary.clone(),
+                        public_base_url.clone(),
+                        Some(secrets_handle.manager()),
+                        None,
+                    )?;
+                }
             }
 
+            prune_rotated_secrets(&bundle, &self.env, &run_targets);
+            report_placeholder_secrets(
+                &bundle,
+                &discovery,
+                &domains_to_setup,
+                &run_targets,
+                &self.env,
+                self.strict_secrets,
+            )?;
+
             let start_result = {
                 let mut started = 0;
                 let guard = (|| -> anyhow::Result<()> {
-                    for target in &run_targets {
+                    for (index, target) in run_targets.iter().enumerate() {
+                        // All targets share the tunnel to the one ingress.
+                        let tunnel = if index == 0 {
+                            tunnel_config.clone()
+                        } else {
+                            None
+                        };
                         demo::demo_up(
                             &bundle,
                             &target.tenant,
@@ -2261,7 +2507,7 @@ impl DemoUpArgs {
                             explicit_nats_url.as_deref(),
                             nats_mode,
                             messaging_enabled,
-                            cloudflared_config.clone(),
+                            tunnel,
                             &log_dir,
                             debug_enabled,
                         )
@@ -2291,10 +2537,20 @@ impl DemoUpArgs {
                 guard
             };
             let mut ingress_server = None;
-            let mut timer_scheduler = None;
+            let mut target_services = Vec::new();
             if start_result.is_ok() {
-                let ingress_secrets_handle =
-                    secrets_gate::resolve_secrets_manager(&bundle, &tenant, self.team.as_deref())?;
+                for target in &run_targets {
+                    target_services.push(TargetServices::start(
+                        &bundle,
+                        &discovery,
+                        &domains_to_setup,
+                        &demo_config,
+                        target,
+                        self.runner_binary.clone(),
+                        debug_enabled,
+                    )?);
+                }
+                let mut ingress_url = None;
                 match start_demo_ingress_server(
                     &bundle,
                     &discovery,
@@ -2302,14 +2558,16 @@ impl DemoUpArgs {
                     &domains_to_setup,
                     self.runner_binary.clone(),
                     debug_enabled,
-                    ingress_secrets_handle.clone(),
+                    &target_services,
                 ) {
                     Ok(server) => {
-                        println!(
-                            "HTTP ingress ready at http://{}:{}",
+                        let url = format!(
+                            "http://{}:{}",
                             demo_config.services.gateway.listen_addr,
                             demo_config.services.gateway.port
                         );
+                        println!("HTTP ingress ready at {url}");
+                        ingress_url = Some(url);
                         ingress_server = Some(server);
                     }
                     Err(err) => {
@@ -2320,29 +2578,7 @@ impl DemoUpArgs {
                         );
                     }
                 }
-                match start_demo_timer_scheduler(
-                    &bundle,
-                    &discovery,
-                    &domains_to_setup,
-                    self.runner_binary.clone(),
-                    debug_enabled,
-                    ingress_secrets_handle.clone(),
-                    &tenant,
-                    self.team.as_deref().unwrap_or(DEMO_DEFAULT_TEAM),
-                ) {
-                    Ok(Some(scheduler)) => {
-                        println!("events timer scheduler ready");
-                        timer_scheduler = Some(scheduler);
-                    }
-                    Ok(None) => {}
-                    Err(err) => {
-                        eprintln!("Warning: events timer scheduler disabled: {err}");
-                        operator_log::warn(
-                            module_path!(),
-                            format!("demo timer scheduler unavailable: {err}"),
-                        );
-                    }
-                }
+                persist_target_services(&state_dir, &target_services, ingress_url)?;
             }
             if let Err(ref err) = start_result {
                 operator_log::error(
@@ -2364,18 +2600,53 @@ impl DemoUpArgs {
                 );
             }
             if start_result.is_ok() {
+                let webhook_sync = match (tunnel_config.as_ref(), self.setup_input.as_ref()) {
+                    (Some(tunnel), Some(setup_input)) => match start_bundle_webhook_sync(
+                        &bundle,
+                        &log_dir,
+                        tunnel,
+                        &run_targets,
+                        &domains_to_setup,
+                        setup_input,
+                        &self.env,
+                        self.runner_binary.clone(),
+                    ) {
+                        Ok(sync) => Some(sync),
+                        Err(err) => {
+                            eprintln!("Warning: webhook sync disabled: {err}");
+                            None
+                        }
+                    },
+                    _ => None,
+                };
                 println!(
                     "{command_label} running (bundle={} targets=[{}]); press Ctrl+C to stop",
                     bundle.display(),
                     &target_summary
                 );
-                wait_for_ctrlc()?;
+                if self.watch {
+                    let reload = BundleReloadContext {
+                        bundle: &bundle,
+                        targets: &run_targets,
+                        domain: self.domain,
+                        profile: self.profile.as_deref(),
+                        runner_binary: self.runner_binary.clone(),
+                        debug_enabled,
+                    };
+                    watch_bundle_until_ctrlc(&reload, &mut ingress_server, &mut target_services)?;
+                } else {
+                    wait_for_ctrlc()?;
+                }
+                if let Some(sync) = webhook_sync {
+                    sync.stop()?;
+                }
                 if let Some(server) = ingress_server.take() {
                     server.stop()?;
                 }
-                if let Some(scheduler) = timer_scheduler.take() {
-                    scheduler.stop()?;
+                for services in target_services {
+                    services.stop()?;
                 }
+                runtime_state::remove_demo_targets(&state_dir)?;
                 for target in run_targets.iter().rev() {
                     demo::demo_down_runtime(&state_dir, &target.tenant, target.team_id(), false)?;
                 }
@@ -2383,6 +2654,17 @@ impl DemoUpArgs {
             return start_result;
         }
 
+        if self.watch {
+            return Err(anyhow::anyhow!(
+                "--watch reloads the in-process services of a bundle; use --bundle instead of --config"
+            ));
+        }
+        if TenantSelection::parse(self.tenant.as_deref()).is_multi() {
+            return Err(anyhow::anyhow!(
+                "--tenant {} runs several tenants from a bundle; use --bundle instead of --config",
+                self.tenant.as_deref().unwrap_or_default()
+            ));
+        }
         let config_path = resolve_demo_config_path(self.config.clone())?;
         let config_dir = config_path.parent().unwrap_or(Path::new(".")).to_path_buf();
         let state_dir = config_dir.join("state");
@@ -2401,7 +2683,8 @@ impl DemoUpArgs {
                 log_dir.display()
             ),
         );
-        let demo_config = config::load_demo_config(&config_path)?;
+        let demo_config =
+            config::load_demo_config_with_profile(&config_path, self.profile.as_deref())?;
         let tenant = demo_config.tenant.clone();
         let team = demo_config.team.clone();
         let operator_config = config::load_operator_config(&config_dir)?;
@@ -2411,29 +2694,19 @@ impl DemoUpArgs {
             &self.dev,
             &config_dir,
         )?;
-        let cloudflared = match self.cloudflared {
-            CloudflaredModeArg::Off => None,
-            CloudflaredModeArg::On => {
-                let explicit = self.cloudflared_binary.clone();
-                let binary = bin_resolver::resolve_binary(
-                    "cloudflared",
-                    &ResolveCtx {
-                        config_dir: config_dir.clone(),
-                        dev: None,
-                        explicit_path: explicit,
-                    },
-                )?;
-                Some(crate::cloudflared::CloudflaredConfig {
-                    binary,
-                    local_port: demo_config.services.gateway.port,
-                    extra_args: Vec::new(),
-                    restart: restart.contains("cloudflared"),
-                })
-            }
-        };
-
+        if !self.dry_run {
+            let binary_checks = bin_version::check_binaries(
+                operator_config.as_ref(),
+                &config_dir,
+                dev_settings.as_ref(),
+            )?;
+            bin_version::ensure_compatible(&binary_checks, self.allow_incompatible_binaries)?;
+        }
         let provider_setup_input = self.setup_input.clone();
         let timer_runner_binary = self.runner_binary.clone();
+        let strict_secrets = self.strict_secrets;
+        let secrets_env = self.env.clone();
+        let dry_run_public_base_url = self.public_base_url.clone();
         let provider_options = crate::providers::ProviderSetupOptions {
             providers: if self.providers.is_empty() {
                 None
@@ -2449,13 +2722,44 @@ impl DemoUpArgs {
             setup_input: provider_setup_input.clone(),
             runner_binary: self.runner_binary,
             continue_on_error: provider_setup_input.is_none(),
+            dry_run: self.dry_run,
+        };
+        if provider_options.dry_run {
+            return crate::providers::run_provider_setup(
+                &config_dir,
+                &demo_config,
+                dev_settings,
+                dry_run_public_base_url.as_deref(),
+                provider_options,
+            );
+        }
+
+        let tunnel = match self.cloudflared {
+            CloudflaredModeArg::Off => None,
+            CloudflaredModeArg::On => Some(crate::tunnel::resolve_tunnel_config(
+                &demo_config.services.tunnel,
+                &config_dir,
+                demo_config.services.gateway.port,
+                self.cloudflared_binary.clone(),
+                restart.contains("cloudflared"),
+            )?),
         };
 
+        let webhook_sync_config = (tunnel.is_some() && !provider_options.skip_setup).then(|| {
+            crate::webhook_sync::WebhookSyncConfig {
+                config_dir: config_dir.clone(),
+                config: demo_config.clone(),
+                dev_settings: dev_settings.clone(),
+                provider_options: provider_options.clone(),
+                poll_interval: std::time::Duration::from_secs(5),
+                tunnel: tunnel.as_ref().and_then(|cfg| cfg.watch(&log_dir)),
+            }
+        });
         let result = demo::demo_up_services(
             &config_path,
             &demo_config,
             dev_settings,
-            cloudflared,
+            tunnel,
             &restart,
             provider_options,
             &log_dir,
@@ -2477,6 +2781,24 @@ impl DemoUpArgs {
                     cbor_only: is_demo_bundle,
                 },
             )?;
+            let target = DemoBundleTarget {
+                tenant: tenant.clone(),
+                team: Some(team.clone()),
+            };
+            prune_rotated_secrets(&config_dir, &secrets_env, std::slice::from_ref(&target));
+            // Config mode only sets up messaging providers.
+            let placeholders = report_placeholder_secrets(
+                &config_dir,
+                &discovery,
+                &[Domain::Messaging],
+                &[target],
+                &secrets_env,
+                strict_secrets,
+            );
+            if let Err(err) = placeholders {
+                demo::demo_down_runtime(&state_dir, &tenant, &team, false)?;
+                return Err(err);
+            }
             let domains = if discovery.domains.events {
                 vec![Domain::Events]
             } else {
@@ -2494,6 +2816,16 @@ impl DemoUpArgs {
                 &tenant,
                 &team,
             )?;
+            let webhook_sync = match webhook_sync_config {
+                Some(sync_config) => match crate::webhook_sync::WebhookSync::start(sync_config) {
+                    Ok(sync) => Some(sync),
+                    Err(err) => {
+                        eprintln!("Warning: webhook sync disabled: {err}");
+                        None
+                    }
+                },
+                None => None,
+            };
             println!(
                 "{command_label} running (config={} tenant={} team={}); press Ctrl+C to stop",
                 config_path.display(),
@@ -2501,6 +2833,9 @@ impl DemoUpArgs {
                 team
             );
             wait_for_ctrlc()?;
+            if let Some(sync) = webhook_sync {
+                sync.stop()?;
+            }
             if let Some(scheduler) = timer_scheduler {
                 scheduler.stop()?;
             }
@@ -2527,9 +2862,9 @@ impl DemoBundleTarget {
         }
     }
 
-    fn matches_filters(&self, tenant_filter: Option<&str>, team_filter: Option<&str>) -> bool {
+    fn matches_filters(&self, tenant_filter: Option<&[String]>, team_filter: Option<&str>) -> bool {
         if let Some(filter) = tenant_filter
-            && filter != self.tenant
+            && !filter.contains(&self.tenant)
         {
             return false;
         }
@@ -2546,44 +2881,190 @@ impl DemoBundleTarget {
     }
 }
 
-fn format_bundle_targets(targets: &[DemoBundleTarget]) -> String {
-    targets
-        .iter()
-        .map(|target| target.label())
-        .collect::<Vec<_>>()
-        .join(", ")
+/// Deletes rotated secret values whose grace period ended for each target.
+/// A failure only warns so it never blocks a start.
+fn prune_rotated_secrets(bundle: &Path, env: &str, targets: &[DemoBundleTarget]) {
+    for target in targets {
+        let scope = SecretsScope {
+            env: env.to_string(),
+            tenant: target.tenant.clone(),
+            team: target.team.clone(),
+        };
+        let pruned =
+            secrets_gate::resolve_secrets_manager(bundle, &target.tenant, target.team.as_deref())
+                .and_then(|handle| secret_rotation::prune_expired(&handle, bundle, &scope));
+        if let Err(err) = pruned {
+            operator_log::warn(
+                module_path!(),
+                format!(
+                    "pruning expired secret versions failed target={}: {err}",
+                    target.label()
+                ),
+            );
+        }
+    }
 }
 
-fn select_bundle_run_targets(
+/// Warns about provider secrets that still hold the placeholder written by
+/// setup; with `strict` any such provider aborts the start.
+fn report_placeholder_secrets(
     bundle: &Path,
-    tenant_filter: Option<&str>,
-    team_filter: Option<&str>,
-) -> anyhow::Result<Vec<DemoBundleTarget>> {
-    let resolved_targets = discover_bundle_run_targets(bundle)?;
-    let filtered = resolved_targets
+    discovery: &discovery::DiscoveryResult,
+    domains_to_setup: &[Domain],
+    targets: &[DemoBundleTarget],
+    env: &str,
+    strict: bool,
+) -> anyhow::Result<()> {
+    let domain_names = domains_to_setup
         .iter()
-        .filter(|target| target.matches_filters(tenant_filter, team_filter))
-        .cloned()
-        .collect::<Vec<_>>();
-    if !filtered.is_empty() {
-        return Ok(filtered);
+        .map(|domain| domains::domain_name(*domain))
+        .collect::<BTreeSet<_>>();
+    let mut unresolved = Vec::new();
+    for target in targets {
+        let handle =
+            secrets_gate::resolve_secrets_manager(bundle, &target.tenant, target.team.as_deref())?;
+        for provider in &discovery.providers {
+            if !domain_names.contains(provider.domain.as_str()) {
+                continue;
+            }
+            let status = secrets_gate::provider_secrets_status(
+                &handle.manager(),
+                env,
+                &target.tenant,
+                target.team.as_deref(),
+                &provider.pack_path,
+                &provider.provider_id,
+                handle.dev_store_path.as_deref(),
+                handle.using_env_fallback,
+            )?;
+            if status.placeholders.is_empty() {
+                continue;
+            }
+            eprintln!(
+                "Warning: provider {} ({}) has placeholder secrets:\n{}",
+                provider.provider_id,
+                target.label(),
+                status
+                    .placeholders
+                    .iter()
+                    .map(|uri| format!("  - {uri}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            );
+            unresolved.push(format!("{} ({})", provider.provider_id, target.label()));
+        }
     }
-    if resolved_targets.is_empty() {
-        let tenant = tenant_filter.unwrap_or(DEMO_DEFAULT_TENANT).to_string();
-        let team = team_filter.map(|value| value.to_string());
-        return Ok(vec![DemoBundleTarget { tenant, team }]);
+    if strict && !unresolved.is_empty() {
+        return Err(anyhow!(
+            "refusing to start with placeholder secrets (--strict-secrets): {}; set them with `greentic-operator demo secrets set <uri>`",
+            unresolved.join(", ")
+        ));
     }
-    anyhow::bail!(
-        "no resolved targets matched tenant={:?} team={:?}",
-        tenant_filter,
-        team_filter
-    );
+    Ok(())
 }
 
-fn discover_bundle_run_targets(bundle: &Path) -> anyhow::Result<Vec<DemoBundleTarget>> {
-    let resolved_dir = bundle.join("state").join("resolved");
-    if !resolved_dir.exists() {
-        return Ok(Vec::new());
+fn format_bundle_targets(targets: &[DemoBundleTarget]) -> String {
+    targets
+        .iter()
+        .map(|target| target.label())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `demo start --tenant` in bundle mode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TenantSelection {
+    /// No `--tenant`: every resolved manifest, or the demo tenant when the
+    /// bundle has none yet.
+    Auto,
+    /// `--tenant all`: every resolved manifest.
+    All,
+    /// `--tenant a` or `--tenant a,b`.
+    Listed(Vec<String>),
+}
+
+impl TenantSelection {
+    fn parse(value: Option<&str>) -> Self {
+        let Some(value) = value else {
+            return Self::Auto;
+        };
+        if value.trim() == "all" {
+            return Self::All;
+        }
+        let tenants = value
+            .split(',')
+            .map(str::trim)
+            .filter(|tenant| !tenant.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if tenants.is_empty() {
+            Self::Auto
+        } else {
+            Self::Listed(tenants)
+        }
+    }
+
+    fn listed(&self) -> Option<&[String]> {
+        match self {
+            Self::Listed(tenants) => Some(tenants),
+            Self::Auto | Self::All => None,
+        }
+    }
+
+    fn is_multi(&self) -> bool {
+        match self {
+            Self::Listed(tenants) => tenants.len() > 1,
+            Self::All => true,
+            Self::Auto => false,
+        }
+    }
+}
+
+fn select_bundle_run_targets(
+    bundle: &Path,
+    tenants: &TenantSelection,
+    team_filter: Option<&str>,
+) -> anyhow::Result<Vec<DemoBundleTarget>> {
+    let resolved_targets = discover_bundle_run_targets(bundle)?;
+    let filtered = resolved_targets
+        .iter()
+        .filter(|target| target.matches_filters(tenants.listed(), team_filter))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !filtered.is_empty() {
+        return Ok(filtered);
+    }
+    if resolved_targets.is_empty() {
+        let team = team_filter.map(|value| value.to_string());
+        return match tenants {
+            TenantSelection::Auto => Ok(vec![DemoBundleTarget {
+                tenant: DEMO_DEFAULT_TENANT.to_string(),
+                team,
+            }]),
+            TenantSelection::Listed(tenants) => Ok(tenants
+                .iter()
+                .map(|tenant| DemoBundleTarget {
+                    tenant: tenant.clone(),
+                    team: team.clone(),
+                })
+                .collect()),
+            TenantSelection::All => Err(anyhow::anyhow!(
+                "--tenant all found no resolved manifests in {}; run demo setup first",
+                bundle.join("state").join("resolved").display()
+            )),
+        };
+    }
+    anyhow::bail!(
+        "no resolved targets matched tenant={:?} team={:?}",
+        tenants.listed(),
+        team_filter
+    );
+}
+
+fn discover_bundle_run_targets(bundle: &Path) -> anyhow::Result<Vec<DemoBundleTarget>> {
+    let resolved_dir = bundle.join("state").join("resolved");
+    if !resolved_dir.exists() {
+        return Ok(Vec::new());
     }
     let mut seen = BTreeSet::new();
     for entry in fs::read_dir(resolved_dir)? {
@@ -2640,12 +3121,15 @@ impl DemoSetupArgs {
                 self.skip_secrets_init
             );
         }
+        if let Some(path) = self.check_answers.as_ref() {
+            return self.check_answers_file(path, &discovery, &domains);
+        }
         let format = match self.format {
             Format::Text => PlanFormat::Text,
             Format::Json => PlanFormat::Json,
             Format::Yaml => PlanFormat::Yaml,
         };
-        for domain in domains {
+        for domain in domains.iter().copied() {
             let discovered_providers = match domain {
                 Domain::Messaging | Domain::Events => Some(
                     discovery
@@ -2687,6 +3171,152 @@ impl DemoSetupArgs {
                 discovered_providers,
             })?;
         }
+        if let Some(path) = self.export_answers.as_ref() {
+            self.export_answers_file(path, &discovery, &domains)?;
+        }
+        Ok(())
+    }
+
+    /// Packs `demo setup` would consider, paired with their provider ids.
+    fn selected_packs(
+        &self,
+        discovery: &discovery::DiscoveryResult,
+        domains: &[Domain],
+    ) -> anyhow::Result<Vec<(domains::ProviderPack, String)>> {
+        let mut selected = Vec::new();
+        for domain in domains.iter().copied() {
+            let discovered = discovery
+                .providers
+                .iter()
+                .filter(|provider| provider.domain == domains::domain_name(domain))
+                .cloned()
+                .collect::<Vec<_>>();
+            let (packs, provider_map) = discover_domain_packs(
+                &self.bundle,
+                domain,
+                &self.tenant,
+                self.team.as_deref(),
+                (domain != Domain::Secrets).then_some(discovered.as_slice()),
+            )?;
+            for pack in packs {
+                if self
+                    .provider
+                    .as_deref()
+                    .is_some_and(|filter| !provider_filter_matches(&pack, filter))
+                {
+                    continue;
+                }
+                let provider_id =
+                    provider_id_for_pack(&pack.path, &pack.pack_id, provider_map.as_ref());
+                selected.push((pack, provider_id));
+            }
+        }
+        Ok(selected)
+    }
+
+    fn check_answers_file(
+        &self,
+        path: &Path,
+        discovery: &discovery::DiscoveryResult,
+        domains: &[Domain],
+    ) -> anyhow::Result<()> {
+        let packs = self.selected_packs(discovery, domains)?;
+        let provider_keys = packs.iter().map(|(pack, _)| pack.pack_id.clone()).collect();
+        let input = SetupInputAnswers::new(load_setup_input(path)?, provider_keys)?;
+        let env = resolve_env(self.secrets_env.as_deref());
+        let mut failed = 0usize;
+        for (pack, _) in &packs {
+            let stored = SecretAnswerStore::new(
+                &self.bundle,
+                &env,
+                &self.tenant,
+                self.team.as_deref(),
+                &pack.pack_id,
+            );
+            match check_provider_answers(&pack.path, &pack.pack_id, &input, Some(&stored)) {
+                Ok(()) => println!("[ok] {}", pack.pack_id),
+                Err(err) => {
+                    failed += 1;
+                    println!("[error] {}: {err:#}", pack.pack_id);
+                }
+            }
+        }
+        if failed > 0 {
+            return Err(anyhow::anyhow!(
+                "{failed} of {} provider(s) have invalid answers in {}",
+                packs.len(),
+                path.display()
+            ));
+        }
+        println!(
+            "{} provider(s) checked against {}",
+            packs.len(),
+            path.display()
+        );
+        Ok(())
+    }
+
+    fn export_answers_file(
+        &self,
+        path: &Path,
+        discovery: &discovery::DiscoveryResult,
+        domains: &[Domain],
+    ) -> anyhow::Result<()> {
+        let env = resolve_env(if self.skip_secrets_init {
+            None
+        } else {
+            self.secrets_env.as_deref()
+        });
+        let providers_root = self
+            .state_dir
+            .as_ref()
+            .unwrap_or(&self.bundle)
+            .join("state")
+            .join("runtime")
+            .join(&self.tenant)
+            .join("providers");
+        let mut exported = serde_json::Map::new();
+        for (pack, provider_id) in self.selected_packs(discovery, domains)? {
+            let answers_path = providers_root
+                .join(&provider_id)
+                .join("answers")
+                .join("setup.answers.json");
+            if !answers_path.exists() {
+                continue;
+            }
+            let persisted = serde_json::from_slice(&std::fs::read(&answers_path)?)
+                .with_context(|| format!("parse {}", answers_path.display()))?;
+            let answers = export_provider_answers(&pack.path, persisted, |key| {
+                secrets_gate::canonical_secret_uri(
+                    &env,
+                    &self.tenant,
+                    self.team.as_deref(),
+                    &provider_id,
+                    key,
+                )
+            })?;
+            exported.insert(pack.pack_id, answers);
+        }
+        let value = serde_json::Value::Object(exported);
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let contents = if is_json {
+            serde_json::to_string_pretty(&value)?
+        } else {
+            serde_yaml_bw::to_string(&value)?
+        };
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, contents).with_context(|| format!("write {}", path.display()))?;
+        println!(
+            "Exported setup answers for {} provider(s) to {}",
+            value.as_object().map_or(0, |map| map.len()),
+            path.display()
+        );
         Ok(())
     }
 }
@@ -3621,20 +4251,22 @@ fn create_demo_bundle_structure(root: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn load_demo_config_or_default(path: &Path) -> config::DemoConfig {
-    match config::load_demo_config(path) {
-        Ok(value) => value,
-        Err(err) => {
-            operator_log::warn(
-                module_path!(),
-                format!(
-                    "failed to load {}: {err}; using default values",
-                    path.display()
-                ),
-            );
-            config::DemoConfig::default()
-        }
+/// Loads the bundle's demo config, using the defaults only when the file does
+/// not exist. A file that fails to parse or validate is an error, so its
+/// ingress access rules are never dropped silently.
+fn load_demo_config_or_default(
+    path: &Path,
+    profile: Option<&str>,
+) -> anyhow::Result<config::DemoConfig> {
+    if profile.is_none() && !path.exists() {
+        operator_log::info(
+            module_path!(),
+            format!("{} not found; using default values", path.display()),
+        );
+        return Ok(config::DemoConfig::default());
     }
+    config::load_demo_config_with_profile(path, profile)
+        .with_context(|| format!("load {}", path.display()))
 }
 
 fn start_demo_ingress_server(
@@ -3644,7 +4276,7 @@ fn start_demo_ingress_server(
     domains: &[Domain],
     runner_binary: Option<PathBuf>,
     debug_enabled: bool,
-    secrets_handle: SecretsManagerHandle,
+    targets: &[TargetServices],
 ) -> anyhow::Result<HttpIngressServer> {
     let addr = format!(
         "{}:{}",
@@ -3653,20 +4285,417 @@ fn start_demo_ingress_server(
     let bind_addr: SocketAddr = addr
         .parse()
         .with_context(|| format!("invalid gateway listen address {addr}"))?;
-    let runner_host = Arc::new(DemoRunnerHost::new(
-        bundle.to_path_buf(),
-        discovery,
-        runner_binary,
-        secrets_handle.clone(),
-        debug_enabled,
-    )?);
+    let runner_host = shared_runner_host(bundle, discovery, runner_binary, debug_enabled, targets)?;
+    let access_policy = IngressAccessPolicy::from_demo_config(demo_config)?;
     HttpIngressServer::start(HttpIngressConfig {
         bind_addr,
         domains: domains.to_vec(),
         runner_host,
+        access_policy,
     })
 }
 
+/// One runner host for the shared ingress. Requests are served with the
+/// secrets of the tenant and team in the URL; requests for a tenant or team
+/// that is not running are refused.
+fn shared_runner_host(
+    bundle: &Path,
+    discovery: &discovery::DiscoveryResult,
+    runner_binary: Option<PathBuf>,
+    debug_enabled: bool,
+    targets: &[TargetServices],
+) -> anyhow::Result<Arc<DemoRunnerHost>> {
+    let primary = targets
+        .first()
+        .ok_or_else(|| anyhow!("no demo targets to serve"))?;
+    let host = DemoRunnerHost::new(
+        bundle.to_path_buf(),
+        discovery,
+        runner_binary,
+        primary.secrets_handle.clone(),
+        debug_enabled,
+    )?
+    .with_tenant_secrets(
+        targets
+            .iter()
+            .map(|services| services.secrets_handle.clone()),
+    );
+    Ok(Arc::new(host))
+}
+
+/// In-process services `demo start --bundle` runs for one tenant and team.
+/// The HTTP ingress is shared by every target.
+struct TargetServices {
+    target: DemoBundleTarget,
+    secrets_handle: SecretsManagerHandle,
+    timer_scheduler: Option<TimerScheduler>,
+    subscriptions: Option<RenewalLoop>,
+}
+
+impl TargetServices {
+    fn start(
+        bundle: &Path,
+        discovery: &discovery::DiscoveryResult,
+        domains: &[Domain],
+        demo_config: &config::DemoConfig,
+        target: &DemoBundleTarget,
+        runner_binary: Option<PathBuf>,
+        debug_enabled: bool,
+    ) -> anyhow::Result<Self> {
+        let secrets_handle =
+            secrets_gate::resolve_secrets_manager(bundle, &target.tenant, target.team.as_deref())?;
+        let mut services = Self {
+            target: target.clone(),
+            secrets_handle,
+            timer_scheduler: None,
+            subscriptions: None,
+        };
+        services.restart_timer_scheduler(
+            bundle,
+            discovery,
+            domains,
+            runner_binary,
+            debug_enabled,
+        )?;
+        if matches!(
+            demo_config.services.subscriptions.mode,
+            config::DemoSubscriptionsMode::UniversalOps
+        ) {
+            match demo::start_universal_subscriptions(
+                bundle,
+                demo_config,
+                &target.tenant,
+                target.team.clone(),
+            ) {
+                Ok(renewal) => {
+                    println!("subscriptions renewal loop ready ({})", target.label());
+                    services.subscriptions = Some(renewal);
+                }
+                Err(err) => {
+                    eprintln!(
+                        "Warning: subscriptions disabled for {}: {err}",
+                        target.label()
+                    );
+                    operator_log::warn(
+                        module_path!(),
+                        format!(
+                            "demo subscriptions unavailable target={}: {err}",
+                            target.label()
+                        ),
+                    );
+                }
+            }
+        }
+        Ok(services)
+    }
+
+    /// Stops the running timer scheduler, if any, and starts a new one. A
+    /// scheduler that fails to start is reported and left off.
+    fn restart_timer_scheduler(
+        &mut self,
+        bundle: &Path,
+        discovery: &discovery::DiscoveryResult,
+        domains: &[Domain],
+        runner_binary: Option<PathBuf>,
+        debug_enabled: bool,
+    ) -> anyhow::Result<()> {
+        if let Some(scheduler) = self.timer_scheduler.take() {
+            scheduler.stop()?;
+        }
+        match start_demo_timer_scheduler(
+            bundle,
+            discovery,
+            domains,
+            runner_binary,
+            debug_enabled,
+            self.secrets_handle.clone(),
+            &self.target.tenant,
+            self.target.team_id(),
+        ) {
+            Ok(Some(scheduler)) => {
+                println!("events timer scheduler ready ({})", self.target.label());
+                self.timer_scheduler = Some(scheduler);
+            }
+            Ok(None) => {}
+            Err(err) => {
+                eprintln!(
+                    "Warning: events timer scheduler disabled for {}: {err}",
+                    self.target.label()
+                );
+                operator_log::warn(
+                    module_path!(),
+                    format!(
+                        "demo timer scheduler unavailable target={}: {err}",
+                        self.target.label()
+                    ),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn stop(self) -> anyhow::Result<()> {
+        if let Some(scheduler) = self.timer_scheduler {
+            scheduler.stop()?;
+        }
+        if let Some(renewal) = self.subscriptions {
+            renewal.stop()?;
+        }
+        Ok(())
+    }
+}
+
+/// Re-runs `--setup-input` provider setup for every target whose webhook
+/// still points at an old URL when the shared tunnel moves.
+#[allow(clippy::too_many_arguments)]
+fn start_bundle_webhook_sync(
+    bundle: &Path,
+    log_dir: &Path,
+    tunnel: &crate::tunnel::TunnelConfig,
+    targets: &[DemoBundleTarget],
+    domains: &[Domain],
+    setup_input: &Path,
+    env: &str,
+    runner_binary: Option<PathBuf>,
+) -> anyhow::Result<crate::webhook_sync::WebhookSync> {
+    let providers = ProvidersInput::load(setup_input)?;
+    let provider_ids = domains
+        .iter()
+        .filter_map(|domain| providers.providers_for_domain(*domain))
+        .flat_map(|map| map.keys().cloned())
+        .collect::<BTreeSet<_>>();
+    let primary = targets
+        .first()
+        .ok_or_else(|| anyhow!("no targets to sync webhooks for"))?;
+    let state_dir = bundle.join("state");
+    let paths = RuntimePaths::new(&state_dir, &primary.tenant, primary.team_id());
+    let bundle = bundle.to_path_buf();
+    let targets = targets.to_vec();
+    let domains = domains.to_vec();
+    let setup_input = setup_input.to_path_buf();
+    let env = env.to_string();
+    let resync = move |url: &str| -> anyhow::Result<Vec<crate::providers::WebhookMove>> {
+        let mut moves = Vec::new();
+        for target in &targets {
+            let providers_root = state_dir
+                .join("runtime")
+                .join(&target.tenant)
+                .join("providers");
+            let moved = provider_ids
+                .iter()
+                .filter_map(|provider| {
+                    crate::providers::detect_webhook_move(&providers_root, provider, url)
+                })
+                .collect::<Vec<_>>();
+            if moved.is_empty() {
+                continue;
+            }
+            for entry in &moved {
+                println!(
+                    "webhook moved provider={} tenant={} {} -> {}",
+                    entry.provider,
+                    target.label(),
+                    entry.previous,
+                    entry.current
+                );
+            }
+            let only = moved
+                .iter()
+                .map(|entry| entry.provider.clone())
+                .collect::<BTreeSet<_>>();
+            let secrets_handle = secrets_gate::resolve_secrets_manager(
+                &bundle,
+                &target.tenant,
+                target.team.as_deref(),
+            )?;
+            run_demo_up_setup(
+                &bundle,
+                &domains,
+                &setup_input,
+                Some(target.tenant.clone()),
+                target.team.clone(),
+                &env,
+                runner_binary.clone(),
+                Some(url.to_string()),
+                Some(secrets_handle.manager()),
+                Some(&only),
+            )?;
+            moves.extend(moved);
+        }
+        Ok(moves)
+    };
+    crate::webhook_sync::WebhookSync::start_with(
+        paths,
+        tunnel.watch(log_dir),
+        std::time::Duration::from_secs(5),
+        Box::new(resync),
+    )
+}
+
+/// Records the running targets for `demo status`.
+fn persist_target_services(
+    state_dir: &Path,
+    targets: &[TargetServices],
+    ingress_url: Option<String>,
+) -> anyhow::Result<()> {
+    runtime_state::persist_demo_targets(
+        state_dir,
+        &runtime_state::DemoTargetsManifest {
+            pid: std::process::id(),
+            ingress_url,
+            targets: targets
+                .iter()
+                .map(|services| runtime_state::DemoTargetEntry {
+                    tenant: services.target.tenant.clone(),
+                    team: services.target.team_id().to_string(),
+                    timer_scheduler: services.timer_scheduler.is_some(),
+                    subscriptions: services.subscriptions.is_some(),
+                })
+                .collect(),
+        },
+    )
+}
+
+/// What `demo start --watch` needs to rebuild the in-process bundle services.
+struct BundleReloadContext<'a> {
+    bundle: &'a Path,
+    targets: &'a [DemoBundleTarget],
+    domain: DemoSetupDomainArg,
+    profile: Option<&'a str>,
+    runner_binary: Option<PathBuf>,
+    debug_enabled: bool,
+}
+
+const WATCH_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Polls the bundle until Ctrl+C and reloads whatever changed. A failed
+/// reload is reported and the previous services keep running.
+fn watch_bundle_until_ctrlc(
+    ctx: &BundleReloadContext<'_>,
+    ingress_server: &mut Option<HttpIngressServer>,
+    target_services: &mut [TargetServices],
+) -> anyhow::Result<()> {
+    let runtime = Runtime::new().context("failed to spawn runtime for Ctrl+C listener")?;
+    let (tx, rx) = std::sync::mpsc::channel::<()>();
+    runtime.spawn(async move {
+        let _ = tokio::signal::ctrl_c().await;
+        let _ = tx.send(());
+    });
+    let mut watcher = demo::watch::BundleWatcher::new(ctx.bundle);
+    println!("watching packs/, providers/, tenants/**/*.gmap and greentic.demo*.yaml for changes");
+    while let Err(std::sync::mpsc::RecvTimeoutError::Timeout) = rx.recv_timeout(WATCH_POLL_INTERVAL)
+    {
+        let changes = watcher.poll();
+        if changes.is_empty() {
+            continue;
+        }
+        let changed = changes
+            .changed
+            .iter()
+            .map(|(kind, paths)| {
+                let paths = paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{} ({paths})", kind.as_str())
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        operator_log::info(module_path!(), format!("watch detected changes: {changed}"));
+        match reload_bundle(ctx, &changes, ingress_server, target_services) {
+            Ok(reloaded) => println!("Reloaded {}", reloaded.join(", ")),
+            Err(err) => {
+                eprintln!("Warning: reload failed; previous services keep running: {err:#}");
+                operator_log::error(module_path!(), format!("watch reload failed: {err:#}"));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn reload_bundle(
+    ctx: &BundleReloadContext<'_>,
+    changes: &demo::watch::WatchChanges,
+    ingress_server: &mut Option<HttpIngressServer>,
+    target_services: &mut [TargetServices],
+) -> anyhow::Result<Vec<String>> {
+    use demo::watch::WatchKind;
+
+    let mut reloaded = Vec::new();
+    if changes.contains(WatchKind::Policy) {
+        project::sync_project(ctx.bundle)?;
+        for target in ctx.targets {
+            copy_resolved_manifest(ctx.bundle, &target.tenant, target.team.as_deref())?;
+        }
+        reloaded.push(format!("policy for {}", format_bundle_targets(ctx.targets)));
+    }
+    if !changes.contains(WatchKind::Packs) && !changes.contains(WatchKind::Config) {
+        return Ok(reloaded);
+    }
+
+    domains::ensure_cbor_packs(ctx.bundle)?;
+    let discovery = discovery::discover_with_options(
+        ctx.bundle,
+        discovery::DiscoveryOptions { cbor_only: true },
+    )?;
+    for tenant in ctx
+        .targets
+        .iter()
+        .map(|target| target.tenant.as_str())
+        .collect::<BTreeSet<_>>()
+    {
+        discovery::persist(ctx.bundle, tenant, &discovery)?;
+    }
+    let demo_config =
+        load_demo_config_or_default(&ctx.bundle.join("greentic.demo.yaml"), ctx.profile)?;
+    let domains = ctx.domain.resolve_domains(Some(&discovery));
+    if changes.contains(WatchKind::Packs) {
+        reloaded.push(format!(
+            "discovery ({} provider(s))",
+            discovery.providers.len()
+        ));
+    }
+    if changes.contains(WatchKind::Config) {
+        reloaded.push("access policy from greentic.demo.yaml".to_string());
+    }
+
+    if let Some(server) = ingress_server.as_ref() {
+        let runner_host = shared_runner_host(
+            ctx.bundle,
+            &discovery,
+            ctx.runner_binary.clone(),
+            ctx.debug_enabled,
+            target_services,
+        )?;
+        let access_policy = IngressAccessPolicy::from_demo_config(&demo_config)?;
+        server.reload(runner_host, domains.clone(), access_policy)?;
+        reloaded.push("ingress runner host".to_string());
+    }
+
+    if changes.contains(WatchKind::Packs) {
+        for services in target_services.iter_mut() {
+            services.restart_timer_scheduler(
+                ctx.bundle,
+                &discovery,
+                &domains,
+                ctx.runner_binary.clone(),
+                ctx.debug_enabled,
+            )?;
+        }
+        let running = target_services
+            .iter()
+            .filter(|services| services.timer_scheduler.is_some())
+            .count();
+        reloaded.push(if running > 0 {
+            format!("timer scheduler ({running} target(s))")
+        } else {
+            "timer scheduler (stopped, no timer handlers)".to_string()
+        });
+    }
+    Ok(reloaded)
+}
+
 #[allow(clippy::too_many_arguments)]
 fn start_demo_timer_scheduler(
     bundle: &Path,
@@ -3766,14 +4795,22 @@ impl DemoStatusArgs {
         let state_dir = resolve_state_dir(self.state_dir, self.bundle.as_ref());
         if demo_debug_enabled() {
             println!(
-                "[demo] status state_dir={} tenant={} team={} verbose={}",
+                "[demo] status state_dir={} tenant={:?} team={} verbose={}",
                 state_dir.display(),
                 self.tenant,
                 self.team,
                 self.verbose
             );
         }
-        demo::demo_status_runtime(&state_dir, &self.tenant, &self.team, self.verbose)
+        if let Some(tenant) = self.tenant.as_deref() {
+            return demo::demo_status_runtime(&state_dir, tenant, &self.team, self.verbose);
+        }
+        match runtime_state::read_demo_targets(&state_dir)? {
+            Some(manifest) if supervisor::is_running(manifest.pid) => {
+                demo::demo_status_targets(&state_dir, &manifest, self.verbose)
+            }
+            _ => demo::demo_status_runtime(&state_dir, "demo", &self.team, self.verbose),
+        }
     }
 }
 
@@ -3807,6 +4844,16 @@ impl DemoDoctorArgs {
         let config = config::load_operator_config(&self.bundle)?;
         let dev_settings =
             resolve_dev_settings(&ctx.settings, config.as_ref(), &self.dev, &self.bundle)?;
+        let binaries =
+            bin_version::check_binaries(config.as_ref(), &self.bundle, dev_settings.as_ref())?;
+        if self.builtin {
+            return demo::demo_doctor(
+                &self.bundle,
+                crate::doctor::DoctorBackend::Builtin,
+                &binaries,
+                self.strict_secrets,
+            );
+        }
         let explicit = config::binary_override(config.as_ref(), "greentic-pack", &self.bundle);
         let pack_command = bin_resolver::resolve_binary(
             "greentic-pack",
@@ -3826,7 +4873,12 @@ impl DemoDoctorArgs {
                 pack_command.display()
             );
         }
-        demo::demo_doctor(&self.bundle, &pack_command)
+        demo::demo_doctor(
+            &self.bundle,
+            crate::doctor::DoctorBackend::External(&pack_command),
+            &binaries,
+            self.strict_secrets,
+        )
     }
 }
 
@@ -3894,11 +4946,19 @@ fn run_demo_up_setup(
     runner_binary: Option<PathBuf>,
     public_base_url: Option<String>,
     secrets_manager: Option<DynSecretsManager>,
+    only_providers: Option<&BTreeSet<String>>,
 ) -> anyhow::Result<()> {
     let providers_input = ProvidersInput::load(setup_input)?;
     for domain in domains {
-        let provider_map = match providers_input.providers_for_domain(*domain) {
+        let provider_map = providers_input.providers_for_domain(*domain).map(|map| {
+            map.iter()
+                .filter(|(provider, _)| only_providers.is_none_or(|only| only.contains(*provider)))
+                .map(|(provider, value)| (provider.clone(), value.clone()))
+                .collect::<BTreeMap<_, _>>()
+        });
+        let provider_map = match provider_map.as_ref() {
             Some(map) if !map.is_empty() => map,
+            _ if only_providers.is_some() => continue,
             _ => {
                 println!(
                     "[demo] no providers configured for domain {}; skipping provider setup",
@@ -4038,9 +5098,9 @@ fn copy_resolved_manifest(bundle: &Path, tenant: &str, team: Option<&str>) -> an
     Ok(())
 }
 
-pub(crate) fn discovery_map(
-    providers: &[discovery::DetectedProvider],
-) -> std::collections::BTreeMap<PathBuf, discovery::DetectedProvider> {
+type ProviderMap = std::collections::BTreeMap<PathBuf, discovery::DetectedProvider>;
+
+pub(crate) fn discovery_map(providers: &[discovery::DetectedProvider]) -> ProviderMap {
     let mut map = std::collections::BTreeMap::new();
     for provider in providers {
         map.insert(provider.pack_path.clone(), provider.clone());
@@ -4221,30 +5281,41 @@ struct DomainRunArgs {
     secrets_manager: Option<DynSecretsManager>,
 }
 
-fn run_domain_command(args: DomainRunArgs) -> anyhow::Result<()> {
-    let is_demo_bundle = args.root.join("greentic.demo.yaml").exists();
+/// Lists the provider packs a domain command would consider for the
+/// tenant/team, plus the pack path -> provider lookup when discovery ran.
+fn discover_domain_packs(
+    root: &Path,
+    domain: Domain,
+    tenant: &str,
+    team: Option<&str>,
+    discovered_providers: Option<&[discovery::DetectedProvider]>,
+) -> anyhow::Result<(Vec<domains::ProviderPack>, Option<ProviderMap>)> {
+    let is_demo_bundle = root.join("greentic.demo.yaml").exists();
     let mut packs = if is_demo_bundle {
-        domains::discover_provider_packs_cbor_only(&args.root, args.domain)?
+        domains::discover_provider_packs_cbor_only(root, domain)?
     } else {
-        domains::discover_provider_packs(&args.root, args.domain)?
+        domains::discover_provider_packs(root, domain)?
     };
-    let provider_map = args.discovered_providers.as_ref().map(|providers| {
-        let mut map = std::collections::BTreeMap::new();
-        for provider in providers {
-            map.insert(provider.pack_path.clone(), provider.clone());
-        }
-        map
-    });
+    let provider_map = discovered_providers.map(discovery_map);
     if let Some(provider_map) = provider_map.as_ref() {
         packs.retain(|pack| provider_map.contains_key(&pack.path));
         packs.sort_by(|a, b| a.path.cmp(&b.path));
     }
-    if is_demo_bundle
-        && let Some(allowed) =
-            demo_provider_files(&args.root, &args.tenant, args.team.as_deref(), args.domain)?
-    {
+    if is_demo_bundle && let Some(allowed) = demo_provider_files(root, tenant, team, domain)? {
         packs.retain(|pack| allowed.contains(&pack.file_name));
     }
+    Ok((packs, provider_map))
+}
+
+fn run_domain_command(args: DomainRunArgs) -> anyhow::Result<()> {
+    let is_demo_bundle = args.root.join("greentic.demo.yaml").exists();
+    let (mut packs, provider_map) = discover_domain_packs(
+        &args.root,
+        args.domain,
+        &args.tenant,
+        args.team.as_deref(),
+        args.discovered_providers.as_deref(),
+    )?;
     if args.action == DomainAction::Setup {
         let setup_flow = domains::config(args.domain).setup_flow;
         let missing: Vec<String> = packs
@@ -4372,6 +5443,7 @@ fn run_domain_command(args: DomainRunArgs) -> anyhow::Result<()> {
         args.team.as_deref(),
         plan,
         args.parallel,
+        args.format,
         dist_offline,
         args.allow_contract_change,
         args.backup,
@@ -4406,6 +5478,49 @@ fn filter_packs_by_allowed(
         .collect()
 }
 
+/// Outcome of one planned flow, printed in plan order once the run is done.
+#[derive(serde::Serialize)]
+struct PlanRunReport {
+    pack: String,
+    pack_id: String,
+    flow: String,
+    status: PlanRunStatus,
+    duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    output: String,
+    #[serde(skip)]
+    failure: Option<anyhow::Error>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum PlanRunStatus {
+    Ok,
+    Failed,
+    /// Not started because an earlier flow failed outside `--best-effort`.
+    Skipped,
+}
+
+impl PlanRunReport {
+    fn new(item: &domains::PlannedRun, status: PlanRunStatus) -> Self {
+        Self {
+            pack: item.pack.file_name.clone(),
+            pack_id: item.pack.pack_id.clone(),
+            flow: item.flow_id.clone(),
+            status,
+            duration_ms: 0,
+            error: None,
+            output: String::new(),
+            failure: None,
+        }
+    }
+}
+
+/// Runs the plan on up to `parallel` workers. Each flow's output is buffered
+/// and printed in plan order, so results do not interleave. Outside
+/// `--best-effort`, the first failure stops flows that have not started yet.
 #[allow(clippy::too_many_arguments)]
 fn run_plan(
     root: &Path,
@@ -4416,124 +5531,147 @@ fn run_plan(
     team: Option<&str>,
     plan: Vec<domains::PlannedRun>,
     parallel: usize,
+    format: PlanFormat,
     dist_offline: bool,
     allow_contract_change: bool,
     backup: bool,
     secrets_env: Option<&str>,
     runner_binary: Option<PathBuf>,
     best_effort: bool,
-    provider_map: Option<std::collections::BTreeMap<PathBuf, discovery::DetectedProvider>>,
+    provider_map: Option<ProviderMap>,
     setup_answers: Option<SetupInputAnswers>,
     interactive: bool,
     public_base_url: Option<String>,
     secrets_manager: Option<DynSecretsManager>,
 ) -> anyhow::Result<()> {
-    let setup_answers = setup_answers.map(Arc::new);
-    let plan_public_base_url = public_base_url.map(Arc::new);
-    let plan_secrets_manager = secrets_manager;
-    if parallel <= 1 {
-        let mut errors = Vec::new();
-        for item in plan {
-            let result = run_plan_item(
-                root,
-                state_root,
-                domain,
-                action,
-                tenant,
-                team,
-                &item,
-                dist_offline,
-                allow_contract_change,
-                backup,
-                secrets_env,
-                runner_binary.as_deref(),
-                setup_answers.as_deref(),
-                provider_map.as_ref(),
-                interactive,
-                plan_public_base_url.clone(),
-                plan_secrets_manager.clone(),
-            );
-            if let Err(err) = result {
-                if best_effort {
-                    errors.push(err);
-                } else {
-                    return Err(err);
+    let workers = parallel.clamp(1, plan.len().max(1));
+    let (setup_answers, interactive) =
+        if interactive && workers > 1 && action == DomainAction::Setup {
+            (Some(prompt_plan_answers(&plan)?), false)
+        } else {
+            (setup_answers, interactive)
+        };
+    let public_base_url = public_base_url.map(Arc::new);
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let stop = std::sync::atomic::AtomicBool::new(false);
+    let (tx, rx) = std::sync::mpsc::channel::<(usize, PlanRunReport)>();
+
+    let mut reports = std::thread::scope(|scope| {
+        for _ in 0..workers {
+            let tx = tx.clone();
+            let (plan, next, stop) = (&plan, &next, &stop);
+            let (setup_answers, provider_map) = (setup_answers.as_ref(), provider_map.as_ref());
+            let (runner_binary, public_base_url, secrets_manager) =
+                (&runner_binary, &public_base_url, &secrets_manager);
+            scope.spawn(move || {
+                loop {
+                    let index = next.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    let Some(item) = plan.get(index) else {
+                        break;
+                    };
+                    if stop.load(std::sync::atomic::Ordering::SeqCst) {
+                        let _ = tx.send((index, PlanRunReport::new(item, PlanRunStatus::Skipped)));
+                        continue;
+                    }
+                    let started = std::time::Instant::now();
+                    let mut output = String::new();
+                    let result = run_plan_item(
+                        root,
+                        state_root,
+                        domain,
+                        action,
+                        tenant,
+                        team,
+                        item,
+                        dist_offline,
+                        allow_contract_change,
+                        backup,
+                        secrets_env,
+                        runner_binary.as_deref(),
+                        setup_answers,
+                        provider_map,
+                        interactive,
+                        public_base_url.clone(),
+                        secrets_manager.clone(),
+                        &mut output,
+                    );
+                    let mut report = PlanRunReport::new(item, PlanRunStatus::Ok);
+                    report.duration_ms = started.elapsed().as_millis() as u64;
+                    report.output = output;
+                    if let Err(err) = result {
+                        if !best_effort {
+                            stop.store(true, std::sync::atomic::Ordering::SeqCst);
+                        }
+                        report.status = PlanRunStatus::Failed;
+                        report.error = Some(format!("{err:#}"));
+                        report.failure = Some(err);
+                    }
+                    let _ = tx.send((index, report));
                 }
-            }
+            });
         }
-        if best_effort && !errors.is_empty() {
-            println!("Best-effort: {} flow(s) failed.", errors.len());
-            return Ok(());
+        drop(tx);
+
+        // Print each report as soon as everything before it in the plan is done.
+        let mut pending = BTreeMap::new();
+        let mut reports = Vec::with_capacity(plan.len());
+        for (index, report) in rx {
+            pending.insert(index, report);
+            while let Some(report) = pending.remove(&reports.len()) {
+                if format == PlanFormat::Text {
+                    print_plan_report(&report, best_effort);
+                }
+                reports.push(report);
+            }
         }
+        reports
+    });
+
+    match format {
+        PlanFormat::Text => {}
+        PlanFormat::Json => println!("{}", serde_json::to_string_pretty(&reports)?),
+        PlanFormat::Yaml => print!("{}", serde_yaml_bw::to_string(&reports)?),
+    }
+    let failed = reports
+        .iter()
+        .filter(|report| report.status == PlanRunStatus::Failed)
+        .count();
+    if failed == 0 {
         return Ok(());
     }
-
-    let mut handles = Vec::new();
-    let plan = std::sync::Arc::new(std::sync::Mutex::new(plan));
-    let errors = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
-
-    for _ in 0..parallel {
-        let plan = plan.clone();
-        let errors = errors.clone();
-        let root = root.to_path_buf();
-        let state_root = state_root.to_path_buf();
-        let tenant = tenant.to_string();
-        let team = team.map(|value| value.to_string());
-        let secrets_env = secrets_env.map(|value| value.to_string());
-        let runner_binary = runner_binary.clone();
-        let provider_map = provider_map.clone();
-        let setup_answers = setup_answers.clone();
-        let interactive_flag = interactive;
-        let thread_public_base_url = plan_public_base_url.clone();
-        let thread_secrets_manager = plan_secrets_manager.clone();
-        handles.push(std::thread::spawn(move || {
-            loop {
-                let next = {
-                    let mut queue = plan.lock().unwrap();
-                    queue.pop()
-                };
-                let Some(item) = next else {
-                    break;
-                };
-                let result = run_plan_item(
-                    &root,
-                    &state_root,
-                    domain,
-                    action,
-                    &tenant,
-                    team.as_deref(),
-                    &item,
-                    dist_offline,
-                    allow_contract_change,
-                    backup,
-                    secrets_env.as_deref(),
-                    runner_binary.as_deref(),
-                    setup_answers.as_deref(),
-                    provider_map.as_ref(),
-                    interactive_flag,
-                    thread_public_base_url.clone(),
-                    thread_secrets_manager.clone(),
-                );
-                if let Err(err) = result {
-                    errors.lock().unwrap().push(err);
-                }
-            }
-        }));
+    if best_effort {
+        if format == PlanFormat::Text {
+            println!("Best-effort: {failed} flow(s) failed.");
+        }
+        return Ok(());
     }
+    let first = reports
+        .iter_mut()
+        .find_map(|report| report.failure.take())
+        .expect("failed report carries its error");
+    Err(first)
+}
 
-    for handle in handles {
-        let _ = handle.join();
+fn print_plan_report(report: &PlanRunReport, best_effort: bool) {
+    print!("{}", report.output);
+    if best_effort && let Some(error) = report.error.as_deref() {
+        println!("{} {} -> failed ({error})", report.pack, report.flow);
     }
+}
 
-    let errors = errors.lock().unwrap();
-    if !errors.is_empty() {
-        if best_effort {
-            println!("Best-effort: {} flow(s) failed.", errors.len());
-            return Ok(());
+/// Prompts for every pack's setup answers before the workers start, so
+/// parallel flows never compete for the terminal.
+fn prompt_plan_answers(plan: &[domains::PlannedRun]) -> anyhow::Result<SetupInputAnswers> {
+    let mut answers = JsonMap::new();
+    for item in plan {
+        if answers.contains_key(&item.pack.pack_id) {
+            continue;
         }
-        return Err(anyhow::anyhow!("{} flow(s) failed.", errors.len()));
+        let value = collect_setup_answers(&item.pack.path, &item.pack.pack_id, None, true, None)?;
+        answers.insert(item.pack.pack_id.clone(), value);
     }
-    Ok(())
+    let provider_keys = answers.keys().cloned().collect();
+    SetupInputAnswers::new(JsonValue::Object(answers), provider_keys)
 }
 
 fn render_plan(plan: &[domains::PlannedRun], format: PlanFormat) -> anyhow::Result<()> {
@@ -4573,11 +5711,14 @@ fn run_plan_item(
     secrets_env: Option<&str>,
     runner_binary: Option<&Path>,
     setup_answers: Option<&SetupInputAnswers>,
-    provider_map: Option<&std::collections::BTreeMap<PathBuf, discovery::DetectedProvider>>,
+    provider_map: Option<&ProviderMap>,
     interactive: bool,
     public_base_url: Option<Arc<String>>,
     secrets_manager: Option<DynSecretsManager>,
+    out: &mut String,
 ) -> anyhow::Result<()> {
+    use std::fmt::Write as _;
+
     let provider_id = provider_id_for_pack(&item.pack.path, &item.pack.pack_id, provider_map);
     let env_value = resolve_env(secrets_env);
 
@@ -4602,34 +5743,48 @@ fn run_plan_item(
                     .map(|entry| format!("  - {entry}"))
                     .collect::<Vec<_>>()
                     .join("\n");
-                println!(
+                writeln!(
+                    out,
                     "[warn] skip setup domain={} tenant={} provider={}: missing secrets:\n{formatted}",
                     domains::domain_name(domain),
                     tenant,
                     provider_id
-                );
+                )?;
                 return Ok(());
             }
             Ok(None) => {}
             Err(err) => {
-                println!(
+                writeln!(
+                    out,
                     "[warn] skip setup domain={} tenant={} provider={}: secrets check failed: {err}",
                     domains::domain_name(domain),
                     tenant,
                     provider_id
-                );
+                )?;
                 return Ok(());
             }
         }
     }
 
     let setup_values = if action == DomainAction::Setup {
-        Some(collect_setup_answers(
+        let stored = SecretAnswerStore::new(root, &env_value, tenant, team, &provider_id);
+        let mut answers = collect_setup_answers(
             &item.pack.path,
             &item.pack.pack_id,
             setup_answers,
             interactive,
-        )?)
+            Some(&stored),
+        )?;
+        store_secret_answers(
+            root,
+            &env_value,
+            tenant,
+            team,
+            &item.pack.path,
+            &provider_id,
+            &mut answers,
+        )?;
+        Some(answers)
     } else {
         None
     };
@@ -4725,12 +5880,13 @@ fn run_plan_item(
         input["config"] = config.clone();
     }
     if demo_debug_enabled() {
-        println!(
+        writeln!(
+            out,
             "[demo] setup input pack={} flow={} input={}",
             item.pack.file_name,
             item.flow_id,
             serde_json::to_string(&input).unwrap_or_else(|_| "<invalid-json>".to_string())
-        );
+        )?;
     }
     if let Some(runner_binary) = runner_binary {
         let run_dir = state_layout::run_dir(state_root, domain, &item.pack.pack_id, &item.flow_id)?;
@@ -4780,14 +5936,15 @@ fn run_plan_item(
         }
         let exit = format_runner_exit(&output);
         if output.status.success() {
-            println!("{} {} -> {}", item.pack.file_name, item.flow_id, exit);
+            writeln!(out, "{} {} -> {}", item.pack.file_name, item.flow_id, exit)?;
         } else if let Some(summary) = summarize_runner_error(&output) {
-            println!(
+            writeln!(
+                out,
                 "{} {} -> {} ({})",
                 item.pack.file_name, item.flow_id, exit, summary
-            );
+            )?;
         } else {
-            println!("{} {} -> {}", item.pack.file_name, item.flow_id, exit);
+            writeln!(out, "{} {} -> {}", item.pack.file_name, item.flow_id, exit)?;
         }
     } else {
         let output = runner_exec::run_provider_pack_flow(runner_exec::RunRequest {
@@ -4845,10 +6002,11 @@ fn run_plan_item(
                 );
             }
         }
-        println!(
+        writeln!(
+            out,
             "{} {} -> {:?}",
             item.pack.file_name, item.flow_id, output.result.status
-        );
+        )?;
     }
 
     Ok(())
@@ -4949,7 +6107,7 @@ fn extract_config_for_envelope(parsed: Option<&JsonValue>) -> Option<JsonValue>
 pub(crate) fn provider_id_for_pack(
     pack_path: &Path,
     fallback: &str,
-    provider_map: Option<&std::collections::BTreeMap<PathBuf, discovery::DetectedProvider>>,
+    provider_map: Option<&ProviderMap>,
 ) -> String {
     provider_map
         .and_then(|map| map.get(pack_path))
@@ -5046,9 +6204,7 @@ fn build_input_payload(
 fn read_public_base_url(root: &Path, tenant: &str, team: Option<&str>) -> Option<String> {
     let team_id = team.unwrap_or("default");
     let paths = crate::runtime_state::RuntimePaths::new(root.join("state"), tenant, team_id);
-    let path = crate::cloudflared::public_url_path(&paths);
-    let contents = std::fs::read_to_string(path).ok()?;
-    crate::cloudflared::parse_public_url(&contents)
+    crate::tunnel::read_public_url(&paths).ok().flatten()
 }
 
 fn parse_kv(input: &str) -> anyhow::Result<(String, JsonValue)> {
@@ -5306,6 +6462,43 @@ mod tests {
     use super::*;
     use std::{collections::BTreeSet, path::PathBuf};
 
+    #[test]
+    fn tenant_selection_picks_resolved_targets() {
+        let temp = tempfile::tempdir().unwrap();
+        let bundle = temp.path();
+        let resolved = bundle.join("state").join("resolved");
+        fs::create_dir_all(&resolved).unwrap();
+        for name in ["acme.yaml", "acme.ops.yaml", "globex.yaml"] {
+            fs::write(resolved.join(name), "").unwrap();
+        }
+        let names = |targets: Vec<DemoBundleTarget>| {
+            targets
+                .iter()
+                .map(|target| format!("{}.{}", target.tenant, target.team_id()))
+                .collect::<Vec<_>>()
+        };
+
+        let all = select_bundle_run_targets(bundle, &TenantSelection::parse(None), None).unwrap();
+        assert_eq!(names(all), ["acme.default", "acme.ops", "globex.default"]);
+        let listed =
+            select_bundle_run_targets(bundle, &TenantSelection::parse(Some("globex, acme")), None)
+                .unwrap();
+        assert_eq!(
+            names(listed),
+            ["acme.default", "acme.ops", "globex.default"]
+        );
+        let team =
+            select_bundle_run_targets(bundle, &TenantSelection::parse(Some("all")), Some("ops"))
+                .unwrap();
+        assert_eq!(names(team), ["acme.ops"]);
+        assert!(TenantSelection::parse(Some("acme,globex")).is_multi());
+        assert!(!TenantSelection::parse(Some("acme")).is_multi());
+
+        let empty = tempfile::tempdir().unwrap();
+        let err = select_bundle_run_targets(empty.path(), &TenantSelection::All, None).unwrap_err();
+        assert!(err.to_string().contains("run demo setup first"), "{err}");
+    }
+
     #[test]
     fn parse_kv_infers_basic_types() {
         let (key, value) = parse_kv("a=1").unwrap();
@@ -0,0 +1,273 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use serde_json::Value as JsonValue;
+
+use crate::config;
+use crate::discovery;
+use crate::provider_config_envelope::{self, ConfigEnvelope, EnvelopeChange, EnvelopeRevision};
+
+#[derive(Parser)]
+#[command(
+    about = "Inspect and roll back provider config envelopes.",
+    long_about = "Every provider setup records the written config envelope under state/runtime/<tenant>[.<team>]/providers/<provider>/history. These commands list, compare, and restore those revisions."
+)]
+pub struct DemoConfigCommand {
+    #[command(subcommand)]
+    command: DemoConfigSubcommand,
+}
+
+#[derive(Subcommand)]
+enum DemoConfigSubcommand {
+    History(DemoConfigHistoryArgs),
+    Diff(DemoConfigDiffArgs),
+    Rollback(DemoConfigRollbackArgs),
+}
+
+#[derive(Parser, Clone)]
+struct ConfigTargetArgs {
+    #[arg(long, default_value = ".")]
+    bundle: PathBuf,
+    #[arg(long, default_value = "demo")]
+    tenant: String,
+    #[arg(long)]
+    team: Option<String>,
+}
+
+impl ConfigTargetArgs {
+    /// `demo setup` keeps provider state under `runtime/<tenant>`, `demo start`
+    /// under `runtime/<tenant>.<team>`. Without `--team` the first layout that
+    /// has the provider wins, falling back to team `default`.
+    fn providers_root(&self, provider: &str) -> PathBuf {
+        let runtime = self.bundle.join("state").join("runtime");
+        if let Some(team) = self.team.as_deref() {
+            return runtime
+                .join(format!("{}.{team}", self.tenant))
+                .join("providers");
+        }
+        let tenant_root = runtime.join(&self.tenant).join("providers");
+        if tenant_root.join(provider).exists() {
+            return tenant_root;
+        }
+        runtime
+            .join(format!("{}.default", self.tenant))
+            .join("providers")
+    }
+
+    fn pack_path(&self, provider: &str) -> anyhow::Result<Option<PathBuf>> {
+        let config_path = self.bundle.join("greentic.demo.yaml");
+        if config_path.exists()
+            && let Some(pack) = config::load_demo_config(&config_path)?
+                .providers
+                .and_then(|mut providers| providers.remove(provider))
+                .and_then(|provider| provider.pack)
+        {
+            let pack = PathBuf::from(pack);
+            return Ok(Some(if pack.is_absolute() {
+                pack
+            } else {
+                self.bundle.join(pack)
+            }));
+        }
+        Ok(discovery::discover(&self.bundle)?
+            .providers
+            .into_iter()
+            .find(|detected| detected.provider_id == provider)
+            .map(|detected| detected.pack_path))
+    }
+}
+
+#[derive(Parser)]
+#[command(
+    about = "List recorded config envelopes for a provider.",
+    long_about = "Prints each revision with its timestamp, operation id, describe hash, and resolved pack digest. The newest revision is marked when it matches the current envelope.",
+    after_help = "Main options:\n  <PROVIDER>\n\nOptional options:\n  --bundle <DIR> (default: .)\n  --tenant <TENANT> (default: demo)\n  --team <TEAM>\n  --json"
+)]
+struct DemoConfigHistoryArgs {
+    #[arg(value_name = "PROVIDER")]
+    provider: String,
+    #[command(flatten)]
+    target: ConfigTargetArgs,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Parser)]
+#[command(
+    about = "Compare two config envelope revisions.",
+    long_about = "Without revisions, compares the two newest. With one revision, compares it to the newest.",
+    after_help = "Main options:\n  <PROVIDER> [REV] [REV]\n\nOptional options:\n  --bundle <DIR> (default: .)\n  --tenant <TENANT> (default: demo)\n  --team <TEAM>\n  --json"
+)]
+struct DemoConfigDiffArgs {
+    #[arg(value_name = "PROVIDER")]
+    provider: String,
+    #[arg(value_name = "REV", num_args = 0..=2)]
+    revs: Vec<u64>,
+    #[command(flatten)]
+    target: ConfigTargetArgs,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Parser)]
+#[command(
+    about = "Restore an earlier config envelope revision.",
+    long_about = "Writes the revision back as the provider's current envelope and records it as a new revision. The revision's describe hash must match the provider pack unless --allow-contract-change is given.",
+    after_help = "Main options:\n  <PROVIDER> <REV>\n\nOptional options:\n  --bundle <DIR> (default: .)\n  --tenant <TENANT> (default: demo)\n  --team <TEAM>\n  --allow-contract-change"
+)]
+struct DemoConfigRollbackArgs {
+    #[arg(value_name = "PROVIDER")]
+    provider: String,
+    #[arg(value_name = "REV")]
+    rev: u64,
+    #[command(flatten)]
+    target: ConfigTargetArgs,
+    #[arg(long)]
+    allow_contract_change: bool,
+}
+
+impl DemoConfigCommand {
+    pub fn run(self) -> anyhow::Result<()> {
+        match self.command {
+            DemoConfigSubcommand::History(args) => args.run(),
+            DemoConfigSubcommand::Diff(args) => args.run(),
+            DemoConfigSubcommand::Rollback(args) => args.run(),
+        }
+    }
+}
+
+impl DemoConfigHistoryArgs {
+    fn run(self) -> anyhow::Result<()> {
+        let providers_root = self.target.providers_root(&self.provider);
+        let history =
+            provider_config_envelope::list_envelope_history(&providers_root, &self.provider)?;
+        let current = provider_config_envelope::read_provider_config_envelope(
+            &providers_root,
+            &self.provider,
+        )?;
+        if history.is_empty() && !self.json {
+            println!(
+                "no config history for {} in {}",
+                self.provider,
+                providers_root.display()
+            );
+            return Ok(());
+        }
+        let current_rev = history
+            .last()
+            .filter(|latest| {
+                current
+                    .as_ref()
+                    .is_some_and(|current| same_envelope(current, &latest.envelope))
+            })
+            .map(|latest| latest.rev);
+        for revision in &history {
+            let envelope = &revision.envelope;
+            if self.json {
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "rev": revision.rev,
+                        "current": Some(revision.rev) == current_rev,
+                        "updated_at": envelope.updated_at,
+                        "operation_id": envelope.operation_id,
+                        "describe_hash": envelope.describe_hash,
+                        "resolved_digest": envelope.resolved_digest,
+                    })
+                );
+                continue;
+            }
+            let marker = if Some(revision.rev) == current_rev {
+                "*"
+            } else {
+                " "
+            };
+            println!(
+                "{marker} {:>3} {} operation_id={} describe_hash={} resolved_digest={}",
+                revision.rev,
+                envelope.updated_at.as_deref().unwrap_or("-"),
+                envelope.operation_id,
+                envelope.describe_hash,
+                envelope.resolved_digest,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl DemoConfigDiffArgs {
+    fn run(self) -> anyhow::Result<()> {
+        let providers_root = self.target.providers_root(&self.provider);
+        let history =
+            provider_config_envelope::list_envelope_history(&providers_root, &self.provider)?;
+        let latest = history.last().map(|revision| revision.rev).ok_or_else(|| {
+            anyhow::anyhow!(
+                "no config history for {} in {}",
+                self.provider,
+                providers_root.display()
+            )
+        })?;
+        let (old, new) = match self.revs.as_slice() {
+            [] => (latest.saturating_sub(1).max(1), latest),
+            [old] => (*old, latest),
+            [old, new, ..] => (*old, *new),
+        };
+        let find = |rev: u64| -> anyhow::Result<&EnvelopeRevision> {
+            history
+                .iter()
+                .find(|revision| revision.rev == rev)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("provider {} has no config revision {rev}", self.provider)
+                })
+        };
+        let changes =
+            provider_config_envelope::diff_envelopes(&find(old)?.envelope, &find(new)?.envelope);
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&changes)?);
+            return Ok(());
+        }
+        if changes.is_empty() {
+            println!("revisions {old} and {new} are identical");
+            return Ok(());
+        }
+        println!("--- rev {old}\n+++ rev {new}");
+        for change in changes {
+            println!("{}", render_change(&change));
+        }
+        Ok(())
+    }
+}
+
+impl DemoConfigRollbackArgs {
+    fn run(self) -> anyhow::Result<()> {
+        let providers_root = self.target.providers_root(&self.provider);
+        let pack_path = self.target.pack_path(&self.provider)?;
+        let revision = provider_config_envelope::rollback_provider_config_envelope(
+            &providers_root,
+            &self.provider,
+            self.rev,
+            pack_path.as_deref(),
+            self.allow_contract_change,
+        )?;
+        println!(
+            "restored {} config revision {} as revision {}",
+            self.provider, self.rev, revision.rev
+        );
+        Ok(())
+    }
+}
+
+fn same_envelope(left: &ConfigEnvelope, right: &ConfigEnvelope) -> bool {
+    left.updated_at == right.updated_at
+        && provider_config_envelope::diff_envelopes(left, right).is_empty()
+}
+
+fn render_change(change: &EnvelopeChange) -> String {
+    let show = |value: &JsonValue| serde_json::to_string(value).unwrap_or_default();
+    match (&change.old, &change.new) {
+        (Some(old), Some(new)) => format!("~ {}: {} -> {}", change.path, show(old), show(new)),
+        (None, Some(new)) => format!("+ {}: {}", change.path, show(new)),
+        (Some(old), None) => format!("- {}: {}", change.path, show(old)),
+        (None, None) => format!("  {}", change.path),
+    }
+}
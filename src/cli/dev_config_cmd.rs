@@ -0,0 +1,233 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::config_layers;
+use crate::config_schema::{self, ConfigFile};
+
+#[derive(Parser)]
+#[command(
+    about = "Validate, show or export the schema of project configuration.",
+    long_about = "greentic.yaml and greentic.demo.yaml are checked against bundled JSON Schemas after ${VAR:-default} interpolation and profile overlays. Unknown keys, wrong types and invalid values are reported with file, line and column."
+)]
+pub struct DevConfigCommand {
+    #[command(subcommand)]
+    command: DevConfigSubcommand,
+}
+
+#[derive(Subcommand)]
+enum DevConfigSubcommand {
+    Validate(DevConfigValidateArgs),
+    Show(DevConfigShowArgs),
+    Schema(DevConfigSchemaArgs),
+}
+
+#[derive(Parser)]
+#[command(
+    about = "Validate greentic.yaml and greentic.demo.yaml.",
+    long_about = "Without --file, validates greentic.yaml and greentic.demo.yaml in the project root when present. Files named *.demo.yaml are checked against the demo schema, everything else against the greentic.yaml schema. With --profile, demo files are validated with their <name>.<profile>.yaml overlay merged on top.",
+    after_help = "Main options:\n  (none)\n\nOptional options:\n  --file <PATH>...\n  --profile <NAME>\n  --project-root <PATH> (default: current directory)"
+)]
+struct DevConfigValidateArgs {
+    #[arg(long)]
+    file: Vec<PathBuf>,
+    #[arg(long)]
+    profile: Option<String>,
+    #[arg(long)]
+    project_root: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+#[command(
+    about = "Print the effective configuration and where each value came from.",
+    long_about = "Interpolates ${VAR:-default} placeholders and merges the <name>.<profile>.yaml overlay over demo files, then prints every value with its origin: base(file:line) or overlay(profile: file:line), followed by env(VAR) or default(VAR) for interpolated values.",
+    after_help = "Main options:\n  (none)\n\nOptional options:\n  --file <PATH>...\n  --profile <NAME>\n  --format <text|yaml> (default: text)\n  --project-root <PATH> (default: current directory)"
+)]
+struct DevConfigShowArgs {
+    #[arg(long)]
+    file: Vec<PathBuf>,
+    #[arg(long)]
+    profile: Option<String>,
+    #[arg(long, value_enum, default_value_t = ConfigShowFormat::Text)]
+    format: ConfigShowFormat,
+    #[arg(long)]
+    project_root: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ConfigShowFormat {
+    /// One `key=value [origin]` line per value.
+    Text,
+    /// The merged document, without origins.
+    Yaml,
+}
+
+#[derive(Parser)]
+#[command(
+    about = "Print the JSON Schema for a config file.",
+    long_about = "Prints the bundled schema so editors can validate and complete greentic.yaml or greentic.demo.yaml.",
+    after_help = "Main options:\n  [greentic|demo] (default: greentic)\n\nOptional options:\n  --output <PATH>"
+)]
+struct DevConfigSchemaArgs {
+    #[arg(value_enum, default_value_t = ConfigSchemaArg::Greentic)]
+    kind: ConfigSchemaArg,
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ConfigSchemaArg {
+    Greentic,
+    Demo,
+}
+
+impl From<ConfigSchemaArg> for ConfigFile {
+    fn from(value: ConfigSchemaArg) -> Self {
+        match value {
+            ConfigSchemaArg::Greentic => ConfigFile::Operator,
+            ConfigSchemaArg::Demo => ConfigFile::Demo,
+        }
+    }
+}
+
+impl DevConfigCommand {
+    pub fn run(self) -> anyhow::Result<()> {
+        match self.command {
+            DevConfigSubcommand::Validate(args) => args.run(),
+            DevConfigSubcommand::Show(args) => args.run(),
+            DevConfigSubcommand::Schema(args) => args.run(),
+        }
+    }
+}
+
+impl DevConfigValidateArgs {
+    fn run(self) -> anyhow::Result<()> {
+        let mut issues = Vec::new();
+        for (kind, path) in config_files(self.file, self.project_root)? {
+            let layered = config_layers::load(kind, &path, profile_for(kind, &self.profile))?;
+            if layered.issues.is_empty() {
+                println!("{}: ok", display_files(&layered.files));
+            }
+            issues.extend(layered.issues);
+        }
+        for issue in &issues {
+            println!("{issue}");
+        }
+        match issues.len() {
+            0 => Ok(()),
+            count => Err(anyhow::anyhow!("{count} configuration error(s)")),
+        }
+    }
+}
+
+impl DevConfigShowArgs {
+    fn run(self) -> anyhow::Result<()> {
+        for (index, (kind, path)) in config_files(self.file, self.project_root)?
+            .into_iter()
+            .enumerate()
+        {
+            let layered = config_layers::load(kind, &path, profile_for(kind, &self.profile))?;
+            config_schema::ensure_valid(&layered.issues)?;
+            if index > 0 {
+                println!();
+            }
+            println!("# {}", display_files(&layered.files));
+            match self.format {
+                ConfigShowFormat::Text => {
+                    for (key, value, origin) in layered.leaves() {
+                        match origin {
+                            Some(origin) => println!("{key}={value} [{origin}]"),
+                            None => println!("{key}={value}"),
+                        }
+                    }
+                }
+                ConfigShowFormat::Yaml => print!("{}", serde_yaml_bw::to_string(&layered.value)?),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `--file` paths, or greentic.yaml and greentic.demo.yaml in the project
+/// root when present.
+fn config_files(
+    files: Vec<PathBuf>,
+    project_root: Option<PathBuf>,
+) -> anyhow::Result<Vec<(ConfigFile, PathBuf)>> {
+    if !files.is_empty() {
+        return files
+            .into_iter()
+            .map(|path| {
+                if path.exists() {
+                    Ok((kind_for(&path), path))
+                } else {
+                    Err(anyhow::anyhow!("config file not found: {}", path.display()))
+                }
+            })
+            .collect();
+    }
+    let root = match project_root {
+        Some(root) => root,
+        None => std::env::current_dir()?,
+    };
+    let files = [ConfigFile::Operator, ConfigFile::Demo]
+        .into_iter()
+        .map(|kind| (kind, root.join(kind.file_name())))
+        .filter(|(_, path)| path.exists())
+        .collect::<Vec<_>>();
+    if files.is_empty() {
+        return Err(anyhow::anyhow!(
+            "no greentic.yaml or greentic.demo.yaml in {}",
+            root.display()
+        ));
+    }
+    Ok(files)
+}
+
+/// Profiles only select overlays for demo config.
+fn profile_for(kind: ConfigFile, profile: &Option<String>) -> Option<&str> {
+    match kind {
+        ConfigFile::Demo => profile.as_deref(),
+        ConfigFile::Operator => None,
+    }
+}
+
+fn display_files(files: &[PathBuf]) -> String {
+    files
+        .iter()
+        .map(|file| file.display().to_string())
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+impl DevConfigSchemaArgs {
+    fn run(self) -> anyhow::Result<()> {
+        let schema = ConfigFile::from(self.kind).schema_text();
+        match self.output {
+            Some(path) => {
+                if let Some(parent) = path
+                    .parent()
+                    .filter(|parent| !parent.as_os_str().is_empty())
+                {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&path, schema)?;
+                println!("Schema written to {}", path.display());
+            }
+            None => print!("{schema}"),
+        }
+        Ok(())
+    }
+}
+
+fn kind_for(path: &std::path::Path) -> ConfigFile {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    if name.ends_with(".demo.yaml") || name.ends_with(".demo.yml") {
+        ConfigFile::Demo
+    } else {
+        ConfigFile::Operator
+    }
+}
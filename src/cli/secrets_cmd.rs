@@ -0,0 +1,425 @@
+use std::io::{IsTerminal, Read};
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use greentic_secrets_lib::SeedDoc;
+
+use crate::secret_audit::{self, SecretAuditQuery};
+use crate::secret_rotation::{self, RotationOptions};
+use crate::secret_value::SecretValue;
+use crate::secrets_admin::{self, SecretStatus, SecretsScope};
+use crate::secrets_gate::{self, SecretsManagerHandle};
+use crate::secrets_setup::resolve_env;
+
+#[derive(Parser)]
+#[command(
+    about = "Manage bundle secrets through the configured secrets backend.",
+    long_about = "Lists, reads (redacted), writes, deletes, imports, and exports secrets for a tenant/team using the backend selected for the bundle."
+)]
+pub struct DemoSecretsCommand {
+    #[command(subcommand)]
+    command: DemoSecretsSubcommand,
+}
+
+#[derive(Subcommand)]
+enum DemoSecretsSubcommand {
+    List(DemoSecretsListArgs),
+    Get(DemoSecretsGetArgs),
+    Set(DemoSecretsSetArgs),
+    Rm(DemoSecretsRmArgs),
+    Import(DemoSecretsImportArgs),
+    Export(DemoSecretsExportArgs),
+    Rotate(DemoSecretsRotateArgs),
+    Audit(DemoSecretsAuditArgs),
+}
+
+#[derive(Parser, Clone)]
+struct SecretsTargetArgs {
+    #[arg(long, default_value = ".")]
+    bundle: PathBuf,
+    #[arg(long, default_value = "demo")]
+    tenant: String,
+    #[arg(long)]
+    team: Option<String>,
+    #[arg(long)]
+    secrets_env: Option<String>,
+}
+
+impl SecretsTargetArgs {
+    /// Resolves the handle and scope.
+    fn open(&self) -> anyhow::Result<(SecretsManagerHandle, SecretsScope)> {
+        let handle = secrets_gate::resolve_secrets_manager(
+            &self.bundle,
+            &self.tenant,
+            self.team.as_deref(),
+        )?;
+        let scope = SecretsScope {
+            env: resolve_env(self.secrets_env.as_deref()),
+            tenant: self.tenant.clone(),
+            team: self.team.clone(),
+        };
+        Ok((handle, scope))
+    }
+
+    /// Like [`Self::open`], for commands that write: rotated values past their
+    /// grace period are pruned first.
+    fn open_for_write(&self) -> anyhow::Result<(SecretsManagerHandle, SecretsScope)> {
+        let (handle, scope) = self.open()?;
+        secret_rotation::prune_expired(&handle, &self.bundle, &scope)?;
+        Ok((handle, scope))
+    }
+}
+
+#[derive(Parser)]
+#[command(
+    about = "List secrets declared by the bundle or present in the store.",
+    long_about = "Prints each secret URI with its provider and status. Values are always redacted.",
+    after_help = "Main options:\n  --bundle <DIR> (default: .)\n\nOptional options:\n  --tenant <TENANT> (default: demo)\n  --team <TEAM>\n  --provider <PROVIDER>\n  --secrets-env <ENV>"
+)]
+struct DemoSecretsListArgs {
+    #[command(flatten)]
+    target: SecretsTargetArgs,
+    #[arg(long)]
+    provider: Option<String>,
+}
+
+#[derive(Parser)]
+#[command(
+    about = "Show a single secret (redacted).",
+    long_about = "Reads a secret by URI or <provider>/<key> and prints its redacted value and length.",
+    after_help = "Main options:\n  <NAME>\n\nOptional options:\n  --bundle <DIR> (default: .)\n  --tenant <TENANT> (default: demo)\n  --team <TEAM>\n  --secrets-env <ENV>"
+)]
+struct DemoSecretsGetArgs {
+    #[arg(value_name = "NAME")]
+    name: String,
+    #[command(flatten)]
+    target: SecretsTargetArgs,
+}
+
+#[derive(Parser)]
+#[command(
+    about = "Write a secret value.",
+    long_about = "Reads the value from stdin, or prompts for it without echo when stdin is a terminal.",
+    after_help = "Main options:\n  <NAME>\n\nOptional options:\n  --bundle <DIR> (default: .)\n  --tenant <TENANT> (default: demo)\n  --team <TEAM>\n  --secrets-env <ENV>"
+)]
+struct DemoSecretsSetArgs {
+    #[arg(value_name = "NAME")]
+    name: String,
+    #[command(flatten)]
+    target: SecretsTargetArgs,
+}
+
+#[derive(Parser)]
+#[command(
+    about = "Delete a secret.",
+    long_about = "Removes the secret from the configured backend.",
+    after_help = "Main options:\n  <NAME>\n\nOptional options:\n  --bundle <DIR> (default: .)\n  --tenant <TENANT> (default: demo)\n  --team <TEAM>\n  --secrets-env <ENV>"
+)]
+struct DemoSecretsRmArgs {
+    #[arg(value_name = "NAME")]
+    name: String,
+    #[command(flatten)]
+    target: SecretsTargetArgs,
+}
+
+#[derive(Parser)]
+#[command(
+    about = "Import secrets from a seeds document.",
+    long_about = "Writes every entry of a seeds.yaml document that belongs to the tenant/team (and --provider, when given).",
+    after_help = "Main options:\n  <FILE>\n\nOptional options:\n  --bundle <DIR> (default: .)\n  --tenant <TENANT> (default: demo)\n  --team <TEAM>\n  --provider <PROVIDER>\n  --secrets-env <ENV>"
+)]
+struct DemoSecretsImportArgs {
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+    #[command(flatten)]
+    target: SecretsTargetArgs,
+    #[arg(long)]
+    provider: Option<String>,
+}
+
+#[derive(Parser)]
+#[command(
+    about = "Export secrets as a seeds document.",
+    long_about = "Writes the present secrets of the tenant/team in seeds.yaml format. The output contains plaintext values.",
+    after_help = "Main options:\n  --out <FILE>\n\nOptional options:\n  --bundle <DIR> (default: .)\n  --tenant <TENANT> (default: demo)\n  --team <TEAM>\n  --provider <PROVIDER>\n  --secrets-env <ENV>"
+)]
+struct DemoSecretsExportArgs {
+    #[arg(long)]
+    out: PathBuf,
+    #[command(flatten)]
+    target: SecretsTargetArgs,
+    #[arg(long)]
+    provider: Option<String>,
+}
+
+#[derive(Parser)]
+#[command(
+    about = "Rotate a provider secret and re-run the provider's setup.",
+    long_about = "Writes a new value (read like `set`), keeps the previous one as <KEY>_previous for the grace period, and re-runs the provider's setup and verify flows. A failed flow restores the previous value; the previous value is kept only for that restore. Expired previous values are deleted on `demo start` and by `demo secrets set`, `rm`, `rotate`, and `import`. History is kept in <bundle>/state/secrets/rotations.json.",
+    after_help = "Main options:\n  <PROVIDER> <KEY>\n\nOptional options:\n  --bundle <DIR> (default: .)\n  --tenant <TENANT> (default: demo)\n  --team <TEAM>\n  --secrets-env <ENV>\n  --grace-hours <HOURS> (default: 24)\n  --runner-binary <PATH>\n  --public-base-url <URL>"
+)]
+struct DemoSecretsRotateArgs {
+    #[arg(value_name = "PROVIDER")]
+    provider: String,
+    #[arg(value_name = "KEY")]
+    key: String,
+    #[command(flatten)]
+    target: SecretsTargetArgs,
+    #[arg(long, default_value_t = 24)]
+    grace_hours: u32,
+    #[arg(long)]
+    runner_binary: Option<PathBuf>,
+    #[arg(long)]
+    public_base_url: Option<String>,
+}
+
+#[derive(Parser)]
+#[command(
+    about = "Show the secret access audit log.",
+    long_about = "Prints entries from <bundle>/state/audit/secrets.jsonl. Each entry records a read, write, or delete with its tenant, team, pack, URI, outcome, and correlation id. Values are never logged.",
+    after_help = "Main options:\n  --bundle <DIR> (default: .)\n\nOptional options:\n  --pack <PACK_ID>\n  --uri-prefix <PREFIX>\n  --since <RFC3339|AGE> (e.g. 2026-01-01T00:00:00Z, 12h, 7d)\n  --until <RFC3339|AGE>\n  --json"
+)]
+struct DemoSecretsAuditArgs {
+    #[arg(long, default_value = ".")]
+    bundle: PathBuf,
+    #[arg(long)]
+    pack: Option<String>,
+    #[arg(long)]
+    uri_prefix: Option<String>,
+    #[arg(long)]
+    since: Option<String>,
+    #[arg(long)]
+    until: Option<String>,
+    #[arg(long)]
+    json: bool,
+}
+
+impl DemoSecretsCommand {
+    pub fn run(self) -> anyhow::Result<()> {
+        match self.command {
+            DemoSecretsSubcommand::List(args) => args.run(),
+            DemoSecretsSubcommand::Get(args) => args.run(),
+            DemoSecretsSubcommand::Set(args) => args.run(),
+            DemoSecretsSubcommand::Rm(args) => args.run(),
+            DemoSecretsSubcommand::Import(args) => args.run(),
+            DemoSecretsSubcommand::Export(args) => args.run(),
+            DemoSecretsSubcommand::Rotate(args) => args.run(),
+            DemoSecretsSubcommand::Audit(args) => args.run(),
+        }
+    }
+}
+
+impl DemoSecretsListArgs {
+    fn run(self) -> anyhow::Result<()> {
+        let (handle, scope) = self.target.open()?;
+        let listings = secrets_admin::list_secrets(
+            &handle,
+            &self.target.bundle,
+            &scope,
+            self.provider.as_deref(),
+        )?;
+        if listings.is_empty() {
+            println!("no secrets for {}", scope.uri_prefix());
+            return Ok(());
+        }
+        for listing in listings {
+            let status = match &listing.status {
+                SecretStatus::Present(value) => format!("{value} ({} bytes)", value.len()),
+                SecretStatus::Placeholder => "placeholder (unresolved)".to_string(),
+                SecretStatus::Missing => "missing".to_string(),
+                SecretStatus::Error(err) => format!("error: {err}"),
+            };
+            let origin = if listing.declared {
+                "declared"
+            } else {
+                "stored"
+            };
+            println!(
+                "{} provider={} {origin} {status}",
+                listing.uri, listing.provider
+            );
+        }
+        Ok(())
+    }
+}
+
+impl DemoSecretsGetArgs {
+    fn run(self) -> anyhow::Result<()> {
+        let (handle, scope) = self.target.open()?;
+        let uri = scope.resolve_uri(&self.name)?;
+        let value = secrets_admin::get_secret(&handle, &uri)?;
+        println!("{uri} = {value} ({} bytes)", value.len());
+        Ok(())
+    }
+}
+
+impl DemoSecretsSetArgs {
+    fn run(self) -> anyhow::Result<()> {
+        let (handle, scope) = self.target.open_for_write()?;
+        let uri = scope.resolve_uri(&self.name)?;
+        let value = read_secret_value(&uri)?;
+        secrets_admin::set_secret(&handle, &uri, &value)?;
+        println!("set {uri} ({} bytes)", value.len());
+        Ok(())
+    }
+}
+
+impl DemoSecretsRmArgs {
+    fn run(self) -> anyhow::Result<()> {
+        let (handle, scope) = self.target.open_for_write()?;
+        let uri = scope.resolve_uri(&self.name)?;
+        secrets_admin::delete_secret(&handle, &uri)?;
+        println!("deleted {uri}");
+        Ok(())
+    }
+}
+
+impl DemoSecretsImportArgs {
+    fn run(self) -> anyhow::Result<()> {
+        let (handle, scope) = self.target.open_for_write()?;
+        let contents = std::fs::read_to_string(&self.file)
+            .with_context(|| format!("read {}", self.file.display()))?;
+        let doc: SeedDoc = serde_yaml_bw::from_str(&contents)
+            .with_context(|| format!("parse {}", self.file.display()))?;
+        let report =
+            secrets_admin::import_seed_doc(&handle, &scope, &doc, self.provider.as_deref())?;
+        for uri in &report.imported {
+            println!("imported {uri}");
+        }
+        if !report.skipped.is_empty() {
+            println!(
+                "skipped {} entries outside {} or the provider filter",
+                report.skipped.len(),
+                scope.uri_prefix()
+            );
+        }
+        for (uri, err) in &report.failed {
+            eprintln!("failed {uri}: {err}");
+        }
+        if !report.failed.is_empty() {
+            return Err(anyhow::anyhow!(
+                "{} secrets failed to import",
+                report.failed.len()
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl DemoSecretsExportArgs {
+    fn run(self) -> anyhow::Result<()> {
+        let (handle, scope) = self.target.open()?;
+        let listings = secrets_admin::list_secrets(
+            &handle,
+            &self.target.bundle,
+            &scope,
+            self.provider.as_deref(),
+        )?;
+        let doc = secrets_admin::export_seed_doc(&listings);
+        let contents = serde_yaml_bw::to_string(&doc).context("serialize seeds document")?;
+        if let Some(parent) = self
+            .out
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.out, contents)
+            .with_context(|| format!("write {}", self.out.display()))?;
+        println!(
+            "exported {} secrets to {}",
+            doc.entries.len(),
+            self.out.display()
+        );
+        Ok(())
+    }
+}
+
+impl DemoSecretsRotateArgs {
+    fn run(self) -> anyhow::Result<()> {
+        let (handle, scope) = self.target.open()?;
+        let uri = scope.resolve_uri(&format!("{}/{}", self.provider, self.key))?;
+        let value = read_secret_value(&uri)?;
+        let record = secret_rotation::rotate_secret(
+            &handle,
+            &self.target.bundle,
+            &scope,
+            &self.provider,
+            &self.key,
+            &value,
+            RotationOptions {
+                grace: chrono::Duration::hours(i64::from(self.grace_hours)),
+                runner_binary: self.runner_binary,
+                dev_settings: None,
+                public_base_url: self.public_base_url,
+            },
+        )?;
+        println!(
+            "rotated {} (previous value kept as {} until {})",
+            record.uri,
+            record.previous_uri,
+            record.grace_until.to_rfc3339()
+        );
+        Ok(())
+    }
+}
+
+impl DemoSecretsAuditArgs {
+    fn run(self) -> anyhow::Result<()> {
+        let now = chrono::Utc::now();
+        let bound = |value: Option<&str>| {
+            value
+                .map(|value| secret_audit::parse_time_bound(value, now))
+                .transpose()
+        };
+        let query = SecretAuditQuery {
+            pack_id: self.pack.clone(),
+            uri_prefix: self.uri_prefix.clone(),
+            since: bound(self.since.as_deref())?,
+            until: bound(self.until.as_deref())?,
+        };
+        let entries = secret_audit::query(&self.bundle, &query)?;
+        if entries.is_empty() && !self.json {
+            println!(
+                "no matching entries in {}",
+                secret_audit::audit_log_path(&self.bundle).display()
+            );
+            return Ok(());
+        }
+        for entry in entries {
+            if self.json {
+                println!("{}", serde_json::to_string(&entry)?);
+                continue;
+            }
+            println!(
+                "{} {} {} pack={} uri={} tenant={} team={} correlation_id={}",
+                entry.timestamp.to_rfc3339(),
+                entry.op.as_str(),
+                entry.outcome.as_str(),
+                entry.pack_id.as_deref().unwrap_or("-"),
+                entry.uri,
+                entry.tenant,
+                entry.team,
+                entry.correlation_id.as_deref().unwrap_or("-"),
+            );
+        }
+        Ok(())
+    }
+}
+
+fn read_secret_value(uri: &str) -> anyhow::Result<SecretValue<'static>> {
+    let value = if std::io::stdin().is_terminal() {
+        rpassword::prompt_password(format!("Value for {uri}: ")).context("read secret value")?
+    } else {
+        let mut value = String::new();
+        std::io::stdin()
+            .read_to_string(&mut value)
+            .context("read secret value from stdin")?;
+        value.trim_end_matches(['\r', '\n']).to_string()
+    };
+    if value.is_empty() {
+        return Err(anyhow::anyhow!("refusing to set an empty value for {uri}"));
+    }
+    Ok(SecretValue::owned(value.into_bytes()))
+}
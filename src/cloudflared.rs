@@ -6,7 +6,8 @@ use crate::runtime_state::{RuntimePaths, atomic_write};
 use crate::supervisor::{self, ServiceId, ServiceSpec};
 
 const SERVICE_ID: &str = "cloudflared";
-const URL_SUFFIX: &str = ".trycloudflare.com";
+/// Quick tunnels are served from random subdomains of this domain.
+pub const URL_SUFFIX: &str = ".trycloudflare.com";
 
 #[derive(Clone)]
 pub struct CloudflaredConfig {
@@ -14,6 +15,29 @@ pub struct CloudflaredConfig {
     pub local_port: u16,
     pub extra_args: Vec<String>,
     pub restart: bool,
+    /// When set, runs a pre-created named tunnel instead of an anonymous
+    /// `trycloudflare.com` quick tunnel.
+    pub named: Option<NamedTunnelConfig>,
+}
+
+/// A named cloudflared tunnel routed to a fixed DNS hostname. The public URL
+/// is derived from `hostname`, so it survives restarts.
+#[derive(Clone, Debug)]
+pub struct NamedTunnelConfig {
+    pub tunnel: String,
+    pub credentials_file: PathBuf,
+    pub hostname: String,
+}
+
+impl NamedTunnelConfig {
+    pub fn public_url(&self) -> String {
+        let host = self
+            .hostname
+            .trim()
+            .trim_start_matches("https://")
+            .trim_end_matches('/');
+        format!("https://{host}")
+    }
 }
 
 pub struct CloudflaredHandle {
@@ -22,6 +46,73 @@ pub struct CloudflaredHandle {
     pub log_path: PathBuf,
 }
 
+pub fn start_tunnel(
+    paths: &RuntimePaths,
+    config: &CloudflaredConfig,
+    log_path: &Path,
+) -> anyhow::Result<CloudflaredHandle> {
+    match config.named.as_ref() {
+        Some(named) => start_named_tunnel(paths, config, named, log_path),
+        None => start_quick_tunnel(paths, config, log_path),
+    }
+}
+
+pub fn start_named_tunnel(
+    paths: &RuntimePaths,
+    config: &CloudflaredConfig,
+    named: &NamedTunnelConfig,
+    log_path: &Path,
+) -> anyhow::Result<CloudflaredHandle> {
+    if !named.credentials_file.exists() {
+        return Err(anyhow::anyhow!(
+            "cloudflared credentials file not found: {}",
+            named.credentials_file.display()
+        ));
+    }
+    let pid_path = paths.pid_path(SERVICE_ID);
+    let url_path = public_url_path(paths);
+    let url = named.public_url();
+    if config.restart {
+        let _ = supervisor::stop_pidfile(&pid_path, 2_000);
+    }
+
+    let mut argv = vec![
+        config.binary.to_string_lossy().to_string(),
+        "tunnel".to_string(),
+        "--no-autoupdate".to_string(),
+        "run".to_string(),
+        "--credentials-file".to_string(),
+        named.credentials_file.to_string_lossy().to_string(),
+        "--url".to_string(),
+        format!("http://127.0.0.1:{}", config.local_port),
+    ];
+    argv.extend(config.extra_args.iter().cloned());
+    argv.push(named.tunnel.clone());
+
+    if let Some(pid) = reusable_pid(paths, &argv)? {
+        write_public_url(&url_path, &url)?;
+        return Ok(CloudflaredHandle {
+            url,
+            pid,
+            log_path: log_path.to_path_buf(),
+        });
+    }
+
+    let spec = ServiceSpec {
+        id: ServiceId::new(SERVICE_ID)?,
+        argv,
+        cwd: None,
+        env: BTreeMap::new(),
+    };
+    let handle = supervisor::spawn_service(paths, spec, Some(log_path.to_path_buf()))?;
+    write_public_url(&url_path, &url)?;
+    Ok(CloudflaredHandle {
+        url,
+        pid: handle.pid,
+        log_path: handle.log_path,
+    })
+}
+
 pub fn start_quick_tunnel(
     paths: &RuntimePaths,
     config: &CloudflaredConfig,
@@ -33,9 +124,16 @@ pub fn start_quick_tunnel(
         let _ = supervisor::stop_pidfile(&pid_path, 2_000);
     }
 
-    if let Some(pid) = read_pid(&pid_path)?
-        && supervisor::is_running(pid)
-    {
+    let mut argv = vec![
+        config.binary.to_string_lossy().to_string(),
+        "tunnel".to_string(),
+        "--url".to_string(),
+        format!("http://127.0.0.1:{}", config.local_port),
+        "--no-autoupdate".to_string(),
+    ];
+    argv.extend(config.extra_args.iter().cloned());
+
+    if let Some(pid) = reusable_pid(paths, &argv)? {
         let log_path_buf = log_path.to_path_buf();
         if let Some(url) = read_public_url(&url_path)? {
             return Ok(CloudflaredHandle {
@@ -53,15 +151,6 @@ pub fn start_quick_tunnel(
         });
     }
 
-    let mut argv = vec![
-        config.binary.to_string_lossy().to_string(),
-        "tunnel".to_string(),
-        "--url".to_string(),
-        format!("http://127.0.0.1:{}", config.local_port),
-        "--no-autoupdate".to_string(),
-    ];
-    argv.extend(config.extra_args.iter().cloned());
-
     let spec = ServiceSpec {
         id: ServiceId::new(SERVICE_ID)?,
         argv,
@@ -79,6 +168,26 @@ pub fn start_quick_tunnel(
     })
 }
 
+/// The pid of a running cloudflared that was started with `argv`. One started
+/// with other arguments (a quick tunnel when a named tunnel is wanted, a
+/// different port, ...) is stopped so the caller spawns a fresh one.
+fn reusable_pid(paths: &RuntimePaths, argv: &[String]) -> anyhow::Result<Option<u32>> {
+    let pid_path = paths.pid_path(SERVICE_ID);
+    let Some(pid) = read_pid(&pid_path)? else {
+        return Ok(None);
+    };
+    if !supervisor::is_running(pid) {
+        return Ok(None);
+    }
+    let resolved = supervisor::read_resolved(paths, &ServiceId::new(SERVICE_ID)?)?;
+    if resolved.is_some_and(|resolved| resolved.argv == argv) {
+        return Ok(Some(pid));
+    }
+    supervisor::stop_pidfile(&pid_path, 2_000)?;
+    let _ = std::fs::remove_file(public_url_path(paths));
+    Ok(None)
+}
+
 pub fn public_url_path(paths: &RuntimePaths) -> PathBuf {
     paths.runtime_root().join("public_base_url.txt")
 }
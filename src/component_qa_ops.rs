@@ -335,7 +335,35 @@ fn supports_component_qa_contract(pack_path: &Path) -> anyhow::Result<bool> {
     Ok(supports)
 }
 
-fn read_pack_config_schema(pack_path: &Path) -> anyhow::Result<Option<JsonValue>> {
+const QA_CONTRACT_OPS: [&str; 3] = ["qa-spec", "apply-answers", "i18n-keys"];
+
+/// Providers that declare some, but not all, of the component QA contract
+/// ops, with the ops they are missing. Packs whose manifest cannot be decoded
+/// as a full pack manifest have no gaps.
+pub(crate) fn qa_contract_gaps(
+    pack_path: &Path,
+) -> anyhow::Result<Vec<(String, Vec<&'static str>)>> {
+    let bytes = read_manifest_cbor_bytes(pack_path)?;
+    let Ok(decoded) = decode_pack_manifest(&bytes) else {
+        return Ok(Vec::new());
+    };
+    let Some(provider_ext) = decoded.provider_extension_inline() else {
+        return Ok(Vec::new());
+    };
+    let mut gaps = Vec::new();
+    for provider in &provider_ext.providers {
+        let missing = QA_CONTRACT_OPS
+            .into_iter()
+            .filter(|required| !provider.ops.iter().any(|op| op == required))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() && missing.len() < QA_CONTRACT_OPS.len() {
+            gaps.push((provider.provider_type.clone(), missing));
+        }
+    }
+    Ok(gaps)
+}
+
+pub fn read_pack_config_schema(pack_path: &Path) -> anyhow::Result<Option<JsonValue>> {
     let bytes = read_manifest_cbor_bytes(pack_path)?;
     let decoded = decode_pack_manifest(&bytes)
         .with_context(|| format!("decode manifest.cbor {}", pack_path.display()))?;
@@ -357,7 +385,7 @@ fn read_manifest_cbor_bytes(pack_path: &Path) -> anyhow::Result<Vec<u8>> {
     Ok(bytes)
 }
 
-fn validate_config_strict(config: &JsonValue, schema: &JsonValue) -> Option<String> {
+pub(crate) fn validate_config_strict(config: &JsonValue, schema: &JsonValue) -> Option<String> {
     if schema.is_object()
         && let Err(err) = jsonschema::validate(schema, config)
     {
@@ -3,6 +3,8 @@ use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 
+use crate::config_layers;
+use crate::config_schema::ConfigFile;
 use crate::dev_mode::DevSettings;
 
 #[derive(Clone, Debug, Deserialize, Default)]
@@ -13,6 +15,9 @@ pub struct OperatorConfig {
     pub services: Option<OperatorServicesConfig>,
     #[serde(default)]
     pub binaries: BTreeMap<String, String>,
+    /// Semver requirements per binary name, checked against `--version`.
+    #[serde(default)]
+    pub binary_versions: BTreeMap<String, String>,
 }
 #[derive(Clone, Copy, Debug, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -74,7 +79,7 @@ pub fn load_operator_config(root: &Path) -> anyhow::Result<Option<OperatorConfig
     {
         return Ok(None);
     }
-    let config: OperatorConfig = serde_yaml_bw::from_str(&contents)?;
+    let config = config_layers::load(ConfigFile::Operator, &path, None)?.deserialize()?;
     Ok(Some(config))
 }
 
@@ -121,6 +126,71 @@ pub struct DemoServicesConfig {
     pub subscriptions: DemoSubscriptionsConfig,
     #[serde(default)]
     pub events: DemoEventsConfig,
+    #[serde(default)]
+    pub tunnel: DemoTunnelConfig,
+}
+
+#[derive(Clone, Debug, Deserialize, Default)]
+pub struct DemoTunnelConfig {
+    #[serde(default)]
+    pub provider: DemoTunnelProvider,
+    #[serde(default)]
+    pub cloudflared: DemoCloudflaredTunnelConfig,
+    #[serde(default)]
+    pub command: DemoCommandTunnelConfig,
+    /// Fixed public URL used by the `static` provider.
+    #[serde(default)]
+    pub public_url: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DemoTunnelProvider {
+    #[default]
+    Cloudflared,
+    Ngrok,
+    Command,
+    Static,
+}
+
+#[derive(Clone, Debug, Deserialize, Default)]
+pub struct DemoCloudflaredTunnelConfig {
+    /// Named tunnel to run; requires `hostname` and `credentials_file`.
+    #[serde(default)]
+    pub tunnel: Option<String>,
+    #[serde(default)]
+    pub credentials_file: Option<String>,
+    #[serde(default)]
+    pub hostname: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DemoCommandTunnelConfig {
+    #[serde(default)]
+    pub binary: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub url_suffix: Option<String>,
+    #[serde(default = "default_tunnel_timeout_seconds")]
+    pub timeout_seconds: u64,
+}
+
+impl Default for DemoCommandTunnelConfig {
+    fn default() -> Self {
+        Self {
+            binary: None,
+            args: Vec::new(),
+            url_suffix: None,
+            timeout_seconds: default_tunnel_timeout_seconds(),
+        }
+    }
+}
+
+fn default_tunnel_timeout_seconds() -> u64 {
+    10
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -153,6 +223,10 @@ pub struct DemoGatewayConfig {
     pub port: u16,
     #[serde(default)]
     pub args: Vec<String>,
+    /// Proxies (CIDR or bare address) whose `X-Forwarded-For`/`Forwarded`
+    /// headers are trusted when resolving the ingress client address.
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -201,6 +275,11 @@ pub struct DemoSubscriptionsUniversalConfig {
     pub renew_interval_seconds: u64,
     #[serde(default = "default_universal_renew_skew")]
     pub renew_skew_minutes: u64,
+    /// First retry delay after a failed renewal; doubles per failure.
+    #[serde(default = "default_universal_retry_base")]
+    pub retry_base_seconds: u64,
+    #[serde(default = "default_universal_retry_max")]
+    pub retry_max_seconds: u64,
     #[serde(default)]
     pub desired: Vec<DemoDesiredSubscription>,
 }
@@ -210,6 +289,8 @@ impl Default for DemoSubscriptionsUniversalConfig {
         Self {
             renew_interval_seconds: default_universal_renew_interval(),
             renew_skew_minutes: default_universal_renew_skew(),
+            retry_base_seconds: default_universal_retry_base(),
+            retry_max_seconds: default_universal_retry_max(),
             desired: Vec::new(),
         }
     }
@@ -223,6 +304,14 @@ fn default_universal_renew_skew() -> u64 {
     10
 }
 
+fn default_universal_retry_base() -> u64 {
+    30
+}
+
+fn default_universal_retry_max() -> u64 {
+    1_800
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct DemoDesiredSubscription {
     pub provider: String,
@@ -277,6 +366,10 @@ pub struct DemoProviderConfig {
     pub setup_flow: Option<String>,
     #[serde(default)]
     pub verify_flow: Option<String>,
+    /// Source networks allowed to reach this provider's ingress routes.
+    /// Empty means unrestricted.
+    #[serde(default)]
+    pub allowed_cidrs: Vec<String>,
 }
 
 impl Default for DemoNatsConfig {
@@ -306,6 +399,7 @@ impl Default for DemoGatewayConfig {
             listen_addr: default_gateway_listen_addr(),
             port: default_gateway_port(),
             args: Vec::new(),
+            trusted_proxies: Vec::new(),
         }
     }
 }
@@ -340,9 +434,16 @@ impl Default for DemoEventsConfig {
 }
 
 pub fn load_demo_config(path: &Path) -> anyhow::Result<DemoConfig> {
-    let contents = std::fs::read_to_string(path)?;
-    let config: DemoConfig = serde_yaml_bw::from_str(&contents)?;
-    Ok(config)
+    load_demo_config_with_profile(path, None)
+}
+
+/// Loads `path` with the `<name>.<profile>.yaml` overlay next to it merged on
+/// top, when a profile is given.
+pub fn load_demo_config_with_profile(
+    path: &Path,
+    profile: Option<&str>,
+) -> anyhow::Result<DemoConfig> {
+    config_layers::load(ConfigFile::Demo, path, profile)?.deserialize()
 }
 
 fn config_binary_path(config: &OperatorConfig, name: &str, config_dir: &Path) -> Option<PathBuf> {
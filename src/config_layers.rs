@@ -0,0 +1,559 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value as JsonValue;
+
+use crate::config_schema::{self, ConfigFile, ConfigIssue, ParsedYaml, escape_pointer};
+
+/// The file a config value was written in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValueSource {
+    Base {
+        file: PathBuf,
+        line: usize,
+    },
+    /// `<name>.<profile>.yaml`, merged over the base file.
+    Overlay {
+        profile: String,
+        file: PathBuf,
+        line: usize,
+    },
+}
+
+/// How a `${...}` placeholder in a value was filled in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Substitution {
+    Env(String),
+    /// The variable was unset or empty; the `:-` default was used.
+    Default(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValueOrigin {
+    pub source: ValueSource,
+    pub substitutions: Vec<Substitution>,
+}
+
+impl fmt::Display for ValueOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = |file: &Path, line: &usize| {
+            let name = file.file_name().unwrap_or(file.as_os_str());
+            format!("{}:{line}", name.to_string_lossy())
+        };
+        match &self.source {
+            ValueSource::Base { file, line } => write!(f, "base({})", location(file, line))?,
+            ValueSource::Overlay {
+                profile,
+                file,
+                line,
+            } => write!(f, "overlay({profile}: {})", location(file, line))?,
+        }
+        for substitution in &self.substitutions {
+            match substitution {
+                Substitution::Env(var) => write!(f, " env({var})")?,
+                Substitution::Default(var) => write!(f, " default({var})")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A config file after `${VAR:-default}` interpolation and the optional
+/// profile overlay, with the origin of every value.
+#[derive(Clone, Debug)]
+pub struct LayeredConfig {
+    pub value: JsonValue,
+    /// Origin of every node in `value`, keyed by JSON pointer.
+    pub origins: BTreeMap<String, ValueOrigin>,
+    /// The base file followed by the overlay, when one was applied.
+    pub files: Vec<PathBuf>,
+    /// Syntax, interpolation and schema problems; `value` is only meaningful
+    /// when this is empty.
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl LayeredConfig {
+    /// Deserializes the effective config, failing on any recorded issue.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> anyhow::Result<T> {
+        config_schema::ensure_valid(&self.issues)?;
+        // Go through YAML text so values deserialize exactly as if they had
+        // been written in the file.
+        let yaml = serde_yaml_bw::to_string(&self.value)?;
+        Ok(serde_yaml_bw::from_str(&yaml)?)
+    }
+
+    /// Every scalar (and empty mapping or sequence) as a dotted key path with
+    /// its value and origin, in document order.
+    pub fn leaves(&self) -> Vec<(String, &JsonValue, Option<&ValueOrigin>)> {
+        let mut out = Vec::new();
+        collect_leaves(&self.value, String::new(), String::new(), self, &mut out);
+        out
+    }
+}
+
+fn collect_leaves<'a>(
+    value: &'a JsonValue,
+    path: String,
+    pointer: String,
+    config: &'a LayeredConfig,
+    out: &mut Vec<(String, &'a JsonValue, Option<&'a ValueOrigin>)>,
+) {
+    match value {
+        JsonValue::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                let child_pointer = format!("{pointer}/{}", escape_pointer(key));
+                collect_leaves(child, child_path, child_pointer, config, out);
+            }
+        }
+        JsonValue::Array(items) if !items.is_empty() => {
+            for (index, child) in items.iter().enumerate() {
+                collect_leaves(
+                    child,
+                    format!("{path}[{index}]"),
+                    format!("{pointer}/{index}"),
+                    config,
+                    out,
+                );
+            }
+        }
+        _ => out.push((path, value, config.origins.get(&pointer))),
+    }
+}
+
+/// `greentic.demo.yaml` + `ci` -> `greentic.demo.ci.yaml`, next to the base.
+pub fn overlay_path(base: &Path, profile: &str) -> PathBuf {
+    let stem = base
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = base
+        .extension()
+        .map(|extension| extension.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "yaml".to_string());
+    base.with_file_name(format!("{stem}.{profile}.{extension}"))
+}
+
+/// Loads `path`, interpolates environment variables and merges the overlay
+/// for `profile`, then validates the result against the schema for `kind`.
+/// Problems inside the files end up in [`LayeredConfig::issues`]; a missing
+/// file is an error.
+pub fn load(kind: ConfigFile, path: &Path, profile: Option<&str>) -> anyhow::Result<LayeredConfig> {
+    load_with_env(kind, path, profile, |name| std::env::var(name).ok())
+}
+
+pub(crate) fn load_with_env(
+    kind: ConfigFile,
+    path: &Path,
+    profile: Option<&str>,
+    env: impl Fn(&str) -> Option<String>,
+) -> anyhow::Result<LayeredConfig> {
+    let mut layers = vec![Layer::read(path, None, &env)?];
+    if let Some(profile) = profile {
+        let overlay = overlay_path(path, profile);
+        if !overlay.exists() {
+            return Err(anyhow::anyhow!(
+                "profile `{profile}` needs {} next to {}",
+                overlay.display(),
+                path.display()
+            ));
+        }
+        layers.push(Layer::read(&overlay, Some(profile), &env)?);
+    }
+
+    let files = layers.iter().map(|layer| layer.file.clone()).collect();
+    let mut issues = layers
+        .iter_mut()
+        .flat_map(|layer| std::mem::take(&mut layer.issues))
+        .collect::<Vec<_>>();
+    let mut value = JsonValue::Object(Default::default());
+    for layer in &layers {
+        if let Some(parsed) = &layer.parsed {
+            merge(&mut value, parsed.value.clone());
+        }
+    }
+    // A node comes from the topmost layer that has it: the overlay either
+    // replaced the base value or, for mappings, was merged into it.
+    let origin_of = |pointer: &str| {
+        layers
+            .iter()
+            .rev()
+            .find_map(|layer| layer.origins.get(pointer))
+    };
+    let mut origins = BTreeMap::new();
+    collect_origins(&value, String::new(), &origin_of, &mut origins);
+
+    if issues.is_empty() {
+        issues = config_schema::validate_value(kind, &value, |pointer, key| {
+            layers
+                .iter()
+                .rev()
+                .find_map(|layer| {
+                    let (line, column) = layer.parsed.as_ref()?.locate(pointer, key)?;
+                    Some((layer.file.clone(), line, column))
+                })
+                .unwrap_or_else(|| (layers[0].file.clone(), 1, 1))
+        });
+    }
+    Ok(LayeredConfig {
+        value,
+        origins,
+        files,
+        issues,
+    })
+}
+
+fn collect_origins<'a>(
+    value: &JsonValue,
+    pointer: String,
+    origin_of: &impl Fn(&str) -> Option<&'a ValueOrigin>,
+    out: &mut BTreeMap<String, ValueOrigin>,
+) {
+    if let Some(origin) = origin_of(&pointer) {
+        out.insert(pointer.clone(), origin.clone());
+    }
+    match value {
+        JsonValue::Object(map) => {
+            for (key, child) in map {
+                let child_pointer = format!("{pointer}/{}", escape_pointer(key));
+                collect_origins(child, child_pointer, origin_of, out);
+            }
+        }
+        JsonValue::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                collect_origins(child, format!("{pointer}/{index}"), origin_of, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Mappings merge key by key; anything else in `overlay` replaces `base`.
+fn merge(base: &mut JsonValue, overlay: JsonValue) {
+    match (base, overlay) {
+        (JsonValue::Object(base), JsonValue::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        // An empty document adds nothing.
+        (_, JsonValue::Null) => {}
+        (base, overlay) => *base = overlay,
+    }
+}
+
+struct Layer {
+    file: PathBuf,
+    parsed: Option<ParsedYaml>,
+    origins: BTreeMap<String, ValueOrigin>,
+    issues: Vec<ConfigIssue>,
+}
+
+impl Layer {
+    fn read(
+        file: &Path,
+        profile: Option<&str>,
+        env: &impl Fn(&str) -> Option<String>,
+    ) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(file)
+            .map_err(|err| anyhow::anyhow!("failed to read {}: {err}", file.display()))?;
+        let mut layer = Layer {
+            file: file.to_path_buf(),
+            parsed: None,
+            origins: BTreeMap::new(),
+            issues: Vec::new(),
+        };
+        let mut parsed = match config_schema::parse_yaml(file, &contents) {
+            Ok(parsed) => parsed,
+            Err(issue) => {
+                layer.issues.push(issue);
+                return Ok(layer);
+            }
+        };
+        let mut value = std::mem::take(&mut parsed.value);
+        layer.interpolate(&mut value, String::new(), &parsed, profile, env);
+        parsed.value = value;
+        layer.parsed = Some(parsed);
+        Ok(layer)
+    }
+
+    /// Fills in `${VAR}` and `${VAR:-default}` in string values under
+    /// `pointer`, recording where every node came from.
+    fn interpolate(
+        &mut self,
+        value: &mut JsonValue,
+        pointer: String,
+        parsed: &ParsedYaml,
+        profile: Option<&str>,
+        env: &impl Fn(&str) -> Option<String>,
+    ) {
+        let line = parsed
+            .locate(&pointer, false)
+            .map(|(line, _)| line)
+            .unwrap_or(1);
+        let source = match profile {
+            Some(profile) => ValueSource::Overlay {
+                profile: profile.to_string(),
+                file: self.file.clone(),
+                line,
+            },
+            None => ValueSource::Base {
+                file: self.file.clone(),
+                line,
+            },
+        };
+        let mut substitutions = Vec::new();
+        match value {
+            JsonValue::Object(map) => {
+                for (key, child) in map.iter_mut() {
+                    let child_pointer = format!("{pointer}/{}", escape_pointer(key));
+                    self.interpolate(child, child_pointer, parsed, profile, env);
+                }
+            }
+            JsonValue::Array(items) => {
+                for (index, child) in items.iter_mut().enumerate() {
+                    self.interpolate(child, format!("{pointer}/{index}"), parsed, profile, env);
+                }
+            }
+            JsonValue::String(text) if text.contains('$') => {
+                match expand(text, env, &mut substitutions) {
+                    Ok(expanded) => {
+                        *value = if parsed.is_plain_scalar(&pointer) {
+                            plain_scalar(expanded)
+                        } else {
+                            JsonValue::String(expanded)
+                        };
+                    }
+                    Err(message) => {
+                        let (line, column) = parsed.locate(&pointer, false).unwrap_or((1, 1));
+                        self.issues.push(ConfigIssue {
+                            file: self.file.clone(),
+                            line,
+                            column,
+                            pointer: pointer.clone(),
+                            message,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.origins.insert(
+            pointer,
+            ValueOrigin {
+                source,
+                substitutions,
+            },
+        );
+    }
+}
+
+/// Expands placeholders in `text`. `$$` is a literal `$`.
+fn expand(
+    text: &str,
+    env: &impl Fn(&str) -> Option<String>,
+    substitutions: &mut Vec<Substitution>,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find('$') {
+        out.push_str(&rest[..at]);
+        rest = &rest[at..];
+        if let Some(after) = rest.strip_prefix("$$") {
+            out.push('$');
+            rest = after;
+            continue;
+        }
+        let Some(body) = rest.strip_prefix("${") else {
+            out.push('$');
+            rest = &rest[1..];
+            continue;
+        };
+        let Some(end) = body.find('}') else {
+            return Err(format!("unterminated `${{` in {text:?}"));
+        };
+        let (name, default) = match body[..end].split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&body[..end], None),
+        };
+        if !is_var_name(name) {
+            return Err(format!(
+                "invalid variable name `{name}` in {text:?}; use ${{NAME}} or ${{NAME:-default}}"
+            ));
+        }
+        match (env(name).filter(|value| !value.is_empty()), default) {
+            (Some(value), _) => {
+                out.push_str(&value);
+                substitutions.push(Substitution::Env(name.to_string()));
+            }
+            (None, Some(default)) => {
+                out.push_str(default);
+                substitutions.push(Substitution::Default(name.to_string()));
+            }
+            (None, None) => {
+                return Err(format!(
+                    "environment variable `{name}` is not set; set it or write ${{{name}:-default}}"
+                ));
+            }
+        }
+        rest = &body[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn is_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first == '_' || first.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
+
+/// Types an expanded unquoted value the way YAML would have typed it had it
+/// been written literally, so `port: ${PORT:-8080}` stays a number.
+fn plain_scalar(text: String) -> JsonValue {
+    match serde_yaml_bw::from_str::<JsonValue>(&text) {
+        Ok(value @ (JsonValue::Null | JsonValue::Bool(_) | JsonValue::Number(_))) => value,
+        _ => JsonValue::String(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(name: &str) -> Option<String> {
+        match name {
+            "GATEWAY_PORT" => Some("9090".to_string()),
+            "TENANT" => Some("acme".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    fn write(dir: &Path, name: &str, contents: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn interpolates_and_keeps_yaml_typing() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = write(
+            temp.path(),
+            "greentic.demo.yaml",
+            "tenant: ${TENANT}\nteam: \"${TEAM:-42}\"\nservices:\n  gateway:\n    port: ${GATEWAY_PORT:-8080}\n    listen_addr: ${EMPTY:-0.0.0.0}\n  nats:\n    url: nats://${NATS_HOST:-127.0.0.1}:4222/$$x\n",
+        );
+        let config = load_with_env(ConfigFile::Demo, &path, None, env).unwrap();
+        assert!(config.issues.is_empty(), "{:?}", config.issues);
+        assert_eq!(config.value["tenant"], "acme");
+        assert_eq!(config.value["team"], "42");
+        assert_eq!(config.value["services"]["gateway"]["port"], 9090);
+        assert_eq!(
+            config.value["services"]["gateway"]["listen_addr"],
+            "0.0.0.0"
+        );
+        assert_eq!(
+            config.value["services"]["nats"]["url"],
+            "nats://127.0.0.1:4222/$x"
+        );
+        assert_eq!(
+            config.origins["/services/gateway/port"].to_string(),
+            "base(greentic.demo.yaml:5) env(GATEWAY_PORT)"
+        );
+        assert_eq!(
+            config.origins["/team"].to_string(),
+            "base(greentic.demo.yaml:2) default(TEAM)"
+        );
+    }
+
+    #[test]
+    fn unset_variables_are_located() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = write(
+            temp.path(),
+            "greentic.demo.yaml",
+            "tenant: demo\nservices:\n  tunnel:\n    public_url: https://${PUBLIC_HOST}\n",
+        );
+        let config = load_with_env(ConfigFile::Demo, &path, None, env).unwrap();
+        assert_eq!(config.issues.len(), 1);
+        let issue = &config.issues[0];
+        assert_eq!((issue.line, issue.column), (4, 17));
+        assert!(
+            issue.message.contains("`PUBLIC_HOST` is not set"),
+            "{issue}"
+        );
+    }
+
+    #[test]
+    fn overlay_merges_deep_and_schema_errors_point_at_the_overlay() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = write(
+            temp.path(),
+            "greentic.demo.yaml",
+            "tenant: demo\nservices:\n  gateway:\n    listen_addr: 127.0.0.1\n    port: 8080\nproviders:\n  telegram:\n    allowed_cidrs: [10.0.0.0/8]\n",
+        );
+        write(
+            temp.path(),
+            "greentic.demo.ci.yaml",
+            "services:\n  gateway:\n    port: ${GATEWAY_PORT}\nproviders:\n  telegram:\n    allowed_cidrs: [0.0.0.0/0]\n",
+        );
+        let config = load_with_env(ConfigFile::Demo, &path, Some("ci"), env).unwrap();
+        assert!(config.issues.is_empty(), "{:?}", config.issues);
+        assert_eq!(
+            config.value["services"]["gateway"]["listen_addr"],
+            "127.0.0.1"
+        );
+        assert_eq!(config.value["services"]["gateway"]["port"], 9090);
+        assert_eq!(
+            config.value["providers"]["telegram"]["allowed_cidrs"],
+            serde_json::json!(["0.0.0.0/0"])
+        );
+        let leaves = config
+            .leaves()
+            .into_iter()
+            .map(|(key, _, origin)| (key, origin.unwrap().to_string()))
+            .collect::<Vec<_>>();
+        assert!(leaves.contains(&(
+            "services.gateway.listen_addr".to_string(),
+            "base(greentic.demo.yaml:4)".to_string()
+        )));
+        assert!(leaves.contains(&(
+            "services.gateway.port".to_string(),
+            "overlay(ci: greentic.demo.ci.yaml:3) env(GATEWAY_PORT)".to_string()
+        )));
+        assert!(leaves.contains(&(
+            "providers.telegram.allowed_cidrs[0]".to_string(),
+            "overlay(ci: greentic.demo.ci.yaml:6)".to_string()
+        )));
+
+        write(
+            temp.path(),
+            "greentic.demo.ci.yaml",
+            "services:\n  gateway:\n    port: nope\n",
+        );
+        let config = load_with_env(ConfigFile::Demo, &path, Some("ci"), env).unwrap();
+        assert_eq!(config.issues.len(), 1);
+        assert!(config.issues[0].file.ends_with("greentic.demo.ci.yaml"));
+        assert_eq!((config.issues[0].line, config.issues[0].column), (3, 11));
+
+        let err = load_with_env(ConfigFile::Demo, &path, Some("staging"), env).unwrap_err();
+        assert!(
+            err.to_string().contains("greentic.demo.staging.yaml"),
+            "{err}"
+        );
+    }
+}
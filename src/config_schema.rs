@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use jsonschema::error::ValidationErrorKind;
+use saphyr_parser::{Event, Marker, Parser, ScalarStyle};
+use serde_json::Value as JsonValue;
+
+const OPERATOR_SCHEMA: &str = include_str!("../schemas/greentic.schema.json");
+const DEMO_SCHEMA: &str = include_str!("../schemas/greentic.demo.schema.json");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigFile {
+    /// `greentic.yaml` in a project root.
+    Operator,
+    /// `greentic.demo.yaml` in a demo bundle.
+    Demo,
+}
+
+impl ConfigFile {
+    pub fn file_name(self) -> &'static str {
+        match self {
+            ConfigFile::Operator => "greentic.yaml",
+            ConfigFile::Demo => "greentic.demo.yaml",
+        }
+    }
+
+    pub fn schema_text(self) -> &'static str {
+        match self {
+            ConfigFile::Operator => OPERATOR_SCHEMA,
+            ConfigFile::Demo => DEMO_SCHEMA,
+        }
+    }
+
+    pub fn schema(self) -> JsonValue {
+        serde_json::from_str(self.schema_text()).expect("bundled config schema is valid JSON")
+    }
+}
+
+/// A schema violation or YAML syntax error, located in the source file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    /// JSON pointer to the offending value, empty for the document root.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.file.display(),
+            self.line,
+            self.column,
+            self.message
+        )?;
+        if !self.pointer.is_empty() {
+            write!(f, " (at {})", self.pointer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Validates `contents` of `file` against the bundled schema for `kind`.
+/// Returns every issue found, in document order.
+pub fn validate_str(kind: ConfigFile, file: &Path, contents: &str) -> Vec<ConfigIssue> {
+    let parsed = match parse_yaml(file, contents) {
+        Ok(parsed) => parsed,
+        Err(issue) => return vec![issue],
+    };
+    // An empty document means "no settings".
+    if parsed.value.is_null() {
+        return Vec::new();
+    }
+    validate_value(kind, &parsed.value, |pointer, key| {
+        let (line, column) = parsed.locate(pointer, key).unwrap_or((1, 1));
+        (file.to_path_buf(), line, column)
+    })
+}
+
+/// Validates an already parsed `value`. `locate` maps a JSON pointer to the
+/// file, line and column it was written at; `key` asks for the position of
+/// the mapping key rather than its value.
+pub(crate) fn validate_value(
+    kind: ConfigFile,
+    value: &JsonValue,
+    locate: impl Fn(&str, bool) -> (PathBuf, usize, usize),
+) -> Vec<ConfigIssue> {
+    let schema = kind.schema();
+    let validator = jsonschema::options()
+        .should_validate_formats(true)
+        .build(&schema)
+        .expect("bundled config schema compiles");
+
+    let mut issues = Vec::new();
+    for error in validator.iter_errors(value) {
+        let instance_path = error.instance_path().as_str().to_string();
+        if let ValidationErrorKind::AdditionalProperties { unexpected } = error.kind() {
+            let known = known_properties(&schema, error.schema_path().as_str());
+            for key in unexpected {
+                let pointer = format!("{instance_path}/{}", escape_pointer(key));
+                let (file, line, column) = locate(&pointer, true);
+                let mut message = format!("unknown key `{key}`");
+                if let Some(suggestion) = closest(key, &known) {
+                    message.push_str(&format!("; did you mean `{suggestion}`?"));
+                }
+                issues.push(ConfigIssue {
+                    file,
+                    line,
+                    column,
+                    pointer,
+                    message,
+                });
+            }
+            continue;
+        }
+        let (file, line, column) = locate(&instance_path, false);
+        issues.push(ConfigIssue {
+            file,
+            line,
+            column,
+            pointer: instance_path,
+            message: error.to_string(),
+        });
+    }
+    issues.sort_by(|a, b| (&a.file, a.line, a.column).cmp(&(&b.file, b.line, b.column)));
+    issues
+}
+
+/// A YAML document converted to JSON, with the source position of every node.
+pub(crate) struct ParsedYaml {
+    pub value: JsonValue,
+    positions: HashMap<String, Position>,
+}
+
+impl ParsedYaml {
+    /// Line and 1-based column of the node at `pointer`, or of its mapping
+    /// key when `key` is set.
+    pub fn locate(&self, pointer: &str, key: bool) -> Option<(usize, usize)> {
+        self.positions.get(pointer).map(|position| {
+            let marker = if key {
+                position.key.unwrap_or(position.value)
+            } else {
+                position.value
+            };
+            (marker.line(), marker.col() + 1)
+        })
+    }
+
+    /// Whether the node at `pointer` is an unquoted scalar, whose type YAML
+    /// infers from its text.
+    pub fn is_plain_scalar(&self, pointer: &str) -> bool {
+        self.positions
+            .get(pointer)
+            .is_some_and(|position| position.plain)
+    }
+}
+
+pub(crate) fn parse_yaml(file: &Path, contents: &str) -> Result<ParsedYaml, ConfigIssue> {
+    match serde_yaml_bw::from_str(contents) {
+        Ok(value) => Ok(ParsedYaml {
+            value,
+            positions: yaml_positions(contents),
+        }),
+        Err(err) => {
+            let (line, column) = err
+                .location()
+                .map(|location| (location.line(), location.column()))
+                .unwrap_or((1, 1));
+            Err(ConfigIssue {
+                file: file.to_path_buf(),
+                line,
+                column,
+                pointer: String::new(),
+                message: format!("invalid YAML: {err}"),
+            })
+        }
+    }
+}
+
+/// Reads and validates `file`. A missing file has no issues.
+pub fn validate_file(kind: ConfigFile, file: &Path) -> anyhow::Result<Vec<ConfigIssue>> {
+    if !file.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(file)?;
+    Ok(validate_str(kind, file, &contents))
+}
+
+/// Turns issues into one error that lists each of them on its own line.
+pub fn ensure_valid(issues: &[ConfigIssue]) -> anyhow::Result<()> {
+    if issues.is_empty() {
+        return Ok(());
+    }
+    let mut message = format!("{} configuration error(s):", issues.len());
+    for issue in issues {
+        message.push_str(&format!("\n  {issue}"));
+    }
+    Err(anyhow::anyhow!(message))
+}
+
+/// The `properties` next to the `additionalProperties` keyword that failed.
+fn known_properties(schema: &JsonValue, schema_path: &str) -> Vec<String> {
+    let parent = schema_path
+        .strip_suffix("/additionalProperties")
+        .unwrap_or(schema_path);
+    schema
+        .pointer(&format!("{parent}/properties"))
+        .and_then(JsonValue::as_object)
+        .map(|properties| properties.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn closest(key: &str, known: &[String]) -> Option<String> {
+    known
+        .iter()
+        .map(|candidate| (edit_distance(key, candidate), candidate))
+        .filter(|(distance, candidate)| *distance <= 2.max(candidate.len() / 4))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.clone())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+pub(crate) fn escape_pointer(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Position {
+    key: Option<Marker>,
+    value: Marker,
+    plain: bool,
+}
+
+enum Frame {
+    Mapping {
+        pointer: String,
+        key: Option<(String, Marker)>,
+    },
+    Sequence {
+        pointer: String,
+        index: usize,
+    },
+}
+
+/// Maps the JSON pointer of every node in the first YAML document to where
+/// its key and value start. Scanner errors stop the walk; the YAML parse in
+/// [`validate_str`] reports those.
+fn yaml_positions(contents: &str) -> HashMap<String, Position> {
+    let mut positions = HashMap::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut root_seen = false;
+    for item in Parser::new_from_str(contents) {
+        let Ok((event, span)) = item else {
+            break;
+        };
+        let is_node = matches!(
+            event,
+            Event::Scalar(..)
+                | Event::Alias(_)
+                | Event::MappingStart(..)
+                | Event::SequenceStart(..)
+        );
+        if is_node {
+            let plain = matches!(event, Event::Scalar(_, ScalarStyle::Plain, ..));
+            // Where the node sits: a key, a mapping value, a sequence item
+            // or the document root.
+            let pointer = match stack.last_mut() {
+                Some(Frame::Mapping { key, .. }) if key.is_none() => {
+                    if let Event::Scalar(value, ..) = &event {
+                        *key = Some((value.to_string(), span.start));
+                    } else {
+                        *key = Some((String::new(), span.start));
+                    }
+                    // Complex keys are not addressable; skip their contents.
+                    match event {
+                        Event::MappingStart(..) | Event::SequenceStart(..) => {
+                            stack.push(Frame::Sequence {
+                                pointer: "/~complex-key".to_string(),
+                                index: 0,
+                            });
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+                Some(Frame::Mapping { pointer, key }) => {
+                    let (name, key_marker) = key.take().expect("mapping key");
+                    let child = format!("{pointer}/{}", escape_pointer(&name));
+                    positions.insert(
+                        child.clone(),
+                        Position {
+                            key: Some(key_marker),
+                            value: span.start,
+                            plain,
+                        },
+                    );
+                    child
+                }
+                Some(Frame::Sequence { pointer, index }) => {
+                    let child = format!("{pointer}/{index}");
+                    *index += 1;
+                    positions.insert(
+                        child.clone(),
+                        Position {
+                            key: None,
+                            value: span.start,
+                            plain,
+                        },
+                    );
+                    child
+                }
+                None => {
+                    if root_seen {
+                        break;
+                    }
+                    root_seen = true;
+                    positions.insert(
+                        String::new(),
+                        Position {
+                            key: None,
+                            value: span.start,
+                            plain,
+                        },
+                    );
+                    String::new()
+                }
+            };
+            match event {
+                Event::MappingStart(..) => stack.push(Frame::Mapping { pointer, key: None }),
+                Event::SequenceStart(..) => stack.push(Frame::Sequence { pointer, index: 0 }),
+                _ => {}
+            }
+            continue;
+        }
+        if matches!(event, Event::MappingEnd | Event::SequenceEnd) {
+            stack.pop();
+        }
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issues(kind: ConfigFile, yaml: &str) -> Vec<(usize, usize, String, String)> {
+        validate_str(kind, Path::new("config.yaml"), yaml)
+            .into_iter()
+            .map(|issue| (issue.line, issue.column, issue.pointer, issue.message))
+            .collect()
+    }
+
+    #[test]
+    fn unknown_keys_are_located_and_get_suggestions() {
+        let found = issues(
+            ConfigFile::Demo,
+            "tenant: demo\nservices:\n  gatway:\n    port: 8080\n  subscriptions:\n    universal:\n      renew_intervall_seconds: 30\n",
+        );
+        assert_eq!(
+            found,
+            vec![
+                (
+                    3,
+                    3,
+                    "/services/gatway".to_string(),
+                    "unknown key `gatway`; did you mean `gateway`?".to_string()
+                ),
+                (
+                    7,
+                    7,
+                    "/services/subscriptions/universal/renew_intervall_seconds".to_string(),
+                    "unknown key `renew_intervall_seconds`; did you mean `renew_interval_seconds`?"
+                        .to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_values_point_at_the_value() {
+        let found = issues(
+            ConfigFile::Demo,
+            "services:\n  gateway:\n    port: 70000\n  tunnel:\n    public_url: not a url\n    command:\n      timeout_seconds: 0\nproviders:\n  telegram:\n    allowed_cidrs: [10.0.0.0/8, 5]\n",
+        );
+        let located = found
+            .iter()
+            .map(|(line, column, pointer, _)| (*line, *column, pointer.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            located,
+            vec![
+                (3, 11, "/services/gateway/port"),
+                (5, 17, "/services/tunnel/public_url"),
+                (7, 24, "/services/tunnel/command/timeout_seconds"),
+                (10, 33, "/providers/telegram/allowed_cidrs/1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn operator_schema_accepts_the_project_template_and_reports_syntax_errors() {
+        let template = "dev:\n  mode: auto\n  root: null\n  profile: debug\n  target_dir: null\n  repo_map: {}\nbinaries: {}\n";
+        assert!(issues(ConfigFile::Operator, template).is_empty());
+        assert!(issues(ConfigFile::Operator, "# only comments\n").is_empty());
+
+        let found = issues(ConfigFile::Operator, "dev:\n  mode: [auto\n");
+        assert_eq!(found.len(), 1);
+        assert!(found[0].3.starts_with("invalid YAML"), "{found:?}");
+        assert_eq!(found[0].0, 3);
+    }
+}
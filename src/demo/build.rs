@@ -28,10 +28,10 @@ struct ResolvedManifest {
 pub fn build_bundle(
     project_root: &Path,
     options: BuildOptions,
-    pack_command: Option<&Path>,
+    doctor: Option<crate::doctor::DoctorBackend<'_>>,
 ) -> anyhow::Result<()> {
     if options.run_doctor && std::env::var("GREENTIC_OPERATOR_SKIP_DOCTOR").is_err() {
-        let pack_command = pack_command
+        let doctor = doctor
             .ok_or_else(|| anyhow::anyhow!("greentic-pack command is required for demo doctor"))?;
         crate::doctor::run_doctor(
             project_root,
@@ -41,9 +41,11 @@ pub fn build_bundle(
                 team: options.team.clone(),
                 strict: false,
                 validator_packs: Vec::new(),
+                binaries: Vec::new(),
             },
-            pack_command,
-        )?;
+            doctor,
+        )?
+        .ensure_passed()?;
     }
 
     let resolved_dir = project_root.join("state").join("resolved");
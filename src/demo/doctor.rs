@@ -1,6 +1,16 @@
 use std::path::Path;
 
-pub fn demo_doctor(bundle_root: &Path, pack_command: &Path) -> anyhow::Result<()> {
+use crate::bin_version::{self, BinaryCheck};
+use crate::doctor::{self, DoctorBackend};
+use crate::doctor_report::{DoctorReport, DoctorReportFormat};
+use crate::secret_placeholders;
+
+pub fn demo_doctor(
+    bundle_root: &Path,
+    backend: DoctorBackend<'_>,
+    binaries: &[BinaryCheck],
+    strict_secrets: bool,
+) -> anyhow::Result<()> {
     let packs_root = bundle_root.join("packs");
     if !packs_root.exists() {
         return Err(anyhow::anyhow!("Bundle packs directory not found."));
@@ -12,18 +22,61 @@ pub fn demo_doctor(bundle_root: &Path, pack_command: &Path) -> anyhow::Result<()
         return Err(anyhow::anyhow!("No .gtpack files found in bundle."));
     }
 
-    for pack in packs {
-        let status = std::process::Command::new(pack_command)
-            .args(["doctor", pack.to_str().unwrap_or_default()])
-            .status()?;
-        if !status.success() {
-            return Err(anyhow::anyhow!(
-                "greentic-pack doctor failed for {}",
-                pack.display()
-            ));
+    print!("{}", bin_version::render_checks(binaries));
+    match backend {
+        DoctorBackend::External(pack_command) => {
+            for pack in packs {
+                let status = std::process::Command::new(pack_command)
+                    .args(["doctor", pack.to_str().unwrap_or_default()])
+                    .status()?;
+                if !status.success() {
+                    return Err(anyhow::anyhow!(
+                        "greentic-pack doctor failed for {}",
+                        pack.display()
+                    ));
+                }
+            }
+        }
+        DoctorBackend::Builtin => {
+            packs.sort();
+            let runs = packs
+                .iter()
+                .map(|pack| {
+                    let label = pack
+                        .file_stem()
+                        .and_then(|name| name.to_str())
+                        .unwrap_or("pack");
+                    doctor::run_builtin_doctor(pack, label, "demo", false)
+                })
+                .collect();
+            let report = DoctorReport {
+                root: bundle_root.to_path_buf(),
+                binaries: Vec::new(),
+                runs,
+            };
+            print!("{}", report.render(DoctorReportFormat::Text)?);
+            report.ensure_passed()?;
         }
     }
+    bin_version::ensure_compatible(binaries, false)?;
 
+    let unresolved = secret_placeholders::unresolved_for_bundle(bundle_root)?;
+    if unresolved.is_empty() {
+        return Ok(());
+    }
+    println!("unresolved secrets (placeholder values from setup):");
+    for (provider, uris) in &unresolved {
+        for uri in uris {
+            println!("  - provider={provider} {uri}");
+        }
+    }
+    println!("set real values with `greentic-operator demo secrets set <uri>` or seeds.yaml");
+    if strict_secrets {
+        let count = unresolved.values().map(Vec::len).sum::<usize>();
+        return Err(anyhow::anyhow!(
+            "{count} secrets still hold placeholder values (--strict-secrets)"
+        ));
+    }
     Ok(())
 }
 
@@ -1,4 +1,9 @@
-use std::{convert::Infallible, net::SocketAddr, sync::Arc, thread};
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+    thread,
+};
 
 use anyhow::{Context, Result};
 use http_body_util::{BodyExt, Full};
@@ -14,6 +19,7 @@ use serde_json::json;
 use tokio::{net::TcpListener, runtime::Runtime, sync::oneshot};
 
 use crate::demo::event_router::route_events_to_default_flow;
+use crate::demo::ingress_access::IngressAccessPolicy;
 use crate::demo::ingress_dispatch::dispatch_http_ingress;
 use crate::demo::ingress_types::{IngressHttpResponse, IngressRequestV1};
 use crate::demo::runner_host::{DemoRunnerHost, OperatorContext};
@@ -25,11 +31,13 @@ pub struct HttpIngressConfig {
     pub bind_addr: SocketAddr,
     pub domains: Vec<Domain>,
     pub runner_host: Arc<DemoRunnerHost>,
+    pub access_policy: IngressAccessPolicy,
 }
 
 pub struct HttpIngressServer {
     shutdown: Option<oneshot::Sender<()>>,
     handle: Option<thread::JoinHandle<Result<()>>>,
+    state: Arc<RwLock<Arc<HttpIngressState>>>,
 }
 
 impl HttpIngressServer {
@@ -37,10 +45,12 @@ impl HttpIngressServer {
         let debug_enabled = config.runner_host.debug_enabled();
         let domains = config.domains;
         let runner_host = config.runner_host;
-        let state = Arc::new(HttpIngressState {
+        let state = Arc::new(RwLock::new(Arc::new(HttpIngressState {
             runner_host,
             domains,
-        });
+            access_policy: config.access_policy,
+        })));
+        let shared_state = state.clone();
         let (tx, rx) = oneshot::channel();
         let addr = config.bind_addr;
         let handle = thread::Builder::new()
@@ -56,7 +66,7 @@ impl HttpIngressServer {
                         format!("demo ingress listening on http://{}", addr),
                     );
                     if debug_enabled {
-                        let domain_list = state
+                        let domain_list = current_state(&state)
                             .domains
                             .iter()
                             .map(|domain| domains::domain_name(*domain))
@@ -75,11 +85,11 @@ impl HttpIngressServer {
                         tokio::select! {
                             _ = &mut shutdown => break,
                             accept = listener.accept() => match accept {
-                                Ok((stream, _peer)) => {
+                                Ok((stream, peer)) => {
                                     let connection_state = state.clone();
                                     tokio::spawn(async move {
                                         let service = service_fn(move |req| {
-                                            handle_request(req, connection_state.clone())
+                                            handle_request(req, peer, current_state(&connection_state))
                                         });
                                         let http = Http1Builder::new();
                                         let stream = TokioIo::new(stream);
@@ -111,9 +121,30 @@ impl HttpIngressServer {
         Ok(Self {
             shutdown: Some(tx),
             handle: Some(handle),
+            state: shared_state,
         })
     }
 
+    /// Swaps the runner host, domains and access policy without rebinding
+    /// the listener. Requests already in flight finish on the previous state.
+    pub fn reload(
+        &self,
+        runner_host: Arc<DemoRunnerHost>,
+        domains: Vec<Domain>,
+        access_policy: IngressAccessPolicy,
+    ) -> Result<()> {
+        let mut state = self
+            .state
+            .write()
+            .map_err(|_| anyhow::anyhow!("ingress state lock poisoned"))?;
+        *state = Arc::new(HttpIngressState {
+            runner_host,
+            domains,
+            access_policy,
+        });
+        Ok(())
+    }
+
     pub fn stop(mut self) -> Result<()> {
         if let Some(tx) = self.shutdown.take() {
             let _ = tx.send(());
@@ -128,17 +159,26 @@ impl HttpIngressServer {
     }
 }
 
+fn current_state(state: &RwLock<Arc<HttpIngressState>>) -> Arc<HttpIngressState> {
+    match state.read() {
+        Ok(state) => state.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
 #[derive(Clone)]
 struct HttpIngressState {
     runner_host: Arc<DemoRunnerHost>,
     domains: Vec<Domain>,
+    access_policy: IngressAccessPolicy,
 }
 
 async fn handle_request(
     req: Request<Incoming>,
+    peer: SocketAddr,
     state: Arc<HttpIngressState>,
 ) -> Result<Response<Full<Bytes>>, Infallible> {
-    let response = match handle_request_inner(req, state).await {
+    let response = match handle_request_inner(req, peer, state).await {
         Ok(response) => response,
         Err(response) => response,
     };
@@ -147,6 +187,7 @@ async fn handle_request(
 
 async fn handle_request_inner(
     req: Request<Incoming>,
+    peer: SocketAddr,
     state: Arc<HttpIngressState>,
 ) -> Result<Response<Full<Bytes>>, Response<Full<Bytes>>> {
     if req.method() != Method::POST && req.method() != Method::GET {
@@ -170,6 +211,12 @@ async fn handle_request_inner(
     if !state.domains.contains(&domain) {
         return Err(error_response(StatusCode::NOT_FOUND, "domain disabled"));
     }
+    if !state.runner_host.serves(&parsed.tenant, Some(&parsed.team)) {
+        return Err(error_response(
+            StatusCode::NOT_FOUND,
+            "tenant/team not running",
+        ));
+    }
     if !state
         .runner_host
         .supports_op(domain, &parsed.provider, "ingest_http")
@@ -179,6 +226,23 @@ async fn handle_request_inner(
             "no ingest_http handler available",
         ));
     }
+    let client_addr = state.access_policy.client_addr(peer, req.headers());
+    if !state
+        .access_policy
+        .is_allowed(&parsed.provider, client_addr)
+    {
+        operator_log::warn(
+            module_path!(),
+            format!(
+                "[demo ingress] rejected provider={} client={} peer={}",
+                parsed.provider, client_addr, peer
+            ),
+        );
+        return Err(error_response(
+            StatusCode::FORBIDDEN,
+            "source address not allowed",
+        ));
+    }
 
     let correlation_id = req
         .headers()
@@ -204,9 +268,10 @@ async fn handle_request_inner(
         operator_log::debug(
             module_path!(),
             format!(
-                "[demo dev] ingress request method={} path={} domain={} provider={} tenant={} team={} corr_id={:?} payload_len={}",
+                "[demo dev] ingress request method={} path={} client={} domain={} provider={} tenant={} team={} corr_id={:?} payload_len={}",
                 method,
                 path,
+                client_addr,
                 domains::domain_name(domain),
                 parsed.provider,
                 context.tenant,
@@ -230,7 +295,7 @@ async fn handle_request_inner(
         headers,
         body: payload_bytes.to_vec(),
         correlation_id: correlation_id.clone(),
-        remote_addr: None,
+        remote_addr: Some(client_addr.to_string()),
     };
 
     let result = dispatch_http_ingress(
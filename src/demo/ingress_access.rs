@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::str::FromStr;
+
+use anyhow::{Context, Result, anyhow};
+use hyper::HeaderMap;
+
+use crate::config::DemoConfig;
+
+/// An IPv4 or IPv6 network in CIDR notation (`10.0.0.0/8`, `2001:db8::/32`).
+/// A bare address is accepted as a single-host network.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IpCidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl IpCidr {
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        match (self.network, addr.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(addr)) => {
+                let mask = prefix_mask_u32(self.prefix);
+                u32::from(network) & mask == u32::from(addr) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(addr)) => {
+                let mask = prefix_mask_u128(self.prefix);
+                u128::from(network) & mask == u128::from(addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpCidr {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let value = value.trim();
+        let (addr, prefix) = match value.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (value, None),
+        };
+        let network = IpAddr::from_str(addr)
+            .map_err(|_| anyhow!("invalid address in CIDR {value:?}"))?
+            .to_canonical();
+        let max_prefix = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= max_prefix)
+                .ok_or_else(|| anyhow!("invalid prefix length in CIDR {value:?}"))?,
+            None => max_prefix,
+        };
+        Ok(Self { network, prefix })
+    }
+}
+
+impl fmt::Display for IpCidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+fn prefix_mask_u32(prefix: u8) -> u32 {
+    if prefix == 0 {
+        0
+    } else {
+        u32::MAX << (32 - u32::from(prefix))
+    }
+}
+
+fn prefix_mask_u128(prefix: u8) -> u128 {
+    if prefix == 0 {
+        0
+    } else {
+        u128::MAX << (128 - u32::from(prefix))
+    }
+}
+
+pub fn parse_cidrs(values: &[String]) -> Result<Vec<IpCidr>> {
+    values.iter().map(|value| value.parse::<IpCidr>()).collect()
+}
+
+/// Decides which client address an ingress request is attributed to and
+/// whether that address may reach a given provider.
+#[derive(Clone, Debug, Default)]
+pub struct IngressAccessPolicy {
+    trusted_proxies: Vec<IpCidr>,
+    provider_allow_lists: BTreeMap<String, Vec<IpCidr>>,
+}
+
+impl IngressAccessPolicy {
+    pub fn new(
+        trusted_proxies: Vec<IpCidr>,
+        provider_allow_lists: BTreeMap<String, Vec<IpCidr>>,
+    ) -> Self {
+        Self {
+            trusted_proxies,
+            provider_allow_lists,
+        }
+    }
+
+    pub fn from_demo_config(config: &DemoConfig) -> Result<Self> {
+        let trusted_proxies = parse_cidrs(&config.services.gateway.trusted_proxies)
+            .context("invalid services.gateway.trusted_proxies")?;
+        let mut provider_allow_lists = BTreeMap::new();
+        for (provider, provider_config) in config.providers.iter().flatten() {
+            if provider_config.allowed_cidrs.is_empty() {
+                continue;
+            }
+            let cidrs = parse_cidrs(&provider_config.allowed_cidrs)
+                .with_context(|| format!("invalid providers.{provider}.allowed_cidrs"))?;
+            provider_allow_lists.insert(provider.clone(), cidrs);
+        }
+        Ok(Self::new(trusted_proxies, provider_allow_lists))
+    }
+
+    /// Resolves the originating client address. `X-Forwarded-For` and
+    /// `Forwarded` are only consulted while the hop that added them is a
+    /// trusted proxy; the chain is walked right to left and the first
+    /// untrusted hop wins.
+    pub fn client_addr(&self, peer: SocketAddr, headers: &HeaderMap) -> IpAddr {
+        let mut client = peer.ip().to_canonical();
+        if !self.is_trusted_proxy(client) {
+            return client;
+        }
+        for hop in forwarded_chain(headers).into_iter().rev() {
+            let Some(hop) = hop else {
+                return client;
+            };
+            client = hop.to_canonical();
+            if !self.is_trusted_proxy(client) {
+                return client;
+            }
+        }
+        client
+    }
+
+    /// Providers without an allow-list accept every source address.
+    pub fn is_allowed(&self, provider: &str, client: IpAddr) -> bool {
+        match self.provider_allow_lists.get(provider) {
+            Some(cidrs) => cidrs.iter().any(|cidr| cidr.contains(client)),
+            None => true,
+        }
+    }
+
+    fn is_trusted_proxy(&self, addr: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|cidr| cidr.contains(addr))
+    }
+}
+
+/// Returns the forwarding chain, leftmost (original client) first. The
+/// standard `Forwarded` header takes precedence over `X-Forwarded-For`.
+/// Hops that cannot be parsed (obfuscated identifiers, `unknown`) are `None`.
+fn forwarded_chain(headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    let forwarded = header_list(headers, "forwarded");
+    if !forwarded.is_empty() {
+        return forwarded
+            .iter()
+            .map(|element| {
+                element
+                    .split(';')
+                    .filter_map(|pair| pair.split_once('='))
+                    .find(|(key, _)| key.trim().eq_ignore_ascii_case("for"))
+                    .and_then(|(_, value)| parse_node(value))
+            })
+            .collect();
+    }
+    header_list(headers, "x-forwarded-for")
+        .iter()
+        .map(|value| parse_node(value))
+        .collect()
+}
+
+fn header_list(headers: &HeaderMap, name: &str) -> Vec<String> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+fn parse_node(value: &str) -> Option<IpAddr> {
+    let value = value.trim().trim_matches('"');
+    if let Ok(addr) = IpAddr::from_str(value) {
+        return Some(addr);
+    }
+    if let Ok(addr) = SocketAddr::from_str(value) {
+        return Some(addr.ip());
+    }
+    let bracketed = value.strip_prefix('[')?;
+    let end = bracketed.find(']')?;
+    Ipv6Addr::from_str(&bracketed[..end]).ok().map(IpAddr::V6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::header::HeaderValue;
+
+    fn policy(trusted: &[&str]) -> IngressAccessPolicy {
+        let trusted = trusted
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>();
+        IngressAccessPolicy::new(parse_cidrs(&trusted).unwrap(), BTreeMap::new())
+    }
+
+    fn peer(value: &str) -> SocketAddr {
+        SocketAddr::new(value.parse().unwrap(), 443)
+    }
+
+    #[test]
+    fn cidr_matches_v4_and_v6_networks() {
+        let v4 = "149.154.160.0/20".parse::<IpCidr>().unwrap();
+        assert!(v4.contains("149.154.167.99".parse().unwrap()));
+        assert!(!v4.contains("149.154.176.1".parse().unwrap()));
+        assert!(v4.contains("::ffff:149.154.160.1".parse().unwrap()));
+        let v6 = "2001:67c:4e8::/48".parse::<IpCidr>().unwrap();
+        assert!(v6.contains("2001:67c:4e8:f004::9".parse().unwrap()));
+        assert!(!v6.contains("2001:67c:4e9::1".parse().unwrap()));
+        assert!("10.0.0.0/33".parse::<IpCidr>().is_err());
+        assert!("not-an-ip/8".parse::<IpCidr>().is_err());
+    }
+
+    #[test]
+    fn forwarded_headers_ignored_from_untrusted_peer() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static("1.2.3.4"));
+        let policy = policy(&["127.0.0.1"]);
+        let client = policy.client_addr(peer("203.0.113.9"), &headers);
+        assert_eq!(client, "203.0.113.9".parse::<IpAddr>().unwrap());
+    }
+
+    #[test]
+    fn forwarded_chain_stops_at_first_untrusted_hop() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("6.6.6.6, 198.51.100.7, 10.0.0.2"),
+        );
+        let policy = policy(&["127.0.0.1", "10.0.0.0/8"]);
+        let client = policy.client_addr(peer("127.0.0.1"), &headers);
+        assert_eq!(client, "198.51.100.7".parse::<IpAddr>().unwrap());
+    }
+
+    #[test]
+    fn standard_forwarded_header_takes_precedence() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static("6.6.6.6"));
+        headers.insert(
+            "forwarded",
+            HeaderValue::from_static("for=\"[2001:db8::1]:4711\";proto=https"),
+        );
+        let policy = policy(&["::1"]);
+        let client = policy.client_addr(peer("::1"), &headers);
+        assert_eq!(client, "2001:db8::1".parse::<IpAddr>().unwrap());
+    }
+
+    #[test]
+    fn provider_allow_list_only_applies_to_listed_providers() {
+        let mut allow = BTreeMap::new();
+        allow.insert(
+            "messaging-telegram".to_string(),
+            parse_cidrs(&["91.108.4.0/22".to_string()]).unwrap(),
+        );
+        let policy = IngressAccessPolicy::new(Vec::new(), allow);
+        assert!(policy.is_allowed("messaging-telegram", "91.108.5.1".parse().unwrap()));
+        assert!(!policy.is_allowed("messaging-telegram", "8.8.8.8".parse().unwrap()));
+        assert!(policy.is_allowed("messaging-slack", "8.8.8.8".parse().unwrap()));
+    }
+}
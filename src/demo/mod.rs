@@ -6,6 +6,7 @@ pub mod event_router;
 pub mod help;
 pub mod history;
 pub mod http_ingress;
+pub mod ingress_access;
 pub mod ingress_dispatch;
 pub mod ingress_types;
 pub mod input;
@@ -17,6 +18,7 @@ mod runtime;
 pub mod setup;
 pub mod timer_scheduler;
 mod types;
+pub mod watch;
 
 pub use build::{BuildOptions, build_bundle};
 pub use doctor::demo_doctor;
@@ -25,7 +27,8 @@ pub use repl::DemoRepl;
 pub use runner::DemoRunner;
 pub use runner_host::{DemoRunnerHost, FlowOutcome, OperatorContext};
 pub use runtime::{
-    NatsMode, demo_down_runtime, demo_logs_runtime, demo_status_runtime, demo_up, demo_up_services,
+    NatsMode, demo_down_runtime, demo_logs_runtime, demo_status_runtime, demo_status_targets,
+    demo_up, demo_up_services, start_universal_subscriptions,
 };
 pub use setup::{ProvidersInput, discover_tenants};
 pub use types::{DemoBlockedOn, UserEvent};
@@ -78,6 +78,9 @@ pub struct DemoRunnerHost {
     runner_mode: RunnerMode,
     catalog: HashMap<(Domain, String), ProviderPack>,
     secrets_handle: SecretsManagerHandle,
+    /// Per tenant and canonical team, for hosts shared by several tenants.
+    /// When set, only these tenants and teams are served.
+    tenant_secrets: HashMap<(String, String), SecretsManagerHandle>,
     card_renderer: CardRenderer,
     debug_enabled: bool,
 }
@@ -95,6 +98,41 @@ impl DemoRunnerHost {
         &self.secrets_handle
     }
 
+    /// Lets one host serve several tenants: invocations for the tenant and
+    /// team of one of `handles` use that handle, and invocations for any other
+    /// tenant or team are refused.
+    pub fn with_tenant_secrets(
+        mut self,
+        handles: impl IntoIterator<Item = SecretsManagerHandle>,
+    ) -> Self {
+        for handle in handles {
+            self.tenant_secrets.insert(
+                (handle.tenant.clone(), handle.canonical_team.clone()),
+                handle,
+            );
+        }
+        self
+    }
+
+    /// Whether invocations for `tenant` and `team` are served by this host.
+    pub fn serves(&self, tenant: &str, team: Option<&str>) -> bool {
+        self.secrets_handle_for(tenant, team).is_ok()
+    }
+
+    pub fn secrets_handle_for(
+        &self,
+        tenant: &str,
+        team: Option<&str>,
+    ) -> anyhow::Result<&SecretsManagerHandle> {
+        if self.tenant_secrets.is_empty() {
+            return Ok(&self.secrets_handle);
+        }
+        let team = secrets_manager::canonical_team(team);
+        self.tenant_secrets
+            .get(&(tenant.to_string(), team.to_string()))
+            .ok_or_else(|| anyhow!("tenant {tenant} team {team} is not served by this host"))
+    }
+
     pub fn new(
         bundle_root: PathBuf,
         discovery: &discovery::DiscoveryResult,
@@ -141,6 +179,7 @@ impl DemoRunnerHost {
             runner_mode: mode,
             catalog,
             secrets_handle,
+            tenant_secrets: HashMap::new(),
             card_renderer: CardRenderer::new(),
             debug_enabled,
         })
@@ -354,6 +393,7 @@ impl DemoRunnerHost {
         let runtime = TokioRuntime::new()
             .context("failed to create tokio runtime for provider invocation")?;
         let payload = payload_bytes.to_vec();
+        let secrets_handle = self.secrets_handle_for(&ctx.tenant, ctx.team.as_deref())?;
         let result = runtime.block_on(async {
             let host_config = Arc::new(build_demo_host_config(&ctx.tenant));
             let dev_store_display = self
@@ -366,14 +406,14 @@ impl DemoRunnerHost {
                 module_path!(),
                 format!(
                     "secrets backend for wasm: using_env_fallback={} dev_store={}",
-                    self.secrets_handle.using_env_fallback, dev_store_display,
+                    secrets_handle.using_env_fallback, dev_store_display,
                 ),
             );
             operator_log::info(
                 module_path!(),
                 format!(
                     "exec secrets: dev_store={} env_fallback={}",
-                    dev_store_display, self.secrets_handle.using_env_fallback,
+                    dev_store_display, secrets_handle.using_env_fallback,
                 ),
             );
             let pack_runtime = PackRuntime::load(
@@ -384,7 +424,12 @@ impl DemoRunnerHost {
                 None::<DynSessionStore>,
                 Some(new_state_store()),
                 Arc::new(RunnerWasiPolicy::default()),
-                self.secrets_handle.runtime_manager(Some(&pack.pack_id)),
+                secrets_handle
+                    .runtime_manager(
+                        Some(&pack.pack_id),
+                        Some(&pack.path),
+                        ctx.correlation_id.as_deref(),
+                    ),
                 None,
                 false,
                 ComponentResolution::default(),
@@ -408,7 +453,7 @@ impl DemoRunnerHost {
                 provider_type,
                 pack.pack_id,
                 runner_dev_store_desc,
-                self.secrets_handle.using_env_fallback,
+                secrets_handle.using_env_fallback,
             );
             let binding = pack_runtime.resolve_provider(None, Some(&provider_type))?;
             let exec_ctx = ComponentExecCtx {
@@ -1,5 +1,4 @@
 use std::path::{Path, PathBuf};
-use std::thread;
 use std::time::Duration;
 
 use std::collections::{BTreeMap, BTreeSet};
@@ -8,19 +7,23 @@ use anyhow::Context;
 
 use crate::operator_log;
 use crate::runtime_state::{
-    RuntimePaths, persist_service_manifest, read_service_manifest, remove_service_manifest,
-    write_json,
+    DemoTargetsManifest, RuntimePaths, persist_service_manifest, read_service_manifest,
+    remove_service_manifest, write_json,
 };
 use crate::services;
 use crate::supervisor;
 
-use crate::cloudflared::{self, CloudflaredConfig};
 use crate::config::{DemoConfig, DemoSubscriptionsMode};
 use crate::dev_mode::DevSettingsResolved;
+use crate::tunnel::TunnelConfig;
 
+use crate::messaging_universal::retry::RetryPolicy;
 use crate::subscriptions_universal::{
-    build_runner, ensure_desired_subscriptions, scheduler::Scheduler, service::SubscriptionService,
-    state_root, store::SubscriptionStore,
+    build_runner, reconcile_desired_subscriptions,
+    scheduler::{RenewalLoop, Scheduler, default_renew_backoff},
+    service::SubscriptionService,
+    state_root,
+    store::SubscriptionStore,
 };
 
 struct ServiceSummary {
@@ -236,6 +239,42 @@ fn spawn_embedded_messaging(
     Ok(summary)
 }
 
+/// Reconciles the desired universal subscriptions of `tenant`/`team` and
+/// starts their renewal loop.
+pub fn start_universal_subscriptions(
+    bundle_root: &Path,
+    config: &DemoConfig,
+    tenant: &str,
+    team: Option<String>,
+) -> anyhow::Result<RenewalLoop> {
+    let universal = &config.services.subscriptions.universal;
+    let (runner_host, context) = build_runner(bundle_root, tenant, team.clone())?;
+    let store = SubscriptionStore::new(state_root(bundle_root));
+    let backoff = RetryPolicy {
+        base_delay_ms: universal.retry_base_seconds.max(1).saturating_mul(1_000),
+        max_delay_ms: universal.retry_max_seconds.max(1).saturating_mul(1_000),
+        ..default_renew_backoff()
+    };
+    let scheduler =
+        Scheduler::new(SubscriptionService::new(runner_host, context), store).with_backoff(backoff);
+
+    let actions = reconcile_desired_subscriptions(
+        bundle_root,
+        tenant,
+        team,
+        &universal.desired,
+        &scheduler,
+        false,
+    )?;
+    for action in &actions {
+        println!("subscriptions: {action}");
+    }
+
+    let interval = Duration::from_secs(universal.renew_interval_seconds.max(1));
+    let skew = Duration::from_secs(universal.renew_skew_minutes.max(1).saturating_mul(60));
+    RenewalLoop::start(scheduler, interval, skew)
+}
+
 fn spawn_universal_subscriptions_service(
     bundle_root: &Path,
     config: &DemoConfig,
@@ -254,56 +293,14 @@ fn spawn_universal_subscriptions_service(
         .with_context(|| "unable to open subscriptions log file")?;
     tracker.record_with_log("subscriptions-universal", "subscriptions", Some(&log_path))?;
 
-    let desired = &config.services.subscriptions.universal.desired;
-    let (runner_host, context) = build_runner(bundle_root, tenant, team_override.clone())?;
-    let store = SubscriptionStore::new(state_root(bundle_root));
-    let scheduler = Scheduler::new(SubscriptionService::new(runner_host, context), store);
-
-    ensure_desired_subscriptions(
-        bundle_root,
-        tenant,
-        team_override.clone(),
-        desired,
-        &scheduler,
-    )?;
-
+    // Runs for the lifetime of the operator process.
+    let _renewal = start_universal_subscriptions(bundle_root, config, tenant, team_override)?;
     let renew_interval_secs = config
         .services
         .subscriptions
         .universal
         .renew_interval_seconds
         .max(1);
-    let renew_skew_secs = config
-        .services
-        .subscriptions
-        .universal
-        .renew_skew_minutes
-        .max(1)
-        .saturating_mul(60);
-    let interval = Duration::from_secs(renew_interval_secs);
-    let skew = Duration::from_secs(renew_skew_secs);
-
-    let scheduler_handle = scheduler;
-    thread::Builder::new()
-        .name("subscriptions-universal".to_string())
-        .spawn(move || {
-            operator_log::info(
-                module_path!(),
-                format!(
-                    "subscriptions-universal scheduler running interval={}s skew={}s",
-                    renew_interval_secs, renew_skew_secs
-                ),
-            );
-            loop {
-                std::thread::sleep(interval);
-                if let Err(err) = scheduler_handle.renew_due(skew) {
-                    operator_log::error(
-                        module_path!(),
-                        format!("subscriptions-universal renew failed err={}", err),
-                    );
-                }
-            }
-        })?;
 
     let mut summary = ServiceSummary::new("subscriptions-universal", None);
     summary.add_detail(format!("log={}", log_path.display()));
@@ -373,6 +370,11 @@ fn should_restart(restart: &BTreeSet<String>, service: &str) -> bool {
     restart.contains("all") || restart.contains(service)
 }
 
+fn display_log_path(path: Option<&Path>) -> String {
+    path.map(|path| path.display().to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
 #[allow(clippy::too_many_arguments)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum NatsMode {
@@ -389,7 +391,7 @@ pub fn demo_up(
     nats_url: Option<&str>,
     nats_mode: NatsMode,
     messaging_enabled: bool,
-    cloudflared: Option<CloudflaredConfig>,
+    tunnel: Option<TunnelConfig>,
     log_dir: &Path,
     debug_enabled: bool,
 ) -> anyhow::Result<()> {
@@ -410,52 +412,47 @@ pub fn demo_up(
             ),
         );
     }
-    if let Some(config) = cloudflared {
-        let cloudflared_log = operator_log::reserve_service_log(log_dir, "cloudflared")
-            .with_context(|| "unable to open cloudflared.log")?;
+    if let Some(config) = tunnel {
+        let service_id = config.service_id().to_string();
         operator_log::info(
             module_path!(),
             format!(
-                "starting cloudflared log={} bundle={}",
-                cloudflared_log.display(),
+                "starting tunnel service={} bundle={}",
+                service_id,
                 bundle_root.display()
             ),
         );
-        let handle = cloudflared::start_quick_tunnel(&paths, &config, &cloudflared_log)?;
+        let handle = config.start(&paths, log_dir)?;
+        let log_label = display_log_path(handle.log_path.as_deref());
         operator_log::info(
             module_path!(),
-            format!(
-                "cloudflared ready url={} log={}",
-                handle.url,
-                handle.log_path.display()
-            ),
+            format!("{} ready url={} log={}", service_id, handle.url, log_label),
         );
         if debug_enabled {
             operator_log::debug(
                 module_path!(),
                 format!(
-                    "[demo dev] tenant={} team={} cloudflared url={} log={}",
-                    tenant,
-                    team_id,
-                    handle.url,
-                    handle.log_path.display()
+                    "[demo dev] tenant={} team={} {} url={} log={}",
+                    tenant, team_id, service_id, handle.url, log_label
                 ),
             );
         }
         let url = handle.url.clone();
-        let log_path = handle.log_path.clone();
-        service_tracker.record_with_log("cloudflared", "cloudflared", Some(&log_path))?;
+        if handle.pid.is_some() {
+            service_tracker.record_with_log(
+                &service_id,
+                &service_id,
+                handle.log_path.as_deref(),
+            )?;
+        }
         let summary = ServiceSummary::with_details(
-            "cloudflared",
-            Some(handle.pid),
-            vec![
-                format!("url={}", url),
-                format!("log={}", log_path.display()),
-            ],
+            &service_id,
+            handle.pid,
+            vec![format!("url={}", url), format!("log={}", log_label)],
         );
         service_summaries.push(summary);
         public_base_url = Some(url.clone());
-        println!("Public URL (service=cloudflared): {}", url);
+        println!("Public URL (service={service_id}): {}", url);
     }
 
     let mut resolved_nats_url = nats_url.map(|value| value.to_string());
@@ -572,7 +569,7 @@ pub fn demo_up_services(
     config_path: &Path,
     config: &DemoConfig,
     dev_settings: Option<DevSettingsResolved>,
-    cloudflared: Option<CloudflaredConfig>,
+    tunnel: Option<TunnelConfig>,
     restart: &BTreeSet<String>,
     provider_options: crate::providers::ProviderSetupOptions,
     log_dir: &Path,
@@ -610,18 +607,20 @@ pub fn demo_up_services(
         );
     }
 
-    if should_restart(restart, "cloudflared") {
-        let _ = supervisor::stop_pidfile(&paths.pid_path("cloudflared"), 2_000);
+    if let Some(cfg) = tunnel.as_ref()
+        && (should_restart(restart, "cloudflared") || should_restart(restart, cfg.service_id()))
+    {
+        let _ = supervisor::stop_pidfile(&paths.pid_path(cfg.service_id()), 2_000);
     }
 
-    let public_base_url = if let Some(cfg) = cloudflared {
-        let cloudflared_log = operator_log::reserve_service_log(log_dir, "cloudflared")
-            .with_context(|| "unable to open cloudflared.log")?;
+    let public_base_url = if let Some(cfg) = tunnel {
+        let service_id = cfg.service_id().to_string();
         operator_log::info(
             module_path!(),
-            format!("starting cloudflared log={}", cloudflared_log.display()),
+            format!("starting tunnel service={service_id}"),
         );
-        let handle = cloudflared::start_quick_tunnel(&paths, &cfg, &cloudflared_log)?;
+        let handle = cfg.start(&paths, log_dir)?;
+        let log_label = display_log_path(handle.log_path.as_deref());
         let mut domain_labels = Vec::new();
         if discovery.domains.messaging {
             domain_labels.push("messaging");
@@ -637,30 +636,30 @@ pub fn demo_up_services(
         operator_log::info(
             module_path!(),
             format!(
-                "cloudflared ready domains={} url={} log={}",
-                domain_list,
-                handle.url,
-                handle.log_path.display()
+                "{} ready domains={} url={} log={}",
+                service_id, domain_list, handle.url, log_label
             ),
         );
         if debug_enabled {
             operator_log::debug(
                 module_path!(),
                 format!(
-                    "[demo dev] tenant={} team={} cloudflared domains={} url={} log={}",
-                    tenant,
-                    team,
-                    domain_list,
-                    handle.url,
-                    handle.log_path.display()
+                    "[demo dev] tenant={} team={} {} domains={} url={} log={}",
+                    tenant, team, service_id, domain_list, handle.url, log_label
                 ),
             );
         }
         println!(
-            "Public URL (service=cloudflared domains={domain_list}): {}",
+            "Public URL (service={service_id} domains={domain_list}): {}",
             handle.url
         );
-        service_tracker.record_with_log("cloudflared", "cloudflared", Some(&handle.log_path))?;
+        if handle.pid.is_some() {
+            service_tracker.record_with_log(
+                &service_id,
+                &service_id,
+                handle.log_path.as_deref(),
+            )?;
+        }
         Some(handle.url)
     } else {
         None
@@ -816,6 +815,39 @@ pub fn demo_status_runtime(
         println!("none running");
         return Ok(());
     }
+    print_service_statuses(&statuses, "", verbose);
+    Ok(())
+}
+
+/// Per-tenant breakdown for a multi-target `demo start --bundle`: pidfile
+/// services of each target plus the in-process services the operator runs
+/// for it.
+pub fn demo_status_targets(
+    state_dir: &Path,
+    manifest: &DemoTargetsManifest,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    let running = if supervisor::is_running(manifest.pid) {
+        "running"
+    } else {
+        "stopped"
+    };
+    println!("operator: {running} (pid={})", manifest.pid);
+    if let Some(url) = manifest.ingress_url.as_deref() {
+        println!("ingress: {url}");
+    }
+    for target in &manifest.targets {
+        println!("{}.{}:", target.tenant, target.team);
+        let paths = RuntimePaths::new(state_dir, &target.tenant, &target.team);
+        print_service_statuses(&supervisor::read_status(&paths)?, "  ", verbose);
+        let in_process = |enabled: bool| if enabled { "running" } else { "off" };
+        println!("  timer-scheduler: {}", in_process(target.timer_scheduler));
+        println!("  subscriptions: {}", in_process(target.subscriptions));
+    }
+    Ok(())
+}
+
+fn print_service_statuses(statuses: &[supervisor::ServiceStatus], indent: &str, verbose: bool) {
     for status in statuses {
         let state = if status.running { "running" } else { "stopped" };
         let pid = status
@@ -824,17 +856,16 @@ pub fn demo_status_runtime(
             .unwrap_or_else(|| "-".to_string());
         if verbose {
             println!(
-                "{}: {} (pid={}, log={})",
+                "{indent}{}: {} (pid={}, log={})",
                 status.id.as_str(),
                 state,
                 pid,
                 status.log_path.display()
             );
         } else {
-            println!("{}: {} (pid={})", status.id.as_str(), state, pid);
+            println!("{indent}{}: {} (pid={})", status.id.as_str(), state, pid);
         }
     }
-    Ok(())
 }
 
 pub fn demo_logs_runtime(
@@ -307,6 +307,86 @@ fn parse_explicit_timer_handlers(
     Ok(handlers)
 }
 
+/// A timer handler entry the scheduler would skip or silently adjust.
+pub(crate) struct TimerHandlerIssue {
+    pub path: String,
+    pub message: String,
+}
+
+/// Checks the explicit timer handlers in the provider extension: every entry
+/// needs an op, a non-zero interval, and an op declared by a provider when
+/// providers list their ops.
+pub(crate) fn timer_handler_issues(manifest_json: &JsonValue) -> Vec<TimerHandlerIssue> {
+    let Ok(inline) = provider_extension_inline_json(manifest_json) else {
+        return Vec::new();
+    };
+    let providers = inline
+        .get("providers")
+        .and_then(JsonValue::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let declared_ops = providers
+        .iter()
+        .filter_map(|provider| provider.get("ops").and_then(JsonValue::as_array))
+        .flatten()
+        .filter_map(JsonValue::as_str)
+        .collect::<std::collections::BTreeSet<_>>();
+
+    let mut entries = Vec::new();
+    for key in ["timer_handlers", "timers"] {
+        if let Some(values) = inline.get(key).and_then(JsonValue::as_array) {
+            for (idx, entry) in values.iter().enumerate() {
+                entries.push((format!("provider_extension.{key}[{idx}]"), entry));
+            }
+        }
+    }
+    for (pdx, provider) in providers.iter().enumerate() {
+        for key in ["timer_handlers", "timers"] {
+            if let Some(values) = provider.get(key).and_then(JsonValue::as_array) {
+                for (idx, entry) in values.iter().enumerate() {
+                    entries.push((
+                        format!("provider_extension.providers[{pdx}].{key}[{idx}]"),
+                        entry,
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut issues = Vec::new();
+    for (path, entry) in entries {
+        let Some(handler) = parse_timer_handler_entry(entry, "", 1) else {
+            issues.push(TimerHandlerIssue {
+                path,
+                message: "timer handler must be an op id or an object with `op_id`".to_string(),
+            });
+            continue;
+        };
+        let interval = entry
+            .get("interval_seconds")
+            .or_else(|| entry.get("interval"));
+        if interval.is_some_and(|value| value.as_u64().is_none_or(|value| value == 0)) {
+            issues.push(TimerHandlerIssue {
+                path: format!("{path}.interval_seconds"),
+                message: format!(
+                    "timer handler `{}` needs a positive whole number of seconds",
+                    handler.op_id
+                ),
+            });
+        }
+        if !declared_ops.is_empty() && !declared_ops.contains(handler.op_id.as_str()) {
+            issues.push(TimerHandlerIssue {
+                path,
+                message: format!(
+                    "timer handler op `{}` is not declared by any provider",
+                    handler.op_id
+                ),
+            });
+        }
+    }
+    issues
+}
+
 fn parse_timer_handler_entry(
     value: &JsonValue,
     default_provider: &str,
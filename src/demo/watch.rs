@@ -0,0 +1,219 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// What kind of input a changed file belongs to, which decides what
+/// `demo start --watch` has to reload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WatchKind {
+    /// Anything under `packs/` or `providers/`.
+    Packs,
+    /// A tenant or team `.gmap` under `tenants/`.
+    Policy,
+    /// `greentic.demo.yaml` or one of its `greentic.demo.<profile>.yaml`
+    /// overlays.
+    Config,
+}
+
+impl WatchKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WatchKind::Packs => "packs",
+            WatchKind::Policy => "policy",
+            WatchKind::Config => "config",
+        }
+    }
+}
+
+/// Files added, modified or removed since the previous poll, relative to the
+/// bundle root and grouped by kind.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WatchChanges {
+    pub changed: BTreeMap<WatchKind, BTreeSet<PathBuf>>,
+}
+
+impl WatchChanges {
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty()
+    }
+
+    pub fn contains(&self, kind: WatchKind) -> bool {
+        self.changed.contains_key(&kind)
+    }
+
+    pub fn paths(&self, kind: WatchKind) -> impl Iterator<Item = &PathBuf> {
+        self.changed.get(&kind).into_iter().flatten()
+    }
+
+    fn merge(&mut self, other: WatchChanges) {
+        for (kind, paths) in other.changed {
+            self.changed.entry(kind).or_default().extend(paths);
+        }
+    }
+}
+
+type Snapshot = BTreeMap<PathBuf, (Option<SystemTime>, u64)>;
+
+/// Polls the bundle inputs that `demo start --watch` reloads. The watcher
+/// compares modification times and sizes, so it needs no platform file
+/// notification support.
+pub struct BundleWatcher {
+    root: PathBuf,
+    snapshot: Snapshot,
+    settle: Duration,
+}
+
+impl BundleWatcher {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            snapshot: snapshot(root),
+            settle: Duration::from_millis(300),
+        }
+    }
+
+    pub fn with_settle(mut self, settle: Duration) -> Self {
+        self.settle = settle;
+        self
+    }
+
+    /// Returns what changed since the last poll. Once a change is seen, the
+    /// watcher waits until the files stop changing, so a pack that is still
+    /// being written is reported once, after the write finishes.
+    pub fn poll(&mut self) -> WatchChanges {
+        let mut changes = self.diff();
+        if changes.is_empty() {
+            return changes;
+        }
+        loop {
+            std::thread::sleep(self.settle);
+            let more = self.diff();
+            if more.is_empty() {
+                return changes;
+            }
+            changes.merge(more);
+        }
+    }
+
+    fn diff(&mut self) -> WatchChanges {
+        let current = snapshot(&self.root);
+        let mut changes = WatchChanges::default();
+        let paths = self
+            .snapshot
+            .keys()
+            .chain(current.keys())
+            .collect::<BTreeSet<_>>();
+        for path in paths {
+            if self.snapshot.get(path) != current.get(path)
+                && let Some(kind) = classify(path)
+            {
+                changes
+                    .changed
+                    .entry(kind)
+                    .or_default()
+                    .insert(path.clone());
+            }
+        }
+        self.snapshot = current;
+        changes
+    }
+}
+
+fn classify(path: &Path) -> Option<WatchKind> {
+    let first = path.components().next()?.as_os_str().to_str()?;
+    match first {
+        "packs" | "providers" => Some(WatchKind::Packs),
+        "tenants" if path.extension().and_then(|ext| ext.to_str()) == Some("gmap") => {
+            Some(WatchKind::Policy)
+        }
+        name if is_demo_config(name) => Some(WatchKind::Config),
+        _ => None,
+    }
+}
+
+fn snapshot(root: &Path) -> Snapshot {
+    let mut files = Snapshot::new();
+    for dir in ["packs", "providers", "tenants"] {
+        collect_files(root, &root.join(dir), &mut files);
+    }
+    if let Ok(entries) = std::fs::read_dir(root) {
+        for entry in entries.flatten() {
+            if entry.file_name().to_str().is_some_and(is_demo_config) {
+                record_file(root, &entry.path(), &mut files);
+            }
+        }
+    }
+    files
+}
+
+fn is_demo_config(name: &str) -> bool {
+    name.starts_with("greentic.demo.") && name.ends_with(".yaml")
+}
+
+fn collect_files(root: &Path, dir: &Path, files: &mut Snapshot) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => collect_files(root, &path, files),
+            Ok(_) => record_file(root, &path, files),
+            Err(_) => {}
+        }
+    }
+}
+
+fn record_file(root: &Path, path: &Path, files: &mut Snapshot) {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return;
+    };
+    let relative = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+    files.insert(relative, (metadata.modified().ok(), metadata.len()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_are_grouped_by_kind_and_ignore_other_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("packs")).unwrap();
+        std::fs::create_dir_all(root.join("tenants/demo")).unwrap();
+        std::fs::write(root.join("packs/a.gtpack"), "a").unwrap();
+        let mut watcher = BundleWatcher::new(root).with_settle(Duration::from_millis(1));
+        assert!(watcher.poll().is_empty());
+
+        std::fs::write(root.join("packs/a.gtpack"), "a2").unwrap();
+        std::fs::write(root.join("tenants/demo/tenant.gmap"), "_ = forbidden").unwrap();
+        std::fs::write(root.join("tenants/demo/notes.txt"), "ignored").unwrap();
+        std::fs::write(root.join("greentic.demo.yaml"), "tenant: demo").unwrap();
+        std::fs::write(root.join("greentic.demo.ci.yaml"), "team: ci").unwrap();
+        std::fs::create_dir_all(root.join("state")).unwrap();
+        std::fs::write(root.join("state/runtime.json"), "{}").unwrap();
+
+        let changes = watcher.poll();
+        let kinds = changes.changed.keys().copied().collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![WatchKind::Packs, WatchKind::Policy, WatchKind::Config]
+        );
+        assert_eq!(
+            changes.paths(WatchKind::Policy).collect::<Vec<_>>(),
+            vec![&PathBuf::from("tenants/demo/tenant.gmap")]
+        );
+        assert_eq!(
+            changes.paths(WatchKind::Config).collect::<Vec<_>>(),
+            vec![
+                &PathBuf::from("greentic.demo.ci.yaml"),
+                &PathBuf::from("greentic.demo.yaml")
+            ]
+        );
+        assert!(watcher.poll().is_empty());
+
+        std::fs::remove_file(root.join("packs/a.gtpack")).unwrap();
+        assert!(watcher.poll().contains(WatchKind::Packs));
+    }
+}
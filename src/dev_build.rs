@@ -0,0 +1,272 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::SystemTime;
+
+use crate::config::{DemoServicesConfig, DemoSubscriptionsMode, OperatorConfig};
+use crate::dev_mode::{DevProfile, DevSettingsResolved, profile_dir};
+
+/// Binaries the operator always resolves: the flow runner and the pack CLI.
+const CORE_BINARIES: &[&str] = &["greentic-runner", "greentic-pack"];
+
+/// Binaries the operator resolves by name for this project: the core
+/// binaries, the gateway, egress and msgraph subscriptions services of
+/// `demo`, and the service components listed in `config`. Binaries given as
+/// paths are never looked up in `dev.repo_map`, so they are left out.
+pub fn dev_binaries(config: Option<&OperatorConfig>, demo: &DemoServicesConfig) -> Vec<String> {
+    let mut binaries = CORE_BINARIES
+        .iter()
+        .map(|name| name.to_string())
+        .collect::<BTreeSet<_>>();
+    binaries.insert(demo.gateway.binary.clone());
+    binaries.insert(demo.egress.binary.clone());
+    let msgraph = &demo.subscriptions.msgraph;
+    if matches!(demo.subscriptions.mode, DemoSubscriptionsMode::LegacyGsm) && msgraph.enabled {
+        binaries.insert(msgraph.binary.clone());
+    }
+    let services = config.and_then(|config| config.services.as_ref());
+    let components = services
+        .into_iter()
+        .flat_map(|services| [&services.messaging.components, &services.events.components])
+        .chain([&demo.events.components])
+        .flatten();
+    binaries.extend(components.map(|component| component.binary.clone()));
+    binaries
+        .into_iter()
+        .filter(|name| !looks_like_path(name))
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RepoBuildStatus {
+    Built,
+    UpToDate,
+}
+
+#[derive(Clone, Debug)]
+pub struct RepoBuild {
+    pub repo: String,
+    pub binaries: Vec<String>,
+    pub status: RepoBuildStatus,
+    pub log_path: Option<PathBuf>,
+}
+
+/// Runs `cargo build` in every `dev.repo_map` repo that owns one of
+/// `binaries`, using the dev profile and target dir. Repos whose binaries are
+/// all newer than their sources are skipped. Build output goes to
+/// `state/logs/dev-build/<repo>.log` under `root`.
+pub fn build_repos(
+    root: &Path,
+    dev: &DevSettingsResolved,
+    binaries: &[&str],
+) -> anyhow::Result<Vec<RepoBuild>> {
+    if !dev.root.exists() {
+        return Err(anyhow::anyhow!(
+            "dev root not found: {}",
+            dev.root.display()
+        ));
+    }
+    let mut repos: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for name in binaries {
+        if let Some(repo) = dev.repo_map.get(*name) {
+            repos
+                .entry(repo.as_str())
+                .or_default()
+                .push((*name).to_string());
+        }
+    }
+
+    let mut builds = Vec::new();
+    for (repo, binaries) in repos {
+        let repo_dir = dev.root.join(repo);
+        if !repo_dir.join("Cargo.toml").exists() {
+            return Err(anyhow::anyhow!(
+                "dev repo {repo} has no Cargo.toml at {}",
+                repo_dir.display()
+            ));
+        }
+        let target_dir = dev
+            .target_dir
+            .clone()
+            .unwrap_or_else(|| repo_dir.join("target"));
+        let out_dir = target_dir.join(profile_dir(dev.profile));
+        let newest_source = newest_source_mtime(&repo_dir, &target_dir);
+        let up_to_date = binaries.iter().all(|name| {
+            let binary_mtime = std::fs::metadata(out_dir.join(binary_name(name)))
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            match (binary_mtime, newest_source) {
+                (Some(binary), Some(source)) => binary >= source,
+                (Some(_), None) => true,
+                (None, _) => false,
+            }
+        });
+        if up_to_date {
+            builds.push(RepoBuild {
+                repo: repo.to_string(),
+                binaries,
+                status: RepoBuildStatus::UpToDate,
+                log_path: None,
+            });
+            continue;
+        }
+
+        let log_path = root
+            .join("state")
+            .join("logs")
+            .join("dev-build")
+            .join(format!("{repo}.log"));
+        cargo_build(&repo_dir, &target_dir, dev.profile, &binaries, &log_path)?;
+        builds.push(RepoBuild {
+            repo: repo.to_string(),
+            binaries,
+            status: RepoBuildStatus::Built,
+            log_path: Some(log_path),
+        });
+    }
+    Ok(builds)
+}
+
+fn cargo_build(
+    repo_dir: &Path,
+    target_dir: &Path,
+    profile: DevProfile,
+    binaries: &[String],
+    log_path: &Path,
+) -> anyhow::Result<()> {
+    if let Some(parent) = log_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let log = std::fs::File::create(log_path)?;
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut command = Command::new(cargo);
+    // Always pass the target dir so an inherited CARGO_TARGET_DIR cannot move
+    // the binaries away from where the resolver looks for them.
+    command
+        .arg("build")
+        .arg("--target-dir")
+        .arg(target_dir)
+        .current_dir(repo_dir);
+    if profile == DevProfile::Release {
+        command.arg("--release");
+    }
+    for name in binaries {
+        command.arg("--bin").arg(name);
+    }
+    let status = command
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log)
+        .status()
+        .map_err(|err| anyhow::anyhow!("failed to run cargo build: {err}"))?;
+    if !status.success() {
+        return Err(anyhow::anyhow!(
+            "cargo build failed in {} ({status}); see {}",
+            repo_dir.display(),
+            log_path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Newest modification time of the files cargo would rebuild from, skipping
+/// build output and VCS metadata.
+fn newest_source_mtime(repo_dir: &Path, target_dir: &Path) -> Option<SystemTime> {
+    let mut newest = None;
+    let mut pending = vec![repo_dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                let name = entry.file_name();
+                if name == "target" || name == ".git" || path == target_dir {
+                    continue;
+                }
+                pending.push(path);
+            } else if let Ok(modified) = entry.metadata().and_then(|meta| meta.modified())
+                && newest.is_none_or(|newest| modified > newest)
+            {
+                newest = Some(modified);
+            }
+        }
+    }
+    newest
+}
+
+fn looks_like_path(value: &str) -> bool {
+    value.contains('/') || value.contains('\\') || Path::new(value).is_absolute()
+}
+
+fn binary_name(name: &str) -> String {
+    if cfg!(windows) {
+        format!("{name}.exe")
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{OperatorServicesConfig, ServiceComponentConfig};
+
+    #[test]
+    fn dev_binaries_cover_services_and_components() {
+        let component = |binary: &str| ServiceComponentConfig {
+            id: binary.to_string(),
+            binary: binary.to_string(),
+            args: Vec::new(),
+        };
+        let mut config = OperatorConfig {
+            services: Some(OperatorServicesConfig::default()),
+            ..Default::default()
+        };
+        let services = config.services.as_mut().unwrap();
+        services.events.components = vec![component("events-worker")];
+        services.messaging.components = vec![component("./bin/local-tool")];
+        let mut demo = DemoServicesConfig::default();
+        demo.gateway.binary = "greentic-gateway".to_string();
+
+        assert_eq!(
+            dev_binaries(Some(&config), &demo),
+            vec![
+                "egress",
+                "events-worker",
+                "greentic-gateway",
+                "greentic-pack",
+                "greentic-runner",
+                "subscriptions-msgraph",
+            ]
+        );
+
+        demo.subscriptions.msgraph.enabled = false;
+        assert!(!dev_binaries(None, &demo).contains(&"subscriptions-msgraph".to_string()));
+    }
+
+    #[test]
+    fn source_mtime_ignores_build_output() {
+        let temp = tempfile::tempdir().unwrap();
+        let repo = temp.path();
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        std::fs::write(repo.join("src/main.rs"), "fn main() {}").unwrap();
+        let source = std::fs::metadata(repo.join("src/main.rs"))
+            .unwrap()
+            .modified()
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        std::fs::create_dir_all(repo.join("target/debug")).unwrap();
+        std::fs::write(repo.join("target/debug/tool"), "bin").unwrap();
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::write(repo.join(".git/HEAD"), "ref").unwrap();
+        assert_eq!(
+            newest_source_mtime(repo, &repo.join("target")),
+            Some(source)
+        );
+    }
+}
@@ -1,5 +1,11 @@
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 
+use greentic_types::validate::{Diagnostic, Severity, ValidationReport};
+use serde::Serialize;
+
+use crate::bin_version::BinaryCheck;
+use crate::doctor_report::{DoctorReport, DoctorReportFormat};
 use crate::domains::{self, Domain};
 
 #[derive(Clone, Debug)]
@@ -8,6 +14,18 @@ pub struct DoctorOptions {
     pub team: Option<String>,
     pub strict: bool,
     pub validator_packs: Vec<PathBuf>,
+    /// `binary_versions` results to include in the report.
+    pub binaries: Vec<BinaryCheck>,
+}
+
+/// What validates each pack.
+#[derive(Clone, Copy, Debug)]
+pub enum DoctorBackend<'a> {
+    /// Runs `greentic-pack doctor` from the given path.
+    External(&'a Path),
+    /// Runs the in-process checks from [`crate::builtin_doctor`]. Validator
+    /// packs are not applied.
+    Builtin,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -16,18 +34,34 @@ pub enum DoctorScope {
     All,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct DoctorRun {
+    pub pack: String,
     pub pack_path: PathBuf,
-    pub status: std::process::ExitStatus,
+    pub domain: String,
+    pub validators: Vec<PathBuf>,
+    pub status: DoctorStatus,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DoctorStatus {
+    Passed,
+    Failed,
 }
 
+/// Validates every pack in scope with `backend`, without stopping at
+/// the first failure, and writes `report.json` next to the per-pack output.
+/// Use [`DoctorReport::ensure_passed`] to turn failures into an error.
 pub fn run_doctor(
     root: &Path,
     scope: DoctorScope,
     options: DoctorOptions,
-    pack_command: &Path,
-) -> anyhow::Result<Vec<DoctorRun>> {
+    backend: DoctorBackend<'_>,
+) -> anyhow::Result<DoctorReport> {
     let base_dir = doctor_root(root)?;
     std::fs::create_dir_all(&base_dir)?;
 
@@ -57,7 +91,7 @@ pub fn run_doctor(
                 &pack.pack_id,
                 &validators,
                 options.strict,
-                pack_command,
+                backend,
             )?;
             runs.push(run);
         }
@@ -75,15 +109,16 @@ pub fn run_doctor(
                     .unwrap_or("pack"),
                 &options.validator_packs,
                 options.strict,
-                pack_command,
+                backend,
             )?;
-            if !run.status.success() {
+            if run.status == DoctorStatus::Failed {
                 let _ = write_summary(
                     &base_dir,
                     "demo",
                     &format!("doctor failed for demo pack {:?}", pack.display()),
                 );
             }
+            runs.push(run);
         }
         let summary = format!(
             "demo packs validated for tenant={} team={}\n",
@@ -93,7 +128,16 @@ pub fn run_doctor(
         write_summary(&base_dir, "demo", &summary)?;
     }
 
-    Ok(runs)
+    let report = DoctorReport {
+        root: root.to_path_buf(),
+        binaries: options.binaries,
+        runs,
+    };
+    std::fs::write(
+        base_dir.join("report.json"),
+        report.render(DoctorReportFormat::Json)?,
+    )?;
+    Ok(report)
 }
 
 pub fn build_doctor_args(
@@ -121,22 +165,52 @@ fn run_doctor_for_pack(
     pack_label: &str,
     validator_packs: &[PathBuf],
     strict: bool,
-    pack_command: &Path,
+    backend: DoctorBackend<'_>,
 ) -> anyhow::Result<DoctorRun> {
     let run_dir = base_dir.join(domain_name(domain)).join(pack_label);
     std::fs::create_dir_all(&run_dir)?;
     let stdout_path = run_dir.join("stdout.txt");
     let stderr_path = run_dir.join("stderr.txt");
 
+    let pack_command = match backend {
+        DoctorBackend::External(pack_command) => pack_command,
+        DoctorBackend::Builtin => {
+            let run = run_builtin_doctor(pack_path, pack_label, domain_name(domain), strict);
+            let report = ValidationReport {
+                pack_id: None,
+                pack_version: None,
+                diagnostics: run.diagnostics.clone(),
+            };
+            std::fs::write(&stdout_path, serde_json::to_string_pretty(&report)?)?;
+            let summary = format!(
+                "pack: {}\nstatus: {} (builtin)\n",
+                pack_path.display(),
+                if run.status == DoctorStatus::Passed {
+                    "passed"
+                } else {
+                    "failed"
+                }
+            );
+            write_summary(
+                base_dir,
+                &format!("{}-{}", domain_name(domain), pack_label),
+                &summary,
+            )?;
+            return Ok(run);
+        }
+    };
+
     let stdout = std::fs::File::create(&stdout_path)?;
     let stderr = std::fs::File::create(&stderr_path)?;
 
     let args = build_doctor_args(pack_path, validator_packs, strict);
+    let started = Instant::now();
     let status = std::process::Command::new(pack_command)
         .args(&args)
         .stdout(stdout)
         .stderr(stderr)
         .status()?;
+    let duration_ms = started.elapsed().as_millis() as u64;
 
     let summary = format!("pack: {}\nstatus: {}\n", pack_path.display(), status);
     write_summary(
@@ -145,19 +219,88 @@ fn run_doctor_for_pack(
         &summary,
     )?;
 
-    if !status.success() {
-        return Err(anyhow::anyhow!(
-            "greentic-pack doctor failed for {}",
-            pack_path.display()
-        ));
-    }
-
+    let stdout = std::fs::read_to_string(&stdout_path).unwrap_or_default();
+    let stderr = std::fs::read_to_string(&stderr_path).unwrap_or_default();
     Ok(DoctorRun {
+        pack: pack_label.to_string(),
         pack_path: pack_path.to_path_buf(),
-        status,
+        domain: domain_name(domain).to_string(),
+        validators: validator_packs.to_vec(),
+        status: if status.success() {
+            DoctorStatus::Passed
+        } else {
+            DoctorStatus::Failed
+        },
+        exit_code: status.code(),
+        duration_ms,
+        diagnostics: parse_diagnostics(&stdout, &stderr, status),
     })
 }
 
+/// Validates one pack with the built-in checks. The pack fails on any error
+/// diagnostic, or on any warning when `strict` is set.
+pub fn run_builtin_doctor(
+    pack_path: &Path,
+    pack_label: &str,
+    domain: &str,
+    strict: bool,
+) -> DoctorRun {
+    let started = Instant::now();
+    let report = crate::builtin_doctor::validate_pack(pack_path);
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let failed = report.diagnostics.iter().any(|diagnostic| {
+        diagnostic.severity == Severity::Error || (strict && diagnostic.severity == Severity::Warn)
+    });
+    DoctorRun {
+        pack: pack_label.to_string(),
+        pack_path: pack_path.to_path_buf(),
+        domain: domain.to_string(),
+        validators: Vec::new(),
+        status: if failed {
+            DoctorStatus::Failed
+        } else {
+            DoctorStatus::Passed
+        },
+        exit_code: None,
+        duration_ms,
+        diagnostics: report.diagnostics,
+    }
+}
+
+/// Diagnostics from a `greentic-pack doctor` JSON validation report on
+/// stdout. A failed run without error diagnostics gets one describing the
+/// exit, using the last line of stderr.
+fn parse_diagnostics(
+    stdout: &str,
+    stderr: &str,
+    status: std::process::ExitStatus,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = serde_json::from_str::<ValidationReport>(stdout.trim())
+        .map(|report| report.diagnostics)
+        .unwrap_or_default();
+    let has_error = diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error);
+    if !status.success() && !has_error {
+        let message = stderr
+            .lines()
+            .rev()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("greentic-pack doctor exited with {status}"));
+        diagnostics.push(Diagnostic {
+            severity: Severity::Error,
+            code: "PACK_DOCTOR_FAILED".to_string(),
+            message,
+            path: None,
+            hint: None,
+            data: serde_json::Value::Null,
+        });
+    }
+    diagnostics
+}
+
 struct DemoPackSelection {
     packs: Vec<PathBuf>,
     tenant: String,
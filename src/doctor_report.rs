@@ -0,0 +1,416 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use greentic_types::validate::{Diagnostic, Severity};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::bin_version::{self, BinaryCheck, BinaryCheckStatus};
+use crate::doctor::{DoctorRun, DoctorStatus};
+
+/// Every pack the doctor looked at, in the order they ran, plus the
+/// `binary_versions` checks from `greentic.yaml`.
+#[derive(Clone, Debug, Serialize)]
+pub struct DoctorReport {
+    pub root: PathBuf,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub binaries: Vec<BinaryCheck>,
+    pub runs: Vec<DoctorRun>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DoctorReportFormat {
+    Text,
+    Json,
+    Junit,
+    Sarif,
+}
+
+impl DoctorReport {
+    pub fn failed(&self) -> usize {
+        self.runs
+            .iter()
+            .filter(|run| run.status == DoctorStatus::Failed)
+            .count()
+    }
+
+    pub fn failed_binaries(&self) -> usize {
+        self.binaries
+            .iter()
+            .filter(|check| check.is_blocking())
+            .count()
+    }
+
+    /// Fails when any pack or binary version check did not pass, after the
+    /// report has been emitted.
+    pub fn ensure_passed(&self) -> anyhow::Result<()> {
+        let mut problems = Vec::new();
+        match self.failed() {
+            0 => {}
+            failed => problems.push(format!(
+                "doctor failed for {failed} of {} pack(s)",
+                self.runs.len()
+            )),
+        }
+        match self.failed_binaries() {
+            0 => {}
+            failed => problems.push(format!("{failed} binary version requirement(s) not met")),
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(problems.join("; ")))
+        }
+    }
+
+    pub fn render(&self, format: DoctorReportFormat) -> anyhow::Result<String> {
+        match format {
+            DoctorReportFormat::Text => Ok(self.render_text()),
+            DoctorReportFormat::Json => Ok(serde_json::to_string_pretty(self)? + "\n"),
+            DoctorReportFormat::Junit => Ok(self.render_junit()),
+            DoctorReportFormat::Sarif => Ok(serde_json::to_string_pretty(&self.sarif())? + "\n"),
+        }
+    }
+
+    fn render_text(&self) -> String {
+        let mut out = bin_version::render_checks(&self.binaries);
+        for run in &self.runs {
+            let status = match run.status {
+                DoctorStatus::Passed => "ok",
+                DoctorStatus::Failed => "FAILED",
+            };
+            out.push_str(&format!(
+                "[{status}] {} ({}) {}ms\n",
+                run.pack, run.domain, run.duration_ms
+            ));
+            for diagnostic in &run.diagnostics {
+                out.push_str(&format!(
+                    "  {} {}: {}\n",
+                    severity_name(diagnostic.severity),
+                    diagnostic.code,
+                    diagnostic.message
+                ));
+            }
+        }
+        out.push_str(&format!(
+            "{} pack(s) checked, {} failed\n",
+            self.runs.len(),
+            self.failed()
+        ));
+        out
+    }
+
+    /// One `<testsuite>` per domain and one `<testcase>` per pack; error
+    /// diagnostics become `<failure>` elements.
+    fn render_junit(&self) -> String {
+        let mut suites: BTreeMap<&str, Vec<&DoctorRun>> = BTreeMap::new();
+        for run in &self.runs {
+            suites.entry(run.domain.as_str()).or_default().push(run);
+        }
+        let seconds = |runs: &[&DoctorRun]| {
+            runs.iter().map(|run| run.duration_ms).sum::<u64>() as f64 / 1000.0
+        };
+        let all = self.runs.iter().collect::<Vec<_>>();
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(&format!(
+            "<testsuites name=\"greentic-operator doctor\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+            self.runs.len() + self.binaries.len(),
+            self.failed() + self.failed_binaries(),
+            seconds(&all)
+        ));
+        if !self.binaries.is_empty() {
+            out.push_str(&format!(
+                "  <testsuite name=\"binaries\" tests=\"{}\" failures=\"{}\" time=\"0.000\">\n",
+                self.binaries.len(),
+                self.failed_binaries()
+            ));
+            for check in &self.binaries {
+                out.push_str(&format!(
+                    "    <testcase classname=\"doctor.binaries\" name=\"{}\" time=\"0.000\"",
+                    xml_escape(&check.binary)
+                ));
+                match check.status {
+                    BinaryCheckStatus::Compatible => out.push_str("/>\n"),
+                    BinaryCheckStatus::NotFound => out.push_str(&format!(
+                        ">\n      <system-out>{}</system-out>\n    </testcase>\n",
+                        xml_escape(&check.message)
+                    )),
+                    BinaryCheckStatus::Incompatible | BinaryCheckStatus::Unknown => {
+                        out.push_str(&format!(
+                            ">\n      <failure type=\"{}\" message=\"{}\"/>\n    </testcase>\n",
+                            binary_rule(check.status),
+                            xml_escape(&check.message)
+                        ))
+                    }
+                }
+            }
+            out.push_str("  </testsuite>\n");
+        }
+        for (domain, runs) in suites {
+            let failures = runs
+                .iter()
+                .filter(|run| run.status == DoctorStatus::Failed)
+                .count();
+            out.push_str(&format!(
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\" time=\"{:.3}\">\n",
+                xml_escape(domain),
+                runs.len(),
+                seconds(&runs)
+            ));
+            for run in runs {
+                out.push_str(&format!(
+                    "    <testcase classname=\"doctor.{}\" name=\"{}\" time=\"{:.3}\"",
+                    xml_escape(domain),
+                    xml_escape(&run.pack),
+                    run.duration_ms as f64 / 1000.0
+                ));
+                if run.status == DoctorStatus::Passed && run.diagnostics.is_empty() {
+                    out.push_str("/>\n");
+                    continue;
+                }
+                out.push_str(">\n");
+                for diagnostic in &run.diagnostics {
+                    if diagnostic.severity == Severity::Error {
+                        out.push_str(&format!(
+                            "      <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+                            xml_escape(&diagnostic.code),
+                            xml_escape(&diagnostic.message),
+                            xml_escape(&diagnostic_detail(run, diagnostic))
+                        ));
+                    }
+                }
+                let notes = run
+                    .diagnostics
+                    .iter()
+                    .filter(|diagnostic| diagnostic.severity != Severity::Error)
+                    .map(|diagnostic| {
+                        format!(
+                            "{} {}: {}",
+                            severity_name(diagnostic.severity),
+                            diagnostic.code,
+                            diagnostic.message
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                if !notes.is_empty() {
+                    out.push_str(&format!(
+                        "      <system-out>{}</system-out>\n",
+                        xml_escape(&notes.join("\n"))
+                    ));
+                }
+                out.push_str("    </testcase>\n");
+            }
+            out.push_str("  </testsuite>\n");
+        }
+        out.push_str("</testsuites>\n");
+        out
+    }
+
+    /// SARIF 2.1.0 log with one result per diagnostic, located at the pack
+    /// archive relative to the project root.
+    fn sarif(&self) -> serde_json::Value {
+        let mut rules = BTreeMap::new();
+        let mut results = Vec::new();
+        for check in &self.binaries {
+            if check.status == BinaryCheckStatus::Compatible {
+                continue;
+            }
+            let code = binary_rule(check.status);
+            rules
+                .entry(code.to_string())
+                .or_insert_with(|| json!({ "id": code, "shortDescription": { "text": code } }));
+            let mut result = json!({
+                "ruleId": code,
+                "level": if check.is_blocking() { "error" } else { "warning" },
+                "message": { "text": check.message },
+                "properties": { "binary": check.binary, "requirement": check.requirement },
+            });
+            if let Some(path) = check.path.as_deref() {
+                result["locations"] = json!([{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": relative_uri(&self.root, path) }
+                    }
+                }]);
+            }
+            results.push(result);
+        }
+        for run in &self.runs {
+            let uri = relative_uri(&self.root, &run.pack_path);
+            for diagnostic in &run.diagnostics {
+                rules.entry(diagnostic.code.clone()).or_insert_with(|| {
+                    json!({
+                        "id": diagnostic.code,
+                        "shortDescription": { "text": diagnostic.code },
+                    })
+                });
+                let mut message = diagnostic.message.clone();
+                if let Some(hint) = diagnostic.hint.as_deref() {
+                    message.push_str(&format!(" ({hint})"));
+                }
+                let mut location = json!({
+                    "physicalLocation": { "artifactLocation": { "uri": uri } }
+                });
+                if let Some(path) = diagnostic.path.as_deref() {
+                    location["logicalLocations"] = json!([{ "fullyQualifiedName": path }]);
+                }
+                results.push(json!({
+                    "ruleId": diagnostic.code,
+                    "level": sarif_level(diagnostic.severity),
+                    "message": { "text": message },
+                    "locations": [location],
+                    "properties": { "domain": run.domain, "pack": run.pack },
+                }));
+            }
+        }
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "greentic-operator doctor",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules.into_values().collect::<Vec<_>>(),
+                    }
+                },
+                "results": results,
+            }]
+        })
+    }
+}
+
+fn diagnostic_detail(run: &DoctorRun, diagnostic: &Diagnostic) -> String {
+    let mut detail = format!("{}: {}", run.pack_path.display(), diagnostic.message);
+    if let Some(path) = diagnostic.path.as_deref() {
+        detail.push_str(&format!("\nat {path}"));
+    }
+    if let Some(hint) = diagnostic.hint.as_deref() {
+        detail.push_str(&format!("\nhint: {hint}"));
+    }
+    detail
+}
+
+fn binary_rule(status: BinaryCheckStatus) -> &'static str {
+    match status {
+        BinaryCheckStatus::Compatible => "OP_BINARY_VERSION_OK",
+        BinaryCheckStatus::Incompatible => "OP_BINARY_VERSION_MISMATCH",
+        BinaryCheckStatus::Unknown => "OP_BINARY_VERSION_UNKNOWN",
+        BinaryCheckStatus::NotFound => "OP_BINARY_NOT_FOUND",
+    }
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "info",
+        Severity::Warn => "warning",
+        Severity::Error => "error",
+    }
+}
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "note",
+        Severity::Warn => "warning",
+        Severity::Error => "error",
+    }
+}
+
+fn relative_uri(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn xml_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            ch if ch.is_control() && ch != '\n' && ch != '\t' => {}
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> DoctorReport {
+        DoctorReport {
+            root: PathBuf::from("/work"),
+            binaries: Vec::new(),
+            runs: vec![
+                DoctorRun {
+                    pack: "telegram".to_string(),
+                    pack_path: PathBuf::from("/work/providers/messaging/telegram.gtpack"),
+                    domain: "messaging".to_string(),
+                    validators: Vec::new(),
+                    status: DoctorStatus::Passed,
+                    exit_code: Some(0),
+                    duration_ms: 1200,
+                    diagnostics: Vec::new(),
+                },
+                DoctorRun {
+                    pack: "webhook".to_string(),
+                    pack_path: PathBuf::from("/work/providers/events/webhook.gtpack"),
+                    domain: "events".to_string(),
+                    validators: Vec::new(),
+                    status: DoctorStatus::Failed,
+                    exit_code: Some(1),
+                    duration_ms: 300,
+                    diagnostics: vec![Diagnostic {
+                        severity: Severity::Error,
+                        code: "PACK_MISSING_SCHEMA".to_string(),
+                        message: "config schema <missing> & required".to_string(),
+                        path: Some("components[0].config_schema".to_string()),
+                        hint: None,
+                        data: serde_json::Value::Null,
+                    }],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn junit_groups_packs_by_domain_and_escapes_messages() {
+        let xml = report().render(DoctorReportFormat::Junit).unwrap();
+        assert!(xml.contains(
+            "<testsuites name=\"greentic-operator doctor\" tests=\"2\" failures=\"1\" time=\"1.500\">"
+        ));
+        assert!(xml.contains("<testsuite name=\"events\" tests=\"1\" failures=\"1\""));
+        assert!(xml.contains(
+            "<testcase classname=\"doctor.messaging\" name=\"telegram\" time=\"1.200\"/>"
+        ));
+        assert!(xml.contains(
+            "<failure type=\"PACK_MISSING_SCHEMA\" message=\"config schema &lt;missing&gt; &amp; required\">"
+        ));
+    }
+
+    #[test]
+    fn sarif_reports_diagnostics_against_the_pack() {
+        let sarif: serde_json::Value =
+            serde_json::from_str(&report().render(DoctorReportFormat::Sarif).unwrap()).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["ruleId"], "PACK_MISSING_SCHEMA");
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "providers/events/webhook.gtpack"
+        );
+        assert_eq!(
+            sarif["runs"][0]["tool"]["driver"]["rules"][0]["id"],
+            "PACK_MISSING_SCHEMA"
+        );
+    }
+}
@@ -332,6 +332,31 @@ fn read_pack_manifest(path: &Path) -> anyhow::Result<PackManifest> {
     })
 }
 
+/// Entry flows from the manifest `meta` and every flow id or entrypoint the
+/// manifest declares, as written (no fallbacks applied).
+pub(crate) struct ManifestFlows {
+    pub entry_flows: Vec<String>,
+    pub declared: BTreeSet<String>,
+}
+
+pub(crate) fn read_manifest_flows(path: &Path) -> anyhow::Result<ManifestFlows> {
+    let file = std::fs::File::open(path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
+    let manifest = read_pack_manifest_data(&mut archive, path)?;
+    let mut declared = BTreeSet::new();
+    for flow in manifest.flows {
+        declared.insert(flow.id);
+        declared.extend(flow.entrypoints);
+    }
+    Ok(ManifestFlows {
+        entry_flows: manifest
+            .meta
+            .map(|meta| meta.entry_flows)
+            .unwrap_or_default(),
+        declared,
+    })
+}
+
 pub(crate) fn read_pack_meta(path: &Path) -> anyhow::Result<PackMeta> {
     let manifest = if path.is_dir() {
         read_pack_manifest_from_dir(path)
@@ -1,16 +1,22 @@
 pub mod bin_resolver;
+pub mod bin_version;
+pub mod builtin_doctor;
 pub mod cards;
 pub mod cli;
 pub mod cloudflared;
 pub mod component_qa_ops;
 pub mod config;
 pub mod config_gate;
+pub mod config_layers;
+pub mod config_schema;
 pub mod demo;
+pub mod dev_build;
 pub mod dev_detect;
 pub mod dev_mode;
 pub mod dev_store_path;
 pub mod discovery;
 pub mod doctor;
+pub mod doctor_report;
 pub mod domains;
 pub mod gmap;
 pub mod messaging_universal;
@@ -21,17 +27,27 @@ pub mod providers;
 pub mod runner_exec;
 pub mod runner_integration;
 pub mod runtime_state;
+pub mod secret_audit;
 pub mod secret_name;
+pub mod secret_placeholders;
 pub mod secret_requirements;
+pub mod secret_rotation;
+pub mod secret_scope;
 pub mod secret_value;
+pub mod secrets_admin;
 pub mod secrets_backend;
 pub mod secrets_client;
+pub mod secrets_file_store;
 pub mod secrets_gate;
+pub mod secrets_http;
 pub mod secrets_manager;
 pub mod secrets_setup;
 pub mod services;
 pub mod settings;
+pub mod setup_fingerprint;
 pub mod setup_input;
 pub mod state_layout;
 pub mod subscriptions_universal;
 pub mod supervisor;
+pub mod tunnel;
+pub mod webhook_sync;
@@ -18,6 +18,8 @@ use zip::ZipArchive;
 use crate::runtime_state::atomic_write;
 
 const ABI_VERSION: &str = "greentic:component@0.6.0";
+/// Envelopes kept per provider in `<provider>/history/`; older ones are pruned.
+pub const ENVELOPE_HISTORY_LIMIT: usize = 20;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigEnvelope {
@@ -86,9 +88,199 @@ pub fn write_provider_config_envelope(
         std::fs::copy(&path, &backup_path)?;
     }
     atomic_write(&path, &bytes)?;
+    record_envelope_history(providers_root, provider_id, &envelope, &bytes)?;
     Ok(path)
 }
 
+/// A past envelope from `<provider>/history/`. Revisions count up from 1.
+#[derive(Debug, Clone)]
+pub struct EnvelopeRevision {
+    pub rev: u64,
+    pub path: PathBuf,
+    pub envelope: ConfigEnvelope,
+}
+
+/// One difference between two envelopes. `path` is a dotted path such as
+/// `describe_hash` or `config.webhook.url`; a missing side means the value was
+/// added or removed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EnvelopeChange {
+    pub path: String,
+    pub old: Option<JsonValue>,
+    pub new: Option<JsonValue>,
+}
+
+fn history_dir(providers_root: &Path, provider_id: &str) -> PathBuf {
+    providers_root.join(provider_id).join("history")
+}
+
+fn record_envelope_history(
+    providers_root: &Path,
+    provider_id: &str,
+    envelope: &ConfigEnvelope,
+    bytes: &[u8],
+) -> anyhow::Result<u64> {
+    let dir = history_dir(providers_root, provider_id);
+    let existing = history_files(&dir)?;
+    let rev = existing.last().map_or(1, |(rev, _)| rev + 1);
+    let stamp = envelope
+        .updated_at
+        .as_deref()
+        .and_then(|value| chrono::DateTime::parse_from_rfc3339(value).ok())
+        .map(|value| value.with_timezone(&Utc))
+        .unwrap_or_else(Utc::now);
+    let path = dir.join(format!(
+        "{rev:06}-{}.envelope.cbor",
+        stamp.format("%Y%m%dT%H%M%SZ")
+    ));
+    atomic_write(&path, bytes)?;
+    let excess = (existing.len() + 1).saturating_sub(ENVELOPE_HISTORY_LIMIT);
+    for (_, old) in existing.into_iter().take(excess) {
+        std::fs::remove_file(&old).with_context(|| format!("prune {}", old.display()))?;
+    }
+    Ok(rev)
+}
+
+fn history_files(dir: &Path) -> anyhow::Result<Vec<(u64, PathBuf)>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("read {}", dir.display()))? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !name.ends_with(".envelope.cbor") {
+            continue;
+        }
+        if let Some(rev) = name
+            .split_once('-')
+            .and_then(|(rev, _)| rev.parse::<u64>().ok())
+        {
+            files.push((rev, path));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Lists the provider's recorded envelopes, oldest first.
+pub fn list_envelope_history(
+    providers_root: &Path,
+    provider_id: &str,
+) -> anyhow::Result<Vec<EnvelopeRevision>> {
+    history_files(&history_dir(providers_root, provider_id))?
+        .into_iter()
+        .map(|(rev, path)| {
+            let bytes = std::fs::read(&path)?;
+            let envelope = serde_cbor::from_slice(&bytes)
+                .with_context(|| format!("decode {}", path.display()))?;
+            Ok(EnvelopeRevision {
+                rev,
+                path,
+                envelope,
+            })
+        })
+        .collect()
+}
+
+pub fn read_envelope_revision(
+    providers_root: &Path,
+    provider_id: &str,
+    rev: u64,
+) -> anyhow::Result<EnvelopeRevision> {
+    list_envelope_history(providers_root, provider_id)?
+        .into_iter()
+        .find(|revision| revision.rev == rev)
+        .ok_or_else(|| anyhow!("provider {provider_id} has no config revision {rev}"))
+}
+
+/// Compares two envelopes field by field, descending into `config`.
+/// `updated_at` is ignored.
+pub fn diff_envelopes(old: &ConfigEnvelope, new: &ConfigEnvelope) -> Vec<EnvelopeChange> {
+    let as_json = |envelope: &ConfigEnvelope| {
+        let mut value = serde_json::to_value(envelope).unwrap_or(JsonValue::Null);
+        if let Some(map) = value.as_object_mut() {
+            map.remove("updated_at");
+        }
+        value
+    };
+    let mut changes = Vec::new();
+    diff_json("", &as_json(old), &as_json(new), &mut changes);
+    changes
+}
+
+fn diff_json(path: &str, old: &JsonValue, new: &JsonValue, changes: &mut Vec<EnvelopeChange>) {
+    let child = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+    match (old, new) {
+        (JsonValue::Object(old_map), JsonValue::Object(new_map)) => {
+            for (key, old_value) in old_map {
+                match new_map.get(key) {
+                    Some(new_value) => diff_json(&child(key), old_value, new_value, changes),
+                    None => changes.push(EnvelopeChange {
+                        path: child(key),
+                        old: Some(old_value.clone()),
+                        new: None,
+                    }),
+                }
+            }
+            for (key, new_value) in new_map {
+                if !old_map.contains_key(key) {
+                    changes.push(EnvelopeChange {
+                        path: child(key),
+                        old: None,
+                        new: Some(new_value.clone()),
+                    });
+                }
+            }
+        }
+        _ if old != new => changes.push(EnvelopeChange {
+            path: path.to_string(),
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+        _ => {}
+    }
+}
+
+/// Makes revision `rev` the provider's current envelope again and records it
+/// as a new revision. Unless `allow_contract_change` is set, the revision's
+/// `describe_hash` must match the pack at `pack_path`, as
+/// [`ensure_contract_compatible`] requires for setup runs.
+pub fn rollback_provider_config_envelope(
+    providers_root: &Path,
+    provider_id: &str,
+    rev: u64,
+    pack_path: Option<&Path>,
+    allow_contract_change: bool,
+) -> anyhow::Result<EnvelopeRevision> {
+    let target = read_envelope_revision(providers_root, provider_id, rev)?;
+    if !allow_contract_change {
+        let pack_path = pack_path.ok_or_else(|| {
+            anyhow!(
+                "no pack found for provider {provider_id} to check the contract (pass --allow-contract-change to override)"
+            )
+        })?;
+        check_describe_hash(&target.envelope, provider_id, "rollback", pack_path)?;
+    }
+    let mut envelope = target.envelope;
+    envelope.updated_at = Some(Utc::now().to_rfc3339());
+    let bytes = canonical::to_canonical_cbor(&envelope).map_err(|err| anyhow!("{err}"))?;
+    let path = providers_root
+        .join(provider_id)
+        .join("config.envelope.cbor");
+    atomic_write(&path, &bytes)?;
+    let rev = record_envelope_history(providers_root, provider_id, &envelope, &bytes)?;
+    read_envelope_revision(providers_root, provider_id, rev)
+}
+
 pub fn read_provider_config_envelope(
     providers_root: &Path,
     provider_id: &str,
@@ -121,8 +313,20 @@ pub fn ensure_contract_compatible(
     let Some(stored) = read_provider_config_envelope(providers_root, provider_id)? else {
         return Ok(());
     };
+    if allow_contract_change {
+        return Ok(());
+    }
+    check_describe_hash(&stored, provider_id, flow_id, pack_path)
+}
+
+fn check_describe_hash(
+    stored: &ConfigEnvelope,
+    provider_id: &str,
+    flow_id: &str,
+    pack_path: &Path,
+) -> anyhow::Result<()> {
     let resolved = resolved_describe_hash(pack_path, provider_id)?;
-    if stored.describe_hash != resolved && !allow_contract_change {
+    if stored.describe_hash != resolved {
         return Err(anyhow!(
             "OP_CONTRACT_DRIFT: provider={} flow={} stored_describe_hash={} resolved_describe_hash={} (pass --allow-contract-change to override)",
             provider_id,
@@ -237,7 +441,14 @@ fn read_pack_provenance(
     })
 }
 
-fn hash_canonical<T: Serialize>(value: &T) -> anyhow::Result<String> {
+/// Digest of the pack archive, as recorded in `resolved_digest`.
+pub fn resolved_pack_digest(pack_path: &Path) -> anyhow::Result<String> {
+    let bytes =
+        std::fs::read(pack_path).with_context(|| format!("read {}", pack_path.display()))?;
+    Ok(digest_hex(&bytes))
+}
+
+pub(crate) fn hash_canonical<T: Serialize>(value: &T) -> anyhow::Result<String> {
     let cbor = canonical::to_canonical_cbor(value).map_err(|err| anyhow!("{err}"))?;
     Ok(digest_hex(&cbor))
 }
@@ -347,6 +558,89 @@ mod tests {
         assert!(err.to_string().contains("OP_CONTRACT_DRIFT"));
     }
 
+    #[test]
+    fn keeps_bounded_history_and_diffs_revisions() {
+        let temp = tempdir().unwrap();
+        let pack = temp.path().join("provider.gtpack");
+        write_test_pack(&pack).unwrap();
+        let providers_root = temp.path().join("providers");
+        let provider_id = "messaging-telegram";
+        for index in 0..ENVELOPE_HISTORY_LIMIT + 2 {
+            write_provider_config_envelope(
+                &providers_root,
+                provider_id,
+                "setup_default",
+                &json!({"chat_id": index, "mode": "polling"}),
+                &pack,
+                false,
+            )
+            .unwrap();
+        }
+
+        let history = list_envelope_history(&providers_root, provider_id).unwrap();
+        assert_eq!(history.len(), ENVELOPE_HISTORY_LIMIT);
+        assert_eq!(history.first().unwrap().rev, 3);
+        let latest = history.last().unwrap();
+        assert_eq!(latest.rev, ENVELOPE_HISTORY_LIMIT as u64 + 2);
+        assert_eq!(latest.envelope.operation_id, "setup_default");
+
+        let changes = diff_envelopes(&history[0].envelope, &latest.envelope);
+        assert_eq!(
+            changes,
+            vec![EnvelopeChange {
+                path: "config.chat_id".to_string(),
+                old: Some(json!(2)),
+                new: Some(json!(ENVELOPE_HISTORY_LIMIT + 1)),
+            }]
+        );
+    }
+
+    #[test]
+    fn rollback_restores_revision_and_checks_contract() {
+        let temp = tempdir().unwrap();
+        let pack = temp.path().join("provider.gtpack");
+        write_test_pack(&pack).unwrap();
+        let providers_root = temp.path().join("providers");
+        let provider_id = "messaging-telegram";
+        for mode in ["polling", "webhook"] {
+            write_provider_config_envelope(
+                &providers_root,
+                provider_id,
+                "setup_default",
+                &json!({"mode": mode}),
+                &pack,
+                false,
+            )
+            .unwrap();
+        }
+
+        let restored =
+            rollback_provider_config_envelope(&providers_root, provider_id, 1, Some(&pack), false)
+                .unwrap();
+        assert_eq!(restored.rev, 3);
+        let current = read_provider_config_envelope(&providers_root, provider_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(current.config, json!({"mode": "polling"}));
+
+        let err =
+            rollback_provider_config_envelope(&providers_root, provider_id, 9, Some(&pack), false)
+                .unwrap_err();
+        assert!(err.to_string().contains("no config revision"));
+
+        let mut drifted = current.clone();
+        drifted.describe_hash = "stale".to_string();
+        let bytes = canonical::to_canonical_cbor(&drifted).unwrap();
+        record_envelope_history(&providers_root, provider_id, &drifted, &bytes).unwrap();
+        let err =
+            rollback_provider_config_envelope(&providers_root, provider_id, 4, Some(&pack), false)
+                .unwrap_err();
+        assert!(err.to_string().contains("OP_CONTRACT_DRIFT"));
+        assert!(
+            rollback_provider_config_envelope(&providers_root, provider_id, 4, None, true).is_ok()
+        );
+    }
+
     fn write_test_pack(path: &Path) -> anyhow::Result<()> {
         let file = File::create(path)?;
         let mut zip = zip::ZipWriter::new(file);
@@ -5,7 +5,7 @@ use std::{
 
 use anyhow::Context;
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::bin_resolver::{self, ResolveCtx};
@@ -15,11 +15,17 @@ use crate::domains::Domain;
 use crate::operator_log;
 use crate::runner_integration;
 use crate::runtime_state::RuntimePaths;
+use crate::secrets_gate::{self, SecretsManagerHandle};
 use crate::secrets_setup::{SecretsSetup, resolve_env};
-use crate::setup_input::{SetupInputAnswers, collect_setup_answers, load_setup_input};
+use crate::setup_fingerprint::{self, SetupFingerprint};
+use crate::setup_input::{
+    SecretAnswerStore, SetupInputAnswers, collect_setup_answers, export_provider_answers,
+    load_setup_input, split_secret_answers, store_secret_answers,
+};
 use greentic_runner_desktop::{RunResult, RunStatus};
 use tokio::runtime::Builder;
 
+#[derive(Clone)]
 pub struct ProviderSetupOptions {
     pub providers: Option<Vec<String>>,
     pub verify_webhooks: bool,
@@ -31,6 +37,8 @@ pub struct ProviderSetupOptions {
     pub setup_input: Option<PathBuf>,
     pub runner_binary: Option<PathBuf>,
     pub continue_on_error: bool,
+    /// Print which providers would run setup, and why, without running it.
+    pub dry_run: bool,
 }
 
 pub fn run_provider_setup(
@@ -45,9 +53,18 @@ pub fn run_provider_setup(
         return Ok(());
     }
 
-    let runner = resolve_runner_binary(config_dir, dev_settings.as_ref(), options.runner_binary)?;
+    // Dry runs never execute flows, so they do not need a runner.
+    let runner = if options.dry_run {
+        None
+    } else {
+        Some(resolve_runner_binary(
+            config_dir,
+            dev_settings.as_ref(),
+            options.runner_binary.clone(),
+        )?)
+    };
     let env = resolve_env(None);
-    let secrets_setup = if options.skip_secrets_init {
+    let secrets_setup = if options.skip_secrets_init || options.dry_run {
         None
     } else {
         Some(SecretsSetup::new(
@@ -85,6 +102,15 @@ pub fn run_provider_setup(
         None
     };
 
+    let fingerprint_context = FingerprintContext {
+        config_dir,
+        env: &env,
+        tenant: &config.tenant,
+        team: &config.team,
+        dry_run: options.dry_run,
+        secrets: std::cell::OnceCell::new(),
+    };
+
     for (provider, cfg) in providers {
         let result = (|| -> anyhow::Result<()> {
             let pack_path = resolve_pack_path(config_dir, &provider, &cfg)?;
@@ -96,19 +122,95 @@ pub fn run_provider_setup(
             }
 
             let setup_path = providers_root.join(format!("{provider}.setup.json"));
-            if setup_path.exists() && !options.force_setup {
-                return Ok(());
-            }
-
+            let webhook_move = public_base_url
+                .and_then(|url| detect_webhook_move(&providers_root, &provider, url));
             let setup_flow = cfg
                 .setup_flow
                 .clone()
                 .unwrap_or_else(|| "setup_default".to_string());
-            let answers = collect_setup_answers(
+            let stored_secrets = SecretAnswerStore::new(
+                config_dir,
+                &env,
+                &config.tenant,
+                Some(&config.team),
+                &provider,
+            );
+            let known_answers = match setup_input_answers.as_ref() {
+                Some(input) => Some(collect_setup_answers(
+                    &pack_path,
+                    &provider,
+                    Some(input),
+                    false,
+                    Some(&stored_secrets),
+                )?),
+                None if setup_path.exists() && !options.force_setup => {
+                    persisted_setup_answers(&providers_root, &provider, &pack_path, config, &env)?
+                }
+                None => None,
+            };
+            let previous = setup_fingerprint::read_setup_fingerprint(&providers_root, &provider)?;
+            let fingerprint_for = |answers: &Value| {
+                fingerprint_context.fingerprint(
+                    &provider,
+                    &pack_path,
+                    &setup_flow,
+                    answers,
+                    public_base_url,
+                    previous.as_ref(),
+                )
+            };
+            let fingerprint = known_answers.as_ref().map(fingerprint_for).transpose()?;
+            let reasons = setup_reasons(
+                setup_path.exists(),
+                options.force_setup,
+                previous.as_ref(),
+                fingerprint.as_ref(),
+                webhook_move.as_ref(),
+            );
+            if options.dry_run {
+                if reasons.is_empty() {
+                    println!("{provider}: up to date");
+                } else {
+                    println!(
+                        "{provider}: would run {setup_flow} ({})",
+                        reasons.join("; ")
+                    );
+                }
+                return Ok(());
+            }
+            let Some(runner) = runner.as_deref().filter(|_| !reasons.is_empty()) else {
+                return Ok(());
+            };
+            if let Some(moved) = webhook_move.as_ref() {
+                println!("{moved}");
+                operator_log::info(module_path!(), moved.to_string());
+            }
+            operator_log::info(
+                module_path!(),
+                format!(
+                    "running setup provider={provider} flow={setup_flow}: {}",
+                    reasons.join("; ")
+                ),
+            );
+
+            let mut answers = match known_answers {
+                Some(answers) => answers,
+                None => {
+                    collect_setup_answers(&pack_path, &provider, None, true, Some(&stored_secrets))?
+                }
+            };
+            let fingerprint = match fingerprint {
+                Some(fingerprint) => fingerprint,
+                None => fingerprint_for(&answers)?,
+            };
+            store_secret_answers(
+                config_dir,
+                &env,
+                &config.tenant,
+                Some(&config.team),
                 &pack_path,
                 &provider,
-                setup_input_answers.as_ref(),
-                setup_input_answers.is_none(),
+                &mut answers,
             )?;
             let mode = Some(crate::component_qa_ops::QaMode::Setup);
             let qa_config_override = if let Some(mode) = mode {
@@ -190,8 +292,15 @@ pub fn run_provider_setup(
             if let Some(config_value) = qa_config_override.as_ref() {
                 input["config"] = config_value.clone();
             }
-            let output = runner_integration::run_flow(&runner, &pack_path, &setup_flow, &input)?;
+            let output = runner_integration::run_flow(runner, &pack_path, &setup_flow, &input)?;
             write_run_output(&setup_path, &provider, &setup_flow, &output)?;
+            if output.status.success() {
+                setup_fingerprint::write_setup_fingerprint(
+                    &providers_root,
+                    &provider,
+                    &fingerprint,
+                )?;
+            }
             if let Some(config_value) = qa_config_override
                 .or_else(|| extract_config_for_envelope(output.parsed.as_ref(), &input))
                 && let Err(err) = crate::provider_config_envelope::write_provider_config_envelope(
@@ -212,21 +321,23 @@ pub fn run_provider_setup(
                 );
             }
 
-            if options.verify_webhooks {
+            // Setup only gets here when its inputs changed, so a provider that
+            // was verified before is verified again against the new inputs.
+            let verify_path = providers_root.join(format!("{provider}.verify.json"));
+            let reverify =
+                cfg.verify_flow.is_some() && (webhook_move.is_some() || verify_path.exists());
+            if options.verify_webhooks || reverify {
                 let verify_flow = cfg
                     .verify_flow
                     .clone()
                     .unwrap_or_else(|| "verify_webhooks".to_string());
-                let verify_path = providers_root.join(format!("{provider}.verify.json"));
-                if !verify_path.exists() || options.force_setup {
-                    let output =
-                        runner_integration::run_flow(&runner, &pack_path, &verify_flow, &input)?;
-                    write_run_output(&verify_path, &provider, &verify_flow, &output)?;
-                }
+                let output =
+                    runner_integration::run_flow(runner, &pack_path, &verify_flow, &input)?;
+                write_run_output(&verify_path, &provider, &verify_flow, &output)?;
             }
 
             let status_path = providers_root.join(format!("{provider}.status.json"));
-            write_status(&status_path, &provider, &setup_path)?;
+            write_status(&status_path, &provider, &setup_path, public_base_url)?;
             Ok(())
         })();
         if let Err(err) = result {
@@ -244,6 +355,220 @@ pub fn run_provider_setup(
     Ok(())
 }
 
+/// Why a provider's setup flow has to run; empty when it is up to date.
+/// Without answers to fingerprint (no setup input and nothing persisted) an
+/// existing setup record is trusted, as before fingerprints were recorded.
+fn setup_reasons(
+    set_up: bool,
+    force_setup: bool,
+    previous: Option<&SetupFingerprint>,
+    current: Option<&SetupFingerprint>,
+    webhook_move: Option<&WebhookMove>,
+) -> Vec<String> {
+    if force_setup {
+        return vec!["--force-setup".to_string()];
+    }
+    if !set_up {
+        return vec!["not set up yet".to_string()];
+    }
+    let mut reasons = match (previous, current) {
+        (Some(previous), Some(current)) => current.changes_from(previous),
+        (None, Some(_)) => vec!["no setup fingerprint recorded".to_string()],
+        (_, None) => Vec::new(),
+    };
+    if reasons.is_empty()
+        && let Some(moved) = webhook_move
+    {
+        reasons.push(moved.to_string());
+    }
+    reasons
+}
+
+/// Reads the answers the provider was last set up with, with `secret: true`
+/// questions pointing at the secrets they were stored as.
+fn persisted_setup_answers(
+    providers_root: &Path,
+    provider: &str,
+    pack_path: &Path,
+    config: &DemoConfig,
+    env: &str,
+) -> anyhow::Result<Option<Value>> {
+    let path = providers_root
+        .join(provider)
+        .join("answers")
+        .join("setup.answers.json");
+    if !path.exists() {
+        return Ok(None);
+    }
+    let persisted = serde_json::from_slice(&std::fs::read(&path)?)
+        .with_context(|| format!("parse {}", path.display()))?;
+    let answers = export_provider_answers(pack_path, persisted, |key| {
+        secrets_gate::canonical_secret_uri(env, &config.tenant, Some(&config.team), provider, key)
+    })?;
+    Ok(Some(answers))
+}
+
+struct FingerprintContext<'a> {
+    config_dir: &'a Path,
+    env: &'a str,
+    tenant: &'a str,
+    team: &'a str,
+    /// Dry runs must not write to the backend, not even the fingerprint key.
+    dry_run: bool,
+    secrets: std::cell::OnceCell<SecretsManagerHandle>,
+}
+
+impl FingerprintContext<'_> {
+    /// `public_base_url` is only known once the tunnel is up; without one the
+    /// previously recorded URL is kept.
+    fn fingerprint(
+        &self,
+        provider: &str,
+        pack_path: &Path,
+        setup_flow: &str,
+        answers: &Value,
+        public_base_url: Option<&str>,
+        previous: Option<&SetupFingerprint>,
+    ) -> anyhow::Result<SetupFingerprint> {
+        let mut public_answers = answers.clone();
+        let supplied = split_secret_answers(pack_path, &mut public_answers)?;
+        let keys = setup_fingerprint::provider_secret_keys(pack_path)?;
+        let secrets = if keys.is_empty() {
+            Some(Default::default())
+        } else {
+            let handle = match self.secrets.get() {
+                Some(handle) => handle,
+                None => {
+                    let handle = secrets_gate::resolve_secrets_manager(
+                        self.config_dir,
+                        self.tenant,
+                        Some(self.team),
+                    )?;
+                    self.secrets.get_or_init(|| handle)
+                }
+            };
+            setup_fingerprint::secret_versions(
+                &handle.manager(),
+                self.env,
+                self.tenant,
+                Some(self.team),
+                provider,
+                &keys,
+                &supplied,
+                !self.dry_run && !handle.is_read_only(),
+            )?
+        };
+        Ok(SetupFingerprint {
+            answers_hash: SetupFingerprint::answers_hash(&public_answers)?,
+            resolved_digest: crate::provider_config_envelope::resolved_pack_digest(pack_path)?,
+            public_base_url: public_base_url
+                .map(str::to_string)
+                .or_else(|| previous.and_then(|previous| previous.public_base_url.clone())),
+            setup_flow: setup_flow.to_string(),
+            secrets_unkeyed: secrets.is_none(),
+            secrets: secrets.unwrap_or_default(),
+        })
+    }
+}
+
+/// A provider whose webhook was registered against a different public URL
+/// than the one the tunnel currently exposes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct WebhookMove {
+    pub provider: String,
+    pub previous: String,
+    pub current: String,
+}
+
+impl std::fmt::Display for WebhookMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "webhook moved provider={} {} -> {}",
+            self.provider, self.previous, self.current
+        )
+    }
+}
+
+/// Returns the public URL a provider was last set up with. The config
+/// envelope is authoritative; the status record covers providers whose
+/// config does not carry `public_base_url`.
+pub fn recorded_public_base_url(providers_root: &Path, provider: &str) -> Option<String> {
+    let from_envelope =
+        crate::provider_config_envelope::read_provider_config_envelope(providers_root, provider)
+            .ok()
+            .flatten()
+            .and_then(|envelope| {
+                envelope
+                    .config
+                    .get("public_base_url")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            });
+    if from_envelope.is_some() {
+        return from_envelope;
+    }
+    let status_path = providers_root.join(format!("{provider}.status.json"));
+    let bytes = std::fs::read(status_path).ok()?;
+    serde_json::from_slice::<ProviderStatus>(&bytes)
+        .ok()?
+        .public_base_url
+}
+
+/// Flows whose last recorded setup or verify run for `provider` exited
+/// unsuccessfully. The runner exit status is recorded, not turned into an
+/// error, so callers that need a hard pass/fail check this afterwards.
+pub fn failed_setup_flows(providers_root: &Path, provider: &str) -> Vec<String> {
+    ["setup", "verify"]
+        .into_iter()
+        .filter_map(|stage| {
+            let path = providers_root.join(format!("{provider}.{stage}.json"));
+            let bytes = std::fs::read(path).ok()?;
+            let record = serde_json::from_slice::<ProviderRunRecord>(&bytes).ok()?;
+            (!record.success).then_some(record.flow)
+        })
+        .collect()
+}
+
+pub fn detect_webhook_move(
+    providers_root: &Path,
+    provider: &str,
+    current: &str,
+) -> Option<WebhookMove> {
+    let previous = recorded_public_base_url(providers_root, provider)?;
+    if normalize_url(&previous) == normalize_url(current) {
+        return None;
+    }
+    Some(WebhookMove {
+        provider: provider.to_string(),
+        previous,
+        current: current.to_string(),
+    })
+}
+
+/// Lists the configured providers whose recorded webhook URL differs from
+/// `current`.
+pub fn detect_webhook_moves(
+    config_dir: &Path,
+    config: &DemoConfig,
+    current: &str,
+) -> Vec<WebhookMove> {
+    let runtime = RuntimePaths::new(
+        config_dir.join("state"),
+        config.tenant.clone(),
+        config.team.clone(),
+    );
+    let providers_root = runtime.runtime_root().join("providers");
+    resolve_providers(config, None)
+        .into_iter()
+        .filter_map(|(provider, _)| detect_webhook_move(&providers_root, &provider, current))
+        .collect()
+}
+
+fn normalize_url(value: &str) -> &str {
+    value.trim().trim_end_matches('/')
+}
+
 fn resolve_providers(
     config: &DemoConfig,
     filter: Option<Vec<String>>,
@@ -416,10 +741,16 @@ pub(crate) fn write_run_result(
     Ok(())
 }
 
-fn write_status(path: &Path, provider: &str, setup_path: &Path) -> anyhow::Result<()> {
+fn write_status(
+    path: &Path,
+    provider: &str,
+    setup_path: &Path,
+    public_base_url: Option<&str>,
+) -> anyhow::Result<()> {
     let status = ProviderStatus {
         provider: provider.to_string(),
         setup_path: setup_path.to_path_buf(),
+        public_base_url: public_base_url.map(str::to_string),
         updated_at: Utc::now(),
     };
     let bytes = serde_json::to_vec_pretty(&status)?;
@@ -430,7 +761,7 @@ fn write_status(path: &Path, provider: &str, setup_path: &Path) -> anyhow::Resul
     Ok(())
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 struct ProviderRunRecord {
     provider: String,
     flow: String,
@@ -442,9 +773,11 @@ struct ProviderRunRecord {
     timestamp: DateTime<Utc>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 struct ProviderStatus {
     provider: String,
     setup_path: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    public_base_url: Option<String>,
     updated_at: DateTime<Utc>,
 }
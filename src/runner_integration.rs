@@ -99,12 +99,25 @@ pub fn run_flow_with_options(
     })
 }
 
+/// Picks the invocation style from the file name, falling back to the name a
+/// renamed or wrapped runner reported in `--version`. Only runners already
+/// probed for `binary_versions` are looked up; nothing is spawned here.
 pub fn detect_runner_flavor(runner: &Path) -> RunnerFlavor {
     let name = runner
         .file_name()
         .and_then(|value| value.to_str())
         .unwrap_or_default();
     if name.contains("runner-cli") {
+        return RunnerFlavor::RunnerCli;
+    }
+    let reported = crate::bin_version::cached_probe(runner)
+        .map(|probe| probe.line)
+        .unwrap_or_default();
+    if reported
+        .split_whitespace()
+        .next()
+        .is_some_and(|name| name.contains("runner-cli"))
+    {
         RunnerFlavor::RunnerCli
     } else {
         RunnerFlavor::RunSubcommand
@@ -166,3 +166,48 @@ pub fn remove_service_manifest(paths: &RuntimePaths) -> anyhow::Result<()> {
     }
     Ok(())
 }
+
+/// Tenants and teams served by a running `demo start --bundle`, with the
+/// in-process services each one has. Lives at `state/runtime/targets.json`.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct DemoTargetsManifest {
+    /// The operator process serving every target.
+    pub pid: u32,
+    #[serde(default)]
+    pub ingress_url: Option<String>,
+    #[serde(default)]
+    pub targets: Vec<DemoTargetEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DemoTargetEntry {
+    pub tenant: String,
+    pub team: String,
+    #[serde(default)]
+    pub timer_scheduler: bool,
+    #[serde(default)]
+    pub subscriptions: bool,
+}
+
+pub fn demo_targets_path(state_dir: &Path) -> PathBuf {
+    state_dir.join("runtime").join("targets.json")
+}
+
+pub fn persist_demo_targets(
+    state_dir: &Path,
+    manifest: &DemoTargetsManifest,
+) -> anyhow::Result<()> {
+    write_json(&demo_targets_path(state_dir), manifest)
+}
+
+pub fn read_demo_targets(state_dir: &Path) -> anyhow::Result<Option<DemoTargetsManifest>> {
+    read_json(&demo_targets_path(state_dir))
+}
+
+pub fn remove_demo_targets(state_dir: &Path) -> anyhow::Result<()> {
+    let path = demo_targets_path(state_dir);
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,277 @@
+//! Append-only audit trail of secret reads, writes and deletes.
+//!
+//! Every access through a [`SecretsManagerHandle`](crate::secrets_gate::SecretsManagerHandle)
+//! appends one JSON line to `<bundle>/state/audit/secrets.jsonl`. Entries
+//! carry the identity of the caller and the outcome, never the value.
+
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
+use greentic_secrets_lib::SecretError;
+use serde::{Deserialize, Serialize};
+
+use crate::operator_log;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretAuditOp {
+    Read,
+    Write,
+    Delete,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretAuditOutcome {
+    Ok,
+    NotFound,
+    Denied,
+    Error,
+}
+
+impl SecretAuditOp {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+impl SecretAuditOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::NotFound => "not_found",
+            Self::Denied => "denied",
+            Self::Error => "error",
+        }
+    }
+
+    pub fn from_result<T>(result: &Result<T, SecretError>) -> Self {
+        match result {
+            Ok(_) => Self::Ok,
+            Err(SecretError::NotFound(_)) => Self::NotFound,
+            Err(SecretError::Permission(_)) => Self::Denied,
+            Err(_) => Self::Error,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecretAuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub op: SecretAuditOp,
+    pub tenant: String,
+    pub team: String,
+    /// Pack whose components made the call; `None` for operator commands.
+    pub pack_id: Option<String>,
+    pub uri: String,
+    pub outcome: SecretAuditOutcome,
+    pub correlation_id: Option<String>,
+}
+
+/// Appends entries to one bundle's audit file. Clones share the same lock so
+/// concurrent writers never interleave lines.
+#[derive(Clone, Debug)]
+pub struct SecretAuditLog {
+    path: PathBuf,
+    lock: Arc<Mutex<()>>,
+}
+
+impl SecretAuditLog {
+    pub fn for_bundle(bundle_root: &Path) -> Self {
+        Self {
+            path: audit_log_path(bundle_root),
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Appends `entry`. Failures are logged rather than returned so auditing
+    /// never changes the outcome of the secret access itself.
+    pub fn record(&self, entry: &SecretAuditEntry) {
+        if let Err(err) = self.append(entry) {
+            operator_log::warn(
+                module_path!(),
+                format!(
+                    "failed to append secret audit entry to {}: {err}",
+                    self.path.display()
+                ),
+            );
+        }
+    }
+
+    fn append(&self, entry: &SecretAuditEntry) -> Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        let _guard = self
+            .lock
+            .lock()
+            .unwrap_or_else(|poison| poison.into_inner());
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(&line)?;
+        Ok(())
+    }
+}
+
+pub fn audit_log_path(bundle_root: &Path) -> PathBuf {
+    bundle_root
+        .join("state")
+        .join("audit")
+        .join("secrets.jsonl")
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SecretAuditQuery {
+    pub pack_id: Option<String>,
+    pub uri_prefix: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl SecretAuditQuery {
+    pub fn matches(&self, entry: &SecretAuditEntry) -> bool {
+        self.pack_id
+            .as_deref()
+            .is_none_or(|pack| entry.pack_id.as_deref() == Some(pack))
+            && self
+                .uri_prefix
+                .as_deref()
+                .is_none_or(|prefix| entry.uri.starts_with(prefix))
+            && self.since.is_none_or(|since| entry.timestamp >= since)
+            && self.until.is_none_or(|until| entry.timestamp <= until)
+    }
+}
+
+/// Reads the bundle's audit file and returns the matching entries in the
+/// order they were written. Unparseable lines are skipped with a warning.
+pub fn query(bundle_root: &Path, query: &SecretAuditQuery) -> Result<Vec<SecretAuditEntry>> {
+    let path = audit_log_path(bundle_root);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file = std::fs::File::open(&path).with_context(|| format!("open {}", path.display()))?;
+    let mut entries = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.with_context(|| format!("read {}", path.display()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<SecretAuditEntry>(&line) {
+            Ok(entry) if query.matches(&entry) => entries.push(entry),
+            Ok(_) => {}
+            Err(err) => operator_log::warn(
+                module_path!(),
+                format!("skipping {} line {}: {err}", path.display(), index + 1),
+            ),
+        }
+    }
+    Ok(entries)
+}
+
+/// Parses an RFC 3339 timestamp or a relative age such as `30m`, `12h` or
+/// `7d` (counted back from `now`).
+pub fn parse_time_bound(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    let invalid =
+        || anyhow!("expected an RFC 3339 timestamp or an age like 30m/12h/7d, got '{value}'");
+    let unit_start = value
+        .char_indices()
+        .last()
+        .map(|(index, _)| index)
+        .ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(unit_start);
+    let amount = amount.parse::<i64>().map_err(|_| invalid())?;
+    let age = match unit {
+        "s" => Duration::seconds(amount),
+        "m" => Duration::minutes(amount),
+        "h" => Duration::hours(amount),
+        "d" => Duration::days(amount),
+        _ => return Err(invalid()),
+    };
+    Ok(now - age)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pack: Option<&str>, uri: &str, timestamp: DateTime<Utc>) -> SecretAuditEntry {
+        SecretAuditEntry {
+            timestamp,
+            op: SecretAuditOp::Read,
+            tenant: "demo".to_string(),
+            team: "_".to_string(),
+            pack_id: pack.map(str::to_string),
+            uri: uri.to_string(),
+            outcome: SecretAuditOutcome::Ok,
+            correlation_id: None,
+        }
+    }
+
+    #[test]
+    fn time_bounds_accept_rfc3339_and_relative_ages() {
+        let now = DateTime::parse_from_rfc3339("2026-01-10T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            parse_time_bound("2026-01-09T00:00:00+00:00", now).unwrap(),
+            now - Duration::hours(36)
+        );
+        assert_eq!(
+            parse_time_bound("90m", now).unwrap(),
+            now - Duration::minutes(90)
+        );
+        assert_eq!(
+            parse_time_bound("2d", now).unwrap(),
+            now - Duration::days(2)
+        );
+        assert!(parse_time_bound("yesterday", now).is_err());
+        assert!(parse_time_bound("", now).is_err());
+    }
+
+    #[test]
+    fn log_appends_and_query_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = SecretAuditLog::for_bundle(dir.path());
+        let now = Utc::now();
+        log.record(&entry(
+            Some("a"),
+            "secrets://dev/demo/_/a/key",
+            now - Duration::hours(2),
+        ));
+        log.record(&entry(Some("b"), "secrets://dev/demo/_/b/key", now));
+        log.record(&entry(None, "secrets://dev/demo/_/a/other", now));
+
+        let all = query(dir.path(), &SecretAuditQuery::default()).unwrap();
+        assert_eq!(all.len(), 3);
+
+        let by_pack = SecretAuditQuery {
+            pack_id: Some("a".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(query(dir.path(), &by_pack).unwrap().len(), 1);
+
+        let recent_a = SecretAuditQuery {
+            uri_prefix: Some("secrets://dev/demo/_/a/".to_string()),
+            since: Some(now - Duration::hours(1)),
+            ..Default::default()
+        };
+        let found = query(dir.path(), &recent_a).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].uri, "secrets://dev/demo/_/a/other");
+    }
+}
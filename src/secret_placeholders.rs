@@ -0,0 +1,241 @@
+//! Tracks secrets that `SecretsSetup` filled with a placeholder value.
+//!
+//! Seeding writes `placeholder for <uri>` for every required secret that has
+//! no value in `seeds.yaml`, so setup can proceed. Those URIs are recorded in
+//! a sidecar index next to the dev store, or where the dev store would be
+//! for other backends. A URI only counts as unresolved while the value the
+//! tenant's secrets backend returns for it is still the placeholder text, so
+//! values set later (by `demo secrets set`, a seeds import, or by editing the
+//! store) resolve it even if the index entry lingers.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Builder;
+
+use crate::dev_store_path;
+use crate::secrets_gate::{self, DynSecretsManager};
+
+const INDEX_FILE: &str = ".dev.secrets.placeholders.json";
+const PLACEHOLDER_PREFIX: &str = "placeholder for ";
+
+pub fn placeholder_text(uri: &str) -> String {
+    format!("{PLACEHOLDER_PREFIX}{uri}")
+}
+
+pub fn is_placeholder_value(uri: &str, value: &[u8]) -> bool {
+    value
+        .strip_prefix(PLACEHOLDER_PREFIX.as_bytes())
+        .is_some_and(|rest| rest == uri.as_bytes())
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlaceholderRecord {
+    pub provider: String,
+    pub seeded_at: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct IndexFile {
+    #[serde(default)]
+    placeholders: BTreeMap<String, PlaceholderRecord>,
+}
+
+/// Sidecar index of placeholder-seeded URIs, stored next to the dev store
+/// (see [`SecretsManagerHandle::placeholder_store_path`]).
+///
+/// [`SecretsManagerHandle::placeholder_store_path`]: crate::secrets_gate::SecretsManagerHandle::placeholder_store_path
+#[derive(Debug)]
+pub struct PlaceholderIndex {
+    path: PathBuf,
+    file: IndexFile,
+}
+
+impl PlaceholderIndex {
+    pub fn path_for_store(store_path: &Path) -> PathBuf {
+        store_path.with_file_name(INDEX_FILE)
+    }
+
+    pub fn load(store_path: &Path) -> Result<Self> {
+        let path = Self::path_for_store(store_path);
+        let file = if path.exists() {
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("read {}", path.display()))?;
+            serde_json::from_str(&contents).with_context(|| format!("parse {}", path.display()))?
+        } else {
+            IndexFile::default()
+        };
+        Ok(Self { path, file })
+    }
+
+    pub fn record(&mut self, uri: &str, provider: &str) {
+        self.file.placeholders.insert(
+            uri.to_string(),
+            PlaceholderRecord {
+                provider: provider.to_string(),
+                seeded_at: Utc::now().to_rfc3339(),
+            },
+        );
+    }
+
+    /// Returns `true` if the URI was tracked.
+    pub fn forget(&mut self, uri: &str) -> bool {
+        self.file.placeholders.remove(uri).is_some()
+    }
+
+    pub fn contains(&self, uri: &str) -> bool {
+        self.file.placeholders.contains_key(uri)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.file.placeholders.is_empty()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if self.file.placeholders.is_empty() {
+            if self.path.exists() {
+                std::fs::remove_file(&self.path)
+                    .with_context(|| format!("remove {}", self.path.display()))?;
+            }
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&self.file)?;
+        std::fs::write(&self.path, contents)
+            .with_context(|| format!("write {}", self.path.display()))
+    }
+
+    /// Drops entries whose value is no longer the placeholder and returns
+    /// the ones that still are, grouped by provider. `manager_for` returns
+    /// the backend of the tenant/team a URI belongs to.
+    pub fn prune_resolved(
+        &mut self,
+        mut manager_for: impl FnMut(&str) -> Result<Option<DynSecretsManager>>,
+    ) -> Result<BTreeMap<String, Vec<String>>> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("build secrets runtime")?;
+        let mut unresolved: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut resolved = Vec::new();
+        for (uri, record) in &self.file.placeholders {
+            let Some(manager) = manager_for(uri)? else {
+                resolved.push(uri.clone());
+                continue;
+            };
+            match runtime.block_on(manager.read(uri)) {
+                Ok(value) if is_placeholder_value(uri, &value) => unresolved
+                    .entry(record.provider.clone())
+                    .or_default()
+                    .push(uri.clone()),
+                Ok(_) => resolved.push(uri.clone()),
+                // Deleted or unreadable secrets are reported as missing elsewhere.
+                Err(_) => resolved.push(uri.clone()),
+            }
+        }
+        for uri in resolved {
+            self.file.placeholders.remove(&uri);
+        }
+        Ok(unresolved)
+    }
+}
+
+/// Unresolved placeholders of the bundle, grouped by provider. Each recorded
+/// URI is read through the secrets backend of its tenant/team, whichever
+/// backend that is. Resolved entries are pruned from the index as a side
+/// effect.
+pub fn unresolved_for_bundle(bundle_root: &Path) -> Result<BTreeMap<String, Vec<String>>> {
+    let override_path = dev_store_path::override_path();
+    let mut anchors = Vec::new();
+    anchors.extend(dev_store_path::find_existing_with_override(
+        bundle_root,
+        override_path.as_deref(),
+    ));
+    anchors.push(dev_store_path::default_path(bundle_root));
+    anchors.dedup();
+
+    let mut managers: BTreeMap<(String, String), DynSecretsManager> = BTreeMap::new();
+    let mut unresolved: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for store_path in anchors {
+        let mut index = PlaceholderIndex::load(&store_path)?;
+        if index.is_empty() {
+            continue;
+        }
+        let found = index.prune_resolved(|uri| {
+            let Some((tenant, team)) = uri_scope(uri) else {
+                return Ok(None);
+            };
+            let key = (tenant.to_string(), team.to_string());
+            if let Some(manager) = managers.get(&key) {
+                return Ok(Some(manager.clone()));
+            }
+            let team = Some(team).filter(|team| *team != "_");
+            let manager =
+                secrets_gate::resolve_secrets_manager(bundle_root, tenant, team)?.manager();
+            managers.insert(key, manager.clone());
+            Ok(Some(manager))
+        })?;
+        index.save()?;
+        for (provider, uris) in found {
+            unresolved.entry(provider).or_default().extend(uris);
+        }
+    }
+    Ok(unresolved)
+}
+
+/// Tenant and team segments of a `secrets://{env}/{tenant}/{team}/...` URI.
+fn uri_scope(uri: &str) -> Option<(&str, &str)> {
+    let mut segments = uri.strip_prefix("secrets://")?.split('/');
+    let (_env, tenant, team) = (segments.next()?, segments.next()?, segments.next()?);
+    Some((tenant, team))
+}
+
+/// Forgets `uri` in the index next to `store_path`, if one exists.
+pub fn forget_placeholder(store_path: &Path, uri: &str) -> Result<()> {
+    if !PlaceholderIndex::path_for_store(store_path).exists() {
+        return Ok(());
+    }
+    let mut index = PlaceholderIndex::load(store_path)?;
+    if index.forget(uri) {
+        index.save()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholder_values_match_only_their_own_uri() {
+        let uri = "secrets://dev/demo/_/messaging-telegram/bot_token";
+        assert!(is_placeholder_value(uri, placeholder_text(uri).as_bytes()));
+        assert!(!is_placeholder_value(
+            uri,
+            placeholder_text("secrets://dev/demo/_/other/key").as_bytes()
+        ));
+        assert!(!is_placeholder_value(uri, b"123:abc"));
+    }
+
+    #[test]
+    fn index_round_trips_and_removes_empty_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join(".dev.secrets.env");
+        let mut index = PlaceholderIndex::load(&store).unwrap();
+        index.record("secrets://dev/demo/_/p/a", "p");
+        index.save().unwrap();
+        let path = PlaceholderIndex::path_for_store(&store);
+        assert!(path.exists());
+
+        let reloaded = PlaceholderIndex::load(&store).unwrap();
+        assert!(reloaded.contains("secrets://dev/demo/_/p/a"));
+
+        forget_placeholder(&store, "secrets://dev/demo/_/p/a").unwrap();
+        assert!(!path.exists());
+    }
+}
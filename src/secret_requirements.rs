@@ -9,14 +9,23 @@ use zip::{ZipArchive, result::ZipError};
 type CborMap = BTreeMap<CborValue, CborValue>;
 
 pub fn load_secret_keys_from_pack(pack_path: &Path) -> Result<Vec<String>> {
-    let keys = load_keys_from_assets(pack_path)?;
+    load_keys(pack_path, false)
+}
+
+/// Every key in the pack's `secret_requirements`, including optional ones.
+pub fn load_declared_secret_keys(pack_path: &Path) -> Result<Vec<String>> {
+    load_keys(pack_path, true)
+}
+
+fn load_keys(pack_path: &Path, include_optional: bool) -> Result<Vec<String>> {
+    let keys = load_keys_from_assets(pack_path, include_optional)?;
     if !keys.is_empty() {
         return Ok(keys);
     }
-    load_keys_from_manifest(pack_path)
+    load_keys_from_manifest(pack_path, include_optional)
 }
 
-fn load_keys_from_assets(pack_path: &Path) -> Result<Vec<String>> {
+fn load_keys_from_assets(pack_path: &Path, include_optional: bool) -> Result<Vec<String>> {
     let file = File::open(pack_path)?;
     let mut archive = ZipArchive::new(file)?;
     const ASSET_PATHS: &[&str] = &[
@@ -32,7 +41,7 @@ fn load_keys_from_assets(pack_path: &Path) -> Result<Vec<String>> {
             let requirements: Vec<AssetSecretRequirement> = serde_json::from_str(&contents)?;
             return Ok(requirements
                 .into_iter()
-                .filter(|req| req.required.unwrap_or(true))
+                .filter(|req| include_optional || req.required.unwrap_or(true))
                 .filter_map(|req| req.key)
                 .map(|key| key.to_lowercase())
                 .collect());
@@ -41,7 +50,7 @@ fn load_keys_from_assets(pack_path: &Path) -> Result<Vec<String>> {
     Ok(Vec::new())
 }
 
-fn load_keys_from_manifest(pack_path: &Path) -> Result<Vec<String>> {
+fn load_keys_from_manifest(pack_path: &Path, include_optional: bool) -> Result<Vec<String>> {
     let file = File::open(pack_path)?;
     let mut archive = ZipArchive::new(file)?;
     let mut manifest = match archive.by_name("manifest.cbor") {
@@ -53,18 +62,18 @@ fn load_keys_from_manifest(pack_path: &Path) -> Result<Vec<String>> {
     manifest.read_to_end(&mut bytes)?;
     let value: CborValue = serde_cbor::from_slice(&bytes)?;
     if let CborValue::Map(map) = &value {
-        return extract_keys_from_manifest_map(map);
+        return extract_keys_from_manifest_map(map, include_optional);
     }
     Ok(Vec::new())
 }
 
-fn extract_keys_from_manifest_map(map: &CborMap) -> Result<Vec<String>> {
+fn extract_keys_from_manifest_map(map: &CborMap, include_optional: bool) -> Result<Vec<String>> {
     let symbols = symbols_map(map);
     let mut keys = Vec::new();
     if let Some(CborValue::Array(entries)) = map_get(map, "secret_requirements") {
         for entry in entries {
             if let CborValue::Map(entry_map) = entry {
-                if !is_required(entry_map) {
+                if !include_optional && !is_required(entry_map) {
                     continue;
                 }
                 if let Some(key_value) = map_get(entry_map, "key")
@@ -0,0 +1,410 @@
+//! Secret rotation behind `demo secrets rotate`.
+//!
+//! A rotation writes the new value in place and keeps the old one under
+//! `<key>_previous` for a grace period. The previous value is only there so a
+//! failed rotation can be restored; nothing reads it at runtime. Expired
+//! previous values are pruned on `demo start` and by the `demo secrets`
+//! commands that write. The provider's setup and verify flows are re-run
+//! through [`providers::run_provider_setup`]; when either fails the old value
+//! is restored. Every rotation is recorded in
+//! `<bundle>/state/secrets/rotations.json`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, DemoConfig, DemoProviderConfig};
+use crate::dev_mode::DevSettingsResolved;
+use crate::domains::{self, Domain};
+use crate::operator_log;
+use crate::providers::{self, ProviderSetupOptions};
+use crate::runtime_state::RuntimePaths;
+use crate::secret_name;
+use crate::secret_value::SecretValue;
+use crate::secrets_admin::{self, SecretsScope};
+use crate::secrets_gate::SecretsManagerHandle;
+
+const PREVIOUS_SUFFIX: &str = "_previous";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationStatus {
+    /// The new value is live and the previous one is kept until `grace_until`.
+    Active,
+    /// The grace period ended and the previous value was deleted.
+    Expired,
+    /// A later rotation of the same secret replaced the previous value.
+    Superseded,
+    /// Provider setup failed and the previous value was restored.
+    RolledBack,
+    /// Provider setup failed and restoring the previous value failed too.
+    RollbackFailed,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RotationRecord {
+    pub uri: String,
+    pub provider: String,
+    pub previous_uri: String,
+    pub rotated_at: DateTime<Utc>,
+    pub grace_until: DateTime<Utc>,
+    pub status: RotationStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RotationHistory {
+    #[serde(default)]
+    rotations: Vec<RotationRecord>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RotationOptions {
+    pub grace: Duration,
+    pub runner_binary: Option<PathBuf>,
+    pub dev_settings: Option<DevSettingsResolved>,
+    pub public_base_url: Option<String>,
+}
+
+pub fn history_path(bundle_root: &Path) -> PathBuf {
+    bundle_root
+        .join("state")
+        .join("secrets")
+        .join("rotations.json")
+}
+
+pub fn load_history(bundle_root: &Path) -> Result<Vec<RotationRecord>> {
+    Ok(read_history(bundle_root)?.rotations)
+}
+
+/// URI that holds the previous value of `uri` during the grace period.
+pub fn previous_uri(scope: &SecretsScope, provider: &str, key: &str) -> Result<String> {
+    let key = format!(
+        "{}{PREVIOUS_SUFFIX}",
+        secret_name::canonical_secret_name(key)
+    );
+    scope.resolve_uri(&format!("{provider}/{key}"))
+}
+
+/// Rotates `provider/key` to `value` and re-runs the provider's setup and
+/// verify flows. Returns an error, after restoring the previous value, if
+/// either flow fails.
+pub fn rotate_secret(
+    handle: &SecretsManagerHandle,
+    bundle_root: &Path,
+    scope: &SecretsScope,
+    provider: &str,
+    key: &str,
+    value: &SecretValue<'_>,
+    options: RotationOptions,
+) -> Result<RotationRecord> {
+    prune_expired(handle, bundle_root, scope)?;
+
+    let uri = scope.resolve_uri(&format!("{provider}/{key}"))?;
+    let previous_uri = previous_uri(scope, provider, key)?;
+    let config = provider_setup_config(bundle_root, scope, provider)?;
+    let previous = secrets_admin::get_secret(handle, &uri).map_err(|err| {
+        anyhow!("{err}; rotation needs an existing value, use `demo secrets set` first")
+    })?;
+    if previous.as_bytes() == value.as_bytes() {
+        return Err(anyhow!(
+            "new value for {uri} is identical to the current one"
+        ));
+    }
+
+    // An older rotation still in its grace period owns the value about to be
+    // overwritten under `previous_uri`; a rollback puts it back.
+    let displaced = if has_active_rotation(bundle_root, &previous_uri)? {
+        secrets_admin::get_secret(handle, &previous_uri).ok()
+    } else {
+        None
+    };
+    secrets_admin::set_secret(handle, &previous_uri, &previous)?;
+    secrets_admin::set_secret(handle, &uri, value)?;
+    let rotated_at = Utc::now();
+    let mut record = RotationRecord {
+        uri: uri.clone(),
+        provider: provider.to_string(),
+        previous_uri: previous_uri.clone(),
+        rotated_at,
+        grace_until: rotated_at + options.grace,
+        status: RotationStatus::Active,
+        error: None,
+    };
+    operator_log::info(
+        module_path!(),
+        format!(
+            "secret rotated uri={uri} previous={previous_uri} grace_until={}",
+            record.grace_until.to_rfc3339()
+        ),
+    );
+
+    let setup_result = run_setup(bundle_root, &config, provider, &options);
+    if let Err(err) = setup_result {
+        operator_log::error(
+            module_path!(),
+            format!("secret rotation failed uri={uri}; rolling back: {err}"),
+        );
+        record.error = Some(err.to_string());
+        let restored =
+            secrets_admin::set_secret(handle, &uri, &previous).and_then(|()| match &displaced {
+                Some(value) => secrets_admin::set_secret(handle, &previous_uri, value),
+                None => secrets_admin::delete_secret(handle, &previous_uri),
+            });
+        match restored {
+            Ok(()) => {
+                record.status = RotationStatus::RolledBack;
+                // Bring the provider back in line with the restored value.
+                if let Err(resetup) = run_setup(bundle_root, &config, provider, &options) {
+                    operator_log::warn(
+                        module_path!(),
+                        format!("provider re-setup after rollback failed uri={uri}: {resetup}"),
+                    );
+                }
+                append_record(bundle_root, record)?;
+                return Err(anyhow!("rotation of {uri} rolled back: {err}"));
+            }
+            Err(restore) => {
+                record.status = RotationStatus::RollbackFailed;
+                append_record(bundle_root, record)?;
+                return Err(anyhow!(
+                    "rotation of {uri} failed ({err}) and restoring the previous value failed: {restore}"
+                ));
+            }
+        }
+    }
+
+    append_record(bundle_root, record.clone())?;
+    Ok(record)
+}
+
+/// Deletes previous values in `scope` whose grace period has ended. Returns
+/// the URIs that were removed.
+pub fn prune_expired(
+    handle: &SecretsManagerHandle,
+    bundle_root: &Path,
+    scope: &SecretsScope,
+) -> Result<Vec<String>> {
+    let prefix = scope.uri_prefix();
+    let mut history = read_history(bundle_root)?;
+    let now = Utc::now();
+    let mut pruned = Vec::new();
+    for record in &mut history.rotations {
+        if record.status != RotationStatus::Active
+            || record.grace_until > now
+            || !record.previous_uri.starts_with(&prefix)
+        {
+            continue;
+        }
+        // The previous value may already be gone if it was removed by hand.
+        if secrets_admin::get_secret(handle, &record.previous_uri).is_ok() {
+            secrets_admin::delete_secret(handle, &record.previous_uri)?;
+        }
+        record.status = RotationStatus::Expired;
+        pruned.push(record.previous_uri.clone());
+    }
+    if !pruned.is_empty() {
+        write_history(bundle_root, &history)?;
+        operator_log::info(
+            module_path!(),
+            format!("pruned expired secret versions: {}", pruned.join(", ")),
+        );
+    }
+    Ok(pruned)
+}
+
+/// Whether a rotation still in its grace period keeps its previous value
+/// under `previous_uri`.
+fn has_active_rotation(bundle_root: &Path, previous_uri: &str) -> Result<bool> {
+    Ok(read_history(bundle_root)?.rotations.iter().any(|record| {
+        record.status == RotationStatus::Active && record.previous_uri == previous_uri
+    }))
+}
+
+fn run_setup(
+    bundle_root: &Path,
+    config: &DemoConfig,
+    provider: &str,
+    options: &RotationOptions,
+) -> Result<()> {
+    let providers_root = RuntimePaths::new(
+        bundle_root.join("state"),
+        config.tenant.clone(),
+        config.team.clone(),
+    )
+    .runtime_root()
+    .join("providers");
+    // Reuse the answers and webhook URL of the last setup so rotation never
+    // prompts and never moves the webhook.
+    let setup_input = providers_root
+        .join(provider)
+        .join("answers")
+        .join("setup.answers.json");
+    let public_base_url = options
+        .public_base_url
+        .clone()
+        .or_else(|| providers::recorded_public_base_url(&providers_root, provider));
+    providers::run_provider_setup(
+        bundle_root,
+        config,
+        options.dev_settings.clone(),
+        public_base_url.as_deref(),
+        ProviderSetupOptions {
+            providers: Some(vec![provider.to_string()]),
+            verify_webhooks: true,
+            force_setup: true,
+            skip_setup: false,
+            skip_secrets_init: true,
+            allow_contract_change: false,
+            backup: true,
+            setup_input: setup_input.exists().then_some(setup_input),
+            runner_binary: options.runner_binary.clone(),
+            continue_on_error: false,
+            dry_run: false,
+        },
+    )?;
+    let failed = providers::failed_setup_flows(&providers_root, provider);
+    if !failed.is_empty() {
+        return Err(anyhow!(
+            "provider {provider} flows failed: {}",
+            failed.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Demo config limited to the tenant/team of `scope`, with an entry for
+/// `provider` taken from `greentic.demo.yaml` or the bundle's provider packs.
+fn provider_setup_config(
+    bundle_root: &Path,
+    scope: &SecretsScope,
+    provider: &str,
+) -> Result<DemoConfig> {
+    let config_path = bundle_root.join("greentic.demo.yaml");
+    let mut config = if config_path.exists() {
+        config::load_demo_config(&config_path)
+            .with_context(|| format!("load {}", config_path.display()))?
+    } else {
+        DemoConfig::default()
+    };
+    config.tenant = scope.tenant.clone();
+    config.team = scope.team.clone().unwrap_or_else(|| "default".to_string());
+    let providers = config.providers.get_or_insert_with(BTreeMap::new);
+    if !providers.contains_key(provider) {
+        let mut pack = None;
+        for domain in [Domain::Messaging, Domain::Events] {
+            pack = domains::discover_provider_packs(bundle_root, domain)?
+                .into_iter()
+                .find(|pack| pack.pack_id == provider);
+            if pack.is_some() {
+                break;
+            }
+        }
+        let pack = pack.ok_or_else(|| {
+            anyhow!("provider {provider} is not configured in greentic.demo.yaml or the bundle's provider packs")
+        })?;
+        providers.insert(
+            provider.to_string(),
+            DemoProviderConfig {
+                pack: Some(pack.path.to_string_lossy().to_string()),
+                setup_flow: None,
+                verify_flow: None,
+                allowed_cidrs: Vec::new(),
+            },
+        );
+    }
+    Ok(config)
+}
+
+fn append_record(bundle_root: &Path, record: RotationRecord) -> Result<()> {
+    let mut history = read_history(bundle_root)?;
+    if record.status == RotationStatus::Active {
+        // Only the newest rotation owns `<key>_previous`.
+        for older in &mut history.rotations {
+            if older.uri == record.uri && older.status == RotationStatus::Active {
+                older.status = RotationStatus::Superseded;
+            }
+        }
+    }
+    history.rotations.push(record);
+    write_history(bundle_root, &history)
+}
+
+fn read_history(bundle_root: &Path) -> Result<RotationHistory> {
+    let path = history_path(bundle_root);
+    if !path.exists() {
+        return Ok(RotationHistory::default());
+    }
+    let contents =
+        std::fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+    serde_json::from_str(&contents).with_context(|| format!("parse {}", path.display()))
+}
+
+fn write_history(bundle_root: &Path, history: &RotationHistory) -> Result<()> {
+    let path = history_path(bundle_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let contents = serde_json::to_string_pretty(history)?;
+    std::fs::write(&path, contents).with_context(|| format!("write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(uri: &str, status: RotationStatus) -> RotationRecord {
+        RotationRecord {
+            uri: uri.to_string(),
+            provider: "p".to_string(),
+            previous_uri: format!("{uri}_previous"),
+            rotated_at: Utc::now(),
+            grace_until: Utc::now(),
+            status,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn newer_rotation_supersedes_active_records_of_the_same_uri() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = "secrets://dev/demo/_/p/a";
+        let b = "secrets://dev/demo/_/p/b";
+        append_record(dir.path(), record(a, RotationStatus::Active)).unwrap();
+        append_record(dir.path(), record(b, RotationStatus::Active)).unwrap();
+        append_record(dir.path(), record(a, RotationStatus::RolledBack)).unwrap();
+        append_record(dir.path(), record(a, RotationStatus::Active)).unwrap();
+
+        let statuses = load_history(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|record| record.status)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                RotationStatus::Superseded,
+                RotationStatus::Active,
+                RotationStatus::RolledBack,
+                RotationStatus::Active,
+            ]
+        );
+    }
+
+    #[test]
+    fn previous_uri_sits_next_to_the_rotated_key() {
+        let scope = SecretsScope {
+            env: "dev".to_string(),
+            tenant: "demo".to_string(),
+            team: None,
+        };
+        assert_eq!(
+            previous_uri(&scope, "messaging-telegram", "BOT_TOKEN").unwrap(),
+            "secrets://dev/demo/_/messaging-telegram/bot_token_previous"
+        );
+    }
+}
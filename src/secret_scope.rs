@@ -0,0 +1,270 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::secret_name;
+use crate::secret_requirements;
+use crate::secrets_gate::SHARED_SECRET_NAMESPACE;
+use crate::secrets_manager;
+
+const GRANTS_FILE: &str = "secret-grants.yaml";
+
+/// Cross-pack secret sharing declared in `tenants/<tenant>/secret-grants.yaml`.
+///
+/// ```yaml
+/// grants:
+///   - pack: messaging-teams
+///     provider: messaging-email
+///     keys: [graph_client_secret]   # omit to share the whole namespace
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct SecretGrants {
+    #[serde(default)]
+    pub grants: Vec<SecretGrant>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SecretGrant {
+    /// Pack that receives access.
+    pub pack: String,
+    /// Provider namespace the pack may read from.
+    pub provider: String,
+    #[serde(default)]
+    pub keys: Vec<String>,
+}
+
+impl SecretGrants {
+    pub fn path(bundle_root: &Path, tenant: &str) -> PathBuf {
+        bundle_root.join("tenants").join(tenant).join(GRANTS_FILE)
+    }
+
+    pub fn load(bundle_root: &Path, tenant: &str) -> anyhow::Result<Self> {
+        let path = Self::path(bundle_root, tenant);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents =
+            std::fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+        serde_yaml_bw::from_str(&contents).with_context(|| format!("parse {}", path.display()))
+    }
+
+    fn for_pack(&self, pack_id: &str) -> Vec<SecretGrant> {
+        self.grants
+            .iter()
+            .filter(|grant| grant.pack == pack_id)
+            .cloned()
+            .collect()
+    }
+}
+
+/// The secret URIs a single pack may read at runtime within one tenant and
+/// team: its own provider namespace, the keys declared in its
+/// `secret_requirements` under the shared namespace, and any explicit grants.
+#[derive(Clone, Debug)]
+pub struct PackSecretScope {
+    pub pack_id: String,
+    pub tenant: String,
+    /// Canonical team segment (`_` for the default team).
+    pub team: String,
+    declared_keys: BTreeSet<String>,
+    grants: Vec<SecretGrant>,
+}
+
+impl PackSecretScope {
+    pub fn new(
+        pack_id: &str,
+        tenant: &str,
+        team: Option<&str>,
+        declared_keys: impl IntoIterator<Item = String>,
+        grants: &SecretGrants,
+    ) -> Self {
+        Self {
+            pack_id: pack_id.to_string(),
+            tenant: tenant.to_string(),
+            team: secrets_manager::canonical_team(team).into_owned(),
+            declared_keys: declared_keys
+                .into_iter()
+                .map(|key| secret_name::canonical_secret_name(&key))
+                .collect(),
+            grants: grants.for_pack(pack_id),
+        }
+    }
+
+    pub fn for_pack(
+        pack_id: &str,
+        pack_path: Option<&Path>,
+        tenant: &str,
+        team: Option<&str>,
+        grants: &SecretGrants,
+    ) -> anyhow::Result<Self> {
+        let declared = match pack_path {
+            Some(path) => secret_requirements::load_declared_secret_keys(path)
+                .with_context(|| format!("read secret requirements from {}", path.display()))?,
+            None => Vec::new(),
+        };
+        Ok(Self::new(pack_id, tenant, team, declared, grants))
+    }
+
+    /// Returns the reason an access to `uri` is denied, or `None` if allowed.
+    pub fn check(&self, uri: &str) -> Option<String> {
+        let Some(parts) = SecretUriParts::parse(uri) else {
+            return Some("not a canonical secrets:// uri".to_string());
+        };
+        if parts.tenant != self.tenant {
+            return Some(format!(
+                "tenant {} is outside {}",
+                parts.tenant, self.tenant
+            ));
+        }
+        if parts.team != self.team {
+            return Some(format!("team {} is outside {}", parts.team, self.team));
+        }
+        let key = secret_name::canonical_secret_name(parts.key);
+        if parts.provider == self.pack_id
+            || (parts.provider == SHARED_SECRET_NAMESPACE && self.declared_keys.contains(&key))
+        {
+            return None;
+        }
+        let granted = self.grants.iter().any(|grant| {
+            grant.provider == parts.provider
+                && (grant.keys.is_empty()
+                    || grant
+                        .keys
+                        .iter()
+                        .any(|granted| secret_name::canonical_secret_name(granted) == key))
+        });
+        if granted {
+            None
+        } else {
+            Some(format!(
+                "provider namespace {} is not granted to pack {}",
+                parts.provider, self.pack_id
+            ))
+        }
+    }
+}
+
+struct SecretUriParts<'a> {
+    tenant: &'a str,
+    team: &'a str,
+    provider: &'a str,
+    key: &'a str,
+}
+
+impl<'a> SecretUriParts<'a> {
+    fn parse(uri: &'a str) -> Option<Self> {
+        let rest = uri.strip_prefix("secrets://")?;
+        let segments = rest.split('/').collect::<Vec<_>>();
+        let [_env, tenant, team, provider, key] = segments.as_slice() else {
+            return None;
+        };
+        Some(Self {
+            tenant,
+            team,
+            provider,
+            key,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grants(yaml: &str) -> SecretGrants {
+        serde_yaml_bw::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn own_namespace_and_declared_keys_are_allowed() {
+        let scope = PackSecretScope::new(
+            "messaging-telegram",
+            "demo",
+            None,
+            vec!["SHARED_API_KEY".to_string(), "BOT_TOKEN".to_string()],
+            &SecretGrants::default(),
+        );
+        assert!(
+            scope
+                .check("secrets://dev/demo/_/messaging-telegram/bot_token")
+                .is_none()
+        );
+        assert!(
+            scope
+                .check("secrets://dev/demo/_/messaging/shared_api_key")
+                .is_none()
+        );
+        assert!(
+            scope
+                .check("secrets://dev/demo/_/messaging-slack/bot_token")
+                .is_some()
+        );
+        assert!(
+            scope
+                .check("secrets://dev/other/_/messaging-telegram/bot_token")
+                .is_some()
+        );
+        assert!(
+            scope
+                .check("secrets://dev/demo/ops/messaging-telegram/bot_token")
+                .is_some()
+        );
+        assert!(scope.check("TELEGRAM_BOT_TOKEN").is_some());
+    }
+
+    #[test]
+    fn declared_keys_do_not_open_other_providers() {
+        let scope = PackSecretScope::new(
+            "messaging-telegram",
+            "demo",
+            Some("ops"),
+            vec!["bot_token".to_string()],
+            &SecretGrants::default(),
+        );
+        assert!(
+            scope
+                .check("secrets://dev/demo/ops/messaging/bot_token")
+                .is_none()
+        );
+        let denied = scope
+            .check("secrets://dev/demo/ops/messaging-slack/bot_token")
+            .unwrap();
+        assert!(denied.contains("messaging-slack"), "{denied}");
+        assert!(
+            scope
+                .check("secrets://dev/demo/_/messaging-telegram/bot_token")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn grants_open_other_namespaces() {
+        let grants = grants(
+            "grants:\n  - pack: messaging-teams\n    provider: messaging-email\n    keys: [graph_client_secret]\n  - pack: messaging-teams\n    provider: state-redis\n",
+        );
+        let scope = PackSecretScope::new("messaging-teams", "demo", None, Vec::new(), &grants);
+        assert!(
+            scope
+                .check("secrets://dev/demo/_/messaging-email/graph_client_secret")
+                .is_none()
+        );
+        assert!(
+            scope
+                .check("secrets://dev/demo/_/messaging-email/smtp_password")
+                .is_some()
+        );
+        assert!(
+            scope
+                .check("secrets://dev/demo/_/state-redis/anything")
+                .is_none()
+        );
+        let other = PackSecretScope::new("messaging-slack", "demo", None, Vec::new(), &grants);
+        assert!(
+            other
+                .check("secrets://dev/demo/_/messaging-email/graph_client_secret")
+                .is_some()
+        );
+    }
+}
@@ -0,0 +1,347 @@
+//! Bundle-level secret management behind `demo secrets`.
+//!
+//! Every operation goes through the backend chosen by
+//! [`secrets_gate::resolve_secrets_manager`], so the same commands work for the
+//! dev store, the encrypted file store and remote backends. Values only leave
+//! this module through [`SecretValue`], which redacts itself when printed.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use greentic_secrets_lib::{SecretError, SecretFormat, SeedDoc, SeedEntry, SeedValue};
+use tokio::runtime::Builder;
+
+use crate::domains::{self, Domain};
+use crate::secret_placeholders;
+use crate::secret_requirements;
+use crate::secret_value::SecretValue;
+use crate::secrets_backend::SecretsBackendKind;
+use crate::secrets_file_store;
+use crate::secrets_gate::{SecretsManagerHandle, canonical_secret_uri};
+use crate::secrets_manager;
+use crate::setup_fingerprint::FINGERPRINT_KEY_NAMESPACE;
+
+/// Identity shared by every secret of one tenant/team in one environment.
+#[derive(Clone, Debug)]
+pub struct SecretsScope {
+    pub env: String,
+    pub tenant: String,
+    pub team: Option<String>,
+}
+
+impl SecretsScope {
+    /// `secrets://{env}/{tenant}/{team}/` prefix of every URI in this scope.
+    pub fn uri_prefix(&self) -> String {
+        format!(
+            "secrets://{}/{}/{}/",
+            self.env,
+            self.tenant,
+            secrets_manager::canonical_team(self.team.as_deref())
+        )
+    }
+
+    /// Accepts a full `secrets://` URI or a `provider/key` shorthand.
+    pub fn resolve_uri(&self, name: &str) -> Result<String> {
+        if name.starts_with("secrets://") {
+            return Ok(name.to_string());
+        }
+        let (provider, key) = name
+            .split_once('/')
+            .filter(|(provider, key)| !provider.is_empty() && !key.is_empty())
+            .ok_or_else(|| anyhow!("expected secrets://... or <provider>/<key>, got '{name}'"))?;
+        Ok(canonical_secret_uri(
+            &self.env,
+            &self.tenant,
+            self.team.as_deref(),
+            provider,
+            key,
+        ))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum SecretStatus {
+    Present(SecretValue<'static>),
+    /// Still holding the value `SecretsSetup` seeded for a missing secret.
+    Placeholder,
+    Missing,
+    Error(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct SecretListing {
+    pub uri: String,
+    pub provider: String,
+    /// `true` when a pack in the bundle declares this secret.
+    pub declared: bool,
+    pub status: SecretStatus,
+}
+
+/// Lists the secrets declared by the bundle's provider packs plus whatever the
+/// backend can enumerate for this scope. The dev store and the encrypted file
+/// store can be enumerated; other backends list the declared secrets.
+pub fn list_secrets(
+    handle: &SecretsManagerHandle,
+    bundle_root: &Path,
+    scope: &SecretsScope,
+    provider: Option<&str>,
+) -> Result<Vec<SecretListing>> {
+    let mut uris = BTreeMap::new();
+    for uri in declared_secret_uris(bundle_root, scope)? {
+        uris.insert(uri, true);
+    }
+    let prefix = scope.uri_prefix();
+    for uri in stored_secret_uris(handle)? {
+        if uri.starts_with(&prefix) {
+            uris.entry(uri).or_insert(false);
+        }
+    }
+    let manager = handle.manager();
+    let runtime = secrets_runtime()?;
+    let mut listings = Vec::new();
+    for (uri, declared) in uris {
+        let Some(uri_provider) = provider_segment(&uri) else {
+            continue;
+        };
+        if provider.is_some_and(|filter| filter != uri_provider) {
+            continue;
+        }
+        let status = match runtime.block_on(manager.read(&uri)) {
+            Ok(value) if secret_placeholders::is_placeholder_value(&uri, &value) => {
+                SecretStatus::Placeholder
+            }
+            Ok(value) => SecretStatus::Present(SecretValue::owned(value)),
+            Err(SecretError::NotFound(_)) => SecretStatus::Missing,
+            Err(err) => SecretStatus::Error(err.to_string()),
+        };
+        listings.push(SecretListing {
+            provider: uri_provider.to_string(),
+            uri,
+            declared,
+            status,
+        });
+    }
+    Ok(listings)
+}
+
+pub fn get_secret(handle: &SecretsManagerHandle, uri: &str) -> Result<SecretValue<'static>> {
+    let manager = handle.manager();
+    let value = secrets_runtime()?
+        .block_on(manager.read(uri))
+        .map_err(|err| anyhow!("read {uri}: {err}"))?;
+    Ok(SecretValue::owned(value))
+}
+
+pub fn set_secret(handle: &SecretsManagerHandle, uri: &str, value: &SecretValue<'_>) -> Result<()> {
+    let manager = handle.manager();
+    secrets_runtime()?
+        .block_on(manager.write(uri, value.as_bytes()))
+        .map_err(|err| anyhow!("write {uri}: {err}"))?;
+    forget_placeholders(handle, [uri])
+}
+
+pub fn delete_secret(handle: &SecretsManagerHandle, uri: &str) -> Result<()> {
+    let manager = handle.manager();
+    secrets_runtime()?
+        .block_on(manager.delete(uri))
+        .map_err(|err| anyhow!("delete {uri}: {err}"))
+}
+
+/// Builds a seed document from every present secret in `listings`.
+pub fn export_seed_doc(listings: &[SecretListing]) -> SeedDoc {
+    let entries = listings
+        .iter()
+        .filter_map(|listing| match &listing.status {
+            SecretStatus::Present(value) => Some(seed_entry(&listing.uri, value.as_bytes())),
+            _ => None,
+        })
+        .collect();
+    SeedDoc { entries }
+}
+
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub imported: Vec<String>,
+    /// Entries outside the scope or filtered out by provider.
+    pub skipped: Vec<String>,
+    pub failed: Vec<(String, String)>,
+}
+
+/// Writes the entries of `doc` that belong to `scope` (and `provider`, when
+/// given) through the handle's backend.
+pub fn import_seed_doc(
+    handle: &SecretsManagerHandle,
+    scope: &SecretsScope,
+    doc: &SeedDoc,
+    provider: Option<&str>,
+) -> Result<ImportReport> {
+    let manager = handle.manager();
+    let runtime = secrets_runtime()?;
+    let prefix = scope.uri_prefix();
+    let mut report = ImportReport::default();
+    for entry in &doc.entries {
+        let in_scope = entry.uri.starts_with(&prefix)
+            && provider_segment(&entry.uri)
+                .is_some_and(|segment| provider.is_none_or(|filter| filter == segment));
+        if !in_scope {
+            report.skipped.push(entry.uri.clone());
+            continue;
+        }
+        let result = seed_value_bytes(&entry.value).and_then(|value| {
+            runtime
+                .block_on(manager.write(&entry.uri, &value))
+                .map_err(|err| anyhow!("{err}"))
+        });
+        match result {
+            Ok(()) => report.imported.push(entry.uri.clone()),
+            Err(err) => report.failed.push((entry.uri.clone(), err.to_string())),
+        }
+    }
+    forget_placeholders(handle, &report.imported)?;
+    Ok(report)
+}
+
+fn declared_secret_uris(bundle_root: &Path, scope: &SecretsScope) -> Result<Vec<String>> {
+    let mut uris = Vec::new();
+    for domain in [Domain::Messaging, Domain::Events, Domain::Secrets] {
+        for pack in domains::discover_provider_packs(bundle_root, domain)? {
+            let keys =
+                secret_requirements::load_declared_secret_keys(&pack.path).with_context(|| {
+                    format!("read secret requirements from {}", pack.path.display())
+                })?;
+            uris.extend(keys.iter().map(|key| {
+                canonical_secret_uri(
+                    &scope.env,
+                    &scope.tenant,
+                    scope.team.as_deref(),
+                    &pack.pack_id,
+                    key,
+                )
+            }));
+        }
+    }
+    Ok(uris)
+}
+
+/// Secrets present in the store, minus the operator's own bookkeeping keys.
+fn stored_secret_uris(handle: &SecretsManagerHandle) -> Result<Vec<String>> {
+    let uris = if let Some(store) = handle.encrypted_store.as_ref() {
+        store.list()?
+    } else {
+        match dev_store_path(handle) {
+            Some(path) if path.exists() => secrets_file_store::dev_store_uris(path)?,
+            _ => Vec::new(),
+        }
+    };
+    Ok(uris
+        .into_iter()
+        .filter(|uri| provider_segment(uri) != Some(FINGERPRINT_KEY_NAMESPACE))
+        .collect())
+}
+
+/// Store file of the handle when it is backed by the dev store.
+fn dev_store_path(handle: &SecretsManagerHandle) -> Option<&Path> {
+    let is_dev_store = !handle.using_env_fallback
+        && matches!(handle.selection.kind(), Ok(SecretsBackendKind::DevStore));
+    handle.dev_store_path.as_deref().filter(|_| is_dev_store)
+}
+
+fn forget_placeholders<I, S>(handle: &SecretsManagerHandle, uris: I) -> Result<()>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    for uri in uris {
+        secret_placeholders::forget_placeholder(&handle.placeholder_store_path, uri.as_ref())?;
+    }
+    Ok(())
+}
+
+fn provider_segment(uri: &str) -> Option<&str> {
+    let rest = uri.strip_prefix("secrets://")?;
+    let segments = rest.split('/').collect::<Vec<_>>();
+    match segments.as_slice() {
+        [_env, _tenant, _team, provider, _key] => Some(provider),
+        _ => None,
+    }
+}
+
+fn seed_entry(uri: &str, value: &[u8]) -> SeedEntry {
+    let (format, value) = match std::str::from_utf8(value) {
+        Ok(text) => (
+            SecretFormat::Text,
+            SeedValue::Text {
+                text: text.to_string(),
+            },
+        ),
+        Err(_) => (
+            SecretFormat::Bytes,
+            SeedValue::BytesB64 {
+                bytes_b64: STANDARD.encode(value),
+            },
+        ),
+    };
+    SeedEntry {
+        uri: uri.to_string(),
+        format,
+        value,
+        description: None,
+    }
+}
+
+pub(crate) fn seed_value_bytes(value: &SeedValue) -> Result<Vec<u8>> {
+    match value {
+        SeedValue::Text { text } => Ok(text.as_bytes().to_vec()),
+        SeedValue::Json { json } => serde_json::to_vec(json).context("encode json seed value"),
+        SeedValue::BytesB64 { bytes_b64 } => STANDARD
+            .decode(bytes_b64)
+            .context("decode base64 seed value"),
+    }
+}
+
+fn secrets_runtime() -> Result<tokio::runtime::Runtime> {
+    Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("build secrets runtime")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(team: Option<&str>) -> SecretsScope {
+        SecretsScope {
+            env: "dev".to_string(),
+            tenant: "demo".to_string(),
+            team: team.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn names_resolve_to_canonical_uris() {
+        let scope = scope(None);
+        assert_eq!(
+            scope.resolve_uri("messaging-telegram/BOT_TOKEN").unwrap(),
+            "secrets://dev/demo/_/messaging-telegram/bot_token"
+        );
+        assert_eq!(
+            scope.resolve_uri("secrets://dev/other/_/x/y").unwrap(),
+            "secrets://dev/other/_/x/y"
+        );
+        assert!(scope.resolve_uri("BOT_TOKEN").is_err());
+        assert_eq!(scope.uri_prefix(), "secrets://dev/demo/_/");
+    }
+
+    #[test]
+    fn seed_entries_round_trip_text_and_bytes() {
+        let text = seed_entry("secrets://dev/demo/_/p/k", b"hello");
+        assert_eq!(text.format, SecretFormat::Text);
+        assert_eq!(seed_value_bytes(&text.value).unwrap(), b"hello");
+        let bytes = seed_entry("secrets://dev/demo/_/p/k", &[0xff, 0x01]);
+        assert_eq!(bytes.format, SecretFormat::Bytes);
+        assert_eq!(seed_value_bytes(&bytes.value).unwrap(), vec![0xff, 0x01]);
+    }
+}
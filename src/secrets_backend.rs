@@ -4,6 +4,8 @@ use anyhow::{Context, Result, anyhow};
 use serde::Deserialize;
 use zip::ZipArchive;
 
+use crate::secrets_http::HttpBackendConfig;
+
 const BACKEND_CONFIG_PATHS: &[&str] = &[
     "assets/secrets_backend.json",
     "assets/secrets-backend.json",
@@ -15,6 +17,8 @@ const BACKEND_CONFIG_PATHS: &[&str] = &[
 pub enum SecretsBackendKind {
     DevStore,
     Env,
+    EncryptedFile,
+    Http,
 }
 
 impl std::fmt::Display for SecretsBackendKind {
@@ -22,17 +26,46 @@ impl std::fmt::Display for SecretsBackendKind {
         let label = match self {
             SecretsBackendKind::DevStore => "dev-store",
             SecretsBackendKind::Env => "env",
+            SecretsBackendKind::EncryptedFile => "encrypted-file",
+            SecretsBackendKind::Http => "http",
         };
         f.write_str(label)
     }
 }
 
-#[derive(Deserialize)]
-struct PackBackendConfig {
-    backend: Option<String>,
+/// Contents of `secrets_backend.json` in a secrets manager pack.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct PackBackendConfig {
+    pub backend: Option<String>,
+    /// Store location for file-based backends, relative to the bundle root.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Key file for the encrypted file backend, relative to the bundle root.
+    #[serde(default)]
+    pub key_file: Option<String>,
+    /// Server settings for the Vault KV v2 compatible HTTP backend.
+    #[serde(default)]
+    pub http: Option<HttpBackendConfig>,
 }
 
 pub fn backend_kind_from_pack(pack_path: &Path) -> Result<SecretsBackendKind> {
+    let config = backend_config_from_pack(pack_path)?;
+    match config.backend {
+        Some(kind) => match kind.trim().to_ascii_lowercase().as_str() {
+            "" | "default" | "dev-store" | "devstore" => Ok(SecretsBackendKind::DevStore),
+            "env" | "environment" => Ok(SecretsBackendKind::Env),
+            "encrypted-file" | "encrypted" => Ok(SecretsBackendKind::EncryptedFile),
+            "http" | "vault" => Ok(SecretsBackendKind::Http),
+            other => Err(anyhow!(
+                "unsupported secrets backend '{other}' in pack {}",
+                pack_path.display()
+            )),
+        },
+        None => Ok(SecretsBackendKind::DevStore),
+    }
+}
+
+pub fn backend_config_from_pack(pack_path: &Path) -> Result<PackBackendConfig> {
     let file = File::open(pack_path)
         .with_context(|| format!("open secrets manager pack {}", pack_path.display()))?;
     let mut archive = ZipArchive::new(file)
@@ -43,19 +76,8 @@ pub fn backend_kind_from_pack(pack_path: &Path) -> Result<SecretsBackendKind> {
             entry
                 .read_to_string(&mut contents)
                 .with_context(|| format!("read backend config {}", entry_name))?;
-            let config: PackBackendConfig = serde_json::from_str(&contents)
-                .with_context(|| format!("parse secrets backend config in {}", entry_name))?;
-            if let Some(kind) = config.backend {
-                return match kind.trim().to_ascii_lowercase().as_str() {
-                    "" | "default" | "dev-store" | "devstore" => Ok(SecretsBackendKind::DevStore),
-                    "env" | "environment" => Ok(SecretsBackendKind::Env),
-                    other => Err(anyhow!(
-                        "unsupported secrets backend '{other}' in pack {}",
-                        pack_path.display()
-                    )),
-                };
-            }
-            return Ok(SecretsBackendKind::DevStore);
+            return serde_json::from_str(&contents)
+                .with_context(|| format!("parse secrets backend config in {}", entry_name));
         }
     }
     Err(anyhow!(
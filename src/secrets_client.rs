@@ -2,16 +2,18 @@ use crate::dev_store_path;
 use anyhow::{Result as AnyhowResult, anyhow};
 use async_trait::async_trait;
 use greentic_secrets_lib::{
-    Result as SecretResult, SecretError, SecretsManager, SecretsStore,
+    Result as SecretResult, SecretError, SecretFormat, SecretsManager, SecretsStore,
     core::{Error as CoreError, seed::DevStore},
+    spec::{SecretUri, SecretsBackend},
 };
+use secrets_provider_dev::DevBackend;
 use std::{
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, RwLock},
 };
 
 pub struct SecretsClient {
-    store: Arc<DevStore>,
+    store: RwLock<Arc<DevStore>>,
     store_path: Option<PathBuf>,
 }
 
@@ -27,7 +29,7 @@ impl SecretsClient {
         let store = DevStore::with_path(store_path.clone())
             .map_err(|err| anyhow!("failed to open dev secrets store: {err}"))?;
         Ok(Self {
-            store: Arc::new(store),
+            store: RwLock::new(Arc::new(store)),
             store_path: Some(store_path),
         })
     }
@@ -36,7 +38,7 @@ impl SecretsClient {
         let store = DevStore::with_path(path.clone())
             .map_err(|err| anyhow!("failed to open dev secrets store: {err}"))?;
         Ok(Self {
-            store: Arc::new(store),
+            store: RwLock::new(Arc::new(store)),
             store_path: Some(path),
         })
     }
@@ -44,12 +46,44 @@ impl SecretsClient {
     pub fn store_path(&self) -> Option<&Path> {
         self.store_path.as_deref()
     }
+
+    fn store(&self) -> SecretResult<Arc<DevStore>> {
+        self.store
+            .read()
+            .map(|store| store.clone())
+            .map_err(|_| SecretError::Backend("dev secrets store lock poisoned".into()))
+    }
+
+    /// `DevStore` only reads and writes, so deletes go straight to the dev
+    /// backend's persisted file and the store is reopened to pick them up.
+    fn delete_persisted(&self, uri: &str) -> SecretResult<()> {
+        let path = self.store_path.clone().ok_or_else(|| {
+            SecretError::Permission("dev secrets store has no backing file".into())
+        })?;
+        let parsed = SecretUri::parse(uri)
+            .map_err(|err| SecretError::Backend(format!("invalid uri {uri}: {err}").into()))?;
+        let backend = DevBackend::with_persistence(path.clone())
+            .map_err(|err| SecretError::Backend(err.to_string().into()))?;
+        match backend.delete(&parsed) {
+            Ok(_) => {}
+            Err(CoreError::NotFound { entity }) => return Err(SecretError::NotFound(entity)),
+            Err(err) => return Err(SecretError::Backend(err.to_string().into())),
+        }
+        let reopened = DevStore::with_path(path)
+            .map_err(|err| SecretError::Backend(err.to_string().into()))?;
+        *self
+            .store
+            .write()
+            .map_err(|_| SecretError::Backend("dev secrets store lock poisoned".into()))? =
+            Arc::new(reopened);
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl SecretsManager for SecretsClient {
     async fn read(&self, path: &str) -> SecretResult<Vec<u8>> {
-        let result = self.store.get(path).await;
+        let result = self.store()?.get(path).await;
         match result {
             Ok(value) => Ok(value),
             Err(CoreError::NotFound { entity }) => Err(SecretError::NotFound(entity)),
@@ -57,16 +91,20 @@ impl SecretsManager for SecretsClient {
         }
     }
 
-    async fn write(&self, _: &str, _: &[u8]) -> SecretResult<()> {
-        Err(SecretError::Permission(
-            "dev secrets store is read-only".into(),
-        ))
+    async fn write(&self, path: &str, value: &[u8]) -> SecretResult<()> {
+        let format = if std::str::from_utf8(value).is_ok() {
+            SecretFormat::Text
+        } else {
+            SecretFormat::Bytes
+        };
+        self.store()?
+            .put(path, format, value)
+            .await
+            .map_err(|err| SecretError::Backend(err.to_string().into()))
     }
 
-    async fn delete(&self, _: &str) -> SecretResult<()> {
-        Err(SecretError::Permission(
-            "dev secrets store is read-only".into(),
-        ))
+    async fn delete(&self, path: &str) -> SecretResult<()> {
+        self.delete_persisted(path)
     }
 }
 
@@ -0,0 +1,595 @@
+use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+use std::env;
+use std::fs::{File, OpenOptions};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result as AnyhowResult, anyhow};
+use async_trait::async_trait;
+use base64::{
+    Engine as _,
+    engine::general_purpose::{STANDARD, STANDARD_NO_PAD},
+};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use greentic_secrets_lib::core::seed::DevStore;
+use greentic_secrets_lib::{Result as SecretResult, SecretError, SecretsManager, SecretsStore};
+use rand::{RngExt, rng};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::operator_log;
+use crate::runtime_state::atomic_write;
+
+pub const DEFAULT_STORE_RELATIVE: &str = ".greentic/secrets/secrets.enc";
+const ENV_KEY: &str = "GREENTIC_SECRETS_KEY";
+const ENV_KEY_FILE: &str = "GREENTIC_SECRETS_KEY_FILE";
+const ENV_PASSPHRASE: &str = "GREENTIC_SECRETS_PASSPHRASE";
+const FORMAT_VERSION: u32 = 1;
+const CIPHER: &str = "xchacha20poly1305";
+const KDF_ALGORITHM: &str = "pbkdf2-sha256";
+pub const DEFAULT_KDF_ITERATIONS: u32 = 310_000;
+const DEV_STORE_STATE_KEY: &str = "SECRETS_BACKEND_STATE";
+
+/// Key material for the encrypted store: either a raw 256-bit key or a
+/// passphrase that is stretched with PBKDF2 using the salt kept in the file.
+#[derive(Clone)]
+pub enum StoreKey {
+    Raw([u8; 32]),
+    Passphrase(String),
+}
+
+impl StoreKey {
+    /// Resolves the key from, in order: `$GREENTIC_SECRETS_KEY` (base64 raw
+    /// key), `key_file` or `$GREENTIC_SECRETS_KEY_FILE`,
+    /// `$GREENTIC_SECRETS_PASSPHRASE`, and finally an interactive prompt when
+    /// stdin is a terminal.
+    pub fn resolve(key_file: Option<&Path>) -> AnyhowResult<Self> {
+        if let Ok(value) = env::var(ENV_KEY) {
+            return decode_raw_key(value.trim())
+                .ok_or_else(|| anyhow!("{ENV_KEY} must be a base64-encoded 32-byte key"));
+        }
+        let key_file = key_file
+            .map(Path::to_path_buf)
+            .or_else(|| env::var_os(ENV_KEY_FILE).map(PathBuf::from));
+        if let Some(path) = key_file {
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("read secrets key file {}", path.display()))?;
+            let trimmed = contents.trim();
+            return Ok(decode_raw_key(trimmed)
+                .unwrap_or_else(|| StoreKey::Passphrase(trimmed.to_string())));
+        }
+        if let Ok(passphrase) = env::var(ENV_PASSPHRASE) {
+            return Ok(StoreKey::Passphrase(passphrase));
+        }
+        if std::io::stdin().is_terminal() {
+            let passphrase = rpassword::prompt_password("Secrets store passphrase: ")
+                .context("read secrets store passphrase")?;
+            return Ok(StoreKey::Passphrase(passphrase));
+        }
+        Err(anyhow!(
+            "encrypted secrets store needs a key: set {ENV_KEY}, {ENV_KEY_FILE} or {ENV_PASSPHRASE}"
+        ))
+    }
+}
+
+fn decode_raw_key(value: &str) -> Option<StoreKey> {
+    let bytes = STANDARD
+        .decode(value)
+        .or_else(|_| STANDARD_NO_PAD.decode(value))
+        .ok()?;
+    let key: [u8; 32] = bytes.try_into().ok()?;
+    Some(StoreKey::Raw(key))
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct KdfParams {
+    algorithm: String,
+    salt: String,
+    iterations: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedFile {
+    version: u32,
+    cipher: String,
+    #[serde(default)]
+    kdf: Option<KdfParams>,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Plaintext {
+    secrets: BTreeMap<String, String>,
+}
+
+/// Secrets store kept in a single XChaCha20-Poly1305 encrypted file. Every
+/// operation re-reads the file so separate operator processes stay in sync.
+/// Updates hold an advisory lock on `<file>.lock` from load to save, so
+/// concurrent writers never drop each other's changes; saves replace the
+/// file atomically, so reads need no lock.
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    key: [u8; 32],
+    kdf: Option<KdfParams>,
+}
+
+impl EncryptedFileStore {
+    pub fn default_path(bundle_root: &Path) -> PathBuf {
+        bundle_root.join(DEFAULT_STORE_RELATIVE)
+    }
+
+    pub fn open(path: PathBuf, key: StoreKey) -> AnyhowResult<Self> {
+        Self::open_with_iterations(path, key, DEFAULT_KDF_ITERATIONS)
+    }
+
+    /// Like [`EncryptedFileStore::open`]; `iterations` only applies when a new
+    /// passphrase-protected file is created.
+    pub fn open_with_iterations(
+        path: PathBuf,
+        key: StoreKey,
+        iterations: u32,
+    ) -> AnyhowResult<Self> {
+        let existing = read_file(&path)?;
+        let (key, kdf) = match (key, existing.and_then(|file| file.kdf)) {
+            (StoreKey::Raw(key), None) => (key, None),
+            (StoreKey::Raw(_), Some(_)) => {
+                return Err(anyhow!(
+                    "{} is protected by a passphrase, not a raw key",
+                    path.display()
+                ));
+            }
+            (StoreKey::Passphrase(passphrase), Some(kdf)) => {
+                (derive_key(&passphrase, &kdf)?, Some(kdf))
+            }
+            (StoreKey::Passphrase(passphrase), None) => {
+                if path.exists() {
+                    return Err(anyhow!(
+                        "{} is protected by a raw key, not a passphrase",
+                        path.display()
+                    ));
+                }
+                let mut salt = [0u8; 16];
+                rng().fill(&mut salt);
+                let kdf = KdfParams {
+                    algorithm: KDF_ALGORITHM.to_string(),
+                    salt: STANDARD.encode(salt),
+                    iterations,
+                };
+                (derive_key(&passphrase, &kdf)?, Some(kdf))
+            }
+        };
+        let store = Self { path, key, kdf };
+        // Fail on a wrong key now rather than on the first read.
+        store.load()?;
+        Ok(store)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn list(&self) -> AnyhowResult<Vec<String>> {
+        Ok(self.load()?.secrets.into_keys().collect())
+    }
+
+    /// Copies every live secret of a plaintext dev store into this store.
+    /// Secrets already present here are kept. Returns the number imported.
+    pub fn import_dev_store(&self, dev_store_path: &Path) -> AnyhowResult<usize> {
+        let uris = dev_store_uris(dev_store_path)?;
+        if uris.is_empty() {
+            return Ok(0);
+        }
+        let dev_store = DevStore::with_path(dev_store_path.to_path_buf())
+            .map_err(|err| anyhow!("failed to open dev secrets store: {err}"))?;
+        let values = block_on_detached(async move {
+            let mut values = Vec::with_capacity(uris.len());
+            for uri in uris {
+                let value = dev_store
+                    .get(&uri)
+                    .await
+                    .map_err(|err| anyhow!("read {uri} from dev store: {err}"))?;
+                values.push((uri, value));
+            }
+            Ok::<_, anyhow::Error>(values)
+        })??;
+
+        let _lock = self.lock()?;
+        let mut plaintext = self.load()?;
+        let mut imported = 0;
+        for (uri, value) in values {
+            if let Entry::Vacant(entry) = plaintext.secrets.entry(uri) {
+                entry.insert(STANDARD.encode(value));
+                imported += 1;
+            }
+        }
+        self.save(&plaintext)?;
+        Ok(imported)
+    }
+
+    fn load(&self) -> AnyhowResult<Plaintext> {
+        let Some(file) = read_file(&self.path)? else {
+            return Ok(Plaintext::default());
+        };
+        if file.version != FORMAT_VERSION || file.cipher != CIPHER {
+            return Err(anyhow!(
+                "unsupported encrypted store format version={} cipher={} in {}",
+                file.version,
+                file.cipher,
+                self.path.display()
+            ));
+        }
+        let nonce = STANDARD.decode(&file.nonce).context("decode nonce")?;
+        if nonce.len() != 24 {
+            return Err(anyhow!("invalid nonce in {}", self.path.display()));
+        }
+        let ciphertext = STANDARD
+            .decode(&file.ciphertext)
+            .context("decode ciphertext")?;
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(&self.key));
+        let plaintext = cipher
+            .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| {
+                anyhow!(
+                    "unable to decrypt {}: wrong key or corrupted file",
+                    self.path.display()
+                )
+            })?;
+        serde_json::from_slice(&plaintext).context("parse decrypted secrets")
+    }
+
+    fn save(&self, plaintext: &Plaintext) -> AnyhowResult<()> {
+        let mut nonce = [0u8; 24];
+        rng().fill(&mut nonce);
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(&self.key));
+        let bytes = serde_json::to_vec(plaintext)?;
+        let ciphertext = cipher
+            .encrypt(XNonce::from_slice(&nonce), bytes.as_slice())
+            .map_err(|_| anyhow!("encrypt secrets store"))?;
+        let file = EncryptedFile {
+            version: FORMAT_VERSION,
+            cipher: CIPHER.to_string(),
+            kdf: self.kdf.clone(),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        };
+        atomic_write(&self.path, &serde_json::to_vec_pretty(&file)?)?;
+        restrict_permissions(&self.path)
+    }
+
+    /// Takes the advisory lock that serializes load-modify-save cycles
+    /// across threads and processes; it is released when the file drops.
+    fn lock(&self) -> AnyhowResult<File> {
+        let mut lock_path = self.path.clone().into_os_string();
+        lock_path.push(".lock");
+        let lock_path = PathBuf::from(lock_path);
+        if let Some(parent) = lock_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("open {}", lock_path.display()))?;
+        file.lock()
+            .with_context(|| format!("lock {}", lock_path.display()))?;
+        Ok(file)
+    }
+
+    fn update<T>(&self, apply: impl FnOnce(&mut Plaintext) -> SecretResult<T>) -> SecretResult<T> {
+        let _lock = self.lock().map_err(backend_error)?;
+        let mut plaintext = self.load().map_err(backend_error)?;
+        let value = apply(&mut plaintext)?;
+        self.save(&plaintext).map_err(backend_error)?;
+        Ok(value)
+    }
+}
+
+#[async_trait]
+impl SecretsManager for EncryptedFileStore {
+    async fn read(&self, path: &str) -> SecretResult<Vec<u8>> {
+        let plaintext = self.load().map_err(backend_error)?;
+        let encoded = plaintext
+            .secrets
+            .get(path)
+            .ok_or_else(|| SecretError::NotFound(path.to_string()))?;
+        STANDARD
+            .decode(encoded)
+            .map_err(|err| SecretError::Backend(format!("decode {path}: {err}").into()))
+    }
+
+    async fn write(&self, path: &str, value: &[u8]) -> SecretResult<()> {
+        self.update(|plaintext| {
+            plaintext
+                .secrets
+                .insert(path.to_string(), STANDARD.encode(value));
+            Ok(())
+        })
+    }
+
+    async fn delete(&self, path: &str) -> SecretResult<()> {
+        self.update(|plaintext| {
+            plaintext
+                .secrets
+                .remove(path)
+                .map(|_| ())
+                .ok_or_else(|| SecretError::NotFound(path.to_string()))
+        })
+    }
+}
+
+/// Opens the encrypted store and, on first use, imports the plaintext dev
+/// store found under `bundle_root`.
+pub fn open_for_bundle(
+    bundle_root: &Path,
+    path: Option<&Path>,
+    key_file: Option<&Path>,
+) -> AnyhowResult<EncryptedFileStore> {
+    let path = path
+        .map(|path| resolve_path(bundle_root, path))
+        .unwrap_or_else(|| EncryptedFileStore::default_path(bundle_root));
+    let key_file = key_file.map(|path| resolve_path(bundle_root, path));
+    let first_use = !path.exists();
+    let store = EncryptedFileStore::open(path, StoreKey::resolve(key_file.as_deref())?)?;
+    if first_use && let Some(dev_store) = crate::dev_store_path::find_existing(bundle_root) {
+        let imported = store.import_dev_store(&dev_store)?;
+        operator_log::info(
+            module_path!(),
+            format!(
+                "migrated {imported} secret(s) from {} to {}",
+                dev_store.display(),
+                store.path().display()
+            ),
+        );
+        if imported > 0 {
+            eprintln!(
+                "secrets: migrated {imported} secret(s) from {} into the encrypted store {}; the plaintext file can now be removed",
+                dev_store.display(),
+                store.path().display()
+            );
+        }
+    }
+    Ok(store)
+}
+
+fn resolve_path(base: &Path, path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        base.join(path)
+    }
+}
+
+fn read_file(path: &Path) -> AnyhowResult<Option<EncryptedFile>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let bytes = std::fs::read(path).with_context(|| format!("read {}", path.display()))?;
+    let file = serde_json::from_slice(&bytes)
+        .with_context(|| format!("parse encrypted secrets store {}", path.display()))?;
+    Ok(Some(file))
+}
+
+fn derive_key(passphrase: &str, kdf: &KdfParams) -> AnyhowResult<[u8; 32]> {
+    if kdf.algorithm != KDF_ALGORITHM {
+        return Err(anyhow!("unsupported key derivation {}", kdf.algorithm));
+    }
+    let salt = STANDARD.decode(&kdf.salt).context("decode kdf salt")?;
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), &salt, kdf.iterations, &mut key);
+    Ok(key)
+}
+
+fn backend_error(err: anyhow::Error) -> SecretError {
+    SecretError::Backend(err.to_string().into())
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) -> AnyhowResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+        .with_context(|| format!("restrict permissions on {}", path.display()))
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) -> AnyhowResult<()> {
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct DevStoreState {
+    secrets: Vec<DevStoreSecret>,
+}
+
+#[derive(Deserialize)]
+struct DevStoreSecret {
+    key: String,
+    versions: Vec<DevStoreVersion>,
+}
+
+#[derive(Deserialize)]
+struct DevStoreVersion {
+    deleted: bool,
+}
+
+/// Lists the URIs whose latest version is live in a dev store file.
+pub(crate) fn dev_store_uris(path: &Path) -> AnyhowResult<Vec<String>> {
+    let contents =
+        std::fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    let Some(encoded) = contents.lines().find_map(|line| {
+        line.split_once('=')
+            .filter(|(key, _)| key.trim() == DEV_STORE_STATE_KEY)
+            .map(|(_, value)| value.trim().to_string())
+    }) else {
+        return Ok(Vec::new());
+    };
+    let decoded = STANDARD_NO_PAD
+        .decode(encoded.as_bytes())
+        .with_context(|| format!("decode dev store {}", path.display()))?;
+    let state: DevStoreState = serde_json::from_slice(&decoded)
+        .with_context(|| format!("parse dev store {}", path.display()))?;
+    Ok(state
+        .secrets
+        .into_iter()
+        .filter(|secret| {
+            secret
+                .versions
+                .last()
+                .is_some_and(|version| !version.deleted)
+        })
+        .map(|secret| secret.key)
+        .collect())
+}
+
+/// Runs `future` on a private runtime in its own thread so it is safe to call
+/// from inside another Tokio runtime.
+fn block_on_detached<F>(future: F) -> AnyhowResult<F::Output>
+where
+    F: std::future::Future + Send,
+    F::Output: Send,
+{
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .context("build secrets migration runtime")
+                    .map(|runtime| runtime.block_on(future))
+            })
+            .join()
+            .map_err(|_| anyhow!("secrets migration thread panicked"))?
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use greentic_secrets_lib::core::seed::{ApplyOptions, apply_seed};
+    use greentic_secrets_lib::{SecretFormat, SeedDoc, SeedEntry, SeedValue};
+    use tempfile::tempdir;
+    use tokio::runtime::Runtime;
+
+    const URI: &str = "secrets://dev/demo/_/messaging-telegram/bot_token";
+
+    fn raw_key(byte: u8) -> StoreKey {
+        StoreKey::Raw([byte; 32])
+    }
+
+    #[test]
+    fn concurrent_stores_keep_every_write() -> AnyhowResult<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("secrets.enc");
+        EncryptedFileStore::open(path.clone(), raw_key(3))?;
+        // Separate store instances stand in for separate operator processes.
+        let writers = (0..4)
+            .map(|writer| {
+                let path = path.clone();
+                std::thread::spawn(move || -> AnyhowResult<()> {
+                    let store = EncryptedFileStore::open(path, raw_key(3))?;
+                    let runtime = Runtime::new()?;
+                    for n in 0..10 {
+                        let uri = format!("secrets://dev/demo/_/p/key_{writer}_{n}");
+                        runtime.block_on(store.write(&uri, b"v"))?;
+                    }
+                    Ok(())
+                })
+            })
+            .collect::<Vec<_>>();
+        for writer in writers {
+            writer.join().expect("writer panicked")?;
+        }
+        let store = EncryptedFileStore::open(path, raw_key(3))?;
+        assert_eq!(store.list()?.len(), 40);
+        Ok(())
+    }
+
+    #[test]
+    fn write_read_delete_round_trip() -> AnyhowResult<()> {
+        let runtime = Runtime::new()?;
+        let dir = tempdir()?;
+        let path = dir.path().join("secrets.enc");
+        let store = EncryptedFileStore::open(path.clone(), raw_key(7))?;
+        runtime.block_on(store.write(URI, b"token-123"))?;
+        assert_eq!(runtime.block_on(store.read(URI))?, b"token-123");
+
+        let on_disk = std::fs::read_to_string(&path)?;
+        assert!(!on_disk.contains("token-123"));
+        assert!(!on_disk.contains("bot_token"));
+
+        let reopened = EncryptedFileStore::open(path, raw_key(7))?;
+        assert_eq!(runtime.block_on(reopened.read(URI))?, b"token-123");
+        runtime.block_on(reopened.delete(URI))?;
+        assert!(matches!(
+            runtime.block_on(reopened.read(URI)),
+            Err(SecretError::NotFound(_))
+        ));
+        assert!(matches!(
+            runtime.block_on(reopened.delete(URI)),
+            Err(SecretError::NotFound(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn wrong_key_is_rejected() -> AnyhowResult<()> {
+        let runtime = Runtime::new()?;
+        let dir = tempdir()?;
+        let path = dir.path().join("secrets.enc");
+        let store = EncryptedFileStore::open(path.clone(), raw_key(1))?;
+        runtime.block_on(store.write(URI, b"value"))?;
+        let err = EncryptedFileStore::open(path, raw_key(2))
+            .err()
+            .expect("wrong key must fail");
+        assert!(err.to_string().contains("wrong key"), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn passphrase_store_keeps_salt() -> AnyhowResult<()> {
+        let runtime = Runtime::new()?;
+        let dir = tempdir()?;
+        let path = dir.path().join("secrets.enc");
+        let passphrase = || StoreKey::Passphrase("correct horse".to_string());
+        let store = EncryptedFileStore::open_with_iterations(path.clone(), passphrase(), 1_000)?;
+        runtime.block_on(store.write(URI, b"value"))?;
+        let reopened = EncryptedFileStore::open(path.clone(), passphrase())?;
+        assert_eq!(runtime.block_on(reopened.read(URI))?, b"value");
+        assert!(
+            EncryptedFileStore::open(path.clone(), StoreKey::Passphrase("wrong".to_string()))
+                .is_err()
+        );
+        assert!(EncryptedFileStore::open(path, raw_key(0)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn imports_plaintext_dev_store() -> AnyhowResult<()> {
+        let dir = tempdir()?;
+        let dev_path = dir.path().join(".dev.secrets.env");
+        let dev_store = DevStore::with_path(dev_path.clone())?;
+        let seed = SeedDoc {
+            entries: vec![SeedEntry {
+                uri: URI.to_string(),
+                format: SecretFormat::Text,
+                value: SeedValue::Text {
+                    text: "from-dev-store".to_string(),
+                },
+                description: None,
+            }],
+        };
+        let runtime = Runtime::new()?;
+        let report = runtime
+            .block_on(async { apply_seed(&dev_store, &seed, ApplyOptions::default()).await });
+        assert_eq!(report.ok, 1);
+
+        let store = EncryptedFileStore::open(dir.path().join("secrets.enc"), raw_key(3))?;
+        assert_eq!(store.import_dev_store(&dev_path)?, 1);
+        assert_eq!(store.import_dev_store(&dev_path)?, 0);
+        assert_eq!(runtime.block_on(store.read(URI))?, b"from-dev-store");
+        Ok(())
+    }
+}
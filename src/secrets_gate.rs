@@ -9,6 +9,7 @@ use std::{
 
 use anyhow::{Context, Error as AnyhowError, Result as AnyhowResult, anyhow};
 use async_trait::async_trait;
+use chrono::Utc;
 use greentic_secrets_lib::env::EnvSecretsManager;
 use greentic_secrets_lib::{Result as SecretResult, SecretError, SecretsManager};
 use serde::Deserialize;
@@ -18,95 +19,172 @@ use tokio::runtime::Builder;
 use tracing::info;
 use zip::{ZipArchive, result::ZipError};
 
+use crate::dev_store_path;
 use crate::operator_log;
+use crate::secret_audit::{SecretAuditEntry, SecretAuditLog, SecretAuditOp, SecretAuditOutcome};
 use crate::secret_name;
-use crate::secret_value::SecretValue;
-use crate::secrets_backend::SecretsBackendKind;
+use crate::secret_placeholders;
+use crate::secret_scope::{PackSecretScope, SecretGrants};
+use crate::secrets_backend::{self, SecretsBackendKind};
 use crate::secrets_client::SecretsClient;
+use crate::secrets_file_store::{self, EncryptedFileStore};
+use crate::secrets_http::HttpSecretsManager;
 use crate::secrets_manager;
 
 type CborMap = BTreeMap<CborValue, CborValue>;
 
 pub type DynSecretsManager = Arc<dyn SecretsManager>;
 
-struct LoggingSecretsManager {
+/// Wraps the backend so every access is checked against the pack's scope
+/// (when there is one) and appended to the bundle's secret audit log.
+struct AuditedSecretsManager {
     inner: DynSecretsManager,
-    dev_store_path_display: String,
-    using_env_fallback: bool,
+    audit: SecretAuditLog,
+    tenant: String,
+    team: String,
+    scope: Option<PackSecretScope>,
+    correlation_id: Option<String>,
 }
 
-impl LoggingSecretsManager {
-    fn new(
-        inner: DynSecretsManager,
-        dev_store_path: Option<&Path>,
-        using_env_fallback: bool,
-    ) -> Self {
-        let dev_store_path_display = dev_store_path
-            .map(|path| path.display().to_string())
-            .unwrap_or_else(|| "<default>".to_string());
-        Self {
-            inner,
-            dev_store_path_display,
-            using_env_fallback,
-        }
+impl AuditedSecretsManager {
+    /// The permission error for `path` when it is outside the pack's scope.
+    fn denied(&self, verb: &str, path: &str) -> Option<SecretError> {
+        let scope = self.scope.as_ref()?;
+        let reason = scope.check(path)?;
+        let pack_id = scope.pack_id.as_str();
+        operator_log::warn(
+            module_path!(),
+            format!("secret access denied pack={pack_id} op={verb} uri={path} reason={reason}"),
+        );
+        Some(SecretError::Permission(format!(
+            "pack {pack_id} may not {verb} {path}: {reason}"
+        )))
+    }
+
+    fn record<T>(&self, op: SecretAuditOp, uri: &str, result: &SecretResult<T>) {
+        self.audit.record(&SecretAuditEntry {
+            timestamp: Utc::now(),
+            op,
+            tenant: self.tenant.clone(),
+            team: self.team.clone(),
+            pack_id: self.scope.as_ref().map(|scope| scope.pack_id.clone()),
+            uri: uri.to_string(),
+            outcome: SecretAuditOutcome::from_result(result),
+            correlation_id: self.correlation_id.clone(),
+        });
     }
 }
 
 #[async_trait]
-impl SecretsManager for LoggingSecretsManager {
+impl SecretsManager for AuditedSecretsManager {
     async fn read(&self, path: &str) -> SecretResult<Vec<u8>> {
-        operator_log::info(
-            module_path!(),
-            format!(
-                "WASM secrets read requested uri={path}; backend dev_store_path={} using_env_fallback={}",
-                self.dev_store_path_display, self.using_env_fallback,
-            ),
-        );
-        match self.inner.read(path).await {
-            Ok(value) => {
-                operator_log::debug(
-                    module_path!(),
-                    format!(
-                        "WASM secrets read resolved uri={path}; value={}",
-                        SecretValue::new(value.as_slice()),
-                    ),
-                );
-                Ok(value)
-            }
-            Err(err) => Err(err),
-        }
+        let result = match self.denied("read", path) {
+            Some(err) => Err(err),
+            None => self.inner.read(path).await,
+        };
+        self.record(SecretAuditOp::Read, path, &result);
+        result
     }
 
     async fn write(&self, path: &str, value: &[u8]) -> SecretResult<()> {
-        self.inner.write(path, value).await
+        let result = match self.denied("write", path) {
+            Some(err) => Err(err),
+            None => self.inner.write(path, value).await,
+        };
+        self.record(SecretAuditOp::Write, path, &result);
+        result
     }
 
     async fn delete(&self, path: &str) -> SecretResult<()> {
-        self.inner.delete(path).await
+        let result = match self.denied("delete", path) {
+            Some(err) => Err(err),
+            None => self.inner.delete(path).await,
+        };
+        self.record(SecretAuditOp::Delete, path, &result);
+        result
     }
 }
 const ENV_ALLOW_ENV_SECRETS: &str = "GREENTIC_ALLOW_ENV_SECRETS";
 
 #[derive(Clone)]
 pub struct SecretsManagerHandle {
-    manager: DynSecretsManager,
+    backend: DynSecretsManager,
     pub selection: secrets_manager::SecretsManagerSelection,
     pub dev_store_path: Option<PathBuf>,
+    /// Store path the placeholder index sits next to: the dev store, or where
+    /// the default dev store would be for other backends, so one index covers
+    /// every backend of the bundle.
+    /// The encrypted file store when it is the backend, so its secrets can be
+    /// enumerated.
+    pub encrypted_store: Option<Arc<EncryptedFileStore>>,
+    pub placeholder_store_path: PathBuf,
     pub canonical_team: String,
     pub using_env_fallback: bool,
+    pub tenant: String,
+    pub grants: SecretGrants,
+    pub audit: SecretAuditLog,
 }
 
 impl SecretsManagerHandle {
+    /// Manager for operator-side access. Every call is audited without a
+    /// pack id.
     pub fn manager(&self) -> DynSecretsManager {
-        self.manager.clone()
+        self.audited(None, None)
     }
 
-    pub fn runtime_manager(&self, _pack_id: Option<&str>) -> DynSecretsManager {
-        Arc::new(LoggingSecretsManager::new(
-            self.manager(),
-            self.dev_store_path.as_deref(),
-            self.using_env_fallback,
-        ))
+    /// Manager handed to a pack's WASM components. With a `pack_id` reads,
+    /// writes and deletes are restricted to that pack's [`PackSecretScope`];
+    /// every call is audited with the pack id and `correlation_id`.
+    pub fn runtime_manager(
+        &self,
+        pack_id: Option<&str>,
+        pack_path: Option<&Path>,
+        correlation_id: Option<&str>,
+    ) -> DynSecretsManager {
+        let scope = pack_id.map(|pack_id| {
+            PackSecretScope::for_pack(
+                pack_id,
+                pack_path,
+                &self.tenant,
+                Some(&self.canonical_team),
+                &self.grants,
+            )
+            .unwrap_or_else(|err| {
+                operator_log::warn(
+                    module_path!(),
+                    format!("secret scope for pack {pack_id} ignores declared keys: {err}"),
+                );
+                PackSecretScope::new(
+                    pack_id,
+                    &self.tenant,
+                    Some(&self.canonical_team),
+                    Vec::new(),
+                    &self.grants,
+                )
+            })
+        });
+        self.audited(scope, correlation_id)
+    }
+
+    /// The env backend, selected or as a fallback, cannot store values;
+    /// callers skip seeding and report such secrets as missing.
+    pub fn is_read_only(&self) -> bool {
+        self.using_env_fallback || matches!(self.selection.kind(), Ok(SecretsBackendKind::Env))
+    }
+
+    fn audited(
+        &self,
+        scope: Option<PackSecretScope>,
+        correlation_id: Option<&str>,
+    ) -> DynSecretsManager {
+        Arc::new(AuditedSecretsManager {
+            inner: self.backend.clone(),
+            audit: self.audit.clone(),
+            tenant: self.tenant.clone(),
+            team: self.canonical_team.clone(),
+            scope,
+            correlation_id: correlation_id.map(str::to_string),
+        })
     }
 }
 
@@ -146,13 +224,18 @@ pub fn resolve_secrets_manager(
             dev_secrets_path,
         ),
     );
-    let (manager, store_path, using_env_fallback) = instantiate_manager_from_selection(
+    let (opened, using_env_fallback) = instantiate_manager_from_selection(
         bundle_root,
         &selection,
         allow_env,
         &pack_desc,
         backend_kind_result,
     )?;
+    let OpenedBackend {
+        manager,
+        store_path,
+        encrypted_store,
+    } = opened;
     operator_log::info(
         module_path!(),
         format!(
@@ -187,25 +270,54 @@ pub fn resolve_secrets_manager(
             ),
         );
     }
+    let grants = SecretGrants::load(bundle_root, tenant)?;
+    let placeholder_store_path = store_path
+        .clone()
+        .filter(|_| {
+            !using_env_fallback && matches!(selection.kind(), Ok(SecretsBackendKind::DevStore))
+        })
+        .unwrap_or_else(|| dev_store_path::default_path(bundle_root));
     Ok(SecretsManagerHandle {
-        manager,
+        backend: manager,
         selection,
         dev_store_path: store_path,
+        encrypted_store,
+        placeholder_store_path,
         canonical_team: team_owned,
         using_env_fallback,
+        tenant: tenant.to_string(),
+        grants,
+        audit: SecretAuditLog::for_bundle(bundle_root),
     })
 }
 
+/// Backend opened for a handle, with the local store behind it when it has one.
+struct OpenedBackend {
+    manager: DynSecretsManager,
+    store_path: Option<PathBuf>,
+    encrypted_store: Option<Arc<EncryptedFileStore>>,
+}
+
+impl OpenedBackend {
+    fn env() -> Self {
+        Self {
+            manager: Arc::new(EnvSecretsManager),
+            store_path: None,
+            encrypted_store: None,
+        }
+    }
+}
+
 fn instantiate_manager_from_selection(
     bundle_root: &Path,
     selection: &secrets_manager::SecretsManagerSelection,
     allow_env: bool,
     pack_desc: &str,
     backend_kind_result: Result<SecretsBackendKind, AnyhowError>,
-) -> AnyhowResult<(DynSecretsManager, Option<PathBuf>, bool)> {
+) -> AnyhowResult<(OpenedBackend, bool)> {
     match backend_kind_result {
         Ok(kind) => match instantiate_manager_for_backend(bundle_root, selection, kind) {
-            Ok((manager, path)) => Ok((manager, path, false)),
+            Ok(opened) => Ok((opened, false)),
             Err(err) => fallback_to_env(allow_env, kind.to_string(), pack_desc, err),
         },
         Err(err) => fallback_to_env(allow_env, "<unknown>".to_string(), pack_desc, err),
@@ -217,7 +329,7 @@ fn fallback_to_env(
     kind_label: String,
     pack_desc: &str,
     err: AnyhowError,
-) -> AnyhowResult<(DynSecretsManager, Option<PathBuf>, bool)> {
+) -> AnyhowResult<(OpenedBackend, bool)> {
     if allow_env {
         operator_log::warn(
             module_path!(),
@@ -227,7 +339,7 @@ fn fallback_to_env(
                 pack = pack_desc,
             ),
         );
-        Ok((Arc::new(EnvSecretsManager) as DynSecretsManager, None, true))
+        Ok((OpenedBackend::env(), true))
     } else {
         Err(err)
     }
@@ -235,23 +347,69 @@ fn fallback_to_env(
 
 fn instantiate_manager_for_backend(
     bundle_root: &Path,
-    _selection: &secrets_manager::SecretsManagerSelection,
+    selection: &secrets_manager::SecretsManagerSelection,
     backend_kind: SecretsBackendKind,
-) -> AnyhowResult<(DynSecretsManager, Option<PathBuf>)> {
+) -> AnyhowResult<OpenedBackend> {
     match backend_kind {
         SecretsBackendKind::DevStore => open_dev_store_manager(bundle_root),
-        SecretsBackendKind::Env => Ok((Arc::new(EnvSecretsManager) as DynSecretsManager, None)),
+        SecretsBackendKind::Env => Ok(OpenedBackend::env()),
+        SecretsBackendKind::EncryptedFile => open_encrypted_file_manager(bundle_root, selection),
+        SecretsBackendKind::Http => open_http_manager(selection),
     }
 }
 
-fn open_dev_store_manager(
+fn open_http_manager(
+    selection: &secrets_manager::SecretsManagerSelection,
+) -> AnyhowResult<OpenedBackend> {
+    let config = match &selection.pack_path {
+        Some(pack_path) => secrets_backend::backend_config_from_pack(pack_path)?.http,
+        None => None,
+    };
+    let manager = HttpSecretsManager::new(config.unwrap_or_default())?;
+    operator_log::info(
+        module_path!(),
+        format!("secrets backend http address={}", manager.address()),
+    );
+    Ok(OpenedBackend {
+        manager: Arc::new(manager),
+        store_path: None,
+        encrypted_store: None,
+    })
+}
+
+fn open_encrypted_file_manager(
     bundle_root: &Path,
-) -> AnyhowResult<(DynSecretsManager, Option<PathBuf>)> {
+    selection: &secrets_manager::SecretsManagerSelection,
+) -> AnyhowResult<OpenedBackend> {
+    let config = match &selection.pack_path {
+        Some(pack_path) => secrets_backend::backend_config_from_pack(pack_path)?,
+        None => Default::default(),
+    };
+    let store = Arc::new(secrets_file_store::open_for_bundle(
+        bundle_root,
+        config.path.as_deref().map(Path::new),
+        config.key_file.as_deref().map(Path::new),
+    )?);
+    Ok(OpenedBackend {
+        manager: store.clone(),
+        store_path: Some(store.path().to_path_buf()),
+        encrypted_store: Some(store),
+    })
+}
+
+fn open_dev_store_manager(bundle_root: &Path) -> AnyhowResult<OpenedBackend> {
     let client = SecretsClient::open(bundle_root)?;
-    let path = client.store_path().map(|path| path.to_path_buf());
-    Ok((Arc::new(client) as DynSecretsManager, path))
+    let store_path = client.store_path().map(|path| path.to_path_buf());
+    Ok(OpenedBackend {
+        manager: Arc::new(client),
+        store_path,
+        encrypted_store: None,
+    })
 }
 
+/// Provider segment for secrets that are not tied to one provider.
+pub const SHARED_SECRET_NAMESPACE: &str = "messaging";
+
 /// Build the canonical secrets URI for the provided identity.
 pub fn canonical_secret_uri(
     env: &str,
@@ -262,7 +420,7 @@ pub fn canonical_secret_uri(
 ) -> String {
     let team_segment = secrets_manager::canonical_team(team);
     let provider_segment = if provider.is_empty() {
-        "messaging".to_string()
+        SHARED_SECRET_NAMESPACE.to_string()
     } else {
         provider.to_string()
     };
@@ -326,7 +484,58 @@ fn display_secret_candidates(
     vec![format!("{prefix}{provider_id}/{normalized_key}")]
 }
 
+/// Required secrets of one provider that are absent or still placeholders.
+#[derive(Clone, Debug, Default)]
+pub struct ProviderSecretsStatus {
+    pub missing: Vec<String>,
+    /// Present, but still holding the value `SecretsSetup` seeded in.
+    pub placeholders: Vec<String>,
+}
+
+impl ProviderSecretsStatus {
+    pub fn is_resolved(&self) -> bool {
+        self.missing.is_empty() && self.placeholders.is_empty()
+    }
+}
+
+/// Writes answers to `secret: true` setup questions under the provider's
+/// namespace. Strings are stored as-is, other values as JSON text. Returns
+/// the URIs written.
+pub fn write_secret_answers(
+    manager: &DynSecretsManager,
+    env: &str,
+    tenant: &str,
+    team: Option<&str>,
+    provider_id: &str,
+    answers: &serde_json::Map<String, serde_json::Value>,
+) -> AnyhowResult<Vec<String>> {
+    let runtime = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("build secrets runtime")?;
+    let mut written = Vec::new();
+    for (name, value) in answers {
+        let uri = canonical_secret_uri(env, tenant, team, provider_id, name);
+        let bytes = match value {
+            serde_json::Value::String(text) => text.as_bytes().to_vec(),
+            other => serde_json::to_vec(other)?,
+        };
+        runtime
+            .block_on(manager.write(&uri, &bytes))
+            .map_err(|err| anyhow!("write setup answer {name} to {uri}: {err}"))?;
+        operator_log::info(
+            module_path!(),
+            format!("stored secret setup answer provider={provider_id} uri={uri}"),
+        );
+        written.push(uri);
+    }
+    Ok(written)
+}
+
 /// Check that the required secrets for the provider exist.
+///
+/// Placeholder values count as present here so setup flows can run, but each
+/// one is logged as unresolved; use [`provider_secrets_status`] to act on them.
 #[allow(clippy::too_many_arguments)]
 pub fn check_provider_secrets(
     manager: &DynSecretsManager,
@@ -339,9 +548,43 @@ pub fn check_provider_secrets(
     store_path: Option<&Path>,
     using_env_fallback: bool,
 ) -> anyhow::Result<Option<Vec<String>>> {
+    let status = provider_secrets_status(
+        manager,
+        env,
+        tenant,
+        team,
+        pack_path,
+        provider_id,
+        store_path,
+        using_env_fallback,
+    )?;
+    for uri in &status.placeholders {
+        operator_log::warn(
+            module_path!(),
+            format!("secret unresolved provider={provider_id} uri={uri}: placeholder value"),
+        );
+    }
+    if status.missing.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(status.missing))
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn provider_secrets_status(
+    manager: &DynSecretsManager,
+    env: &str,
+    tenant: &str,
+    team: Option<&str>,
+    pack_path: &Path,
+    provider_id: &str,
+    store_path: Option<&Path>,
+    using_env_fallback: bool,
+) -> anyhow::Result<ProviderSecretsStatus> {
     let keys = load_secret_keys_from_pack(pack_path)?;
     if keys.is_empty() {
-        return Ok(None);
+        return Ok(ProviderSecretsStatus::default());
     }
 
     let canonical_team = secrets_manager::canonical_team(team);
@@ -366,6 +609,7 @@ pub fn check_provider_secrets(
         .context("build secrets runtime")?;
     runtime.block_on(async {
         let mut missing = Vec::new();
+        let mut placeholders = Vec::new();
         for key in keys {
             let normalized_key = secret_name::canonical_secret_name(&key);
             let candidates = secret_uri_candidates(
@@ -420,7 +664,10 @@ pub fn check_provider_secrets(
                     store_path_display
                 );
                 match manager.read(uri).await {
-                    Ok(_) => {
+                    Ok(value) => {
+                        if secret_placeholders::is_placeholder_value(uri, &value) {
+                            placeholders.push(uri.clone());
+                        }
                         resolved = true;
                         matched_uri = Some(uri.clone());
                         break;
@@ -464,11 +711,10 @@ pub fn check_provider_secrets(
                 );
             }
         }
-        if missing.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(missing))
-        }
+        Ok(ProviderSecretsStatus {
+            missing,
+            placeholders,
+        })
     })
 }
 
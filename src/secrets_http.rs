@@ -0,0 +1,437 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result as AnyhowResult, anyhow};
+use async_trait::async_trait;
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use greentic_secrets_lib::{Result as SecretResult, SecretError, SecretsManager};
+use reqwest::{Method, StatusCode};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+const ENV_ADDR: &str = "VAULT_ADDR";
+const ENV_TOKEN: &str = "VAULT_TOKEN";
+const ENV_ROLE_ID: &str = "VAULT_ROLE_ID";
+const ENV_SECRET_ID: &str = "VAULT_SECRET_ID";
+const ENV_NAMESPACE: &str = "VAULT_NAMESPACE";
+/// Tokens are renewed this long before their lease runs out.
+const TOKEN_RENEW_MARGIN: Duration = Duration::from_secs(30);
+
+/// `http` section of a secrets pack's `secrets_backend.json`.
+///
+/// ```json
+/// { "backend": "http",
+///   "http": { "address": "https://vault.example.com", "mount": "secret",
+///             "path_prefix": "greentic", "auth": { "method": "approle", "role_id": "..." } } }
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+pub struct HttpBackendConfig {
+    /// Server address; defaults to `$VAULT_ADDR`.
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default = "default_mount")]
+    pub mount: String,
+    /// Prefix prepended to every KV path.
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+    /// Sent as `X-Vault-Namespace`; defaults to `$VAULT_NAMESPACE`.
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub auth: HttpAuthConfig,
+    /// How long read values are cached; `0` disables caching.
+    #[serde(default = "default_cache_ttl")]
+    pub cache_ttl_seconds: u64,
+    #[serde(default = "default_timeout")]
+    pub timeout_seconds: u64,
+}
+
+impl Default for HttpBackendConfig {
+    fn default() -> Self {
+        Self {
+            address: None,
+            mount: default_mount(),
+            path_prefix: None,
+            namespace: None,
+            auth: HttpAuthConfig::default(),
+            cache_ttl_seconds: default_cache_ttl(),
+            timeout_seconds: default_timeout(),
+        }
+    }
+}
+
+fn default_mount() -> String {
+    "secret".to_string()
+}
+
+fn default_cache_ttl() -> u64 {
+    30
+}
+
+fn default_timeout() -> u64 {
+    10
+}
+
+/// Credentials never live in the pack; only the environment variable names
+/// do. Token auth reads `$VAULT_TOKEN`, AppRole reads `$VAULT_ROLE_ID` and
+/// `$VAULT_SECRET_ID` unless overridden.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum HttpAuthConfig {
+    Token {
+        #[serde(default)]
+        token_env: Option<String>,
+    },
+    Approle {
+        #[serde(default)]
+        role_id: Option<String>,
+        #[serde(default)]
+        role_id_env: Option<String>,
+        #[serde(default)]
+        secret_id_env: Option<String>,
+        #[serde(default = "default_approle_mount")]
+        mount: String,
+    },
+}
+
+impl Default for HttpAuthConfig {
+    fn default() -> Self {
+        HttpAuthConfig::Token { token_env: None }
+    }
+}
+
+fn default_approle_mount() -> String {
+    "approle".to_string()
+}
+
+struct CachedToken {
+    token: String,
+    expires_at: Option<Instant>,
+}
+
+/// Secrets manager backed by a Vault KV v2 compatible HTTP API. Each canonical
+/// URI `secrets://{env}/{tenant}/{team}/{provider}/{key}` is stored as its own
+/// KV secret at `{path_prefix}/{env}/{tenant}/{team}/{provider}/{key}`.
+pub struct HttpSecretsManager {
+    client: reqwest::Client,
+    address: String,
+    config: HttpBackendConfig,
+    namespace: Option<String>,
+    token: Mutex<Option<CachedToken>>,
+    cache: Mutex<HashMap<String, (Vec<u8>, Instant)>>,
+}
+
+impl HttpSecretsManager {
+    pub fn new(config: HttpBackendConfig) -> AnyhowResult<Self> {
+        let address = config
+            .address
+            .clone()
+            .or_else(|| env::var(ENV_ADDR).ok())
+            .ok_or_else(|| anyhow!("http secrets backend needs an address or ${ENV_ADDR}"))?;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds.max(1)))
+            .build()
+            .context("build http secrets client")?;
+        let namespace = config
+            .namespace
+            .clone()
+            .or_else(|| env::var(ENV_NAMESPACE).ok());
+        Ok(Self {
+            client,
+            address: address.trim_end_matches('/').to_string(),
+            config,
+            namespace,
+            token: Mutex::new(None),
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Maps a canonical secret URI onto its KV path (without the mount).
+    pub fn kv_path(&self, uri: &str) -> Option<String> {
+        kv_path_for_uri(uri, self.config.path_prefix.as_deref())
+    }
+
+    async fn token(&self) -> SecretResult<String> {
+        if let Some(cached) = self.token.lock().map_err(lock_error)?.as_ref()
+            && cached
+                .expires_at
+                .is_none_or(|expires_at| Instant::now() < expires_at)
+        {
+            return Ok(cached.token.clone());
+        }
+        let fresh = self.login().await?;
+        let token = fresh.token.clone();
+        *self.token.lock().map_err(lock_error)? = Some(fresh);
+        Ok(token)
+    }
+
+    async fn login(&self) -> SecretResult<CachedToken> {
+        match &self.config.auth {
+            HttpAuthConfig::Token { token_env } => {
+                let name = token_env.as_deref().unwrap_or(ENV_TOKEN);
+                let token = env::var(name)
+                    .map_err(|_| SecretError::Permission(format!("${name} is not set")))?;
+                Ok(CachedToken {
+                    token,
+                    expires_at: None,
+                })
+            }
+            HttpAuthConfig::Approle {
+                role_id,
+                role_id_env,
+                secret_id_env,
+                mount,
+            } => {
+                let role_id_env = role_id_env.as_deref().unwrap_or(ENV_ROLE_ID);
+                let role_id = role_id
+                    .clone()
+                    .or_else(|| env::var(role_id_env).ok())
+                    .ok_or_else(|| {
+                        SecretError::Permission(format!(
+                            "approle role_id or ${role_id_env} missing"
+                        ))
+                    })?;
+                let secret_id_env = secret_id_env.as_deref().unwrap_or(ENV_SECRET_ID);
+                let secret_id = env::var(secret_id_env)
+                    .map_err(|_| SecretError::Permission(format!("${secret_id_env} is not set")))?;
+                let url = format!("{}/v1/auth/{mount}/login", self.address);
+                let response = self
+                    .with_namespace(self.client.post(&url))
+                    .json(&json!({ "role_id": role_id, "secret_id": secret_id }))
+                    .send()
+                    .await
+                    .map_err(transport_error)?;
+                let status = response.status();
+                if !status.is_success() {
+                    return Err(SecretError::Permission(format!(
+                        "approle login failed: {status}"
+                    )));
+                }
+                let body: Value = response.json().await.map_err(transport_error)?;
+                let auth = &body["auth"];
+                let token = auth["client_token"]
+                    .as_str()
+                    .ok_or_else(|| SecretError::Backend("approle login without token".into()))?
+                    .to_string();
+                let expires_at = auth["lease_duration"]
+                    .as_u64()
+                    .filter(|seconds| *seconds > 0)
+                    .map(|seconds| {
+                        Instant::now()
+                            + Duration::from_secs(seconds).saturating_sub(TOKEN_RENEW_MARGIN)
+                    });
+                Ok(CachedToken { token, expires_at })
+            }
+        }
+    }
+
+    fn with_namespace(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.namespace {
+            Some(namespace) => request.header("X-Vault-Namespace", namespace),
+            None => request,
+        }
+    }
+
+    /// Sends an authenticated request, logging in again once if the server
+    /// rejects a cached AppRole token.
+    async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&Value>,
+    ) -> SecretResult<reqwest::Response> {
+        let mut retried = false;
+        loop {
+            let token = self.token().await?;
+            let mut request = self
+                .with_namespace(self.client.request(method.clone(), url))
+                .header("X-Vault-Token", token);
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+            let response = request.send().await.map_err(transport_error)?;
+            if response.status() == StatusCode::FORBIDDEN
+                && !retried
+                && matches!(self.config.auth, HttpAuthConfig::Approle { .. })
+            {
+                *self.token.lock().map_err(lock_error)? = None;
+                retried = true;
+                continue;
+            }
+            if response.status() == StatusCode::FORBIDDEN {
+                return Err(SecretError::Permission(format!("{method} {url} forbidden")));
+            }
+            return Ok(response);
+        }
+    }
+
+    fn url(&self, kind: &str, path: &str) -> String {
+        format!("{}/v1/{}/{kind}/{path}", self.address, self.config.mount)
+    }
+
+    fn cached(&self, uri: &str) -> SecretResult<Option<Vec<u8>>> {
+        let cache = self.cache.lock().map_err(lock_error)?;
+        Ok(cache
+            .get(uri)
+            .filter(|(_, expires_at)| Instant::now() < *expires_at)
+            .map(|(value, _)| value.clone()))
+    }
+
+    fn remember(&self, uri: &str, value: &[u8]) -> SecretResult<()> {
+        if self.config.cache_ttl_seconds == 0 {
+            return Ok(());
+        }
+        let expires_at = Instant::now() + Duration::from_secs(self.config.cache_ttl_seconds);
+        self.cache
+            .lock()
+            .map_err(lock_error)?
+            .insert(uri.to_string(), (value.to_vec(), expires_at));
+        Ok(())
+    }
+
+    fn forget(&self, uri: &str) -> SecretResult<()> {
+        self.cache.lock().map_err(lock_error)?.remove(uri);
+        Ok(())
+    }
+
+    fn require_path(&self, uri: &str) -> SecretResult<String> {
+        self.kv_path(uri).ok_or_else(|| {
+            SecretError::Backend(format!("{uri} is not a canonical secrets:// uri").into())
+        })
+    }
+}
+
+#[async_trait]
+impl SecretsManager for HttpSecretsManager {
+    async fn read(&self, path: &str) -> SecretResult<Vec<u8>> {
+        if let Some(value) = self.cached(path)? {
+            return Ok(value);
+        }
+        let kv_path = self.require_path(path)?;
+        let response = self
+            .send(Method::GET, &self.url("data", &kv_path), None)
+            .await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => return Err(SecretError::NotFound(path.to_string())),
+            status if !status.is_success() => {
+                return Err(SecretError::Backend(
+                    format!("read {kv_path} failed: {status}").into(),
+                ));
+            }
+            _ => {}
+        }
+        let body: Value = response.json().await.map_err(transport_error)?;
+        let data = &body["data"]["data"];
+        let value = if let Some(text) = data["value"].as_str() {
+            text.as_bytes().to_vec()
+        } else if let Some(encoded) = data["value_base64"].as_str() {
+            STANDARD
+                .decode(encoded)
+                .map_err(|err| SecretError::Backend(format!("decode {kv_path}: {err}").into()))?
+        } else {
+            return Err(SecretError::NotFound(path.to_string()));
+        };
+        self.remember(path, &value)?;
+        Ok(value)
+    }
+
+    async fn write(&self, path: &str, value: &[u8]) -> SecretResult<()> {
+        let kv_path = self.require_path(path)?;
+        let data = match std::str::from_utf8(value) {
+            Ok(text) => json!({ "value": text }),
+            Err(_) => json!({ "value_base64": STANDARD.encode(value) }),
+        };
+        let response = self
+            .send(
+                Method::POST,
+                &self.url("data", &kv_path),
+                Some(&json!({ "data": data })),
+            )
+            .await?;
+        if !response.status().is_success() {
+            return Err(SecretError::Backend(
+                format!("write {kv_path} failed: {}", response.status()).into(),
+            ));
+        }
+        self.remember(path, value)
+    }
+
+    async fn delete(&self, path: &str) -> SecretResult<()> {
+        let kv_path = self.require_path(path)?;
+        self.forget(path)?;
+        let response = self
+            .send(Method::DELETE, &self.url("metadata", &kv_path), None)
+            .await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Err(SecretError::NotFound(path.to_string())),
+            status if status.is_success() => Ok(()),
+            status => Err(SecretError::Backend(
+                format!("delete {kv_path} failed: {status}").into(),
+            )),
+        }
+    }
+}
+
+/// `secrets://{env}/{tenant}/{team}/{provider}/{key}` becomes
+/// `{prefix}/{env}/{tenant}/{team}/{provider}/{key}`.
+pub fn kv_path_for_uri(uri: &str, prefix: Option<&str>) -> Option<String> {
+    let rest = uri.strip_prefix("secrets://")?;
+    let segments = rest.split('/').collect::<Vec<_>>();
+    if segments.len() != 5 || segments.iter().any(|segment| segment.is_empty()) {
+        return None;
+    }
+    let path = segments.join("/");
+    match prefix.map(|prefix| prefix.trim_matches('/')) {
+        Some(prefix) if !prefix.is_empty() => Some(format!("{prefix}/{path}")),
+        _ => Some(path),
+    }
+}
+
+fn transport_error(err: reqwest::Error) -> SecretError {
+    SecretError::Backend(format!("http secrets backend: {err}").into())
+}
+
+fn lock_error<T>(_: T) -> SecretError {
+    SecretError::Backend("http secrets backend lock poisoned".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets_gate::canonical_secret_uri;
+
+    #[test]
+    fn canonical_uris_map_to_kv_paths() {
+        let uri = canonical_secret_uri("dev", "demo", None, "messaging-telegram", "BOT_TOKEN");
+        assert_eq!(
+            kv_path_for_uri(&uri, Some("/greentic/")).as_deref(),
+            Some("greentic/dev/demo/_/messaging-telegram/bot_token")
+        );
+        assert_eq!(
+            kv_path_for_uri(&uri, None).as_deref(),
+            Some("dev/demo/_/messaging-telegram/bot_token")
+        );
+        assert!(kv_path_for_uri("TELEGRAM_BOT_TOKEN", None).is_none());
+        assert!(kv_path_for_uri("secrets://dev/demo//messaging/key", None).is_none());
+    }
+
+    #[test]
+    fn auth_config_parses_from_backend_json() {
+        let config: HttpBackendConfig = serde_json::from_str(
+            r#"{"address":"http://127.0.0.1:8200","auth":{"method":"approle","role_id":"abc"}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.mount, "secret");
+        assert_eq!(config.cache_ttl_seconds, 30);
+        assert!(matches!(
+            config.auth,
+            HttpAuthConfig::Approle { role_id: Some(ref id), ref mount, .. } if id == "abc" && mount == "approle"
+        ));
+    }
+}
@@ -1,14 +1,16 @@
 //! SecretsSetup is the single entrypoint for secrets initialization and resolution inside greentic-operator.
 //!
 //! Inputs:
-//! - the bundle root where the operator bundle puts state and the `.greentic/dev/.dev.secrets.env` dev store
+//! - the bundle root, whose secrets manager pack selects the backend (the `.greentic/dev/.dev.secrets.env` dev store by default)
 //! - the environment, tenant, and optional team that define canonical secret URIs
 //! - optional seeds documents embedded in the bundle (`seeds.yaml` or `<bundle>/state/seeds.yaml`)
 //!
 //! Guarantees:
-//! - exactly one dev store backend is opened per operator process and owned for the lifetime of SecretsSetup
-//! - every required secret discovered from packs/providers is canonicalized and registered in that store
+//! - the backend is resolved once through `secrets_gate::resolve_secrets_manager`, the same one runtime reads use
+//! - every required secret discovered from packs/providers is canonicalized and registered in that backend
 //! - missing secrets are seeded either from the documents above or with deterministic placeholders
+//! - read-only backends (env) are never written; their missing secrets are reported instead
+//! - placeholder-seeded URIs are recorded in the `secret_placeholders` index so they can be reported
 //!
 //! Non-goals:
 //! - interactive prompting for secrets or manual overrides
@@ -21,16 +23,15 @@ use std::{
 };
 
 use anyhow::{Result, anyhow};
-use greentic_secrets_lib::core::Error as SecretError;
-use greentic_secrets_lib::{
-    ApplyOptions, DevStore, SecretFormat, SecretsStore, SeedDoc, SeedEntry, SeedValue, apply_seed,
-};
+use greentic_secrets_lib::{SecretError, SecretFormat, SeedDoc, SeedEntry, SeedValue};
 use serde_yaml_bw;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::{
-    dev_store_path, secret_requirements::load_secret_keys_from_pack,
-    secrets_gate::canonical_secret_uri,
+    secret_placeholders::{PlaceholderIndex, placeholder_text},
+    secret_requirements::load_secret_keys_from_pack,
+    secrets_admin::seed_value_bytes,
+    secrets_gate::{self, DynSecretsManager, canonical_secret_uri},
 };
 
 pub fn resolve_env(override_env: Option<&str>) -> String {
@@ -41,32 +42,33 @@ pub fn resolve_env(override_env: Option<&str>) -> String {
 }
 
 pub struct SecretsSetup {
-    store: DevStore,
+    manager: DynSecretsManager,
+    /// Anchor for the placeholder index: the dev store, or where it would be
+    /// for other backends.
     store_path: PathBuf,
     env: String,
     tenant: String,
     team: Option<String>,
     seeds: HashMap<String, SeedEntry>,
+    read_only: bool,
 }
 
 impl SecretsSetup {
     pub fn new(bundle_root: &Path, env: &str, tenant: &str, team: Option<&str>) -> Result<Self> {
-        let store_path = dev_store_path::ensure_path(bundle_root)?;
-        info!(path = %store_path.display(), "secrets: using dev store backend");
-        let store = DevStore::with_path(&store_path).map_err(|err| {
-            anyhow!(
-                "failed to open dev secrets store {}: {err}",
-                store_path.display()
-            )
-        })?;
+        let handle = secrets_gate::resolve_secrets_manager(bundle_root, tenant, team)?;
+        info!(
+            backend = %handle.selection.kind().map(|kind| kind.to_string()).unwrap_or_default(),
+            "secrets: seeding through the configured backend"
+        );
         let seeds = load_seed_entries(bundle_root)?;
         Ok(Self {
-            store,
-            store_path,
+            manager: handle.manager(),
+            store_path: handle.placeholder_store_path.clone(),
             env: env.to_string(),
             tenant: tenant.to_string(),
             team: team.map(|value| value.to_string()),
             seeds,
+            read_only: handle.is_read_only(),
         })
     }
 
@@ -80,6 +82,7 @@ impl SecretsSetup {
             return Ok(());
         }
         let mut missing = Vec::new();
+        let mut placeholders = PlaceholderIndex::load(&self.store_path)?;
         for key in keys {
             let uri = canonical_secret_uri(
                 &self.env,
@@ -89,15 +92,23 @@ impl SecretsSetup {
                 &key,
             );
             debug!(uri = %uri, provider = %provider_id, key = %key, "canonicalized secret requirement");
-            match self.store.get(&uri).await {
+            match self.manager.read(&uri).await {
                 Ok(_) => continue,
-                Err(SecretError::NotFound { .. }) => {
+                Err(SecretError::NotFound(_)) if self.read_only => {
+                    warn!(uri = %uri, provider = %provider_id, "secret missing; read-only backend is not seeded");
+                }
+                Err(SecretError::NotFound(_)) => {
                     let source = if self.seeds.contains_key(&uri) {
                         "seeds.yaml"
                     } else {
                         "placeholder"
                     };
                     debug!(uri = %uri, source, "seeding missing secret");
+                    if self.seeds.contains_key(&uri) {
+                        placeholders.forget(&uri);
+                    } else {
+                        placeholders.record(&uri, provider_id);
+                    }
                     missing.push(
                         self.seeds
                             .get(&uri)
@@ -113,15 +124,24 @@ impl SecretsSetup {
         if missing.is_empty() {
             return Ok(());
         }
-        let report = apply_seed(
-            &self.store,
-            &SeedDoc { entries: missing },
-            ApplyOptions::default(),
-        )
-        .await;
-        if !report.failed.is_empty() {
-            return Err(anyhow!("failed to seed secrets: {:?}", report.failed));
+        let mut failed = Vec::new();
+        for entry in missing {
+            let written = match seed_value_bytes(&entry.value) {
+                Ok(value) => self
+                    .manager
+                    .write(&entry.uri, &value)
+                    .await
+                    .map_err(|err| err.to_string()),
+                Err(err) => Err(err.to_string()),
+            };
+            if let Err(err) = written {
+                failed.push(format!("{}: {err}", entry.uri));
+            }
+        }
+        if !failed.is_empty() {
+            return Err(anyhow!("failed to seed secrets: {failed:?}"));
         }
+        placeholders.save()?;
         Ok(())
     }
 }
@@ -153,7 +173,7 @@ fn placeholder_entry(uri: String) -> SeedEntry {
         uri: uri.clone(),
         format: SecretFormat::Text,
         value: SeedValue::Text {
-            text: format!("placeholder for {uri}"),
+            text: placeholder_text(&uri),
         },
         description: Some("auto-applied placeholder".to_string()),
     }
@@ -0,0 +1,292 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, anyhow};
+use greentic_secrets_lib::SecretError;
+use hmac::{Hmac, Mac};
+use rand::{RngExt, rng};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, Value};
+use sha2::Sha256;
+use tokio::runtime::{Builder, Runtime};
+use zip::result::ZipError;
+
+use crate::provider_config_envelope::hash_canonical;
+use crate::runtime_state::atomic_write;
+use crate::secret_requirements::load_declared_secret_keys;
+use crate::secrets_gate::{DynSecretsManager, canonical_secret_uri};
+use crate::setup_input::load_setup_spec;
+
+/// Inputs of a provider's last successful setup run. `demo start` re-runs
+/// setup only when the fingerprint of the current inputs differs.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetupFingerprint {
+    /// Hash of the non-secret setup answers.
+    pub answers_hash: String,
+    pub resolved_digest: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_base_url: Option<String>,
+    pub setup_flow: String,
+    /// Keyed digest of each provider secret's value, keyed by secret URI;
+    /// `None` when the secret is not set. See [`secret_versions`].
+    #[serde(default)]
+    pub secrets: BTreeMap<String, Option<String>>,
+    /// Set when the secrets could not be digested because no fingerprint key
+    /// exists yet; they then count as changed. Never persisted.
+    #[serde(skip)]
+    pub secrets_unkeyed: bool,
+}
+
+impl SetupFingerprint {
+    pub fn answers_hash(answers: &Value) -> anyhow::Result<String> {
+        hash_canonical(answers)
+    }
+
+    /// Human-readable reasons this fingerprint differs from `previous`;
+    /// empty when setup is up to date.
+    pub fn changes_from(&self, previous: &SetupFingerprint) -> Vec<String> {
+        let mut changes = Vec::new();
+        if self.answers_hash != previous.answers_hash {
+            changes.push("setup answers changed".to_string());
+        }
+        if self.resolved_digest != previous.resolved_digest {
+            changes.push("pack changed".to_string());
+        }
+        if self.public_base_url != previous.public_base_url {
+            changes.push(format!(
+                "public_base_url changed ({} -> {})",
+                previous.public_base_url.as_deref().unwrap_or("-"),
+                self.public_base_url.as_deref().unwrap_or("-")
+            ));
+        }
+        if self.setup_flow != previous.setup_flow {
+            changes.push(format!(
+                "setup flow changed ({} -> {})",
+                previous.setup_flow, self.setup_flow
+            ));
+        }
+        if self.secrets_unkeyed {
+            changes.push("secrets not fingerprinted yet".to_string());
+            return changes;
+        }
+        let uris = self
+            .secrets
+            .keys()
+            .chain(previous.secrets.keys())
+            .collect::<BTreeSet<_>>();
+        for uri in uris {
+            let (old, new) = (previous.secrets.get(uri), self.secrets.get(uri));
+            if old != new {
+                let what = match (old.cloned().flatten(), new.cloned().flatten()) {
+                    (None, Some(_)) => "set",
+                    (Some(_), None) => "removed",
+                    _ => "changed",
+                };
+                changes.push(format!("secret {uri} {what}"));
+            }
+        }
+        changes
+    }
+}
+
+pub fn fingerprint_path(providers_root: &Path, provider: &str) -> PathBuf {
+    providers_root.join(format!("{provider}.fingerprint.json"))
+}
+
+pub fn read_setup_fingerprint(
+    providers_root: &Path,
+    provider: &str,
+) -> anyhow::Result<Option<SetupFingerprint>> {
+    let path = fingerprint_path(providers_root, provider);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let bytes = std::fs::read(&path).with_context(|| format!("read {}", path.display()))?;
+    let fingerprint =
+        serde_json::from_slice(&bytes).with_context(|| format!("parse {}", path.display()))?;
+    Ok(Some(fingerprint))
+}
+
+pub fn write_setup_fingerprint(
+    providers_root: &Path,
+    provider: &str,
+    fingerprint: &SetupFingerprint,
+) -> anyhow::Result<()> {
+    let path = fingerprint_path(providers_root, provider);
+    atomic_write(&path, &serde_json::to_vec_pretty(fingerprint)?)
+}
+
+/// Secret keys that feed a provider's setup: every key the pack declares
+/// plus the `secret: true` setup questions. Packs that are not archives
+/// declare none.
+pub fn provider_secret_keys(pack_path: &Path) -> anyhow::Result<BTreeSet<String>> {
+    let declared = match load_declared_secret_keys(pack_path) {
+        Ok(keys) => keys,
+        Err(err)
+            if matches!(
+                err.downcast_ref::<ZipError>(),
+                Some(ZipError::InvalidArchive(_) | ZipError::UnsupportedArchive(_))
+            ) =>
+        {
+            Vec::new()
+        }
+        Err(err) => return Err(err),
+    };
+    let mut keys = declared.into_iter().collect::<BTreeSet<_>>();
+    if let Some(spec) = load_setup_spec(pack_path)? {
+        keys.extend(
+            spec.questions
+                .into_iter()
+                .filter(|question| question.secret)
+                .map(|question| question.name),
+        );
+    }
+    Ok(keys)
+}
+
+/// Provider namespace of the key that [`secret_versions`] digests with.
+pub const FINGERPRINT_KEY_NAMESPACE: &str = "greentic-operator";
+const FINGERPRINT_KEY_NAME: &str = "setup_fingerprint_key";
+
+/// HMAC-SHA256 of each secret's value, keyed by secret URI. Values in
+/// `supplied` are about to be written by this run and take precedence over
+/// the stored ones.
+///
+/// The HMAC key is a random value kept in the same secrets backend as the
+/// secrets, created on first use, so a fingerprint file on its own cannot be
+/// used to guess a secret's value. Without `create_key` (dry runs, read-only
+/// backends) a missing key is not created and `None` is returned.
+#[allow(clippy::too_many_arguments)]
+pub fn secret_versions(
+    manager: &DynSecretsManager,
+    env: &str,
+    tenant: &str,
+    team: Option<&str>,
+    provider_id: &str,
+    keys: &BTreeSet<String>,
+    supplied: &JsonMap<String, Value>,
+    create_key: bool,
+) -> anyhow::Result<Option<BTreeMap<String, Option<String>>>> {
+    let runtime = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("build secrets runtime")?;
+    let key_uri = canonical_secret_uri(
+        env,
+        tenant,
+        team,
+        FINGERPRINT_KEY_NAMESPACE,
+        FINGERPRINT_KEY_NAME,
+    );
+    let Some(hmac_key) = fingerprint_key(&runtime, manager, &key_uri, create_key)? else {
+        return Ok(None);
+    };
+    let digest = |bytes: &[u8]| {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&hmac_key).expect("HMAC accepts keys of any length");
+        mac.update(bytes);
+        hex_lower(&mac.finalize().into_bytes())
+    };
+    let mut versions = BTreeMap::new();
+    for key in keys {
+        let uri = canonical_secret_uri(env, tenant, team, provider_id, key);
+        let version = match supplied.get(key) {
+            Some(Value::String(text)) => Some(digest(text.as_bytes())),
+            Some(other) => Some(digest(&serde_json::to_vec(other)?)),
+            None => match runtime.block_on(manager.read(&uri)) {
+                Ok(bytes) => Some(digest(&bytes)),
+                Err(SecretError::NotFound(_)) => None,
+                Err(err) => return Err(anyhow!("read secret {uri}: {err}")),
+            },
+        };
+        versions.insert(uri, version);
+    }
+    Ok(Some(versions))
+}
+
+fn fingerprint_key(
+    runtime: &Runtime,
+    manager: &DynSecretsManager,
+    uri: &str,
+    create: bool,
+) -> anyhow::Result<Option<Vec<u8>>> {
+    match runtime.block_on(manager.read(uri)) {
+        Ok(key) => return Ok(Some(key)),
+        Err(SecretError::NotFound(_)) if !create => return Ok(None),
+        Err(SecretError::NotFound(_)) => {}
+        Err(err) => return Err(anyhow!("read fingerprint key {uri}: {err}")),
+    }
+    let mut key = vec![0u8; 32];
+    rng().fill(key.as_mut_slice());
+    runtime
+        .block_on(manager.write(uri, &key))
+        .map_err(|err| anyhow!("write fingerprint key {uri}: {err}"))?;
+    Ok(Some(key))
+}
+
+fn hex_lower(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fingerprint() -> SetupFingerprint {
+        SetupFingerprint {
+            answers_hash: "a1".to_string(),
+            resolved_digest: "d1".to_string(),
+            public_base_url: Some("https://one.example".to_string()),
+            setup_flow: "setup_default".to_string(),
+            secrets: BTreeMap::from([
+                (
+                    "secrets://demo/t/_/p/token".to_string(),
+                    Some("v1".to_string()),
+                ),
+                ("secrets://demo/t/_/p/extra".to_string(), None),
+            ]),
+            secrets_unkeyed: false,
+        }
+    }
+
+    #[test]
+    fn unchanged_fingerprint_has_no_changes() {
+        assert!(fingerprint().changes_from(&fingerprint()).is_empty());
+    }
+
+    #[test]
+    fn lists_each_changed_input() {
+        let previous = fingerprint();
+        let mut current = fingerprint();
+        current.resolved_digest = "d2".to_string();
+        current.public_base_url = Some("https://two.example".to_string());
+        current.secrets.insert(
+            "secrets://demo/t/_/p/token".to_string(),
+            Some("v2".to_string()),
+        );
+        current.secrets.insert(
+            "secrets://demo/t/_/p/extra".to_string(),
+            Some("v1".to_string()),
+        );
+        assert_eq!(
+            current.changes_from(&previous),
+            vec![
+                "pack changed".to_string(),
+                "public_base_url changed (https://one.example -> https://two.example)".to_string(),
+                "secret secrets://demo/t/_/p/extra set".to_string(),
+                "secret secrets://demo/t/_/p/token changed".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn unkeyed_secrets_count_as_changed() {
+        let mut current = fingerprint();
+        current.secrets.clear();
+        current.secrets_unkeyed = true;
+        assert_eq!(
+            current.changes_from(&fingerprint()),
+            vec!["secrets not fingerprinted yet".to_string()]
+        );
+    }
+}
@@ -1,17 +1,27 @@
 use std::{
+    cell::OnceCell,
     collections::BTreeSet,
     fs::{self, File},
-    io::{self, Read, Write},
-    path::Path,
+    io::{self, IsTerminal, Read, Write},
+    path::{Path, PathBuf},
     str::FromStr,
 };
 
 use anyhow::{Context, anyhow};
+use greentic_secrets_lib::SecretError;
 use rpassword::prompt_password;
 use serde::Deserialize;
 use serde_json::{Map as JsonMap, Value};
+use tokio::runtime::Builder;
 use zip::{ZipArchive, result::ZipError};
 
+use crate::{
+    component_qa_ops, operator_log, secret_placeholders,
+    secrets_gate::{self, DynSecretsManager},
+};
+
+const SECRET_REFERENCE_PREFIX: &str = "secrets://";
+
 /// Answers loaded from a user-provided `--setup-input` file.
 #[derive(Clone)]
 pub struct SetupInputAnswers {
@@ -58,7 +68,7 @@ pub struct SetupSpec {
     pub questions: Vec<SetupQuestion>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct SetupQuestion {
     #[serde(default)]
     pub name: String,
@@ -76,6 +86,145 @@ pub struct SetupQuestion {
     pub secret: bool,
     #[serde(default)]
     pub title: Option<String>,
+    #[serde(default)]
+    pub validate: Option<SetupValidation>,
+    /// Ask this question only when an earlier answer matches.
+    #[serde(default)]
+    pub when: Option<SetupCondition>,
+}
+
+/// Constraints checked on every answer, whether typed or read from a
+/// `--setup-input` file. `min`/`max` bound numbers, or the length of strings.
+#[derive(Debug, Default, Deserialize)]
+pub struct SetupValidation {
+    #[serde(default)]
+    pub regex: Option<String>,
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+    #[serde(default)]
+    pub url: bool,
+    #[serde(default, rename = "enum")]
+    pub allowed: Vec<Value>,
+    /// Replaces the generated error message.
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Condition on an earlier answer. Without `equals`, `not_equals` or
+/// `one_of`, the question is asked when the earlier answer is set and is not
+/// `false` or empty.
+#[derive(Debug, Default, Deserialize)]
+pub struct SetupCondition {
+    pub question: String,
+    #[serde(default)]
+    pub equals: Option<Value>,
+    #[serde(default)]
+    pub not_equals: Option<Value>,
+    #[serde(default)]
+    pub one_of: Vec<Value>,
+}
+
+impl SetupCondition {
+    pub fn matches(&self, answers: &JsonMap<String, Value>) -> bool {
+        let answer = answers.get(&self.question).filter(|value| !value.is_null());
+        if let Some(expected) = &self.equals {
+            return answer.is_some_and(|value| values_match(value, expected));
+        }
+        if let Some(unexpected) = &self.not_equals {
+            return answer.is_none_or(|value| !values_match(value, unexpected));
+        }
+        if !self.one_of.is_empty() {
+            return answer.is_some_and(|value| {
+                self.one_of
+                    .iter()
+                    .any(|expected| values_match(value, expected))
+            });
+        }
+        match answer {
+            None | Some(Value::Bool(false)) => false,
+            Some(Value::String(value)) => !value.is_empty(),
+            Some(_) => true,
+        }
+    }
+}
+
+impl SetupQuestion {
+    /// `true` when the question applies given the answers collected so far.
+    pub fn is_active(&self, answers: &JsonMap<String, Value>) -> bool {
+        self.when
+            .as_ref()
+            .is_none_or(|condition| condition.matches(answers))
+    }
+
+    /// Checks `value` against the question's `validate` rules.
+    pub fn validate(&self, value: &Value) -> anyhow::Result<()> {
+        let Some(rules) = &self.validate else {
+            return Ok(());
+        };
+        let fail = |reason: String| anyhow!(rules.message.clone().unwrap_or(reason));
+        if !rules.allowed.is_empty()
+            && !rules
+                .allowed
+                .iter()
+                .any(|allowed| values_match(value, allowed))
+        {
+            let allowed = rules
+                .allowed
+                .iter()
+                .map(display_value)
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(fail(format!("must be one of: {allowed}")));
+        }
+        let (measure, what) = match value {
+            Value::Number(number) => (number.as_f64(), "value"),
+            Value::String(text) => (Some(text.chars().count() as f64), "length"),
+            _ => (None, "value"),
+        };
+        if let (Some(min), Some(measure)) = (rules.min, measure)
+            && measure < min
+        {
+            return Err(fail(format!("{what} must be at least {min}")));
+        }
+        if let (Some(max), Some(measure)) = (rules.max, measure)
+            && measure > max
+        {
+            return Err(fail(format!("{what} must be at most {max}")));
+        }
+        let text = display_value(value);
+        if rules.url {
+            let parsed =
+                url::Url::parse(&text).map_err(|err| fail(format!("invalid URL: {err}")))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(fail("URL must use http or https".to_string()));
+            }
+        }
+        if let Some(pattern) = &rules.regex {
+            let regex = regex::Regex::new(pattern)
+                .with_context(|| format!("invalid regex for setup question {}", self.name))?;
+            if !regex.is_match(&text) {
+                return Err(fail(format!("must match {pattern}")));
+            }
+        }
+        Ok(())
+    }
+
+    fn choice_list(&self) -> Vec<String> {
+        if !self.choices.is_empty() {
+            return self.choices.clone();
+        }
+        self.validate
+            .as_ref()
+            .map(|rules| rules.allowed.iter().map(display_value).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Compares answers loosely so `8080` matches `"8080"` and `true` matches `"true"`.
+fn values_match(value: &Value, expected: &Value) -> bool {
+    value == expected || display_value(value) == display_value(expected)
 }
 
 fn default_kind() -> String {
@@ -113,17 +262,82 @@ fn read_setup_yaml(archive: &mut ZipArchive<File>) -> anyhow::Result<Option<Stri
     Ok(None)
 }
 
+/// Where one provider's answers to `secret: true` questions are stored once
+/// given. A required secret question may be left out of the answers only
+/// when its canonical URI already holds a real value. The secrets backend is
+/// resolved on the first lookup.
+pub struct SecretAnswerStore {
+    bundle_root: PathBuf,
+    env: String,
+    tenant: String,
+    team: Option<String>,
+    provider_id: String,
+    manager: OnceCell<DynSecretsManager>,
+}
+
+impl SecretAnswerStore {
+    pub fn new(
+        bundle_root: &Path,
+        env: &str,
+        tenant: &str,
+        team: Option<&str>,
+        provider_id: &str,
+    ) -> Self {
+        Self {
+            bundle_root: bundle_root.to_path_buf(),
+            env: env.to_string(),
+            tenant: tenant.to_string(),
+            team: team.map(str::to_string),
+            provider_id: provider_id.to_string(),
+            manager: OnceCell::new(),
+        }
+    }
+
+    fn contains(&self, name: &str) -> anyhow::Result<bool> {
+        let manager = match self.manager.get() {
+            Some(manager) => manager,
+            None => {
+                let handle = secrets_gate::resolve_secrets_manager(
+                    &self.bundle_root,
+                    &self.tenant,
+                    self.team.as_deref(),
+                )?;
+                self.manager.get_or_init(|| handle.manager())
+            }
+        };
+        let uri = secrets_gate::canonical_secret_uri(
+            &self.env,
+            &self.tenant,
+            self.team.as_deref(),
+            &self.provider_id,
+            name,
+        );
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("build secrets runtime")?;
+        match runtime.block_on(manager.read(&uri)) {
+            Ok(value) => Ok(!secret_placeholders::is_placeholder_value(&uri, &value)),
+            Err(SecretError::NotFound(_)) => Ok(false),
+            Err(err) => Err(anyhow!("read {uri}: {err}")),
+        }
+    }
+}
+
+/// `stored` is consulted for required secret answers missing from the
+/// answers; without it they are an error.
 pub fn collect_setup_answers(
     pack_path: &Path,
     provider_id: &str,
     setup_input: Option<&SetupInputAnswers>,
     interactive: bool,
+    stored: Option<&SecretAnswerStore>,
 ) -> anyhow::Result<Value> {
     let spec = load_setup_spec(pack_path)?;
     if let Some(input) = setup_input {
         if let Some(value) = input.answers_for_provider(provider_id) {
             let answers = ensure_object(value.clone())?;
-            ensure_required_answers(spec.as_ref(), &answers)?;
+            ensure_required_answers(spec.as_ref(), &answers, stored)?;
             return Ok(answers);
         }
         if has_required_questions(spec.as_ref()) {
@@ -137,7 +351,7 @@ pub fn collect_setup_answers(
         }
         if interactive {
             let answers = prompt_setup_answers(&spec, provider_id)?;
-            ensure_required_answers(Some(&spec), &answers)?;
+            ensure_required_answers(Some(&spec), &answers, stored)?;
             return Ok(answers);
         }
         return Err(anyhow!(
@@ -152,26 +366,179 @@ fn has_required_questions(spec: Option<&SetupSpec>) -> bool {
         .unwrap_or(false)
 }
 
-fn ensure_required_answers(spec: Option<&SetupSpec>, answers: &Value) -> anyhow::Result<()> {
+fn ensure_required_answers(
+    spec: Option<&SetupSpec>,
+    answers: &Value,
+    stored: Option<&SecretAnswerStore>,
+) -> anyhow::Result<()> {
     let map = answers
         .as_object()
         .ok_or_else(|| anyhow!("setup answers must be an object"))?;
     if let Some(spec) = spec {
-        for question in spec.questions.iter().filter(|question| question.required) {
+        for question in &spec.questions {
+            if !question.is_active(map) {
+                continue;
+            }
             match map.get(&question.name) {
-                Some(value) if !value.is_null() => continue,
-                _ => {
+                Some(value) if question.secret && is_secret_reference(value) => {}
+                Some(value) if !value.is_null() => question
+                    .validate(value)
+                    .with_context(|| format!("invalid setup answer for {}", question.name))?,
+                // Secret answers are not persisted with the others; a missing
+                // one must already be in the secrets manager.
+                _ if question.required && question.secret => {
+                    let found = match stored {
+                        Some(stored) => stored.contains(&question.name)?,
+                        None => false,
+                    };
+                    if !found {
+                        return Err(anyhow!(
+                            "missing required setup answer for {}: not in the answers or the secrets manager",
+                            question.name
+                        ));
+                    }
+                }
+                _ if question.required => {
                     return Err(anyhow!(
                         "missing required setup answer for {}",
                         question.name
                     ));
                 }
+                _ => {}
             }
         }
     }
     Ok(())
 }
 
+/// Exported answers files carry `secrets://` URIs in place of secret
+/// values; such answers point at a secret that is already stored.
+fn is_secret_reference(value: &Value) -> bool {
+    value
+        .as_str()
+        .is_some_and(|text| text.starts_with(SECRET_REFERENCE_PREFIX))
+}
+
+/// Removes the answers to `secret: true` questions from `answers` and
+/// returns them, so they can be written to the secrets manager instead of
+/// the persisted answers and config envelope. Secret references are
+/// dropped without being returned.
+pub fn split_secret_answers(
+    pack_path: &Path,
+    answers: &mut Value,
+) -> anyhow::Result<JsonMap<String, Value>> {
+    let mut secrets = JsonMap::new();
+    let (Some(spec), Some(map)) = (load_setup_spec(pack_path)?, answers.as_object_mut()) else {
+        return Ok(secrets);
+    };
+    for question in spec.questions.iter().filter(|question| question.secret) {
+        if let Some(value) = map.remove(&question.name)
+            && !value.is_null()
+            && !is_secret_reference(&value)
+        {
+            secrets.insert(question.name.clone(), value);
+        }
+    }
+    Ok(secrets)
+}
+
+/// Moves the answers to `secret: true` questions out of `answers` and into
+/// the bundle's secrets manager, under the provider's namespace. A read-only
+/// backend keeps nothing; each dropped answer is logged with its URI.
+pub fn store_secret_answers(
+    bundle_root: &Path,
+    env: &str,
+    tenant: &str,
+    team: Option<&str>,
+    pack_path: &Path,
+    provider_id: &str,
+    answers: &mut Value,
+) -> anyhow::Result<Vec<String>> {
+    let secrets = split_secret_answers(pack_path, answers)?;
+    if secrets.is_empty() {
+        return Ok(Vec::new());
+    }
+    let handle = secrets_gate::resolve_secrets_manager(bundle_root, tenant, team)?;
+    if handle.is_read_only() {
+        for name in secrets.keys() {
+            let uri = secrets_gate::canonical_secret_uri(env, tenant, team, provider_id, name);
+            operator_log::warn(
+                module_path!(),
+                format!(
+                    "secret setup answer {name} not stored: read-only secrets backend; provide {uri} through the environment"
+                ),
+            );
+        }
+        return Ok(Vec::new());
+    }
+    secrets_gate::write_secret_answers(&handle.manager(), env, tenant, team, provider_id, &secrets)
+}
+
+/// Checks one provider's entry of an answers file against the pack's
+/// `assets/setup.yaml` and, when the manifest declares one, its config
+/// schema. Packs whose manifest cannot be decoded skip the schema check.
+/// No flows run.
+pub fn check_provider_answers(
+    pack_path: &Path,
+    provider_id: &str,
+    input: &SetupInputAnswers,
+    stored: Option<&SecretAnswerStore>,
+) -> anyhow::Result<()> {
+    let spec = load_setup_spec(pack_path)?;
+    let Some(value) = input.answers_for_provider(provider_id) else {
+        if has_required_questions(spec.as_ref()) {
+            return Err(anyhow!("setup input missing answers for {provider_id}"));
+        }
+        return Ok(());
+    };
+    let answers = ensure_object(value.clone())?;
+    ensure_required_answers(spec.as_ref(), &answers, stored)?;
+    let schema = match component_qa_ops::read_pack_config_schema(pack_path) {
+        Ok(schema) => schema,
+        Err(err) => {
+            operator_log::warn(
+                module_path!(),
+                format!(
+                    "skipping config schema check for {provider_id}: {}: {err}",
+                    pack_path.display()
+                ),
+            );
+            None
+        }
+    };
+    if let Some(schema) = schema
+        && let Some(reason) = component_qa_ops::validate_config_strict(&answers, &schema)
+    {
+        return Err(anyhow!(
+            "answers do not match the pack config schema: {reason}"
+        ));
+    }
+    Ok(())
+}
+
+/// Builds the answers-file entry for a provider from its persisted setup
+/// answers, putting a reference to the stored secret in place of every
+/// active `secret: true` question.
+pub fn export_provider_answers(
+    pack_path: &Path,
+    persisted: Value,
+    secret_uri: impl Fn(&str) -> String,
+) -> anyhow::Result<Value> {
+    let mut answers = ensure_object(persisted)?;
+    let (Some(spec), Some(map)) = (load_setup_spec(pack_path)?, answers.as_object_mut()) else {
+        return Ok(answers);
+    };
+    for question in spec.questions.iter().filter(|question| question.secret) {
+        if question.is_active(map) {
+            map.insert(
+                question.name.clone(),
+                Value::String(secret_uri(&question.name)),
+            );
+        }
+    }
+    Ok(answers)
+}
+
 fn ensure_object(value: Value) -> anyhow::Result<Value> {
     match value {
         Value::Object(_) => Ok(value),
@@ -190,7 +557,7 @@ fn prompt_setup_answers(spec: &SetupSpec, provider: &str) -> anyhow::Result<Valu
     println!("\nConfiguring {provider}: {title}");
     let mut answers = JsonMap::new();
     for question in &spec.questions {
-        if question.name.trim().is_empty() {
+        if question.name.trim().is_empty() || !question.is_active(&answers) {
             continue;
         }
         if let Some(value) = ask_setup_question(question)? {
@@ -206,9 +573,13 @@ fn ask_setup_question(question: &SetupQuestion) -> anyhow::Result<Option<Value>>
     {
         println!("  {help}");
     }
-    if !question.choices.is_empty() {
+    let choices = question.choice_list();
+    if !choices.is_empty() && !question.secret && io::stdin().is_terminal() {
+        return select_choice(question, &choices).map(Some);
+    }
+    if !choices.is_empty() {
         println!("  Choices:");
-        for (idx, choice) in question.choices.iter().enumerate() {
+        for (idx, choice) in choices.iter().enumerate() {
             println!("    {}) {}", idx + 1, choice);
         }
     }
@@ -226,7 +597,9 @@ fn ask_setup_question(question: &SetupQuestion) -> anyhow::Result<Option<Value>>
             }
             return Ok(None);
         }
-        match parse_question_value(question, trimmed) {
+        match parse_question_value(question, trimmed)
+            .and_then(|value| question.validate(&value).map(|()| value))
+        {
             Ok(value) => return Ok(Some(value)),
             Err(err) => {
                 println!("  {err}");
@@ -236,6 +609,33 @@ fn ask_setup_question(question: &SetupQuestion) -> anyhow::Result<Option<Value>>
     }
 }
 
+/// Arrow-key selection for questions with a fixed set of answers.
+fn select_choice(question: &SetupQuestion, choices: &[String]) -> anyhow::Result<Value> {
+    let default_index = question
+        .default
+        .as_ref()
+        .and_then(|default| {
+            choices
+                .iter()
+                .position(|choice| *choice == display_value(default))
+        })
+        .unwrap_or(0);
+    let prompt = question.title.as_deref().unwrap_or(&question.name);
+    loop {
+        let index = dialoguer::Select::new()
+            .with_prompt(prompt)
+            .items(choices)
+            .default(default_index)
+            .interact()
+            .map_err(|err| anyhow!("read choice: {err}"))?;
+        let value = parse_question_value(question, &choices[index])?;
+        match question.validate(&value) {
+            Ok(()) => return Ok(value),
+            Err(err) => println!("  {err}"),
+        }
+    }
+}
+
 fn build_question_prompt(question: &SetupQuestion) -> String {
     let mut prompt = question
         .title
@@ -271,15 +671,16 @@ fn parse_question_value(question: &SetupQuestion, input: &str) -> anyhow::Result
             .map(Value::Number)
             .map_err(|err| anyhow!("invalid number: {err}")),
         "choice" => {
-            if question.choices.is_empty() {
+            let choices = question.choice_list();
+            if choices.is_empty() {
                 return Ok(Value::String(input.to_string()));
             }
             if let Ok(index) = input.parse::<usize>()
-                && let Some(choice) = question.choices.get(index - 1)
+                && let Some(choice) = index.checked_sub(1).and_then(|index| choices.get(index))
             {
                 return Ok(Value::String(choice.clone()));
             }
-            for choice in &question.choices {
+            for choice in &choices {
                 if choice == input {
                     return Ok(Value::String(choice.clone()));
                 }
@@ -360,8 +761,13 @@ questions:
             }
         });
         let answers = SetupInputAnswers::new(raw, provider_keys)?;
-        let collected =
-            collect_setup_answers(&pack_path, "messaging-telegram", Some(&answers), false)?;
+        let collected = collect_setup_answers(
+            &pack_path,
+            "messaging-telegram",
+            Some(&answers),
+            false,
+            None,
+        )?;
         assert_eq!(
             collected.get("public_base_url"),
             Some(&Value::String("https://example.com".to_string()))
@@ -383,9 +789,171 @@ questions:
             "messaging-slack": {}
         });
         let answers = SetupInputAnswers::new(raw, provider_keys)?;
-        let error = collect_setup_answers(&pack_path, "messaging-slack", Some(&answers), false)
-            .unwrap_err();
+        let error =
+            collect_setup_answers(&pack_path, "messaging-slack", Some(&answers), false, None)
+                .unwrap_err();
         assert!(error.to_string().contains("missing required setup answer"));
         Ok(())
     }
+
+    #[test]
+    fn validation_rules_reject_bad_answers() -> Result<()> {
+        let yaml = r#"
+questions:
+  - name: port
+    kind: number
+    validate: { min: 1, max: 65535 }
+  - name: bot_token
+    validate: { regex: "^[0-9]+:[A-Za-z0-9_-]+$", message: "expected <id>:<secret>" }
+  - name: public_base_url
+    validate: { url: true }
+  - name: region
+    validate: { enum: [eu, us] }
+  - name: label
+    validate: { min: 3 }
+"#;
+        let spec: SetupSpec = serde_yaml_bw::from_str(yaml)?;
+        let question = |name: &str| {
+            spec.questions
+                .iter()
+                .find(|question| question.name == name)
+                .unwrap()
+        };
+        assert!(question("port").validate(&json!(8080)).is_ok());
+        assert!(question("port").validate(&json!(0)).is_err());
+        assert!(question("port").validate(&json!(70000)).is_err());
+        assert!(question("bot_token").validate(&json!("123:abc")).is_ok());
+        let err = question("bot_token").validate(&json!("abc")).unwrap_err();
+        assert_eq!(err.to_string(), "expected <id>:<secret>");
+        assert!(
+            question("public_base_url")
+                .validate(&json!("https://example.com"))
+                .is_ok()
+        );
+        assert!(
+            question("public_base_url")
+                .validate(&json!("example.com"))
+                .is_err()
+        );
+        assert!(
+            question("public_base_url")
+                .validate(&json!("ftp://example.com"))
+                .is_err()
+        );
+        assert!(question("region").validate(&json!("eu")).is_ok());
+        assert!(question("region").validate(&json!("ap")).is_err());
+        assert!(question("label").validate(&json!("ab")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn conditional_questions_only_apply_when_matched() -> Result<()> {
+        let yaml = r#"
+questions:
+  - name: mode
+    kind: choice
+    choices: [polling, webhook]
+    required: true
+  - name: public_base_url
+    required: true
+    when: { question: mode, equals: webhook }
+    validate: { url: true }
+  - name: poll_seconds
+    when: { question: mode, not_equals: webhook }
+  - name: tls
+    kind: boolean
+  - name: cert_path
+    when: { question: tls }
+"#;
+        let (_dir, pack_path) = create_test_pack(yaml)?;
+        let input = |raw: Value| SetupInputAnswers::new(raw, BTreeSet::new());
+        let polling = input(json!({ "mode": "polling" }))?;
+        assert!(collect_setup_answers(&pack_path, "p", Some(&polling), false, None).is_ok());
+
+        let webhook = input(json!({ "mode": "webhook" }))?;
+        let err = collect_setup_answers(&pack_path, "p", Some(&webhook), false, None).unwrap_err();
+        assert!(err.to_string().contains("public_base_url"), "{err}");
+
+        let bad_url = input(json!({ "mode": "webhook", "public_base_url": "nope" }))?;
+        let err = collect_setup_answers(&pack_path, "p", Some(&bad_url), false, None).unwrap_err();
+        assert!(err.to_string().contains("invalid setup answer"), "{err}");
+
+        let spec = load_setup_spec(&pack_path)?.unwrap();
+        let active = |answers: Value| {
+            let answers = answers.as_object().cloned().unwrap();
+            spec.questions
+                .iter()
+                .filter(|question| question.is_active(&answers))
+                .map(|question| question.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            active(json!({ "mode": "polling", "tls": false })),
+            vec!["mode", "poll_seconds", "tls"]
+        );
+        assert_eq!(
+            active(json!({ "mode": "webhook", "tls": true })),
+            vec!["mode", "public_base_url", "tls", "cert_path"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn secret_answers_are_split_from_config_answers() -> Result<()> {
+        let yaml = r#"
+questions:
+  - name: bot_token
+    secret: true
+    required: true
+  - name: chat_id
+"#;
+        let (_dir, pack_path) = create_test_pack(yaml)?;
+        let persisted = SetupInputAnswers::new(json!({ "chat_id": "42" }), BTreeSet::new())?;
+        // Without a store to look it up in, a left-out secret answer is missing.
+        let err =
+            collect_setup_answers(&pack_path, "p", Some(&persisted), false, None).unwrap_err();
+        assert!(err.to_string().contains("bot_token"), "{err}");
+
+        let mut answers = json!({ "bot_token": "123:abc", "chat_id": "42" });
+        let secrets = split_secret_answers(&pack_path, &mut answers)?;
+        assert_eq!(answers, json!({ "chat_id": "42" }));
+        assert_eq!(secrets.get("bot_token"), Some(&json!("123:abc")));
+        Ok(())
+    }
+
+    #[test]
+    fn exported_secret_references_round_trip() -> Result<()> {
+        let yaml = r#"
+questions:
+  - name: mode
+  - name: bot_token
+    secret: true
+    required: true
+    validate:
+      regex: "^[0-9]+:[A-Za-z]+$"
+  - name: webhook_secret
+    secret: true
+    when:
+      question: mode
+      equals: webhook
+"#;
+        let (_dir, pack_path) = create_test_pack(yaml)?;
+        let exported = export_provider_answers(&pack_path, json!({ "mode": "polling" }), |key| {
+            format!("secrets://dev/demo/_/p/{key}")
+        })?;
+        assert_eq!(
+            exported,
+            json!({ "mode": "polling", "bot_token": "secrets://dev/demo/_/p/bot_token" })
+        );
+
+        let input = SetupInputAnswers::new(exported.clone(), BTreeSet::new())?;
+        check_provider_answers(&pack_path, "p", &input, None)?;
+        let mut answers = collect_setup_answers(&pack_path, "p", Some(&input), false, None)?;
+        assert!(split_secret_answers(&pack_path, &mut answers)?.is_empty());
+        assert_eq!(answers, json!({ "mode": "polling" }));
+
+        let bad = SetupInputAnswers::new(json!({ "bot_token": "nope" }), BTreeSet::new())?;
+        assert!(check_provider_answers(&pack_path, "p", &bad, None).is_err());
+        Ok(())
+    }
 }
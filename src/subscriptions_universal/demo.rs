@@ -9,6 +9,9 @@ use crate::demo::runner_host::{DemoRunnerHost, OperatorContext};
 use crate::discovery;
 use crate::domains::Domain;
 use crate::secrets_gate;
+use crate::subscriptions_universal::reconcile::{
+    DesiredBinding, ReconcileAction, apply_reconcile, plan_reconcile,
+};
 use crate::subscriptions_universal::scheduler::Scheduler;
 use crate::subscriptions_universal::{AuthUserRefV1, SubscriptionEnsureRequest};
 
@@ -39,39 +42,62 @@ pub fn build_runner(
     Ok((runner_host, context))
 }
 
-pub fn ensure_desired_subscriptions(
+/// Reconciles the bindings of `tenant`/`team` against `desired`: missing
+/// bindings are created, changed ones re-created and managed bindings no
+/// longer in config deleted. With `plan_only` the actions are returned
+/// without touching the provider.
+pub fn reconcile_desired_subscriptions(
     bundle: &Path,
     tenant: &str,
     team: Option<String>,
     desired: &[DemoDesiredSubscription],
     scheduler: &Scheduler<DemoRunnerHost>,
-) -> Result<()> {
+    plan_only: bool,
+) -> Result<Vec<ReconcileAction>> {
+    let desired = resolve_desired_bindings(bundle, tenant, team.as_deref(), desired)?;
+    let team_label = team.as_deref().unwrap_or("default");
+    let current = scheduler
+        .store()
+        .list_states()?
+        .into_iter()
+        .filter(|state| state.tenant == tenant)
+        .filter(|state| state.team.as_deref().unwrap_or("default") == team_label)
+        .collect::<Vec<_>>();
+    let actions = plan_reconcile(&desired, &current);
+    if !plan_only {
+        apply_reconcile(scheduler, &actions)?;
+    }
+    Ok(actions)
+}
+
+fn resolve_desired_bindings(
+    bundle: &Path,
+    tenant: &str,
+    team: Option<&str>,
+    desired: &[DemoDesiredSubscription],
+) -> Result<Vec<DesiredBinding>> {
     if desired.is_empty() {
-        return Ok(());
+        return Ok(Vec::new());
     }
-    let team_ref = team.as_deref();
+    let discovery =
+        discovery::discover_with_options(bundle, discovery::DiscoveryOptions { cbor_only: true })?;
+    let provider_map = discovery_map(&discovery.providers);
+    let mut resolved = Vec::with_capacity(desired.len());
     for entry in desired {
-        let pack = resolve_demo_provider_pack(
-            bundle,
-            tenant,
-            team_ref,
-            &entry.provider,
-            Domain::Messaging,
-        )?;
-        let discovery = discovery::discover_with_options(
-            bundle,
-            discovery::DiscoveryOptions { cbor_only: true },
-        )?;
-        let provider_map = discovery_map(&discovery.providers);
+        let pack =
+            resolve_demo_provider_pack(bundle, tenant, team, &entry.provider, Domain::Messaging)?;
         let provider_id = provider_id_for_pack(&pack.path, &pack.pack_id, Some(&provider_map));
         let binding_id = entry
             .binding_id
             .clone()
             .unwrap_or_else(|| Uuid::new_v4().to_string());
-        let request = to_request(entry, &binding_id);
-        scheduler.ensure_once(&provider_id, &request)?;
+        resolved.push(DesiredBinding {
+            provider: provider_id,
+            explicit_binding_id: entry.binding_id.is_some(),
+            request: to_request(entry, &binding_id),
+        });
     }
-    Ok(())
+    Ok(resolved)
 }
 
 fn to_request(entry: &DemoDesiredSubscription, binding_id: &str) -> SubscriptionEnsureRequest {
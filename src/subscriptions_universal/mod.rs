@@ -1,9 +1,11 @@
 pub mod demo;
+pub mod reconcile;
 pub mod scheduler;
 pub mod service;
 pub mod store;
 
-pub use demo::{build_runner, ensure_desired_subscriptions, state_root};
+pub use demo::{build_runner, reconcile_desired_subscriptions, state_root};
+pub use reconcile::{ReconcileAction, plan_reconcile};
 pub use scheduler::Scheduler;
 pub use service::{SubscriptionEnsureRequest, SubscriptionService};
 pub use store::{AuthUserRefV1, SubscriptionState, SubscriptionStore};
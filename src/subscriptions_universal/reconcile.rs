@@ -0,0 +1,193 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+
+use anyhow::{Result, anyhow};
+
+use crate::operator_log;
+use crate::subscriptions_universal::scheduler::Scheduler;
+use crate::subscriptions_universal::service::{ProviderRunner, SubscriptionEnsureRequest};
+use crate::subscriptions_universal::store::SubscriptionState;
+
+/// A desired subscription with its provider id already resolved.
+#[derive(Clone, Debug)]
+pub struct DesiredBinding {
+    pub provider: String,
+    /// `true` when the binding id came from config rather than being generated.
+    pub explicit_binding_id: bool,
+    pub request: SubscriptionEnsureRequest,
+}
+
+#[derive(Clone, Debug)]
+pub enum ReconcileAction {
+    Create(DesiredBinding),
+    Update {
+        desired: DesiredBinding,
+        current: Box<SubscriptionState>,
+    },
+    /// An unmanaged binding (created by hand or before reconcile existed) that
+    /// already matches config is marked managed instead of created again.
+    Adopt(SubscriptionState),
+    Delete(SubscriptionState),
+}
+
+impl fmt::Display for ReconcileAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReconcileAction::Create(desired) => write!(
+                f,
+                "create provider={} binding={} resource={} change_types={}",
+                desired.provider,
+                desired.request.binding_id,
+                desired.request.resource.as_deref().unwrap_or("-"),
+                desired.request.change_types.join(",")
+            ),
+            ReconcileAction::Update { desired, current } => write!(
+                f,
+                "update provider={} binding={} resource={} -> {} change_types={} -> {}",
+                desired.provider,
+                current.binding_id,
+                current.resource.as_deref().unwrap_or("-"),
+                desired.request.resource.as_deref().unwrap_or("-"),
+                current.change_types.join(","),
+                desired.request.change_types.join(",")
+            ),
+            ReconcileAction::Adopt(state) => write!(
+                f,
+                "adopt provider={} binding={} resource={}",
+                state.provider,
+                state.binding_id,
+                state.resource.as_deref().unwrap_or("-")
+            ),
+            ReconcileAction::Delete(state) => write!(
+                f,
+                "delete provider={} binding={} resource={}",
+                state.provider,
+                state.binding_id,
+                state.resource.as_deref().unwrap_or("-")
+            ),
+        }
+    }
+}
+
+/// Diffs `desired` against the persisted bindings of one tenant/team.
+///
+/// Desired entries with an explicit `binding_id` match the stored binding with
+/// that id; the others match a binding of the same provider, resource and
+/// user, preferring managed ones. A matching unmanaged binding is adopted
+/// rather than duplicated. Only managed bindings are ever deleted, so bindings
+/// created by hand with `demo subscriptions ensure` that config does not list
+/// are left alone.
+pub fn plan_reconcile(
+    desired: &[DesiredBinding],
+    current: &[SubscriptionState],
+) -> Vec<ReconcileAction> {
+    let mut claimed = HashSet::new();
+    let mut actions = Vec::new();
+    for entry in desired {
+        let candidates = || {
+            current
+                .iter()
+                .enumerate()
+                .filter(|(index, state)| !claimed.contains(index) && matches_binding(entry, state))
+        };
+        let matched = candidates()
+            .find(|(_, state)| state.managed)
+            .or_else(|| candidates().next());
+        match matched {
+            Some((index, state)) => {
+                claimed.insert(index);
+                if needs_update(entry, state) {
+                    let mut desired = entry.clone();
+                    desired.request.binding_id = state.binding_id.clone();
+                    actions.push(ReconcileAction::Update {
+                        desired,
+                        current: Box::new(state.clone()),
+                    });
+                } else if !state.managed {
+                    actions.push(ReconcileAction::Adopt(state.clone()));
+                }
+            }
+            None => actions.push(ReconcileAction::Create(entry.clone())),
+        }
+    }
+    for (index, state) in current.iter().enumerate() {
+        if state.managed && !claimed.contains(&index) {
+            actions.push(ReconcileAction::Delete(state.clone()));
+        }
+    }
+    actions
+}
+
+/// Applies every action, continuing past failures so one broken provider
+/// does not block the rest; the failures are reported together.
+pub fn apply_reconcile<R: ProviderRunner>(
+    scheduler: &Scheduler<R>,
+    actions: &[ReconcileAction],
+) -> Result<()> {
+    let mut failures = Vec::new();
+    for action in actions {
+        let result = match action {
+            ReconcileAction::Create(desired) => scheduler
+                .ensure_managed(&desired.provider, &desired.request)
+                .map(|_| ()),
+            ReconcileAction::Update { desired, current } => scheduler
+                .delete_binding(current)
+                .and_then(|_| scheduler.ensure_managed(&desired.provider, &desired.request))
+                .map(|_| ()),
+            ReconcileAction::Adopt(state) => {
+                let mut adopted = state.clone();
+                adopted.managed = true;
+                scheduler.store().write_state(&adopted)
+            }
+            ReconcileAction::Delete(state) => scheduler.delete_binding(state),
+        };
+        match result {
+            Ok(()) => operator_log::info(module_path!(), format!("subscriptions {action}")),
+            Err(err) => {
+                operator_log::error(
+                    module_path!(),
+                    format!("subscriptions {action} failed: {err}"),
+                );
+                failures.push(format!("{action}: {err}"));
+            }
+        }
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "subscription reconcile failed:\n  {}",
+            failures.join("\n  ")
+        ))
+    }
+}
+
+fn matches_binding(desired: &DesiredBinding, state: &SubscriptionState) -> bool {
+    if state.provider != desired.provider {
+        return false;
+    }
+    if desired.explicit_binding_id {
+        return state.binding_id == desired.request.binding_id;
+    }
+    let desired_user = desired
+        .request
+        .user
+        .as_ref()
+        .map(|user| user.user_id.as_str());
+    state.resource == desired.request.resource && user_id(state) == desired_user
+}
+
+fn needs_update(desired: &DesiredBinding, state: &SubscriptionState) -> bool {
+    let wanted: BTreeSet<&str> = desired
+        .request
+        .change_types
+        .iter()
+        .map(String::as_str)
+        .collect();
+    let current: BTreeSet<&str> = state.change_types.iter().map(String::as_str).collect();
+    state.resource != desired.request.resource || wanted != current
+}
+
+fn user_id(state: &SubscriptionState) -> Option<&str> {
+    state.user.as_ref().map(|user| user.user_id.as_str())
+}
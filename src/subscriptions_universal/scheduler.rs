@@ -1,8 +1,13 @@
-use std::time::Duration;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::Utc;
+use rand::{RngExt, rng};
 
+use crate::messaging_universal::retry::RetryPolicy;
 use crate::operator_log;
 use crate::subscriptions_universal::service::{
     ProviderRunner, SubscriptionDeleteRequest, SubscriptionEnsureRequest, SubscriptionRenewRequest,
@@ -15,11 +20,31 @@ const DEFAULT_RENEW_EXTENSION_MS: u64 = 86_400_000;
 pub struct Scheduler<R: ProviderRunner> {
     service: SubscriptionService<R>,
     store: SubscriptionStore,
+    backoff: RetryPolicy,
+}
+
+/// Outcome counts of a single [`Scheduler::renew_due`] pass.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenewSummary {
+    pub renewed: usize,
+    pub failed: usize,
+    /// Due bindings skipped because they are still backing off.
+    pub deferred: usize,
+    pub expired: usize,
 }
 
 impl<R: ProviderRunner> Scheduler<R> {
     pub fn new(service: SubscriptionService<R>, store: SubscriptionStore) -> Self {
-        Self { service, store }
+        Self {
+            service,
+            store,
+            backoff: default_renew_backoff(),
+        }
+    }
+
+    pub fn with_backoff(mut self, backoff: RetryPolicy) -> Self {
+        self.backoff = backoff;
+        self
     }
 
     pub fn ensure_once(&self, provider: &str, request: &SubscriptionEnsureRequest) -> Result<()> {
@@ -27,27 +52,63 @@ impl<R: ProviderRunner> Scheduler<R> {
         self.store.write_state(&state)
     }
 
-    pub fn renew_due(&self, skew: Duration) -> Result<()> {
+    /// Like [`Scheduler::ensure_once`] but marks the binding as managed by
+    /// desired-state reconciliation.
+    pub fn ensure_managed(
+        &self,
+        provider: &str,
+        request: &SubscriptionEnsureRequest,
+    ) -> Result<SubscriptionState> {
+        let mut state = self.service.ensure_once(provider, request)?;
+        state.managed = true;
+        self.store.write_state(&state)?;
+        Ok(state)
+    }
+
+    pub fn store(&self) -> &SubscriptionStore {
+        &self.store
+    }
+
+    pub fn renew_due(&self, skew: Duration) -> Result<RenewSummary> {
         let now = Utc::now().timestamp_millis();
         let skew_ms = skew.as_millis() as i64;
         let states = self.store.list_states()?;
+        let mut summary = RenewSummary::default();
         for state in states {
-            if let Some(expiration) = state.expiration_unix_ms {
-                let renew_at = expiration.saturating_sub(skew_ms);
-                if now >= renew_at
-                    && let Err(err) = self.renew_binding(&state)
-                {
+            let Some(expiration) = state.expiration_unix_ms else {
+                continue;
+            };
+            let renew_at = expiration.saturating_sub(skew_ms);
+            if now < renew_at {
+                continue;
+            }
+            if state.next_attempt_unix_ms.is_some_and(|next| now < next) {
+                summary.deferred += 1;
+                continue;
+            }
+            match self.renew_binding(&state) {
+                Ok(()) => summary.renewed += 1,
+                Err(err) => {
+                    let failed = self.record_failure(&state, &err, now)?;
                     operator_log::error(
                         module_path!(),
                         format!(
-                            "subscription renew failed binding={} provider={} err={}",
-                            state.binding_id, state.provider, err
+                            "subscription renew failed binding={} provider={} failures={} expired={} err={}",
+                            state.binding_id,
+                            state.provider,
+                            failed.consecutive_failures,
+                            failed.expired,
+                            err
                         ),
                     );
+                    summary.failed += 1;
+                    if failed.expired {
+                        summary.expired += 1;
+                    }
                 }
             }
         }
-        Ok(())
+        Ok(summary)
     }
 
     pub fn renew_binding(&self, state: &SubscriptionState) -> Result<()> {
@@ -59,7 +120,15 @@ impl<R: ProviderRunner> Scheduler<R> {
             change_types: state.change_types.clone(),
             expiration_target_unix_ms: Some(next_expiration_target(state)),
         };
-        let renewed = self.service.renew_once(&state.provider, &request)?;
+        let mut renewed = self.service.renew_once(&state.provider, &request)?;
+        if renewed.notification_url.is_none() {
+            renewed.notification_url = state.notification_url.clone();
+        }
+        if renewed.client_state.is_none() {
+            renewed.client_state = state.client_state.clone();
+        }
+        renewed.last_attempt_unix_ms = Some(Utc::now().timestamp_millis());
+        renewed.managed = state.managed;
         self.store.write_state(&renewed)
     }
 
@@ -72,6 +141,37 @@ impl<R: ProviderRunner> Scheduler<R> {
         self.service.delete_once(&state.provider, &request)?;
         self.store.delete_state(state)
     }
+
+    fn record_failure(
+        &self,
+        state: &SubscriptionState,
+        err: &anyhow::Error,
+        now: i64,
+    ) -> Result<SubscriptionState> {
+        let mut failed = state.clone();
+        failed.consecutive_failures = failed.consecutive_failures.saturating_add(1);
+        failed.last_attempt_unix_ms = Some(now);
+        failed.last_error = Some(err.to_string());
+        let jitter_ms = rng().random_range(0..=self.backoff.jitter_ms);
+        let delay = self
+            .backoff
+            .delay_with_jitter(failed.consecutive_failures, jitter_ms);
+        failed.next_attempt_unix_ms = Some(now.saturating_add(delay.as_millis() as i64));
+        failed.expired = state.expiration_unix_ms.is_some_and(|expiry| expiry <= now);
+        self.store.write_state(&failed)?;
+        Ok(failed)
+    }
+}
+
+/// Failing renewals back off exponentially from 30s up to 30 minutes, with up
+/// to 10s of jitter so bindings of the same provider do not retry in lockstep.
+pub fn default_renew_backoff() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: u32::MAX,
+        base_delay_ms: 30_000,
+        max_delay_ms: 1_800_000,
+        jitter_ms: 10_000,
+    }
 }
 
 fn next_expiration_target(state: &SubscriptionState) -> u64 {
@@ -83,3 +183,84 @@ fn next_expiration_target(state: &SubscriptionState) -> u64 {
         .unwrap_or_else(|| now_ms as u64);
     base + DEFAULT_RENEW_EXTENSION_MS
 }
+
+/// Background thread that calls [`Scheduler::renew_due`] every `interval`.
+/// Dropping the handle leaves the loop running; call [`RenewalLoop::stop`]
+/// to end it.
+pub struct RenewalLoop {
+    shutdown: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl RenewalLoop {
+    pub fn start<R>(scheduler: Scheduler<R>, interval: Duration, skew: Duration) -> Result<Self>
+    where
+        R: ProviderRunner + Send + 'static,
+    {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let flag = shutdown.clone();
+        let handle = thread::Builder::new()
+            .name("subscriptions-universal".to_string())
+            .spawn(move || {
+                operator_log::info(
+                    module_path!(),
+                    format!(
+                        "subscriptions-universal renewal loop running interval={}s skew={}s",
+                        interval.as_secs(),
+                        skew.as_secs()
+                    ),
+                );
+                while sleep_until_shutdown(&flag, interval) {
+                    match scheduler.renew_due(skew) {
+                        Ok(summary) if summary != RenewSummary::default() => {
+                            operator_log::info(
+                                module_path!(),
+                                format!(
+                                    "subscriptions-universal renewed={} failed={} deferred={} expired={}",
+                                    summary.renewed,
+                                    summary.failed,
+                                    summary.deferred,
+                                    summary.expired
+                                ),
+                            );
+                        }
+                        Ok(_) => {}
+                        Err(err) => operator_log::error(
+                            module_path!(),
+                            format!("subscriptions-universal renew failed err={err}"),
+                        ),
+                    }
+                }
+                operator_log::info(module_path!(), "subscriptions-universal renewal loop stopped");
+            })
+            .context("spawn subscriptions renewal thread")?;
+        Ok(Self {
+            shutdown,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn stop(mut self) -> Result<()> {
+        self.shutdown.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            handle
+                .join()
+                .map_err(|err| anyhow::anyhow!("renewal loop panicked: {err:?}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Sleeps for `interval` in short slices; returns `false` once shutdown is
+/// requested.
+fn sleep_until_shutdown(flag: &AtomicBool, interval: Duration) -> bool {
+    let deadline = Instant::now() + interval;
+    while Instant::now() < deadline {
+        if flag.load(Ordering::SeqCst) {
+            return false;
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        thread::sleep(remaining.min(Duration::from_millis(200)));
+    }
+    !flag.load(Ordering::SeqCst)
+}
@@ -30,6 +30,22 @@ pub struct SubscriptionState {
     pub expiration_unix_ms: Option<i64>,
     #[serde(default)]
     pub last_error: Option<String>,
+    /// Renewal failures since the last successful ensure/renew.
+    #[serde(default)]
+    pub consecutive_failures: u32,
+    #[serde(default)]
+    pub last_attempt_unix_ms: Option<i64>,
+    /// Earliest time the renewal loop retries a failing binding.
+    #[serde(default)]
+    pub next_attempt_unix_ms: Option<i64>,
+    /// Set when the subscription expired at the provider while renewals
+    /// were failing.
+    #[serde(default)]
+    pub expired: bool,
+    /// Created from `services.subscriptions.universal.desired`; reconciliation
+    /// deletes managed bindings that are no longer listed in config.
+    #[serde(default)]
+    pub managed: bool,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -71,6 +87,11 @@ impl SubscriptionState {
             subscription_id,
             expiration_unix_ms,
             last_error,
+            consecutive_failures: 0,
+            last_attempt_unix_ms: None,
+            next_attempt_unix_ms: None,
+            expired: false,
+            managed: false,
         }
     }
 }
@@ -211,7 +211,7 @@ pub fn is_running(pid: u32) -> bool {
     system.process(pid).is_some()
 }
 
-fn read_pid(pid_path: &Path) -> anyhow::Result<Option<u32>> {
+pub fn read_pid(pid_path: &Path) -> anyhow::Result<Option<u32>> {
     if !pid_path.exists() {
         return Ok(None);
     }
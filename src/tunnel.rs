@@ -0,0 +1,383 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+
+use crate::bin_resolver::{self, ResolveCtx};
+use crate::cloudflared::{self, CloudflaredConfig, NamedTunnelConfig};
+use crate::config::{DemoTunnelConfig, DemoTunnelProvider};
+use crate::operator_log;
+use crate::runtime_state::{RuntimePaths, atomic_write};
+use crate::supervisor::{self, ServiceId, ServiceSpec};
+
+const NGROK_DEFAULT_ARGS: &[&str] = &[
+    "http",
+    "{port}",
+    "--log",
+    "stdout",
+    "--log-format",
+    "logfmt",
+];
+
+/// How the operator obtains the `public_base_url` handed to provider setup
+/// flows. Every variant persists the URL to [`public_url_path`].
+#[derive(Clone)]
+pub enum TunnelConfig {
+    Cloudflared(CloudflaredConfig),
+    Command(CommandTunnelConfig),
+    Static(String),
+}
+
+/// A tunnel tool (ngrok and similar) that is spawned as a service and prints
+/// its public URL to stdout/stderr.
+#[derive(Clone)]
+pub struct CommandTunnelConfig {
+    pub id: String,
+    pub binary: PathBuf,
+    /// Arguments passed to the binary; `{port}` is replaced with the local port.
+    pub args: Vec<String>,
+    pub local_port: u16,
+    /// Only URLs ending with this suffix are accepted when scraping the log.
+    pub url_suffix: Option<String>,
+    pub restart: bool,
+    pub timeout: Duration,
+}
+
+/// Where a running tunnel prints its public URL when the URL can change
+/// without the operator restarting it (quick tunnels, ngrok reconnects).
+#[derive(Clone, Debug)]
+pub struct TunnelWatch {
+    pub log_path: PathBuf,
+    pub url_suffix: Option<String>,
+}
+
+pub struct TunnelHandle {
+    pub service_id: String,
+    pub url: String,
+    pub pid: Option<u32>,
+    pub log_path: Option<PathBuf>,
+}
+
+impl TunnelConfig {
+    pub fn service_id(&self) -> &str {
+        match self {
+            TunnelConfig::Cloudflared(_) => "cloudflared",
+            TunnelConfig::Command(config) => &config.id,
+            TunnelConfig::Static(_) => "static-url",
+        }
+    }
+
+    pub fn set_restart(&mut self, restart: bool) {
+        match self {
+            TunnelConfig::Cloudflared(config) => config.restart = restart,
+            TunnelConfig::Command(config) => config.restart = restart,
+            TunnelConfig::Static(_) => {}
+        }
+    }
+
+    /// The log to scrape for URL changes, for providers whose URL is not fixed.
+    pub fn watch(&self, log_dir: &Path) -> Option<TunnelWatch> {
+        match self {
+            TunnelConfig::Cloudflared(config) if config.named.is_none() => Some(TunnelWatch {
+                log_path: operator_log::service_log_path(log_dir, self.service_id()),
+                url_suffix: Some(cloudflared::URL_SUFFIX.to_string()),
+            }),
+            TunnelConfig::Command(config) => Some(TunnelWatch {
+                log_path: operator_log::service_log_path(log_dir, &config.id),
+                url_suffix: config.url_suffix.clone(),
+            }),
+            TunnelConfig::Cloudflared(_) | TunnelConfig::Static(_) => None,
+        }
+    }
+
+    pub fn start(&self, paths: &RuntimePaths, log_dir: &Path) -> anyhow::Result<TunnelHandle> {
+        match self {
+            TunnelConfig::Cloudflared(config) => {
+                let log_path = operator_log::reserve_service_log(log_dir, "cloudflared")
+                    .with_context(|| "unable to open cloudflared.log")?;
+                let handle = cloudflared::start_tunnel(paths, config, &log_path)?;
+                Ok(TunnelHandle {
+                    service_id: self.service_id().to_string(),
+                    url: handle.url,
+                    pid: Some(handle.pid),
+                    log_path: Some(handle.log_path),
+                })
+            }
+            TunnelConfig::Command(config) => {
+                let log_path = operator_log::reserve_service_log(log_dir, &config.id)
+                    .with_context(|| format!("unable to open {}.log", config.id))?;
+                start_command_tunnel(paths, config, &log_path)
+            }
+            TunnelConfig::Static(url) => {
+                let url = url.trim().trim_end_matches('/').to_string();
+                if !url.starts_with("https://") && !url.starts_with("http://") {
+                    return Err(anyhow::anyhow!(
+                        "static public URL must be absolute http(s): {url}"
+                    ));
+                }
+                atomic_write(&public_url_path(paths), url.as_bytes())?;
+                Ok(TunnelHandle {
+                    service_id: self.service_id().to_string(),
+                    url,
+                    pid: None,
+                    log_path: None,
+                })
+            }
+        }
+    }
+}
+
+/// Builds the tunnel configuration from `services.tunnel` in
+/// `greentic.demo.yaml`. `explicit_binary` is the CLI binary override and
+/// applies to whichever process-backed provider is selected.
+pub fn resolve_tunnel_config(
+    config: &DemoTunnelConfig,
+    config_dir: &Path,
+    local_port: u16,
+    explicit_binary: Option<PathBuf>,
+    restart: bool,
+) -> anyhow::Result<TunnelConfig> {
+    match config.provider {
+        DemoTunnelProvider::Cloudflared => {
+            let binary = bin_resolver::resolve_binary(
+                "cloudflared",
+                &ResolveCtx {
+                    config_dir: config_dir.to_path_buf(),
+                    dev: None,
+                    explicit_path: explicit_binary,
+                },
+            )?;
+            let settings = &config.cloudflared;
+            let named = match (&settings.tunnel, &settings.hostname) {
+                (Some(tunnel), Some(hostname)) => {
+                    let credentials = settings.credentials_file.as_deref().ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "services.tunnel.cloudflared.credentials_file is required for named tunnel {tunnel}"
+                        )
+                    })?;
+                    Some(NamedTunnelConfig {
+                        tunnel: tunnel.clone(),
+                        credentials_file: resolve_path(config_dir, credentials),
+                        hostname: hostname.clone(),
+                    })
+                }
+                (None, None) => None,
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "services.tunnel.cloudflared requires both tunnel and hostname for a named tunnel"
+                    ));
+                }
+            };
+            Ok(TunnelConfig::Cloudflared(CloudflaredConfig {
+                binary,
+                local_port,
+                extra_args: settings.args.clone(),
+                restart,
+                named,
+            }))
+        }
+        DemoTunnelProvider::Ngrok | DemoTunnelProvider::Command => {
+            let settings = &config.command;
+            let is_ngrok = matches!(config.provider, DemoTunnelProvider::Ngrok);
+            let binary_name = match (&settings.binary, is_ngrok) {
+                (Some(binary), _) => binary.clone(),
+                (None, true) => "ngrok".to_string(),
+                (None, false) => {
+                    return Err(anyhow::anyhow!(
+                        "services.tunnel.command.binary is required for the command tunnel provider"
+                    ));
+                }
+            };
+            let binary = bin_resolver::resolve_binary(
+                &binary_name,
+                &ResolveCtx {
+                    config_dir: config_dir.to_path_buf(),
+                    dev: None,
+                    explicit_path: explicit_binary,
+                },
+            )?;
+            let args = if settings.args.is_empty() && is_ngrok {
+                NGROK_DEFAULT_ARGS
+                    .iter()
+                    .map(|arg| arg.to_string())
+                    .collect()
+            } else {
+                settings.args.clone()
+            };
+            Ok(TunnelConfig::Command(CommandTunnelConfig {
+                id: if is_ngrok { "ngrok" } else { "tunnel" }.to_string(),
+                binary,
+                args,
+                local_port,
+                url_suffix: settings.url_suffix.clone(),
+                restart,
+                timeout: Duration::from_secs(settings.timeout_seconds),
+            }))
+        }
+        DemoTunnelProvider::Static => {
+            let url = config.public_url.clone().ok_or_else(|| {
+                anyhow::anyhow!("services.tunnel.public_url is required for the static provider")
+            })?;
+            Ok(TunnelConfig::Static(url))
+        }
+    }
+}
+
+pub fn start_command_tunnel(
+    paths: &RuntimePaths,
+    config: &CommandTunnelConfig,
+    log_path: &Path,
+) -> anyhow::Result<TunnelHandle> {
+    let pid_path = paths.pid_path(&config.id);
+    let url_path = public_url_path(paths);
+    if config.restart {
+        let _ = supervisor::stop_pidfile(&pid_path, 2_000);
+    }
+    let suffix = config.url_suffix.as_deref();
+    let port = config.local_port.to_string();
+    let mut argv = vec![config.binary.to_string_lossy().to_string()];
+    argv.extend(config.args.iter().map(|arg| arg.replace("{port}", &port)));
+
+    if let Some(pid) = reusable_pid(paths, &config.id, &argv)? {
+        let url = match read_public_url(paths)? {
+            Some(url) => url,
+            None => discover_url(log_path, suffix, config.timeout)?,
+        };
+        atomic_write(&url_path, url.as_bytes())?;
+        return Ok(TunnelHandle {
+            service_id: config.id.clone(),
+            url,
+            pid: Some(pid),
+            log_path: Some(log_path.to_path_buf()),
+        });
+    }
+
+    let spec = ServiceSpec {
+        id: ServiceId::new(config.id.clone())?,
+        argv,
+        cwd: None,
+        env: BTreeMap::new(),
+    };
+    let handle = supervisor::spawn_service(paths, spec, Some(log_path.to_path_buf()))?;
+    let url = discover_url(&handle.log_path, suffix, config.timeout)?;
+    atomic_write(&url_path, url.as_bytes())?;
+    Ok(TunnelHandle {
+        service_id: config.id.clone(),
+        url,
+        pid: Some(handle.pid),
+        log_path: Some(handle.log_path),
+    })
+}
+
+/// The pid of a running tunnel command that was started with `argv`. One
+/// started with another command or other arguments is stopped so the caller
+/// spawns a fresh one.
+fn reusable_pid(paths: &RuntimePaths, id: &str, argv: &[String]) -> anyhow::Result<Option<u32>> {
+    let pid_path = paths.pid_path(id);
+    let Some(pid) = supervisor::read_pid(&pid_path)? else {
+        return Ok(None);
+    };
+    if !supervisor::is_running(pid) {
+        return Ok(None);
+    }
+    let resolved = supervisor::read_resolved(paths, &ServiceId::new(id)?)?;
+    if resolved.is_some_and(|resolved| resolved.argv == argv) {
+        return Ok(Some(pid));
+    }
+    supervisor::stop_pidfile(&pid_path, 2_000)?;
+    let _ = std::fs::remove_file(public_url_path(paths));
+    Ok(None)
+}
+
+pub fn public_url_path(paths: &RuntimePaths) -> PathBuf {
+    cloudflared::public_url_path(paths)
+}
+
+/// Reads the last persisted public URL regardless of which provider wrote it.
+/// Static and command providers may persist any absolute http(s) URL.
+pub fn read_public_url(paths: &RuntimePaths) -> anyhow::Result<Option<String>> {
+    let path = public_url_path(paths);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = std::fs::read_to_string(&path)?;
+    let trimmed = contents.trim();
+    if !trimmed.contains(char::is_whitespace)
+        && url::Url::parse(trimmed)
+            .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.has_host())
+    {
+        return Ok(Some(trimmed.to_string()));
+    }
+    Ok(cloudflared::parse_public_url(&contents))
+}
+
+impl TunnelWatch {
+    /// Persists the newest URL the tunnel printed when it differs from the
+    /// stored one, and returns it.
+    pub fn refresh(&self, paths: &RuntimePaths) -> anyhow::Result<Option<String>> {
+        if !self.log_path.exists() {
+            return Ok(None);
+        }
+        let contents = std::fs::read_to_string(&self.log_path)?;
+        let Some(latest) = https_urls(&contents, self.url_suffix.as_deref()).last() else {
+            return Ok(None);
+        };
+        if read_public_url(paths)?.as_deref() != Some(latest) {
+            atomic_write(&public_url_path(paths), latest.as_bytes())?;
+        }
+        Ok(Some(latest.to_string()))
+    }
+}
+
+fn discover_url(
+    log_path: &Path,
+    suffix: Option<&str>,
+    timeout: Duration,
+) -> anyhow::Result<String> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if log_path.exists() {
+            let contents = std::fs::read_to_string(log_path)?;
+            if let Some(url) = find_https_url(&contents, suffix) {
+                return Ok(url);
+            }
+        }
+        if Instant::now() >= deadline {
+            return Err(anyhow::anyhow!(
+                "timed out waiting for tunnel public URL in {}",
+                log_path.display()
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+fn find_https_url(contents: &str, suffix: Option<&str>) -> Option<String> {
+    https_urls(contents, suffix).next().map(str::to_string)
+}
+
+fn https_urls<'a>(contents: &'a str, suffix: Option<&'a str>) -> impl Iterator<Item = &'a str> {
+    contents
+        .match_indices("https://")
+        .map(move |(start, _)| {
+            let tail = &contents[start..];
+            let end = tail
+                .find(|ch: char| ch.is_whitespace() || matches!(ch, '"' | '\'' | '<' | '>'))
+                .unwrap_or(tail.len());
+            tail[..end].trim_end_matches([')', ',', '|', ']', '/'])
+        })
+        .filter(move |candidate| {
+            candidate.len() > "https://".len()
+                && suffix.is_none_or(|suffix| candidate.ends_with(suffix))
+        })
+}
+
+fn resolve_path(base: &Path, value: &str) -> PathBuf {
+    let path = PathBuf::from(value);
+    if path.is_absolute() {
+        path
+    } else {
+        base.join(path)
+    }
+}
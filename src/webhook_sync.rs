@@ -0,0 +1,170 @@
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Context;
+
+use crate::config::DemoConfig;
+use crate::dev_mode::DevSettingsResolved;
+use crate::operator_log;
+use crate::providers::{self, ProviderSetupOptions, WebhookMove};
+use crate::runtime_state::RuntimePaths;
+use crate::tunnel::{self, TunnelWatch};
+
+#[derive(Clone)]
+pub struct WebhookSyncConfig {
+    pub config_dir: PathBuf,
+    pub config: DemoConfig,
+    pub dev_settings: Option<DevSettingsResolved>,
+    pub provider_options: ProviderSetupOptions,
+    pub poll_interval: Duration,
+    /// Tunnel log to scrape for URL changes; `None` for fixed URLs.
+    pub tunnel: Option<TunnelWatch>,
+}
+
+/// Re-registers webhooks at a new public URL and reports which providers
+/// moved.
+pub type ResyncFn = Box<dyn FnMut(&str) -> anyhow::Result<Vec<WebhookMove>> + Send>;
+
+/// Watches the tunnel while `demo start` is running, persists the URL when it
+/// moves, and re-runs setup/verify for providers whose webhook still points at
+/// the previous URL.
+pub struct WebhookSync {
+    shutdown: Option<mpsc::Sender<()>>,
+    handle: Option<thread::JoinHandle<anyhow::Result<()>>>,
+}
+
+impl WebhookSync {
+    /// Syncs the providers of a `--config` demo.
+    pub fn start(config: WebhookSyncConfig) -> anyhow::Result<Self> {
+        let paths = RuntimePaths::new(
+            config.config_dir.join("state"),
+            config.config.tenant.clone(),
+            config.config.team.clone(),
+        );
+        let tunnel = config.tunnel.clone();
+        let poll_interval = config.poll_interval;
+        Self::start_with(
+            paths,
+            tunnel,
+            poll_interval,
+            Box::new(move |url| resync_webhooks(&config, url)),
+        )
+    }
+
+    /// Watches the URL persisted under `paths` and calls `resync` whenever it
+    /// changes.
+    pub fn start_with(
+        paths: RuntimePaths,
+        tunnel: Option<TunnelWatch>,
+        poll_interval: Duration,
+        resync: ResyncFn,
+    ) -> anyhow::Result<Self> {
+        let (tx, rx) = mpsc::channel::<()>();
+        let handle = thread::Builder::new()
+            .name("demo-webhook-sync".to_string())
+            .spawn(move || run_sync_loop(paths, tunnel, poll_interval, resync, rx))
+            .context("spawn webhook sync thread")?;
+        Ok(Self {
+            shutdown: Some(tx),
+            handle: Some(handle),
+        })
+    }
+
+    pub fn stop(mut self) -> anyhow::Result<()> {
+        if let Some(tx) = self.shutdown.take() {
+            let _ = tx.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            handle
+                .join()
+                .map_err(|err| anyhow::anyhow!("webhook sync panicked: {err:?}"))??;
+        }
+        Ok(())
+    }
+}
+
+fn run_sync_loop(
+    paths: RuntimePaths,
+    tunnel: Option<TunnelWatch>,
+    poll_interval: Duration,
+    mut resync: ResyncFn,
+    rx: mpsc::Receiver<()>,
+) -> anyhow::Result<()> {
+    let mut last_url = tunnel::read_public_url(&paths).ok().flatten();
+    operator_log::info(
+        module_path!(),
+        format!(
+            "webhook sync started url={}",
+            last_url.as_deref().unwrap_or("-")
+        ),
+    );
+    while rx.recv_timeout(poll_interval).is_err() {
+        if let Some(watch) = tunnel.as_ref()
+            && let Err(err) = watch.refresh(&paths)
+        {
+            operator_log::warn(module_path!(), format!("tunnel url refresh failed: {err}"));
+        }
+        let current = match tunnel::read_public_url(&paths) {
+            Ok(Some(url)) => url,
+            Ok(None) => continue,
+            Err(err) => {
+                operator_log::warn(module_path!(), format!("read public url failed: {err}"));
+                continue;
+            }
+        };
+        if last_url.as_deref() == Some(current.as_str()) {
+            continue;
+        }
+        println!("Public URL changed: {current}");
+        match resync(&current) {
+            Ok(moves) if moves.is_empty() => {
+                operator_log::info(
+                    module_path!(),
+                    format!("public url changed to {current}; no webhooks to move"),
+                );
+            }
+            Ok(moves) => {
+                operator_log::info(
+                    module_path!(),
+                    format!("re-registered {} webhook(s) at {current}", moves.len()),
+                );
+            }
+            Err(err) => {
+                eprintln!("Warning: webhook re-registration failed: {err}");
+                operator_log::error(
+                    module_path!(),
+                    format!("webhook re-registration failed url={current}: {err}"),
+                );
+            }
+        }
+        last_url = Some(current);
+    }
+    operator_log::info(module_path!(), "webhook sync stopped");
+    Ok(())
+}
+
+/// Re-runs provider setup (and verify, when configured) for exactly the
+/// providers whose recorded webhook URL differs from `public_base_url`.
+pub fn resync_webhooks(
+    config: &WebhookSyncConfig,
+    public_base_url: &str,
+) -> anyhow::Result<Vec<WebhookMove>> {
+    let moves =
+        providers::detect_webhook_moves(&config.config_dir, &config.config, public_base_url);
+    if moves.is_empty() {
+        return Ok(moves);
+    }
+    let mut options = config.provider_options.clone();
+    options.providers = Some(moves.iter().map(|moved| moved.provider.clone()).collect());
+    options.skip_setup = false;
+    providers::run_provider_setup(
+        &config.config_dir,
+        &config.config,
+        config.dev_settings.clone(),
+        Some(public_base_url),
+        options,
+    )?;
+    Ok(moves)
+}
@@ -0,0 +1,151 @@
+#![cfg(unix)]
+
+use std::io::Write;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn write_tool(path: &Path, version_line: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, format!("#!/bin/sh\necho '{version_line}'\n")).unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn write_config(root: &Path, requirement: &str) {
+    std::fs::write(
+        root.join("greentic.yaml"),
+        format!(
+            "binaries:\n  greentic-runner: bin/runner.sh\nbinary_versions:\n  greentic-runner: \"{requirement}\"\n  nats-server: \">=2.10\"\n"
+        ),
+    )
+    .unwrap();
+}
+
+fn write_pack(path: &Path) {
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    zip.start_file("manifest.cbor", zip::write::FileOptions::<()>::default())
+        .unwrap();
+    let manifest = serde_json::json!({
+        "meta": { "pack_id": "good", "entry_flows": ["setup_default"] },
+        "flows": [{ "id": "setup_default" }],
+    });
+    zip.write_all(&serde_cbor::to_vec(&manifest).unwrap())
+        .unwrap();
+    zip.finish().unwrap();
+}
+
+fn operator(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-operator"))
+        .args(args)
+        .env("PATH", "")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn demo_doctor_reports_binary_version_mismatches() {
+    let temp = tempfile::tempdir().unwrap();
+    let bundle = temp.path();
+    std::fs::create_dir_all(bundle.join("packs")).unwrap();
+    write_pack(&bundle.join("packs/good.gtpack"));
+    write_tool(&bundle.join("bin/runner.sh"), "greentic-runner 0.3.1");
+    let bundle_arg = bundle.to_string_lossy();
+    let args = [
+        "demo",
+        "doctor",
+        "--builtin",
+        "--bundle",
+        bundle_arg.as_ref(),
+    ];
+
+    write_config(bundle, ">=0.4, <0.6");
+    let output = operator(&args);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("[FAILED] binary greentic-runner 0.3.1 at "),
+        "{stdout}"
+    );
+    assert!(stdout.contains("does not satisfy >=0.4, <0.6"), "{stdout}");
+    assert!(
+        stdout.contains("[warning] binary nats-server not found"),
+        "{stdout}"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("binary version requirements not met"),
+        "{stderr}"
+    );
+
+    write_config(bundle, ">=0.3");
+    let output = operator(&args);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("[ok] binary greentic-runner 0.3.1 satisfies >=0.3"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn dev_doctor_json_report_includes_binary_checks() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    write_tool(&root.join("bin/runner.sh"), "runner version: unknown");
+    write_config(root, ">=0.4");
+    let output = operator(&[
+        "dev",
+        "doctor",
+        "all",
+        "--builtin",
+        "--format",
+        "json",
+        "--project-root",
+        root.to_string_lossy().as_ref(),
+    ]);
+    assert!(!output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let binaries = report["binaries"].as_array().unwrap();
+    assert_eq!(binaries.len(), 2);
+    assert_eq!(binaries[0]["binary"], "greentic-runner");
+    assert_eq!(binaries[0]["status"], "unknown");
+    assert_eq!(binaries[1]["status"], "not_found");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("1 binary version requirement(s) not met"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn dev_up_refuses_incompatible_binaries_unless_allowed() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    write_tool(&root.join("bin/runner.sh"), "greentic-runner 0.5.0");
+    write_config(root, "~0.4");
+    let root_arg = root.to_string_lossy();
+    let mut args = vec![
+        "dev",
+        "up",
+        "--tenant",
+        "demo",
+        "--no-nats",
+        "--project-root",
+        root_arg.as_ref(),
+    ];
+
+    let output = operator(&args);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("greentic-runner 0.5.0 at"), "{stderr}");
+    assert!(stderr.contains("--allow-incompatible-binaries"), "{stderr}");
+
+    args.push("--allow-incompatible-binaries");
+    let output = operator(&args);
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Warning: greentic-runner 0.5.0"),
+        "{stderr}"
+    );
+}
@@ -0,0 +1,181 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+use greentic_operator::builtin_doctor;
+use greentic_types::validate::Severity;
+
+fn write_pack(path: &Path, manifest: serde_json::Value, files: &[(&str, &str)]) {
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    let options = zip::write::FileOptions::<()>::default();
+    zip.start_file("manifest.cbor", options).unwrap();
+    zip.write_all(&serde_cbor::to_vec(&manifest).unwrap())
+        .unwrap();
+    for (name, contents) in files {
+        zip.start_file(*name, options).unwrap();
+        zip.write_all(contents.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+fn good_manifest() -> serde_json::Value {
+    serde_json::json!({
+        "meta": { "pack_id": "good", "entry_flows": ["setup_default"] },
+        "flows": [{ "id": "setup_default" }],
+    })
+}
+
+fn broken_manifest() -> serde_json::Value {
+    serde_json::json!({
+        "meta": { "pack_id": "broken", "entry_flows": ["setup_default", "diagnostics"] },
+        "flows": [{ "id": "setup_default" }],
+        "secret_requirements": [{ "key": "api_token" }, { "key": "API_TOKEN" }],
+        "extensions": {
+            "greentic.provider-extension.v1": {
+                "inline": {
+                    "providers": [{ "provider_type": "broken", "ops": ["send", "timer_tick"] }],
+                    "timer_handlers": [
+                        { "op_id": "timer_tick", "interval_seconds": 0 },
+                        { "op_id": "timer_poll" },
+                        { "handler_id": "orphan" },
+                    ],
+                }
+            }
+        },
+    })
+}
+
+fn codes(pack: &Path) -> Vec<(Severity, String, String)> {
+    builtin_doctor::validate_pack(pack)
+        .diagnostics
+        .into_iter()
+        .map(|diagnostic| {
+            (
+                diagnostic.severity,
+                diagnostic.code,
+                diagnostic.path.unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn builtin_validation_passes_a_well_formed_pack() {
+    let temp = tempfile::tempdir().unwrap();
+    let pack = temp.path().join("good.gtpack");
+    write_pack(
+        &pack,
+        good_manifest(),
+        &[(
+            "assets/setup.yaml",
+            "questions:\n  - name: token\n    secret: true\n",
+        )],
+    );
+    let report = builtin_doctor::validate_pack(&pack);
+    assert_eq!(report.pack_id.unwrap().as_str(), "good");
+    assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+}
+
+#[test]
+fn builtin_validation_reports_flow_secret_and_timer_problems() {
+    let temp = tempfile::tempdir().unwrap();
+    let pack = temp.path().join("broken.gtpack");
+    write_pack(
+        &pack,
+        broken_manifest(),
+        &[("assets/setup.yaml", "questions: [oops")],
+    );
+    let found = codes(&pack);
+    let expected = [
+        (
+            Severity::Error,
+            "OP_ENTRY_FLOW_UNDECLARED",
+            "meta.entry_flows[1]",
+        ),
+        (
+            Severity::Error,
+            "OP_TIMER_HANDLER_INVALID",
+            "provider_extension.timer_handlers[0].interval_seconds",
+        ),
+        (
+            Severity::Error,
+            "OP_TIMER_HANDLER_INVALID",
+            "provider_extension.timer_handlers[1]",
+        ),
+        (
+            Severity::Error,
+            "OP_TIMER_HANDLER_INVALID",
+            "provider_extension.timer_handlers[2]",
+        ),
+        (
+            Severity::Warn,
+            "OP_SECRET_REQUIREMENT_DUPLICATE",
+            "secret_requirements[1].key",
+        ),
+        (
+            Severity::Error,
+            "OP_SETUP_SPEC_INVALID",
+            "assets/setup.yaml",
+        ),
+    ]
+    .map(|(severity, code, path)| (severity, code.to_string(), path.to_string()));
+    assert_eq!(found, expected);
+}
+
+#[test]
+fn builtin_validation_flags_malformed_manifests() {
+    let temp = tempfile::tempdir().unwrap();
+    let pack = temp.path().join("bad-cbor.gtpack");
+    write_pack(&pack, serde_json::json!({ "meta": "good" }), &[]);
+    let found = codes(&pack);
+    assert_eq!(found.len(), 1, "{found:?}");
+    assert_eq!(found[0].1, "OP_MANIFEST_CBOR_INVALID");
+
+    let not_a_zip = temp.path().join("stub.gtpack");
+    std::fs::write(&not_a_zip, "stub").unwrap();
+    let found = codes(&not_a_zip);
+    assert_eq!(found[0].1, "OP_PACK_ARCHIVE_INVALID");
+}
+
+#[test]
+fn demo_doctor_builtin_runs_without_greentic_pack() {
+    let temp = tempfile::tempdir().unwrap();
+    let bundle = temp.path();
+    let packs = bundle.join("packs");
+    std::fs::create_dir_all(&packs).unwrap();
+    write_pack(&packs.join("good.gtpack"), good_manifest(), &[]);
+
+    let doctor = |bundle: &Path| {
+        Command::new(env!("CARGO_BIN_EXE_greentic-operator"))
+            .args([
+                "demo",
+                "doctor",
+                "--builtin",
+                "--bundle",
+                bundle.to_string_lossy().as_ref(),
+            ])
+            .env("PATH", "")
+            .output()
+            .unwrap()
+    };
+
+    let output = doctor(bundle);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[ok] good (demo)"), "{stdout}");
+
+    write_pack(&packs.join("broken.gtpack"), broken_manifest(), &[]);
+    let output = doctor(bundle);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[FAILED] broken (demo)"), "{stdout}");
+    assert!(
+        stdout.contains("error OP_ENTRY_FLOW_UNDECLARED: entry flow `diagnostics`"),
+        "{stdout}"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("doctor failed for 1 of 2 pack(s)"),
+        "{stderr}"
+    );
+}
@@ -1,8 +1,14 @@
 use std::path::PathBuf;
 use std::process::Command;
 
-use greentic_operator::cloudflared::{CloudflaredConfig, start_quick_tunnel};
+use std::time::Duration;
+
+use greentic_operator::cloudflared::{
+    CloudflaredConfig, NamedTunnelConfig, start_quick_tunnel, start_tunnel,
+};
 use greentic_operator::runtime_state::RuntimePaths;
+use greentic_operator::supervisor;
+use greentic_operator::tunnel::{CommandTunnelConfig, TunnelConfig, read_public_url};
 
 #[test]
 fn cloudflared_discovers_public_url() {
@@ -13,6 +19,7 @@ fn cloudflared_discovers_public_url() {
         local_port: 8080,
         extra_args: Vec::new(),
         restart: true,
+        named: None,
     };
     let paths = RuntimePaths::new(temp.path(), "demo", "default");
     let log_path = temp.path().join("logs").join("cloudflared.log");
@@ -28,6 +35,165 @@ fn cloudflared_discovers_public_url() {
     assert_eq!(persisted.trim(), "https://example.trycloudflare.com");
 }
 
+#[test]
+fn named_tunnel_uses_fixed_hostname() {
+    let temp = tempfile::tempdir().unwrap();
+    let credentials = temp.path().join("tunnel.json");
+    std::fs::write(&credentials, "{}").unwrap();
+    let config = CloudflaredConfig {
+        binary: resolve_fake_cloudflared(),
+        local_port: 8080,
+        extra_args: Vec::new(),
+        restart: true,
+        named: Some(NamedTunnelConfig {
+            tunnel: "greentic-demo".to_string(),
+            credentials_file: credentials,
+            hostname: "hooks.example.com".to_string(),
+        }),
+    };
+    let paths = RuntimePaths::new(temp.path(), "demo", "default");
+    let log_path = temp.path().join("logs").join("cloudflared.log");
+    std::fs::create_dir_all(log_path.parent().unwrap()).unwrap();
+    let handle = start_tunnel(&paths, &config, &log_path).unwrap();
+    assert_eq!(handle.url, "https://hooks.example.com");
+    assert_eq!(
+        read_public_url(&paths).unwrap().as_deref(),
+        Some("https://hooks.example.com")
+    );
+    let _ = supervisor::stop_pidfile(&paths.pid_path("cloudflared"), 500);
+}
+
+#[test]
+fn named_tunnel_requires_credentials_file() {
+    let temp = tempfile::tempdir().unwrap();
+    let config = CloudflaredConfig {
+        binary: resolve_fake_cloudflared(),
+        local_port: 8080,
+        extra_args: Vec::new(),
+        restart: true,
+        named: Some(NamedTunnelConfig {
+            tunnel: "greentic-demo".to_string(),
+            credentials_file: temp.path().join("missing.json"),
+            hostname: "hooks.example.com".to_string(),
+        }),
+    };
+    let paths = RuntimePaths::new(temp.path(), "demo", "default");
+    let err = start_tunnel(&paths, &config, &temp.path().join("cloudflared.log"))
+        .err()
+        .expect("missing credentials should fail");
+    assert!(err.to_string().contains("credentials file not found"));
+}
+
+#[test]
+fn command_tunnel_scrapes_url_from_log() {
+    let temp = tempfile::tempdir().unwrap();
+    let config = TunnelConfig::Command(CommandTunnelConfig {
+        id: "tunnel".to_string(),
+        binary: resolve_fake_cloudflared(),
+        args: vec!["{port}".to_string()],
+        local_port: 8080,
+        url_suffix: None,
+        restart: true,
+        timeout: Duration::from_secs(10),
+    });
+    let paths = RuntimePaths::new(temp.path(), "demo", "default");
+    let handle = config.start(&paths, &temp.path().join("logs")).unwrap();
+    assert_eq!(handle.url, "https://example.trycloudflare.com");
+    assert_eq!(handle.service_id, "tunnel");
+    assert!(handle.pid.is_some());
+    let _ = supervisor::stop_pidfile(&paths.pid_path("tunnel"), 500);
+}
+
+#[test]
+fn static_tunnel_persists_url_without_process() {
+    let temp = tempfile::tempdir().unwrap();
+    let config = TunnelConfig::Static("https://demo.example.com/".to_string());
+    let paths = RuntimePaths::new(temp.path(), "demo", "default");
+    let handle = config.start(&paths, &temp.path().join("logs")).unwrap();
+    assert_eq!(handle.url, "https://demo.example.com");
+    assert!(handle.pid.is_none());
+    assert_eq!(
+        read_public_url(&paths).unwrap().as_deref(),
+        Some("https://demo.example.com")
+    );
+}
+
+#[test]
+fn static_tunnel_reads_back_http_url() {
+    let temp = tempfile::tempdir().unwrap();
+    let config = TunnelConfig::Static("http://10.0.0.5:8080".to_string());
+    let paths = RuntimePaths::new(temp.path(), "demo", "default");
+    config.start(&paths, &temp.path().join("logs")).unwrap();
+    assert_eq!(
+        read_public_url(&paths).unwrap().as_deref(),
+        Some("http://10.0.0.5:8080")
+    );
+}
+
+#[test]
+fn named_tunnel_replaces_running_quick_tunnel() {
+    let temp = tempfile::tempdir().unwrap();
+    let credentials = temp.path().join("tunnel.json");
+    std::fs::write(&credentials, "{}").unwrap();
+    let mut config = CloudflaredConfig {
+        binary: resolve_fake_cloudflared(),
+        local_port: 8080,
+        extra_args: Vec::new(),
+        restart: false,
+        named: None,
+    };
+    let paths = RuntimePaths::new(temp.path(), "demo", "default");
+    let log_path = temp.path().join("logs").join("cloudflared.log");
+    std::fs::create_dir_all(log_path.parent().unwrap()).unwrap();
+    let quick = start_tunnel(&paths, &config, &log_path).unwrap();
+    assert_eq!(quick.url, "https://example.trycloudflare.com");
+
+    // Same arguments: the running tunnel is reused.
+    let again = start_tunnel(&paths, &config, &log_path).unwrap();
+    assert_eq!(again.pid, quick.pid);
+
+    config.named = Some(NamedTunnelConfig {
+        tunnel: "greentic-demo".to_string(),
+        credentials_file: credentials,
+        hostname: "hooks.example.com".to_string(),
+    });
+    let named = start_tunnel(&paths, &config, &log_path).unwrap();
+    assert_ne!(named.pid, quick.pid);
+    assert_eq!(named.url, "https://hooks.example.com");
+    let _ = supervisor::stop_pidfile(&paths.pid_path("cloudflared"), 500);
+}
+
+#[test]
+fn command_tunnel_restarts_when_args_change() {
+    let temp = tempfile::tempdir().unwrap();
+    let mut config = CommandTunnelConfig {
+        id: "tunnel".to_string(),
+        binary: resolve_fake_cloudflared(),
+        args: vec!["{port}".to_string()],
+        local_port: 8080,
+        url_suffix: None,
+        restart: false,
+        timeout: Duration::from_secs(10),
+    };
+    let paths = RuntimePaths::new(temp.path(), "demo", "default");
+    let logs = temp.path().join("logs");
+    let first = TunnelConfig::Command(config.clone())
+        .start(&paths, &logs)
+        .unwrap();
+
+    // Same command: the running tunnel is reused.
+    let again = TunnelConfig::Command(config.clone())
+        .start(&paths, &logs)
+        .unwrap();
+    assert_eq!(again.pid, first.pid);
+
+    config.args.push("--region=eu".to_string());
+    let changed = TunnelConfig::Command(config).start(&paths, &logs).unwrap();
+    assert_ne!(changed.pid, first.pid);
+    assert_eq!(changed.url, "https://example.trycloudflare.com");
+    let _ = supervisor::stop_pidfile(&paths.pid_path("tunnel"), 500);
+}
+
 fn resolve_fake_cloudflared() -> PathBuf {
     example_bin("fake_cloudflared")
 }
@@ -0,0 +1,73 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn operator(args: &[&str], cwd: &Path, env: &[(&str, &str)]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_greentic-operator"));
+    command
+        .args(args)
+        .current_dir(cwd)
+        .env_remove("CI_GATEWAY_PORT")
+        .env_remove("CI_NATS_HOST");
+    for (name, value) in env {
+        command.env(name, value);
+    }
+    command.output().unwrap()
+}
+
+#[test]
+fn dev_config_show_annotates_the_merged_profile() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    std::fs::write(
+        root.join("greentic.demo.yaml"),
+        "tenant: demo\nservices:\n  gateway:\n    listen_addr: 127.0.0.1\n    port: 8080\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("greentic.demo.ci.yaml"),
+        "services:\n  gateway:\n    port: ${CI_GATEWAY_PORT:-18080}\n  nats:\n    url: nats://${CI_NATS_HOST}:4222\n",
+    )
+    .unwrap();
+
+    let output = operator(
+        &["dev", "config", "show", "--profile", "ci"],
+        root,
+        &[("CI_NATS_HOST", "nats.ci")],
+    );
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for line in [
+        "tenant=\"demo\" [base(greentic.demo.yaml:1)]",
+        "services.gateway.listen_addr=\"127.0.0.1\" [base(greentic.demo.yaml:4)]",
+        "services.gateway.port=18080 [overlay(ci: greentic.demo.ci.yaml:3) default(CI_GATEWAY_PORT)]",
+        "services.nats.url=\"nats://nats.ci:4222\" [overlay(ci: greentic.demo.ci.yaml:5) env(CI_NATS_HOST)]",
+    ] {
+        assert!(
+            stdout.lines().any(|found| found == line),
+            "{line}\n{stdout}"
+        );
+    }
+
+    // Without the variable the overlay cannot be resolved.
+    let output = operator(&["dev", "config", "validate", "--profile", "ci"], root, &[]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout
+            .contains("greentic.demo.ci.yaml:5:10: environment variable `CI_NATS_HOST` is not set"),
+        "{stdout}"
+    );
+
+    // The base file alone is still valid.
+    let output = operator(&["dev", "config", "validate"], root, &[]);
+    assert!(output.status.success(), "{output:?}");
+
+    let output = operator(
+        &["dev", "config", "show", "--profile", "staging"],
+        root,
+        &[],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("greentic.demo.staging.yaml"), "{stderr}");
+}
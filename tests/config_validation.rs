@@ -0,0 +1,96 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn operator(args: &[&str], cwd: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-operator"))
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn dev_config_validate_reports_located_errors() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    std::fs::write(
+        root.join("greentic.yaml"),
+        "dev:\n  mode: auto\nbinaries: {}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("greentic.demo.yaml"),
+        "tenant: demo\nservices:\n  gatway:\n    port: 8080\n  gateway:\n    port: \"8080\"\n",
+    )
+    .unwrap();
+
+    let output = operator(&["dev", "config", "validate"], root);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("greentic.yaml: ok"), "{stdout}");
+    assert!(
+        stdout.contains(
+            "greentic.demo.yaml:3:3: unknown key `gatway`; did you mean `gateway`? (at /services/gatway)"
+        ),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("greentic.demo.yaml:6:11: \"8080\" is not of type \"integer\""),
+        "{stdout}"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("2 configuration error(s)"), "{stderr}");
+
+    // Commands that load a config fail with the same locations.
+    std::fs::write(
+        root.join("greentic.yaml"),
+        "binaries: {}\nbinary_version:\n  cloudflared: \">=2024.1\"\n",
+    )
+    .unwrap();
+    let output = operator(
+        &[
+            "dev",
+            "svc-status",
+            "--tenant",
+            "demo",
+            "--no-nats",
+            "--project-root",
+            root.to_string_lossy().as_ref(),
+        ],
+        root,
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "greentic.yaml:2:1: unknown key `binary_version`; did you mean `binary_versions`?"
+        ),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn dev_config_schema_exports_the_demo_schema() {
+    let temp = tempfile::tempdir().unwrap();
+    let out = temp.path().join("schemas/demo.json");
+    let output = operator(
+        &[
+            "dev",
+            "config",
+            "schema",
+            "demo",
+            "--output",
+            out.to_string_lossy().as_ref(),
+        ],
+        temp.path(),
+    );
+    assert!(output.status.success(), "{output:?}");
+    let schema: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(schema["title"], "greentic.demo.yaml");
+    assert!(schema["properties"]["services"]["properties"]["gateway"].is_object());
+
+    let output = operator(&["dev", "config", "schema"], temp.path());
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(schema["title"], "greentic.yaml");
+}
@@ -0,0 +1,123 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use greentic_operator::provider_config_envelope::read_provider_config_envelope;
+
+fn write_pack(path: &Path, pack_id: &str) {
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    zip.start_file("manifest.cbor", zip::write::FileOptions::<()>::default())
+        .unwrap();
+    let manifest = serde_json::json!({
+        "meta": { "pack_id": pack_id, "entry_flows": ["setup_default"] }
+    });
+    std::io::Write::write_all(&mut zip, &serde_cbor::to_vec(&manifest).unwrap()).unwrap();
+    zip.finish().unwrap();
+}
+
+fn operator(root: &Path, args: &[&str]) -> Output {
+    Command::new(fake_bin("greentic-operator"))
+        .args(args)
+        .args(["--bundle", root.to_string_lossy().as_ref()])
+        .output()
+        .unwrap()
+}
+
+fn setup_with(root: &Path, mode: &str) {
+    let input = root.join("answers.json");
+    std::fs::write(&input, format!(r#"{{"msg-a": {{"mode": "{mode}"}}}}"#)).unwrap();
+    let output = operator(
+        root,
+        &[
+            "demo",
+            "setup",
+            "--tenant",
+            "demo",
+            "--domain",
+            "messaging",
+            "--runner-binary",
+            fake_bin("fake_runner").to_string_lossy().as_ref(),
+            "--setup-input",
+            input.to_string_lossy().as_ref(),
+        ],
+    );
+    assert!(output.status.success(), "{output:?}");
+}
+
+#[test]
+fn config_history_diff_and_rollback() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    let messaging = root.join("providers").join("messaging");
+    std::fs::create_dir_all(&messaging).unwrap();
+    write_pack(&messaging.join("a.gtpack"), "msg-a");
+
+    setup_with(root, "polling");
+    setup_with(root, "webhook");
+
+    let history = operator(root, &["demo", "config", "history", "msg-a"]);
+    assert!(history.status.success(), "{history:?}");
+    let stdout = String::from_utf8_lossy(&history.stdout);
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2, "{stdout}");
+    assert!(lines[1].starts_with("*"), "{stdout}");
+    assert!(lines[1].contains("operation_id=setup_default"), "{stdout}");
+
+    let diff = operator(root, &["demo", "config", "diff", "msg-a"]);
+    assert!(diff.status.success(), "{diff:?}");
+    let stdout = String::from_utf8_lossy(&diff.stdout);
+    // The fake runner echoes its invocation, answers included, as the config.
+    assert!(stdout.starts_with("--- rev 1\n+++ rev 2\n"), "{stdout}");
+    assert!(stdout.contains("~ config.argv:"), "{stdout}");
+
+    let rollback = operator(root, &["demo", "config", "rollback", "msg-a", "1"]);
+    assert!(rollback.status.success(), "{rollback:?}");
+    let providers_root = root.join("state/runtime/demo/providers");
+    let current = read_provider_config_envelope(&providers_root, "msg-a")
+        .unwrap()
+        .unwrap();
+    let argv = current.config["argv"].to_string();
+    assert!(
+        argv.contains("polling") && !argv.contains("webhook"),
+        "{argv}"
+    );
+
+    let history = operator(root, &["demo", "config", "history", "msg-a"]);
+    let stdout = String::from_utf8_lossy(&history.stdout);
+    assert_eq!(stdout.lines().count(), 3, "{stdout}");
+
+    let missing = operator(root, &["demo", "config", "rollback", "msg-a", "7"]);
+    assert!(!missing.status.success());
+}
+
+fn fake_bin(name: &str) -> PathBuf {
+    if name == "greentic-operator" {
+        return PathBuf::from(env!("CARGO_BIN_EXE_greentic-operator"));
+    }
+    example_bin(name)
+}
+
+fn binary_name(name: &str) -> String {
+    if cfg!(windows) {
+        format!("{name}.exe")
+    } else {
+        name.to_string()
+    }
+}
+
+fn example_bin(name: &str) -> PathBuf {
+    let mut path = std::env::current_exe().unwrap();
+    path.pop();
+    if path.file_name().and_then(|name| name.to_str()) == Some("deps") {
+        path.pop();
+    }
+    let candidate = path.join("examples").join(binary_name(name));
+    if candidate.exists() {
+        return candidate;
+    }
+    let status = Command::new("cargo")
+        .args(["build", "--example", name])
+        .status()
+        .expect("failed to build example binary");
+    assert!(status.success(), "failed to build example binary");
+    candidate
+}
@@ -39,8 +39,6 @@ fn demo_up_uses_in_process_events_when_events_packs_exist() {
         r#"tenant: demo
 team: default
 services:
-  messaging:
-    enabled: false
   nats:
     enabled: true
     url: "nats://127.0.0.1:4222"
@@ -99,6 +97,7 @@ services:
             setup_input: None,
             runner_binary: None,
             continue_on_error: true,
+            dry_run: false,
         },
         &log_dir,
         true,
@@ -0,0 +1,255 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use greentic_operator::secret_value::SecretValue;
+use greentic_operator::secrets_admin;
+use greentic_operator::secrets_gate::resolve_secrets_manager;
+use greentic_types::{ComponentManifest, PackId, PackKind, PackManifest, PackSignatures};
+use semver::Version;
+
+const SETUP_YAML: &str = "questions:\n  - name: bot_token\n    secret: true\n    required: true\n  - name: chat_id\n    required: true\n";
+
+fn write_pack(path: &Path, manifest: &[u8]) {
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    let options = zip::write::FileOptions::<()>::default();
+    zip.start_file("manifest.cbor", options).unwrap();
+    zip.write_all(manifest).unwrap();
+    zip.start_file("assets/setup.yaml", options).unwrap();
+    zip.write_all(SETUP_YAML.as_bytes()).unwrap();
+    zip.finish().unwrap();
+}
+
+fn messaging_pack_path(root: &Path) -> PathBuf {
+    let messaging = root.join("providers").join("messaging");
+    std::fs::create_dir_all(&messaging).unwrap();
+    messaging.join("messaging-telegram.gtpack")
+}
+
+/// Pack with a setup flow, as the other `demo setup` tests write them.
+fn write_setup_bundle(root: &Path) {
+    let manifest = serde_json::json!({
+        "meta": { "pack_id": "messaging-telegram", "entry_flows": ["setup_default"] }
+    });
+    write_pack(
+        &messaging_pack_path(root),
+        &serde_cbor::to_vec(&manifest).unwrap(),
+    );
+}
+
+/// Pack whose manifest declares a config schema for `chat_id`.
+fn write_schema_bundle(root: &Path) {
+    let component: ComponentManifest = serde_json::from_value(serde_json::json!({
+        "id": "messaging-telegram",
+        "version": "1.0.0",
+        "supports": ["messaging"],
+        "world": "greentic:component/component-v0-v6-v0@0.6.0",
+        "profiles": {},
+        "capabilities": { "wasi": {}, "host": {} },
+        "configurators": null,
+        "operations": [],
+        "config_schema": {
+            "type": "object",
+            "properties": { "chat_id": { "type": "string" } }
+        },
+        "resources": {},
+        "dev_flows": {}
+    }))
+    .unwrap();
+    let manifest = PackManifest {
+        schema_version: "pack-v1".into(),
+        pack_id: PackId::new("messaging-telegram").unwrap(),
+        name: None,
+        version: Version::parse("1.0.0").unwrap(),
+        kind: PackKind::Provider,
+        publisher: "tests".into(),
+        components: vec![component],
+        flows: Vec::new(),
+        dependencies: Vec::new(),
+        capabilities: Vec::new(),
+        secret_requirements: Vec::new(),
+        signatures: PackSignatures::default(),
+        bootstrap: None,
+        extensions: None,
+    };
+    write_pack(
+        &messaging_pack_path(root),
+        &greentic_types::encode_pack_manifest(&manifest).unwrap(),
+    );
+}
+
+fn demo_setup(root: &Path, extra: &[&str]) -> Output {
+    Command::new(fake_bin("greentic-operator"))
+        .args([
+            "demo",
+            "setup",
+            "--bundle",
+            root.to_string_lossy().as_ref(),
+            "--tenant",
+            "demo",
+            "--domain",
+            "messaging",
+            "--runner-binary",
+            fake_bin("fake_runner").to_string_lossy().as_ref(),
+        ])
+        .args(extra)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn exported_answers_reference_secrets_and_replay() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    write_setup_bundle(root);
+    let input = root.join("input.json");
+    std::fs::write(
+        &input,
+        r#"{"messaging-telegram": {"bot_token": "123:abc", "chat_id": "42"}}"#,
+    )
+    .unwrap();
+    let exported = root.join("out").join("answers.yaml");
+
+    let output = demo_setup(
+        root,
+        &[
+            "--setup-input",
+            input.to_string_lossy().as_ref(),
+            "--export-answers",
+            exported.to_string_lossy().as_ref(),
+        ],
+    );
+    assert!(output.status.success(), "{output:?}");
+
+    let contents = std::fs::read_to_string(&exported).unwrap();
+    assert!(!contents.contains("123:abc"), "{contents}");
+    let value: serde_json::Value = serde_yaml_bw::from_str(&contents).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "messaging-telegram": {
+                "bot_token": "secrets://demo/demo/_/messaging-telegram/bot_token",
+                "chat_id": "42"
+            }
+        })
+    );
+
+    let check = demo_setup(
+        root,
+        &["--check-answers", exported.to_string_lossy().as_ref()],
+    );
+    assert!(check.status.success(), "{check:?}");
+
+    let replay = demo_setup(
+        root,
+        &["--setup-input", exported.to_string_lossy().as_ref()],
+    );
+    assert!(replay.status.success(), "{replay:?}");
+    let handle = resolve_secrets_manager(root, "demo", None).unwrap();
+    let stored = secrets_admin::get_secret(
+        &handle,
+        "secrets://demo/demo/_/messaging-telegram/bot_token",
+    )
+    .unwrap();
+    assert_eq!(stored.as_bytes(), b"123:abc");
+}
+
+#[test]
+fn check_answers_reports_spec_and_schema_errors_without_running_flows() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    write_schema_bundle(root);
+    let answers = root.join("answers.yaml");
+    let providers_root = root.join("state/runtime/demo/providers");
+
+    std::fs::write(&answers, "messaging-telegram:\n  bot_token: \"1:a\"\n").unwrap();
+    let missing = demo_setup(
+        root,
+        &["--check-answers", answers.to_string_lossy().as_ref()],
+    );
+    assert!(!missing.status.success());
+    let stdout = String::from_utf8_lossy(&missing.stdout);
+    assert!(
+        stdout.contains("missing required setup answer for chat_id"),
+        "{stdout}"
+    );
+
+    std::fs::write(
+        &answers,
+        "messaging-telegram:\n  bot_token: \"1:a\"\n  chat_id: 42\n",
+    )
+    .unwrap();
+    let mistyped = demo_setup(
+        root,
+        &["--check-answers", answers.to_string_lossy().as_ref()],
+    );
+    assert!(!mistyped.status.success());
+    let stdout = String::from_utf8_lossy(&mistyped.stdout);
+    assert!(stdout.contains("config schema"), "{stdout}");
+
+    assert!(
+        !providers_root
+            .join("messaging-telegram.setup.json")
+            .exists()
+    );
+}
+
+#[test]
+fn left_out_secret_answer_must_already_be_stored() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    write_setup_bundle(root);
+    let input = root.join("input.json");
+    std::fs::write(&input, r#"{"messaging-telegram": {"chat_id": "42"}}"#).unwrap();
+
+    let missing = demo_setup(root, &["--setup-input", input.to_string_lossy().as_ref()]);
+    assert!(!missing.status.success());
+    let stderr = String::from_utf8_lossy(&missing.stderr);
+    assert!(
+        stderr.contains("missing required setup answer for bot_token"),
+        "{stderr}"
+    );
+
+    let handle = resolve_secrets_manager(root, "demo", None).unwrap();
+    secrets_admin::set_secret(
+        &handle,
+        "secrets://demo/demo/_/messaging-telegram/bot_token",
+        &SecretValue::new(b"123:abc"),
+    )
+    .unwrap();
+    let stored = demo_setup(root, &["--setup-input", input.to_string_lossy().as_ref()]);
+    assert!(stored.status.success(), "{stored:?}");
+}
+
+fn fake_bin(name: &str) -> PathBuf {
+    if name == "greentic-operator" {
+        return PathBuf::from(env!("CARGO_BIN_EXE_greentic-operator"));
+    }
+    example_bin(name)
+}
+
+fn binary_name(name: &str) -> String {
+    if cfg!(windows) {
+        format!("{name}.exe")
+    } else {
+        name.to_string()
+    }
+}
+
+fn example_bin(name: &str) -> PathBuf {
+    let mut path = std::env::current_exe().unwrap();
+    path.pop();
+    if path.file_name().and_then(|name| name.to_str()) == Some("deps") {
+        path.pop();
+    }
+    let candidate = path.join("examples").join(binary_name(name));
+    if candidate.exists() {
+        return candidate;
+    }
+    let status = Command::new("cargo")
+        .args(["build", "--example", name])
+        .status()
+        .expect("failed to build example binary");
+    assert!(status.success(), "failed to build example binary");
+    candidate
+}
@@ -0,0 +1,159 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn write_pack(path: &Path, pack_id: &str, setup_yaml: Option<&str>) {
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    let options = zip::write::FileOptions::<()>::default();
+    zip.start_file("manifest.cbor", options).unwrap();
+    let manifest = serde_json::json!({
+        "meta": { "pack_id": pack_id, "entry_flows": ["setup_default"] }
+    });
+    zip.write_all(&serde_cbor::to_vec(&manifest).unwrap())
+        .unwrap();
+    if let Some(setup_yaml) = setup_yaml {
+        zip.start_file("assets/setup.yaml", options).unwrap();
+        zip.write_all(setup_yaml.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+/// Three messaging packs; `msg-b` requires an answer the setup input lacks.
+fn write_bundle(root: &Path) -> PathBuf {
+    let messaging = root.join("providers").join("messaging");
+    std::fs::create_dir_all(&messaging).unwrap();
+    write_pack(&messaging.join("a.gtpack"), "msg-a", None);
+    write_pack(
+        &messaging.join("b.gtpack"),
+        "msg-b",
+        Some("questions:\n  - name: chat_id\n    required: true\n"),
+    );
+    write_pack(&messaging.join("c.gtpack"), "msg-c", None);
+    let input = root.join("answers.json");
+    std::fs::write(&input, r#"{"msg-a": {}, "msg-b": {}, "msg-c": {}}"#).unwrap();
+    input
+}
+
+fn demo_setup(root: &Path, input: &Path, extra: &[&str]) -> Output {
+    Command::new(fake_bin("greentic-operator"))
+        .args([
+            "demo",
+            "setup",
+            "--bundle",
+            root.to_string_lossy().as_ref(),
+            "--tenant",
+            "demo",
+            "--domain",
+            "messaging",
+            "--runner-binary",
+            fake_bin("fake_runner").to_string_lossy().as_ref(),
+            "--setup-input",
+            input.to_string_lossy().as_ref(),
+            "--parallel",
+            "3",
+        ])
+        .args(extra)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn parallel_setup_reports_in_plan_order_with_timing() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    let input = write_bundle(root);
+
+    let output = demo_setup(root, &input, &["--best-effort", "--format", "json"]);
+    assert!(output.status.success(), "{output:?}");
+    let reports: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let reports = reports.as_array().unwrap();
+    let summary = reports
+        .iter()
+        .map(|report| {
+            (
+                report["pack_id"].as_str().unwrap(),
+                report["status"].as_str().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![("msg-a", "ok"), ("msg-b", "failed"), ("msg-c", "ok")]
+    );
+    assert!(reports.iter().all(|report| report["duration_ms"].is_u64()));
+    assert!(reports[1]["error"].as_str().unwrap().contains("chat_id"));
+    assert!(
+        reports[0]["output"]
+            .as_str()
+            .unwrap()
+            .starts_with("a.gtpack setup_default")
+    );
+
+    let providers_root = root.join("state/runtime/demo/providers");
+    assert!(providers_root.join("msg-a.setup.json").exists());
+    assert!(!providers_root.join("msg-b.setup.json").exists());
+    assert!(providers_root.join("msg-c.setup.json").exists());
+}
+
+#[test]
+fn parallel_setup_text_output_is_grouped_and_fails_without_best_effort() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    let input = write_bundle(root);
+
+    let output = demo_setup(root, &input, &[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("chat_id"), "{stderr}");
+
+    let output = demo_setup(root, &input, &["--best-effort"]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let packs = stdout
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|pack| pack.ends_with(".gtpack"))
+        .collect::<Vec<_>>();
+    assert_eq!(packs, vec!["a.gtpack", "b.gtpack", "c.gtpack"], "{stdout}");
+    assert!(
+        stdout.contains("b.gtpack setup_default -> failed ("),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Best-effort: 1 flow(s) failed."),
+        "{stdout}"
+    );
+}
+
+fn fake_bin(name: &str) -> PathBuf {
+    if name == "greentic-operator" {
+        return PathBuf::from(env!("CARGO_BIN_EXE_greentic-operator"));
+    }
+    example_bin(name)
+}
+
+fn binary_name(name: &str) -> String {
+    if cfg!(windows) {
+        format!("{name}.exe")
+    } else {
+        name.to_string()
+    }
+}
+
+fn example_bin(name: &str) -> PathBuf {
+    let mut path = std::env::current_exe().unwrap();
+    path.pop();
+    if path.file_name().and_then(|name| name.to_str()) == Some("deps") {
+        path.pop();
+    }
+    let candidate = path.join("examples").join(binary_name(name));
+    if candidate.exists() {
+        return candidate;
+    }
+    let status = Command::new("cargo")
+        .args(["build", "--example", name])
+        .status()
+        .expect("failed to build example binary");
+    assert!(status.success(), "failed to build example binary");
+    candidate
+}
@@ -0,0 +1,253 @@
+#![cfg(unix)]
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use greentic_operator::secret_value::SecretValue;
+use greentic_operator::secrets_admin;
+use greentic_operator::secrets_gate::{canonical_secret_uri, resolve_secrets_manager};
+
+fn write_pack_with(path: &Path, manifest: serde_json::Value, assets: &[(&str, &str)]) {
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    zip.start_file("manifest.cbor", zip::write::FileOptions::<()>::default())
+        .unwrap();
+    zip.write_all(&serde_cbor::to_vec(&manifest).unwrap())
+        .unwrap();
+    for (name, contents) in assets {
+        zip.start_file(*name, zip::write::FileOptions::<()>::default())
+            .unwrap();
+        zip.write_all(contents.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+fn write_pack(path: &Path, pack_id: &str) {
+    write_pack_with(
+        path,
+        serde_json::json!({
+            "meta": { "pack_id": pack_id, "entry_flows": ["setup_default"] }
+        }),
+        &[],
+    );
+}
+
+fn free_port() -> u16 {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// A bundle with resolved manifests for `acme` and `globex`, serving the
+/// `dummy` messaging provider on the returned port.
+fn write_bundle(bundle: &Path) -> u16 {
+    std::fs::create_dir_all(bundle.join("providers/messaging")).unwrap();
+    write_pack(&bundle.join("providers/messaging/dummy.gtpack"), "dummy");
+    std::fs::create_dir_all(bundle.join("state/resolved")).unwrap();
+    for tenant in ["acme", "globex"] {
+        std::fs::write(
+            bundle.join(format!("state/resolved/{tenant}.yaml")),
+            format!("tenant: {tenant}\n"),
+        )
+        .unwrap();
+    }
+    let port = free_port();
+    std::fs::write(
+        bundle.join("greentic.demo.yaml"),
+        format!(
+            "tenant: demo\nteam: default\nservices:\n  gateway:\n    listen_addr: 127.0.0.1\n    port: {port}\n"
+        ),
+    )
+    .unwrap();
+    port
+}
+
+struct Operator {
+    child: Child,
+    lines: mpsc::Receiver<String>,
+}
+
+impl Operator {
+    fn start(bundle: &Path, extra: &[&str]) -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_greentic-operator"))
+            .args([
+                "demo",
+                "start",
+                "--bundle",
+                bundle.to_string_lossy().as_ref(),
+                "--cloudflared",
+                "off",
+                "--nats",
+                "off",
+            ])
+            .args(extra)
+            .env("GREENTIC_ENV", "dev")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        let (tx, lines) = mpsc::channel();
+        let stdout = child.stdout.take().unwrap();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                let _ = tx.send(line);
+            }
+        });
+        Self { child, lines }
+    }
+
+    fn wait_for(&self, needle: &str) -> String {
+        loop {
+            let line = self
+                .lines
+                .recv_timeout(Duration::from_secs(60))
+                .unwrap_or_else(|_| panic!("timed out waiting for {needle:?}"));
+            if line.contains(needle) {
+                return line;
+            }
+        }
+    }
+
+    fn stop(mut self) {
+        unsafe {
+            libc::kill(self.child.id() as i32, libc::SIGINT);
+        }
+        let status = self.child.wait().unwrap();
+        assert!(status.success(), "{status:?}");
+    }
+}
+
+fn example_bin(name: &str) -> PathBuf {
+    let mut path = std::env::current_exe().unwrap();
+    path.pop();
+    if path.file_name().and_then(|name| name.to_str()) == Some("deps") {
+        path.pop();
+    }
+    let candidate = path.join("examples").join(name);
+    if !candidate.exists() {
+        let status = Command::new("cargo")
+            .args(["build", "--example", name])
+            .status()
+            .expect("failed to build example binary");
+        assert!(status.success(), "failed to build example binary");
+    }
+    candidate
+}
+
+fn ingress(port: u16, provider: &str, tenant: &str) -> (u16, String) {
+    let response = reqwest::blocking::Client::new()
+        .post(format!(
+            "http://127.0.0.1:{port}/v1/messaging/ingress/{provider}/{tenant}/default"
+        ))
+        .body("{}")
+        .send()
+        .unwrap();
+    (response.status().as_u16(), response.text().unwrap())
+}
+
+#[test]
+fn ingress_refuses_tenants_that_are_not_running() {
+    let temp = tempfile::tempdir().unwrap();
+    let bundle = temp.path();
+    let port = write_bundle(bundle);
+
+    let operator = Operator::start(bundle, &["--tenant", "acme,globex"]);
+    operator.wait_for("running (bundle=");
+
+    let (status, body) = ingress(port, "dummy", "initech");
+    assert_eq!(status, 404, "{body}");
+    assert!(body.contains("tenant/team not running"), "{body}");
+    // Running tenants get past the tenant check to the provider lookup.
+    for tenant in ["acme", "globex"] {
+        let (status, body) = ingress(port, "missing", tenant);
+        assert_eq!(status, 404, "{tenant}: {body}");
+        assert!(body.contains("no ingest_http handler"), "{tenant}: {body}");
+    }
+
+    operator.stop();
+}
+
+#[test]
+fn each_target_runs_with_its_own_secrets_and_services() {
+    let temp = tempfile::tempdir().unwrap();
+    let bundle = temp.path();
+    write_bundle(bundle);
+    // `dummy` needs a bot token before it can be set up.
+    write_pack_with(
+        &bundle.join("providers/messaging/dummy.gtpack"),
+        serde_json::json!({
+            "meta": { "pack_id": "dummy", "entry_flows": ["setup_default"] }
+        }),
+        &[(
+            "assets/secret-requirements.json",
+            r#"[{"key":"bot_token","required":true}]"#,
+        )],
+    );
+    std::fs::create_dir_all(bundle.join("providers/events")).unwrap();
+    write_pack_with(
+        &bundle.join("providers/events/ticker.gtpack"),
+        serde_json::json!({
+            "meta": { "pack_id": "ticker", "entry_flows": ["setup_default"] },
+            "extensions": {
+                "greentic.provider-extension.v1": {
+                    "inline": {
+                        "providers": [{ "provider_type": "ticker", "ops": ["timer_tick"] }]
+                    }
+                }
+            }
+        }),
+        &[],
+    );
+    let demo_config = std::fs::read_to_string(bundle.join("greentic.demo.yaml")).unwrap();
+    std::fs::write(
+        bundle.join("greentic.demo.yaml"),
+        format!("{demo_config}  subscriptions:\n    mode: universal_ops\n"),
+    )
+    .unwrap();
+    let setup_input = bundle.join("setup-input.json");
+    std::fs::write(&setup_input, r#"{"messaging": {"dummy": {}}}"#).unwrap();
+
+    // Only acme has the token; globex must not borrow it.
+    let acme = resolve_secrets_manager(bundle, "acme", None).unwrap();
+    let uri = canonical_secret_uri("demo", "acme", None, "dummy", "bot_token");
+    secrets_admin::set_secret(&acme, &uri, &SecretValue::new(b"acme-token")).unwrap();
+
+    let runner = example_bin("fake_runner");
+    let operator = Operator::start(
+        bundle,
+        &[
+            "--setup-input",
+            setup_input.to_string_lossy().as_ref(),
+            "--runner-binary",
+            runner.to_string_lossy().as_ref(),
+        ],
+    );
+    operator.wait_for("running (bundle=");
+
+    let providers = |tenant: &str| bundle.join(format!("state/runtime/{tenant}/providers"));
+    assert!(providers("acme").join("dummy.setup.json").exists());
+    assert!(!providers("globex").join("dummy.setup.json").exists());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_greentic-operator"))
+        .args(["demo", "status", "--bundle"])
+        .arg(bundle)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let status = String::from_utf8_lossy(&output.stdout);
+    for tenant in ["acme", "globex"] {
+        let block = status
+            .split_once(&format!("{tenant}.default:\n"))
+            .map(|(_, rest)| rest)
+            .unwrap_or_else(|| panic!("no {tenant} block in {status}"));
+        let block = block.split(".default:").next().unwrap();
+        assert!(block.contains("timer-scheduler: running"), "{status}");
+        assert!(block.contains("subscriptions: running"), "{status}");
+    }
+
+    operator.stop();
+}
@@ -0,0 +1,132 @@
+#![cfg(unix)]
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+fn write_pack(path: &Path, pack_id: &str) {
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    zip.start_file("manifest.cbor", zip::write::FileOptions::<()>::default())
+        .unwrap();
+    let manifest = serde_json::json!({
+        "meta": { "pack_id": pack_id, "entry_flows": ["setup_default"] }
+    });
+    zip.write_all(&serde_cbor::to_vec(&manifest).unwrap())
+        .unwrap();
+    zip.finish().unwrap();
+}
+
+fn free_port() -> u16 {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+#[test]
+fn watch_reloads_packs_and_policy_without_restarting() {
+    let temp = tempfile::tempdir().unwrap();
+    let bundle = temp.path();
+    std::fs::create_dir_all(bundle.join("packs")).unwrap();
+    std::fs::create_dir_all(bundle.join("providers/messaging")).unwrap();
+    std::fs::create_dir_all(bundle.join("tenants/demo")).unwrap();
+    std::fs::write(bundle.join("tenants/demo/tenant.gmap"), "_ = forbidden\n").unwrap();
+    std::fs::write(
+        bundle.join("greentic.demo.yaml"),
+        format!(
+            "tenant: demo\nteam: default\nservices:\n  gateway:\n    listen_addr: 127.0.0.1\n    port: {}\n",
+            free_port()
+        ),
+    )
+    .unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_greentic-operator"))
+        .args([
+            "demo",
+            "start",
+            "--bundle",
+            bundle.to_string_lossy().as_ref(),
+            "--cloudflared",
+            "off",
+            "--nats",
+            "off",
+            "--watch",
+        ])
+        .env("GREENTIC_ENV", "dev")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let (tx, rx) = mpsc::channel();
+    let stdout = child.stdout.take().unwrap();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            let _ = tx.send(line);
+        }
+    });
+    let wait_for = |needle: &str| -> String {
+        loop {
+            let line = rx
+                .recv_timeout(Duration::from_secs(30))
+                .unwrap_or_else(|_| panic!("timed out waiting for {needle:?}"));
+            if line.contains(needle) {
+                return line;
+            }
+        }
+    };
+
+    wait_for("watching packs/");
+    write_pack(&bundle.join("providers/messaging/dummy.gtpack"), "dummy");
+    let line = wait_for("Reloaded");
+    assert!(line.contains("discovery (1 provider(s))"), "{line}");
+    assert!(line.contains("ingress runner host"), "{line}");
+
+    // A policy change re-resolves the tenant and copies it into the bundle.
+    let resolved = bundle.join("resolved/demo.yaml");
+    assert!(!resolved.exists());
+    std::fs::write(bundle.join("tenants/demo/tenant.gmap"), "_ = public\n").unwrap();
+    let line = wait_for("Reloaded");
+    assert_eq!(line, "Reloaded policy for demo");
+    let resolved = std::fs::read_to_string(resolved).unwrap();
+    assert!(resolved.contains("dummy.gtpack"), "{resolved}");
+
+    unsafe {
+        libc::kill(child.id() as i32, libc::SIGINT);
+    }
+    let status = child.wait().unwrap();
+    assert!(status.success(), "{status:?}");
+}
+
+#[test]
+fn invalid_demo_config_refuses_to_start() {
+    let temp = tempfile::tempdir().unwrap();
+    let bundle = temp.path();
+    std::fs::create_dir_all(bundle.join("providers/messaging")).unwrap();
+    std::fs::write(
+        bundle.join("greentic.demo.yaml"),
+        "tenant: demo\nteam: default\nservices:\n  gateway:\n    port: not-a-port\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_greentic-operator"))
+        .args([
+            "demo",
+            "start",
+            "--bundle",
+            bundle.to_string_lossy().as_ref(),
+            "--cloudflared",
+            "off",
+            "--nats",
+            "off",
+        ])
+        .env("GREENTIC_ENV", "dev")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(!output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("greentic.demo.yaml"), "{stderr}");
+}
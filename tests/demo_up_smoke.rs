@@ -63,6 +63,7 @@ services:
             setup_input: None,
             runner_binary: None,
             continue_on_error: true,
+            dry_run: false,
         },
         &log_dir,
         true,
@@ -0,0 +1,102 @@
+use std::path::Path;
+use std::process::Command;
+
+fn write_fixture_repo(repo: &Path, binary: &str, message: &str) {
+    std::fs::create_dir_all(repo.join("src")).unwrap();
+    std::fs::write(
+        repo.join("Cargo.toml"),
+        format!(
+            "[package]\nname = \"{binary}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n"
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        repo.join("src/main.rs"),
+        format!("fn main() {{ println!(\"{message}\"); }}\n"),
+    )
+    .unwrap();
+}
+
+fn dev_up(root: &Path) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-operator"))
+        .args([
+            "dev",
+            "up",
+            "--tenant",
+            "demo",
+            "--no-nats",
+            "--build",
+            "--project-root",
+            root.to_string_lossy().as_ref(),
+        ])
+        .env_remove("CARGO_TARGET_DIR")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn dev_up_build_builds_stale_repos_and_skips_fresh_ones() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path().join("project");
+    let dev_root = temp.path().join("workspace");
+    std::fs::create_dir_all(&root).unwrap();
+    write_fixture_repo(&dev_root.join("runner-repo"), "greentic-runner", "v1");
+    write_fixture_repo(&dev_root.join("worker-repo"), "events-worker", "worker");
+    std::fs::write(
+        root.join("greentic.yaml"),
+        format!(
+            "dev:\n  mode: on\n  root: {}\n  profile: debug\n  repo_map:\n    greentic-runner: runner-repo\n    events-worker: worker-repo\n    unrelated-tool: missing-repo\nservices:\n  events:\n    components:\n      - id: worker\n        binary: events-worker\n",
+            dev_root.display()
+        ),
+    )
+    .unwrap();
+
+    let output = dev_up(&root);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("dev build: runner-repo built"), "{stdout}");
+    // Service components from greentic.yaml are built too.
+    assert!(
+        stdout.contains("dev build: worker-repo built events-worker"),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("missing-repo"), "{stdout}");
+    let log = root.join("state/logs/dev-build/runner-repo.log");
+    assert!(
+        std::fs::read_to_string(&log).unwrap().contains("Compiling"),
+        "{}",
+        log.display()
+    );
+    let binary = dev_root
+        .join("runner-repo/target/debug")
+        .join(format!("greentic-runner{}", std::env::consts::EXE_SUFFIX));
+    let run = Command::new(&binary).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&run.stdout).trim(), "v1");
+
+    let output = dev_up(&root);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("dev build: runner-repo up to date"),
+        "{stdout}"
+    );
+
+    // Touching a source file makes the binary stale again.
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    write_fixture_repo(&dev_root.join("runner-repo"), "greentic-runner", "v2");
+    let output = dev_up(&root);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("dev build: runner-repo built"), "{stdout}");
+    let run = Command::new(&binary).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&run.stdout).trim(), "v2");
+}
+
+#[test]
+fn dev_up_build_requires_dev_mode() {
+    let temp = tempfile::tempdir().unwrap();
+    let output = dev_up(temp.path());
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("dev up --build needs dev mode"), "{stderr}");
+}
@@ -1,6 +1,9 @@
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
-use greentic_operator::doctor;
+use greentic_operator::doctor::{self, DoctorBackend, DoctorOptions, DoctorScope, DoctorStatus};
+use greentic_operator::doctor_report::DoctorReportFormat;
 use greentic_operator::domains::{self, Domain};
 
 #[test]
@@ -37,3 +40,190 @@ fn doctor_args_include_validator_packs() {
         ]
     );
 }
+
+fn write_pack(path: &Path, pack_id: &str) {
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    zip.start_file("manifest.cbor", zip::write::FileOptions::<()>::default())
+        .unwrap();
+    let manifest = serde_json::json!({
+        "meta": { "pack_id": pack_id, "entry_flows": ["setup_default"] }
+    });
+    zip.write_all(&serde_cbor::to_vec(&manifest).unwrap())
+        .unwrap();
+    zip.finish().unwrap();
+}
+
+/// `good` passes cleanly, `warned` passes with a warning and `broken` fails
+/// with a validation report plus a pack that fails without one.
+fn write_project(root: &Path) {
+    let messaging = root.join("providers").join("messaging");
+    let events = root.join("providers").join("events");
+    std::fs::create_dir_all(&messaging).unwrap();
+    std::fs::create_dir_all(&events).unwrap();
+    write_pack(&messaging.join("good.gtpack"), "good");
+    write_pack(&messaging.join("warned.gtpack"), "warned");
+    std::fs::write(
+        messaging.join("warned.gtpack.doctor.json"),
+        r#"{"pack_id":"warned","diagnostics":[{"severity":"warn","code":"PACK_NO_README","message":"pack has no README"}]}"#,
+    )
+    .unwrap();
+    write_pack(&events.join("broken.gtpack"), "broken");
+    std::fs::write(
+        events.join("broken.gtpack.doctor.json"),
+        r#"{"pack_id":"broken","diagnostics":[{"severity":"error","code":"PACK_MISSING_SCHEMA","message":"config schema missing","path":"components[0]"}]}"#,
+    )
+    .unwrap();
+    std::fs::write(events.join("broken.gtpack.fail"), "validation failed").unwrap();
+    write_pack(&events.join("crashed.gtpack"), "crashed");
+    std::fs::write(
+        events.join("crashed.gtpack.fail"),
+        "error: manifest unreadable\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn doctor_report_covers_every_pack_with_diagnostics() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    write_project(root);
+
+    let report = doctor::run_doctor(
+        root,
+        DoctorScope::All,
+        DoctorOptions {
+            tenant: None,
+            team: None,
+            strict: false,
+            validator_packs: Vec::new(),
+            binaries: Vec::new(),
+        },
+        DoctorBackend::External(&example_bin("fake_greentic_pack")),
+    )
+    .unwrap();
+
+    let runs = report
+        .runs
+        .iter()
+        .map(|run| (run.domain.as_str(), run.pack.as_str(), run.status))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        runs,
+        vec![
+            ("messaging", "good", DoctorStatus::Passed),
+            ("messaging", "warned", DoctorStatus::Passed),
+            ("events", "broken", DoctorStatus::Failed),
+            ("events", "crashed", DoctorStatus::Failed),
+        ]
+    );
+    assert_eq!(report.runs[1].diagnostics[0].code, "PACK_NO_README");
+    assert_eq!(report.runs[2].diagnostics.len(), 1);
+    assert_eq!(report.runs[3].diagnostics[0].code, "PACK_DOCTOR_FAILED");
+    assert_eq!(
+        report.runs[3].diagnostics[0].message,
+        "error: manifest unreadable"
+    );
+    let err = report.ensure_passed().unwrap_err();
+    assert_eq!(err.to_string(), "doctor failed for 2 of 4 pack(s)");
+
+    let junit = report.render(DoctorReportFormat::Junit).unwrap();
+    assert!(junit.contains("<testsuite name=\"events\" tests=\"2\" failures=\"2\""));
+    assert!(junit.contains("<system-out>warning PACK_NO_README: pack has no README</system-out>"));
+
+    let saved = std::fs::read_dir(root.join("state").join("doctor"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path().join("report.json"))
+        .find(|path| path.exists())
+        .expect("report.json written");
+    let saved: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(saved).unwrap()).unwrap();
+    assert_eq!(saved["runs"].as_array().unwrap().len(), 4);
+    assert_eq!(saved["runs"][2]["status"], "failed");
+}
+
+#[test]
+fn dev_doctor_writes_sarif_and_fails_on_broken_packs() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    write_project(root);
+    let output_path = root.join("out").join("doctor.sarif");
+    std::fs::write(
+        root.join("greentic.yaml"),
+        format!(
+            "binaries:\n  greentic-pack: {}\n",
+            example_bin("fake_greentic_pack").display()
+        ),
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_greentic-operator"))
+        .args([
+            "dev",
+            "doctor",
+            "all",
+            "--format",
+            "sarif",
+            "--output",
+            output_path.to_string_lossy().as_ref(),
+            "--project-root",
+            root.to_string_lossy().as_ref(),
+            "--dev-mode",
+            "off",
+        ])
+        .output()
+        .unwrap();
+    assert!(!output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("failed for 2 of 4 pack(s)"), "{stderr}");
+
+    let sarif: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&output_path).unwrap()).unwrap();
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    let levels = results
+        .iter()
+        .map(|result| {
+            (
+                result["ruleId"].as_str().unwrap(),
+                result["level"].as_str().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        levels,
+        vec![
+            ("PACK_NO_README", "warning"),
+            ("PACK_MISSING_SCHEMA", "error"),
+            ("PACK_DOCTOR_FAILED", "error"),
+        ]
+    );
+    assert_eq!(
+        results[1]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "providers/events/broken.gtpack"
+    );
+}
+
+fn binary_name(name: &str) -> String {
+    if cfg!(windows) {
+        format!("{name}.exe")
+    } else {
+        name.to_string()
+    }
+}
+
+fn example_bin(name: &str) -> PathBuf {
+    let mut path = std::env::current_exe().unwrap();
+    path.pop();
+    if path.file_name().and_then(|name| name.to_str()) == Some("deps") {
+        path.pop();
+    }
+    let candidate = path.join("examples").join(binary_name(name));
+    if candidate.exists() {
+        return candidate;
+    }
+    let status = Command::new("cargo")
+        .args(["build", "--example", name])
+        .status()
+        .expect("failed to build example binary");
+    assert!(status.success(), "failed to build example binary");
+    candidate
+}
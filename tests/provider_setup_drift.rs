@@ -0,0 +1,276 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use greentic_operator::config::{DemoConfig, DemoProviderConfig};
+use greentic_operator::providers::{ProviderSetupOptions, run_provider_setup};
+use greentic_operator::secret_value::SecretValue;
+use greentic_operator::secrets_admin;
+use greentic_operator::secrets_gate::{canonical_secret_uri, resolve_secrets_manager};
+
+const PROVIDER: &str = "messaging-telegram";
+
+fn write_bundle(root: &Path) -> DemoConfig {
+    let pack_dir = root.join("provider-packs");
+    std::fs::create_dir_all(&pack_dir).unwrap();
+    let pack_path = pack_dir.join(format!("{PROVIDER}.gtpack"));
+    let mut writer = zip::ZipWriter::new(std::fs::File::create(&pack_path).unwrap());
+    writer
+        .start_file(
+            "assets/setup.yaml",
+            zip::write::FileOptions::<()>::default(),
+        )
+        .unwrap();
+    writer
+        .write_all(
+            b"questions:\n  - name: bot_token\n    secret: true\n    required: true\n  - name: chat_id\n",
+        )
+        .unwrap();
+    writer.finish().unwrap();
+    std::fs::write(
+        root.join("greentic.demo.yaml"),
+        format!(
+            "tenant: demo\nteam: default\nproviders:\n  {PROVIDER}:\n    pack: provider-packs/{PROVIDER}.gtpack\n"
+        ),
+    )
+    .unwrap();
+    DemoConfig {
+        tenant: "demo".to_string(),
+        team: "default".to_string(),
+        services: Default::default(),
+        providers: Some(BTreeMap::from([(
+            PROVIDER.to_string(),
+            DemoProviderConfig {
+                pack: Some(pack_path.to_string_lossy().to_string()),
+                setup_flow: None,
+                verify_flow: None,
+                allowed_cidrs: Vec::new(),
+            },
+        )])),
+    }
+}
+
+fn setup(root: &Path, config: &DemoConfig, setup_input: Option<PathBuf>, verify_webhooks: bool) {
+    let options = ProviderSetupOptions {
+        providers: None,
+        verify_webhooks,
+        force_setup: false,
+        skip_setup: false,
+        skip_secrets_init: true,
+        allow_contract_change: false,
+        backup: false,
+        setup_input,
+        runner_binary: Some(example_bin("fake_runner")),
+        continue_on_error: false,
+        dry_run: false,
+    };
+    run_provider_setup(root, config, None, Some("https://one.test"), options).unwrap();
+}
+
+fn dry_run(root: &Path) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_greentic-operator"))
+        .args([
+            "demo",
+            "start",
+            "--config",
+            root.join("greentic.demo.yaml").to_string_lossy().as_ref(),
+            "--dry-run",
+        ])
+        .env("GREENTIC_ENV", "dev")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn setup_reruns_only_when_fingerprint_changes() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    let config = write_bundle(root);
+    let input = root.join("answers.json");
+    std::fs::write(
+        &input,
+        format!(r#"{{"{PROVIDER}": {{"bot_token": "1:a", "chat_id": "42"}}}}"#),
+    )
+    .unwrap();
+    let setup_record = root
+        .join("state/runtime/demo.default/providers")
+        .join(format!("{PROVIDER}.setup.json"));
+
+    setup(root, &config, Some(input.clone()), false);
+    let first = std::fs::read(&setup_record).unwrap();
+
+    setup(root, &config, None, false);
+    setup(root, &config, Some(input), false);
+    assert_eq!(std::fs::read(&setup_record).unwrap(), first);
+    assert_eq!(dry_run(root), format!("{PROVIDER}: up to date\n"));
+
+    let handle = resolve_secrets_manager(root, "demo", Some("default")).unwrap();
+    let uri = canonical_secret_uri("dev", "demo", Some("default"), PROVIDER, "bot_token");
+    secrets_admin::set_secret(&handle, &uri, &SecretValue::new(b"2:b")).unwrap();
+    assert_eq!(
+        dry_run(root),
+        format!("{PROVIDER}: would run setup_default (secret {uri} changed)\n")
+    );
+    assert_eq!(std::fs::read(&setup_record).unwrap(), first);
+
+    setup(root, &config, None, false);
+    assert_ne!(std::fs::read(&setup_record).unwrap(), first);
+    assert_eq!(dry_run(root), format!("{PROVIDER}: up to date\n"));
+}
+
+#[test]
+fn secret_change_reruns_verify_and_keeps_digests_keyed() {
+    let fingerprints = ["one", "two"].map(|name| {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().join(name);
+        let mut config = write_bundle(&root);
+        if let Some(provider) = config
+            .providers
+            .as_mut()
+            .and_then(|providers| providers.get_mut(PROVIDER))
+        {
+            provider.verify_flow = Some("verify_webhooks".to_string());
+        }
+        let input = root.join("answers.json");
+        std::fs::write(
+            &input,
+            format!(r#"{{"{PROVIDER}": {{"bot_token": "1:a", "chat_id": "42"}}}}"#),
+        )
+        .unwrap();
+        let providers_root = root.join("state/runtime/demo.default/providers");
+        let verify_record = providers_root.join(format!("{PROVIDER}.verify.json"));
+
+        setup(&root, &config, Some(input), true);
+        let verified = std::fs::read(&verify_record).unwrap();
+        let fingerprint =
+            std::fs::read_to_string(providers_root.join(format!("{PROVIDER}.fingerprint.json")))
+                .unwrap();
+
+        let handle = resolve_secrets_manager(&root, "demo", Some("default")).unwrap();
+        let uri = canonical_secret_uri("dev", "demo", Some("default"), PROVIDER, "bot_token");
+        secrets_admin::set_secret(&handle, &uri, &SecretValue::new(b"2:b")).unwrap();
+        setup(&root, &config, None, false);
+        assert_ne!(std::fs::read(&verify_record).unwrap(), verified);
+
+        // The HMAC key lives in the secrets backend, not in listings.
+        let listed = secrets_admin::list_secrets(
+            &handle,
+            &root,
+            &secrets_admin::SecretsScope {
+                env: "dev".to_string(),
+                tenant: "demo".to_string(),
+                team: Some("default".to_string()),
+            },
+            None,
+        )
+        .unwrap();
+        assert!(
+            listed
+                .iter()
+                .all(|listing| listing.provider != "greentic-operator")
+        );
+        let fingerprint: serde_json::Value = serde_json::from_str(&fingerprint).unwrap();
+        fingerprint["secrets"][uri.as_str()]
+            .as_str()
+            .unwrap()
+            .to_string()
+    });
+    // Same secret value, different bundles: the digests do not match.
+    assert_ne!(fingerprints[0], fingerprints[1]);
+}
+
+#[test]
+fn dry_run_never_creates_the_fingerprint_key() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    let config = write_bundle(root);
+    let input = root.join("answers.json");
+    std::fs::write(
+        &input,
+        format!(r#"{{"{PROVIDER}": {{"bot_token": "1:a", "chat_id": "42"}}}}"#),
+    )
+    .unwrap();
+    setup(root, &config, Some(input), false);
+
+    let handle = resolve_secrets_manager(root, "demo", Some("default")).unwrap();
+    let key_uri = canonical_secret_uri(
+        "dev",
+        "demo",
+        Some("default"),
+        "greentic-operator",
+        "setup_fingerprint_key",
+    );
+    secrets_admin::delete_secret(&handle, &key_uri).unwrap();
+    assert_eq!(
+        dry_run(root),
+        format!("{PROVIDER}: would run setup_default (secrets not fingerprinted yet)\n")
+    );
+    assert!(secrets_admin::get_secret(&handle, &key_uri).is_err());
+}
+
+#[test]
+fn env_backend_setup_runs_without_a_fingerprint_key() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    let config = write_bundle(root);
+    let secrets_dir = root.join("providers").join("secrets");
+    std::fs::create_dir_all(&secrets_dir).unwrap();
+    let mut writer =
+        zip::ZipWriter::new(std::fs::File::create(secrets_dir.join("env.gtpack")).unwrap());
+    writer
+        .start_file(
+            "assets/secrets_backend.json",
+            zip::write::FileOptions::<()>::default(),
+        )
+        .unwrap();
+    writer.write_all(br#"{"backend":"env"}"#).unwrap();
+    writer.finish().unwrap();
+    let input = root.join("answers.json");
+    std::fs::write(
+        &input,
+        format!(r#"{{"{PROVIDER}": {{"bot_token": "1:a", "chat_id": "42"}}}}"#),
+    )
+    .unwrap();
+
+    setup(root, &config, Some(input), false);
+    let providers_root = root.join("state/runtime/demo.default/providers");
+    assert!(
+        providers_root
+            .join(format!("{PROVIDER}.setup.json"))
+            .exists()
+    );
+    assert!(
+        providers_root
+            .join(format!("{PROVIDER}.fingerprint.json"))
+            .exists()
+    );
+}
+
+fn binary_name(name: &str) -> String {
+    if cfg!(windows) {
+        format!("{name}.exe")
+    } else {
+        name.to_string()
+    }
+}
+
+fn example_bin(name: &str) -> PathBuf {
+    let mut path = std::env::current_exe().unwrap();
+    path.pop();
+    if path.file_name().and_then(|name| name.to_str()) == Some("deps") {
+        path.pop();
+    }
+    let candidate = path.join("examples").join(binary_name(name));
+    if candidate.exists() {
+        return candidate;
+    }
+    let status = Command::new("cargo")
+        .args(["build", "--example", name])
+        .status()
+        .expect("failed to build example binary");
+    assert!(status.success(), "failed to build example binary");
+    candidate
+}
@@ -1,9 +1,13 @@
 use std::collections::BTreeMap;
+use std::io::Write;
 use std::path::PathBuf;
 use std::process::Command;
 
 use greentic_operator::config::{DemoConfig, DemoProviderConfig};
 use greentic_operator::providers::{ProviderSetupOptions, run_provider_setup};
+use greentic_operator::secrets_admin;
+use greentic_operator::secrets_gate::resolve_secrets_manager;
+use greentic_operator::setup_input::store_secret_answers;
 
 #[test]
 fn provider_setup_writes_outputs() {
@@ -24,6 +28,7 @@ fn provider_setup_writes_outputs() {
                 pack: Some(pack_path.to_string_lossy().to_string()),
                 setup_flow: Some("setup_default".to_string()),
                 verify_flow: Some("verify_webhooks".to_string()),
+                allowed_cidrs: Vec::new(),
             },
         )])),
     };
@@ -39,6 +44,7 @@ fn provider_setup_writes_outputs() {
         setup_input: None,
         runner_binary: Some(fake_bin("fake_runner")),
         continue_on_error: true,
+        dry_run: false,
     };
 
     run_provider_setup(
@@ -80,6 +86,109 @@ fn provider_setup_writes_outputs() {
     );
 }
 
+#[test]
+fn secret_setup_answers_go_to_secrets_manager() {
+    let temp = tempfile::tempdir().unwrap();
+    let config_dir = temp.path();
+    let pack_dir = config_dir.join("provider-packs");
+    std::fs::create_dir_all(&pack_dir).unwrap();
+    let pack_path = pack_dir.join("messaging-telegram.gtpack");
+    let mut writer = zip::ZipWriter::new(std::fs::File::create(&pack_path).unwrap());
+    let options: zip::write::FileOptions<'_, ()> =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    writer.start_file("assets/setup.yaml", options).unwrap();
+    writer
+        .write_all(
+            b"questions:\n  - name: bot_token\n    secret: true\n    required: true\n  - name: chat_id\n",
+        )
+        .unwrap();
+    writer.finish().unwrap();
+    let setup_input = config_dir.join("answers.json");
+    std::fs::write(
+        &setup_input,
+        r#"{"messaging-telegram": {"bot_token": "123:abc", "chat_id": "42"}}"#,
+    )
+    .unwrap();
+
+    let config = DemoConfig {
+        tenant: "demo".to_string(),
+        team: "default".to_string(),
+        services: Default::default(),
+        providers: Some(BTreeMap::from([(
+            "messaging-telegram".to_string(),
+            DemoProviderConfig {
+                pack: Some(pack_path.to_string_lossy().to_string()),
+                setup_flow: None,
+                verify_flow: None,
+                allowed_cidrs: Vec::new(),
+            },
+        )])),
+    };
+    let options = ProviderSetupOptions {
+        providers: None,
+        verify_webhooks: false,
+        force_setup: true,
+        skip_setup: false,
+        skip_secrets_init: true,
+        allow_contract_change: false,
+        backup: false,
+        setup_input: Some(setup_input),
+        runner_binary: Some(fake_bin("fake_runner")),
+        continue_on_error: false,
+        dry_run: false,
+    };
+    run_provider_setup(config_dir, &config, None, None, options).unwrap();
+
+    let answers_path = config_dir
+        .join("state/runtime/demo.default/providers/messaging-telegram/answers/setup.answers.json");
+    let persisted = std::fs::read_to_string(answers_path).unwrap();
+    assert!(persisted.contains("chat_id"));
+    assert!(!persisted.contains("123:abc"));
+
+    let handle = resolve_secrets_manager(config_dir, "demo", Some("default")).unwrap();
+    let stored =
+        secrets_admin::get_secret(&handle, "secrets://dev/demo/_/messaging-telegram/bot_token")
+            .unwrap();
+    assert_eq!(stored.as_bytes(), b"123:abc");
+}
+
+#[test]
+fn secret_setup_answers_are_not_written_to_the_env_backend() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    let pack_path = root.join("messaging-telegram.gtpack");
+    let options: zip::write::FileOptions<'_, ()> = zip::write::FileOptions::default();
+    let mut writer = zip::ZipWriter::new(std::fs::File::create(&pack_path).unwrap());
+    writer.start_file("assets/setup.yaml", options).unwrap();
+    writer
+        .write_all(b"questions:\n  - name: bot_token\n    secret: true\n  - name: chat_id\n")
+        .unwrap();
+    writer.finish().unwrap();
+    let secrets_dir = root.join("providers").join("secrets");
+    std::fs::create_dir_all(&secrets_dir).unwrap();
+    let mut writer =
+        zip::ZipWriter::new(std::fs::File::create(secrets_dir.join("env.gtpack")).unwrap());
+    writer
+        .start_file("assets/secrets_backend.json", options)
+        .unwrap();
+    writer.write_all(br#"{"backend":"env"}"#).unwrap();
+    writer.finish().unwrap();
+
+    let mut answers = serde_json::json!({ "bot_token": "123:abc", "chat_id": "42" });
+    let written = store_secret_answers(
+        root,
+        "dev",
+        "demo",
+        None,
+        &pack_path,
+        "messaging-telegram",
+        &mut answers,
+    )
+    .unwrap();
+    assert!(written.is_empty());
+    assert_eq!(answers, serde_json::json!({ "chat_id": "42" }));
+}
+
 fn fake_bin(name: &str) -> PathBuf {
     if name == "greentic-operator" {
         return PathBuf::from(env!("CARGO_BIN_EXE_greentic-operator"));
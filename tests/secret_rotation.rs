@@ -0,0 +1,259 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use greentic_operator::secret_rotation::{self, RotationOptions, RotationStatus};
+use greentic_operator::secret_value::SecretValue;
+use greentic_operator::secrets_admin::{self, SecretsScope};
+use greentic_operator::secrets_gate::{SecretsManagerHandle, resolve_secrets_manager};
+
+const PROVIDER: &str = "messaging-telegram";
+
+fn scope() -> SecretsScope {
+    SecretsScope {
+        env: "dev".to_string(),
+        tenant: "demo".to_string(),
+        team: None,
+    }
+}
+
+fn write_bundle(bundle: &Path) -> PathBuf {
+    let pack_dir = bundle.join("provider-packs");
+    std::fs::create_dir_all(&pack_dir).unwrap();
+    let pack = pack_dir.join(format!("{PROVIDER}.gtpack"));
+    std::fs::write(&pack, "stub").unwrap();
+    std::fs::write(
+        bundle.join("greentic.demo.yaml"),
+        format!(
+            "tenant: demo\nteam: default\nproviders:\n  {PROVIDER}:\n    pack: provider-packs/{PROVIDER}.gtpack\n    verify_flow: verify_webhooks\n"
+        ),
+    )
+    .unwrap();
+    pack
+}
+
+fn options(grace: chrono::Duration) -> RotationOptions {
+    RotationOptions {
+        grace,
+        runner_binary: Some(example_bin("fake_runner")),
+        dev_settings: None,
+        public_base_url: None,
+    }
+}
+
+fn seed_token(handle: &SecretsManagerHandle, value: &str) -> String {
+    let uri = scope()
+        .resolve_uri(&format!("{PROVIDER}/bot_token"))
+        .unwrap();
+    secrets_admin::set_secret(handle, &uri, &SecretValue::new(value.as_bytes())).unwrap();
+    uri
+}
+
+fn read(handle: &SecretsManagerHandle, uri: &str) -> Option<Vec<u8>> {
+    secrets_admin::get_secret(handle, uri)
+        .ok()
+        .map(|value| value.as_bytes().to_vec())
+}
+
+#[test]
+fn rotation_keeps_previous_value_until_grace_period_ends() {
+    let bundle = tempfile::tempdir().unwrap();
+    write_bundle(bundle.path());
+    let handle = resolve_secrets_manager(bundle.path(), "demo", None).unwrap();
+    let uri = seed_token(&handle, "old-token");
+
+    let record = secret_rotation::rotate_secret(
+        &handle,
+        bundle.path(),
+        &scope(),
+        PROVIDER,
+        "bot_token",
+        &SecretValue::new(b"new-token"),
+        options(chrono::Duration::zero()),
+    )
+    .unwrap();
+    assert_eq!(record.status, RotationStatus::Active);
+    assert_eq!(read(&handle, &uri).unwrap(), b"new-token");
+    assert_eq!(read(&handle, &record.previous_uri).unwrap(), b"old-token");
+
+    let providers_root = bundle.path().join("state/runtime/demo.default/providers");
+    assert!(
+        providers_root
+            .join(format!("{PROVIDER}.setup.json"))
+            .exists()
+    );
+    assert!(
+        providers_root
+            .join(format!("{PROVIDER}.verify.json"))
+            .exists()
+    );
+
+    let pruned = secret_rotation::prune_expired(&handle, bundle.path(), &scope()).unwrap();
+    assert_eq!(pruned, vec![record.previous_uri.clone()]);
+    assert!(read(&handle, &record.previous_uri).is_none());
+    let history = secret_rotation::load_history(bundle.path()).unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].status, RotationStatus::Expired);
+}
+
+#[test]
+fn failed_verification_rolls_back_to_previous_value() {
+    let bundle = tempfile::tempdir().unwrap();
+    let pack = write_bundle(bundle.path());
+    std::fs::write(pack.with_extension("gtpack.fail"), "verify_webhooks\n").unwrap();
+    let handle = resolve_secrets_manager(bundle.path(), "demo", None).unwrap();
+    let uri = seed_token(&handle, "old-token");
+
+    let err = secret_rotation::rotate_secret(
+        &handle,
+        bundle.path(),
+        &scope(),
+        PROVIDER,
+        "bot_token",
+        &SecretValue::new(b"bad-token"),
+        options(chrono::Duration::hours(24)),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("rolled back"), "{err}");
+    assert!(err.to_string().contains("verify_webhooks"), "{err}");
+
+    assert_eq!(read(&handle, &uri).unwrap(), b"old-token");
+    let previous = secret_rotation::previous_uri(&scope(), PROVIDER, "bot_token").unwrap();
+    assert!(read(&handle, &previous).is_none());
+    let history = secret_rotation::load_history(bundle.path()).unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].status, RotationStatus::RolledBack);
+    assert!(history[0].error.is_some());
+}
+
+#[test]
+fn rolled_back_rotation_keeps_the_previous_value_of_an_active_rotation() {
+    let bundle = tempfile::tempdir().unwrap();
+    let pack = write_bundle(bundle.path());
+    let handle = resolve_secrets_manager(bundle.path(), "demo", None).unwrap();
+    let uri = seed_token(&handle, "first-token");
+    let record = secret_rotation::rotate_secret(
+        &handle,
+        bundle.path(),
+        &scope(),
+        PROVIDER,
+        "bot_token",
+        &SecretValue::new(b"second-token"),
+        options(chrono::Duration::hours(24)),
+    )
+    .unwrap();
+
+    std::fs::write(pack.with_extension("gtpack.fail"), "verify_webhooks\n").unwrap();
+    secret_rotation::rotate_secret(
+        &handle,
+        bundle.path(),
+        &scope(),
+        PROVIDER,
+        "bot_token",
+        &SecretValue::new(b"bad-token"),
+        options(chrono::Duration::hours(24)),
+    )
+    .unwrap_err();
+
+    assert_eq!(read(&handle, &uri).unwrap(), b"second-token");
+    assert_eq!(read(&handle, &record.previous_uri).unwrap(), b"first-token");
+    let statuses = secret_rotation::load_history(bundle.path())
+        .unwrap()
+        .into_iter()
+        .map(|record| record.status)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        statuses,
+        vec![RotationStatus::Active, RotationStatus::RolledBack]
+    );
+}
+
+#[test]
+fn writing_secrets_commands_prune_expired_previous_values() {
+    let bundle = tempfile::tempdir().unwrap();
+    write_bundle(bundle.path());
+    let handle = resolve_secrets_manager(bundle.path(), "demo", None).unwrap();
+    seed_token(&handle, "old-token");
+    let record = secret_rotation::rotate_secret(
+        &handle,
+        bundle.path(),
+        &scope(),
+        PROVIDER,
+        "bot_token",
+        &SecretValue::new(b"new-token"),
+        options(chrono::Duration::zero()),
+    )
+    .unwrap();
+    assert_eq!(read(&handle, &record.previous_uri).unwrap(), b"old-token");
+
+    // Another tenant or env leaves this tenant's rotation alone.
+    let other = resolve_secrets_manager(bundle.path(), "acme", None).unwrap();
+    let other_scope = SecretsScope {
+        tenant: "acme".to_string(),
+        ..scope()
+    };
+    assert!(
+        secret_rotation::prune_expired(&other, bundle.path(), &other_scope)
+            .unwrap()
+            .is_empty()
+    );
+    let prod_scope = SecretsScope {
+        env: "prod".to_string(),
+        ..scope()
+    };
+    assert!(
+        secret_rotation::prune_expired(&handle, bundle.path(), &prod_scope)
+            .unwrap()
+            .is_empty()
+    );
+
+    let secrets = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_greentic-operator"))
+            .args(["demo", "secrets"])
+            .args(args)
+            .arg("--bundle")
+            .arg(bundle.path())
+            .args(["--tenant", "demo"])
+            .env("GREENTIC_ENV", "dev")
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+    };
+
+    // Read-only commands never prune.
+    secrets(&["list", "--provider", PROVIDER]);
+    secrets(&["get", &record.uri]);
+    let handle = resolve_secrets_manager(bundle.path(), "demo", None).unwrap();
+    assert_eq!(read(&handle, &record.previous_uri).unwrap(), b"old-token");
+
+    secrets(&["rm", &record.uri]);
+    let handle = resolve_secrets_manager(bundle.path(), "demo", None).unwrap();
+    assert!(read(&handle, &record.previous_uri).is_none());
+    let history = secret_rotation::load_history(bundle.path()).unwrap();
+    assert_eq!(history[0].status, RotationStatus::Expired);
+}
+
+fn binary_name(name: &str) -> String {
+    if cfg!(windows) {
+        format!("{name}.exe")
+    } else {
+        name.to_string()
+    }
+}
+
+fn example_bin(name: &str) -> PathBuf {
+    let mut path = std::env::current_exe().unwrap();
+    path.pop();
+    if path.file_name().and_then(|name| name.to_str()) == Some("deps") {
+        path.pop();
+    }
+    let candidate = path.join("examples").join(binary_name(name));
+    if candidate.exists() {
+        return candidate;
+    }
+    let status = Command::new("cargo")
+        .args(["build", "--example", name])
+        .status()
+        .expect("failed to build example binary");
+    assert!(status.success(), "failed to build example binary");
+    candidate
+}
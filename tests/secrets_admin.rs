@@ -0,0 +1,58 @@
+use greentic_operator::secret_value::SecretValue;
+use greentic_operator::secrets_admin::{self, SecretStatus, SecretsScope};
+use greentic_operator::secrets_gate::resolve_secrets_manager;
+
+fn scope(tenant: &str) -> SecretsScope {
+    SecretsScope {
+        env: "dev".to_string(),
+        tenant: tenant.to_string(),
+        team: None,
+    }
+}
+
+#[test]
+fn set_list_export_and_import_round_trip_through_dev_store() {
+    let bundle = tempfile::tempdir().unwrap();
+    let handle = resolve_secrets_manager(bundle.path(), "demo", None).unwrap();
+    let demo = scope("demo");
+
+    let token = demo.resolve_uri("messaging-telegram/bot_token").unwrap();
+    let redis = demo.resolve_uri("state-redis/password").unwrap();
+    let foreign = scope("other")
+        .resolve_uri("messaging-telegram/bot_token")
+        .unwrap();
+    for (uri, value) in [(&token, "123:abc"), (&redis, "hunter2"), (&foreign, "x")] {
+        secrets_admin::set_secret(&handle, uri, &SecretValue::new(value.as_bytes())).unwrap();
+    }
+
+    let listings = secrets_admin::list_secrets(&handle, bundle.path(), &demo, None).unwrap();
+    let uris = listings.iter().map(|l| l.uri.as_str()).collect::<Vec<_>>();
+    assert_eq!(uris, vec![token.as_str(), redis.as_str()]);
+    assert!(
+        listings.iter().all(
+            |l| matches!(&l.status, SecretStatus::Present(v) if format!("{v}") == "[REDACTED]")
+        )
+    );
+
+    let filtered =
+        secrets_admin::list_secrets(&handle, bundle.path(), &demo, Some("state-redis")).unwrap();
+    let doc = secrets_admin::export_seed_doc(&filtered);
+    assert_eq!(doc.entries.len(), 1);
+    assert_eq!(doc.entries[0].uri, redis);
+
+    secrets_admin::delete_secret(&handle, &redis).unwrap();
+    assert!(secrets_admin::get_secret(&handle, &redis).is_err());
+
+    let mut full = secrets_admin::export_seed_doc(&listings);
+    full.entries.push(doc.entries[0].clone());
+    full.entries[0].uri = foreign.clone();
+    let report = secrets_admin::import_seed_doc(&handle, &demo, &full, None).unwrap();
+    assert_eq!(report.imported, vec![redis.clone(), redis.clone()]);
+    assert_eq!(report.skipped, vec![foreign]);
+    assert_eq!(
+        secrets_admin::get_secret(&handle, &redis)
+            .unwrap()
+            .as_bytes(),
+        b"hunter2"
+    );
+}
@@ -0,0 +1,159 @@
+use greentic_operator::secret_audit::{self, SecretAuditOp, SecretAuditOutcome, SecretAuditQuery};
+use greentic_operator::secret_value::SecretValue;
+use greentic_operator::secrets_admin::{self, SecretsScope};
+use greentic_operator::secrets_gate::resolve_secrets_manager;
+use greentic_secrets_lib::SecretError;
+use tokio::runtime::Builder;
+
+#[test]
+fn secret_accesses_are_audited_without_values() {
+    let bundle = tempfile::tempdir().unwrap();
+    let handle = resolve_secrets_manager(bundle.path(), "demo", None).unwrap();
+    let scope = SecretsScope {
+        env: "dev".to_string(),
+        tenant: "demo".to_string(),
+        team: None,
+    };
+    let token = scope.resolve_uri("messaging-telegram/bot_token").unwrap();
+    let missing = scope
+        .resolve_uri("messaging-telegram/webhook_secret")
+        .unwrap();
+    let foreign = scope.resolve_uri("messaging-slack/bot_token").unwrap();
+    secrets_admin::set_secret(&handle, &token, &SecretValue::new(b"123:super-secret")).unwrap();
+
+    let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+    let manager = handle.runtime_manager(Some("messaging-telegram"), None, Some("corr-1"));
+    runtime.block_on(async {
+        assert!(manager.read(&token).await.is_ok());
+        assert!(manager.read(&missing).await.is_err());
+        assert!(manager.read(&foreign).await.is_err());
+    });
+
+    let contents = std::fs::read_to_string(secret_audit::audit_log_path(bundle.path())).unwrap();
+    assert!(!contents.contains("super-secret"));
+
+    let all = secret_audit::query(bundle.path(), &SecretAuditQuery::default()).unwrap();
+    let summary = all
+        .iter()
+        .map(|entry| {
+            (
+                entry.op,
+                entry.pack_id.as_deref(),
+                entry.uri.as_str(),
+                entry.outcome,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                SecretAuditOp::Write,
+                None,
+                token.as_str(),
+                SecretAuditOutcome::Ok
+            ),
+            (
+                SecretAuditOp::Read,
+                Some("messaging-telegram"),
+                token.as_str(),
+                SecretAuditOutcome::Ok
+            ),
+            (
+                SecretAuditOp::Read,
+                Some("messaging-telegram"),
+                missing.as_str(),
+                SecretAuditOutcome::NotFound
+            ),
+            (
+                SecretAuditOp::Read,
+                Some("messaging-telegram"),
+                foreign.as_str(),
+                SecretAuditOutcome::Denied
+            ),
+        ]
+    );
+    assert!(
+        all.iter()
+            .all(|entry| entry.tenant == "demo" && entry.team == "_")
+    );
+    assert_eq!(all[1].correlation_id.as_deref(), Some("corr-1"));
+
+    let slack = SecretAuditQuery {
+        pack_id: Some("messaging-telegram".to_string()),
+        uri_prefix: Some("secrets://dev/demo/_/messaging-slack/".to_string()),
+        ..Default::default()
+    };
+    let denied = secret_audit::query(bundle.path(), &slack).unwrap();
+    assert_eq!(denied.len(), 1);
+    assert_eq!(denied[0].outcome, SecretAuditOutcome::Denied);
+
+    let future = SecretAuditQuery {
+        since: Some(chrono::Utc::now() + chrono::Duration::minutes(1)),
+        ..Default::default()
+    };
+    assert!(
+        secret_audit::query(bundle.path(), &future)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn runtime_writes_and_deletes_outside_scope_are_denied() {
+    let bundle = tempfile::tempdir().unwrap();
+    let handle = resolve_secrets_manager(bundle.path(), "demo", None).unwrap();
+    let scope = SecretsScope {
+        env: "dev".to_string(),
+        tenant: "demo".to_string(),
+        team: None,
+    };
+    let own = scope.resolve_uri("messaging-telegram/bot_token").unwrap();
+    let foreign = scope.resolve_uri("messaging-slack/bot_token").unwrap();
+    let other_tenant = SecretsScope {
+        tenant: "acme".to_string(),
+        ..scope.clone()
+    }
+    .resolve_uri("messaging-telegram/bot_token")
+    .unwrap();
+    secrets_admin::set_secret(&handle, &foreign, &SecretValue::new(b"slack-token")).unwrap();
+
+    let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+    let manager = handle.runtime_manager(Some("messaging-telegram"), None, None);
+    runtime.block_on(async {
+        assert!(manager.write(&own, b"mine").await.is_ok());
+        assert!(matches!(
+            manager.write(&foreign, b"overwritten").await,
+            Err(SecretError::Permission(_))
+        ));
+        assert!(matches!(
+            manager.write(&other_tenant, b"overwritten").await,
+            Err(SecretError::Permission(_))
+        ));
+        assert!(matches!(
+            manager.delete(&foreign).await,
+            Err(SecretError::Permission(_))
+        ));
+        assert!(manager.delete(&own).await.is_ok());
+    });
+
+    let admin = handle.manager();
+    runtime.block_on(async {
+        assert_eq!(admin.read(&foreign).await.unwrap(), b"slack-token");
+    });
+
+    let ops = secret_audit::query(bundle.path(), &SecretAuditQuery::default())
+        .unwrap()
+        .into_iter()
+        .filter(|entry| entry.outcome == SecretAuditOutcome::Denied)
+        .map(|entry| entry.op)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ops,
+        vec![
+            SecretAuditOp::Write,
+            SecretAuditOp::Write,
+            SecretAuditOp::Delete
+        ]
+    );
+}
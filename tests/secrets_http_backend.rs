@@ -0,0 +1,156 @@
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+
+use greentic_operator::secrets_gate::canonical_secret_uri;
+use greentic_operator::secrets_http::{HttpAuthConfig, HttpBackendConfig, HttpSecretsManager};
+use greentic_secrets_lib::{SecretError, SecretsManager};
+
+struct FakeVault {
+    child: Child,
+    address: String,
+}
+
+impl FakeVault {
+    fn start(envs: &[(&str, &str)]) -> Self {
+        let mut child = Command::new(example_bin("fake_vault_kv"))
+            .envs(envs.iter().copied())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("spawn fake_vault_kv");
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let address = line
+            .trim()
+            .strip_prefix("fake_vault_kv listening on ")
+            .expect("fake_vault_kv banner")
+            .to_string();
+        Self { child, address }
+    }
+
+    fn reads(&self) -> u64 {
+        let body: serde_json::Value =
+            reqwest::blocking::get(format!("{}/_fake/stats", self.address))
+                .unwrap()
+                .json()
+                .unwrap();
+        body["reads"].as_u64().unwrap()
+    }
+}
+
+impl Drop for FakeVault {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn token_auth_round_trips_and_caches_reads() {
+    let vault = FakeVault::start(&[("FAKE_VAULT_TOKEN", "test-token")]);
+    unsafe {
+        std::env::set_var("HTTP_BACKEND_TEST_TOKEN", "test-token");
+    }
+    let manager = HttpSecretsManager::new(HttpBackendConfig {
+        address: Some(vault.address.clone()),
+        path_prefix: Some("greentic".to_string()),
+        auth: HttpAuthConfig::Token {
+            token_env: Some("HTTP_BACKEND_TEST_TOKEN".to_string()),
+        },
+        ..HttpBackendConfig::default()
+    })
+    .unwrap();
+    let uri = canonical_secret_uri("dev", "demo", None, "messaging-telegram", "bot_token");
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        assert!(matches!(
+            manager.read(&uri).await,
+            Err(SecretError::NotFound(_))
+        ));
+        manager.write(&uri, b"123:abc").await.unwrap();
+        assert_eq!(manager.read(&uri).await.unwrap(), b"123:abc");
+        assert_eq!(manager.read(&uri).await.unwrap(), b"123:abc");
+    });
+    assert_eq!(vault.reads(), 1, "second read should come from the cache");
+
+    runtime.block_on(async {
+        manager.delete(&uri).await.unwrap();
+        assert!(matches!(
+            manager.read(&uri).await,
+            Err(SecretError::NotFound(_))
+        ));
+    });
+}
+
+#[test]
+fn approle_login_and_rejected_token() {
+    let vault = FakeVault::start(&[
+        ("FAKE_VAULT_ROLE_ID", "role-1"),
+        ("FAKE_VAULT_SECRET_ID", "secret-1"),
+    ]);
+    unsafe {
+        std::env::set_var("HTTP_BACKEND_TEST_SECRET_ID", "secret-1");
+        std::env::set_var("HTTP_BACKEND_TEST_BAD_TOKEN", "nope");
+    }
+    let manager = HttpSecretsManager::new(HttpBackendConfig {
+        address: Some(vault.address.clone()),
+        cache_ttl_seconds: 0,
+        auth: HttpAuthConfig::Approle {
+            role_id: Some("role-1".to_string()),
+            role_id_env: None,
+            secret_id_env: Some("HTTP_BACKEND_TEST_SECRET_ID".to_string()),
+            mount: "approle".to_string(),
+        },
+        ..HttpBackendConfig::default()
+    })
+    .unwrap();
+    let uri = canonical_secret_uri("dev", "demo", Some("ops"), "state-redis", "password");
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        manager.write(&uri, &[0xff, 0x00, 0x7f]).await.unwrap();
+        assert_eq!(manager.read(&uri).await.unwrap(), vec![0xff, 0x00, 0x7f]);
+    });
+
+    let denied = HttpSecretsManager::new(HttpBackendConfig {
+        address: Some(vault.address.clone()),
+        auth: HttpAuthConfig::Token {
+            token_env: Some("HTTP_BACKEND_TEST_BAD_TOKEN".to_string()),
+        },
+        ..HttpBackendConfig::default()
+    })
+    .unwrap();
+    runtime.block_on(async {
+        assert!(matches!(
+            denied.read(&uri).await,
+            Err(SecretError::Permission(_))
+        ));
+    });
+}
+
+fn binary_name(name: &str) -> String {
+    if cfg!(windows) {
+        format!("{name}.exe")
+    } else {
+        name.to_string()
+    }
+}
+
+fn example_bin(name: &str) -> PathBuf {
+    let mut path = std::env::current_exe().unwrap();
+    path.pop();
+    if path.file_name().and_then(|name| name.to_str()) == Some("deps") {
+        path.pop();
+    }
+    let candidate = path.join("examples").join(binary_name(name));
+    if candidate.exists() {
+        return candidate;
+    }
+    let status = Command::new("cargo")
+        .args(["build", "--example", name])
+        .status()
+        .expect("failed to build example binary");
+    assert!(status.success(), "failed to build example binary");
+    candidate
+}
@@ -16,7 +16,10 @@ use greentic_operator::{
     config::{DemoConfig, DemoProviderConfig},
     dev_store_path,
     providers::{ProviderSetupOptions, run_provider_setup},
-    secrets_gate,
+    secret_placeholders,
+    secret_value::SecretValue,
+    secrets_admin, secrets_gate,
+    secrets_setup::SecretsSetup,
 };
 use greentic_secrets_lib::{SecretsStore, core::seed::DevStore};
 
@@ -108,6 +111,7 @@ fn provider_setup_seeds_and_resolves_secret() -> Result<()> {
                 pack: Some("provider-packs/messaging-telegram.gtpack".to_string()),
                 setup_flow: Some("setup_default".to_string()),
                 verify_flow: None,
+                allowed_cidrs: Vec::new(),
             },
         )])),
         ..Default::default()
@@ -123,6 +127,7 @@ fn provider_setup_seeds_and_resolves_secret() -> Result<()> {
         setup_input: None,
         runner_binary: Some(fake_bin("fake_runner")),
         continue_on_error: false,
+        dry_run: false,
     };
 
     run_provider_setup(&bundle_root, &config, None, None, options)?;
@@ -144,5 +149,166 @@ fn provider_setup_seeds_and_resolves_secret() -> Result<()> {
     let handle = secrets_gate::resolve_secrets_manager(&bundle_root, "demo", Some("default"))?;
     let resolved_value = runtime.block_on(async { handle.manager().read(&uri).await })?;
     assert_eq!(stored_value, resolved_value);
+
+    let status = secrets_gate::provider_secrets_status(
+        &handle.manager(),
+        "dev",
+        "demo",
+        Some("default"),
+        &pack_path,
+        "messaging-telegram",
+        handle.dev_store_path.as_deref(),
+        handle.using_env_fallback,
+    )?;
+    assert!(status.missing.is_empty());
+    assert_eq!(status.placeholders, vec![uri.clone()]);
+    let unresolved = secret_placeholders::unresolved_for_bundle(&bundle_root)?;
+    assert_eq!(
+        unresolved.get("messaging-telegram"),
+        Some(&vec![uri.clone()])
+    );
+
+    secrets_admin::set_secret(&handle, &uri, &SecretValue::new(b"123:abc"))?;
+    assert!(secret_placeholders::unresolved_for_bundle(&bundle_root)?.is_empty());
+    assert!(!secret_placeholders::PlaceholderIndex::path_for_store(&store_path).exists());
+    Ok(())
+}
+
+#[test]
+fn provider_setup_seeds_into_encrypted_backend() -> Result<()> {
+    let bundle_temp = tempdir()?;
+    let bundle_root = bundle_temp.path().to_path_buf();
+    let pack_dir = bundle_root.join("provider-packs");
+    fs::create_dir_all(&pack_dir)?;
+    let pack_path = pack_dir.join("messaging-telegram.gtpack");
+    write_pack_with_secret_requirements(
+        &pack_path,
+        "messaging-telegram",
+        &["setup_default"],
+        &["telegram_bot_token"],
+    )?;
+    let secrets_dir = bundle_root.join("providers").join("secrets");
+    fs::create_dir_all(&secrets_dir)?;
+    fs::write(
+        bundle_root.join("secrets.key"),
+        "correct horse battery staple",
+    )?;
+    let mut zip = ZipWriter::new(File::create(secrets_dir.join("encrypted.gtpack"))?);
+    zip.start_file::<_, ()>("manifest.cbor", FileOptions::default())?;
+    zip.write_all(&serde_cbor::to_vec(
+        &json!({ "meta": { "pack_id": "secrets-encrypted" } }),
+    )?)?;
+    zip.start_file::<_, ()>("assets/secrets_backend.json", FileOptions::default())?;
+    zip.write_all(br#"{"backend":"encrypted-file","key_file":"secrets.key"}"#)?;
+    zip.finish()?;
+
+    let config = DemoConfig {
+        tenant: "demo".to_string(),
+        team: "default".to_string(),
+        providers: Some(BTreeMap::from([(
+            "messaging-telegram".to_string(),
+            DemoProviderConfig {
+                pack: Some("provider-packs/messaging-telegram.gtpack".to_string()),
+                setup_flow: Some("setup_default".to_string()),
+                verify_flow: None,
+                allowed_cidrs: Vec::new(),
+            },
+        )])),
+        ..Default::default()
+    };
+    let options = ProviderSetupOptions {
+        providers: Some(vec!["messaging-telegram".to_string()]),
+        verify_webhooks: false,
+        force_setup: true,
+        skip_setup: false,
+        skip_secrets_init: false,
+        allow_contract_change: false,
+        backup: false,
+        setup_input: None,
+        runner_binary: Some(fake_bin("fake_runner")),
+        continue_on_error: false,
+        dry_run: false,
+    };
+    run_provider_setup(&bundle_root, &config, None, None, options)?;
+
+    // Nothing lands in the plaintext dev store.
+    assert!(!dev_store_path::default_path(&bundle_root).exists());
+    let uri = secrets_gate::canonical_secret_uri(
+        "dev",
+        "demo",
+        Some("default"),
+        "messaging-telegram",
+        "telegram_bot_token",
+    );
+    let handle = secrets_gate::resolve_secrets_manager(&bundle_root, "demo", Some("default"))?;
+    let value = Runtime::new()?.block_on(async { handle.manager().read(&uri).await })?;
+    assert_eq!(value, format!("placeholder for {uri}").into_bytes());
+
+    // Placeholders in other backends are reported and resolved the same way.
+    let unresolved = secret_placeholders::unresolved_for_bundle(&bundle_root)?;
+    assert_eq!(
+        unresolved.get("messaging-telegram"),
+        Some(&vec![uri.clone()])
+    );
+    secrets_admin::set_secret(&handle, &uri, &SecretValue::new(b"123:abc"))?;
+    assert!(secret_placeholders::unresolved_for_bundle(&bundle_root)?.is_empty());
+    assert!(
+        !secret_placeholders::PlaceholderIndex::path_for_store(&handle.placeholder_store_path)
+            .exists()
+    );
+
+    // Stored secrets are listed from the encrypted file.
+    let scope = secrets_admin::SecretsScope {
+        env: "dev".to_string(),
+        tenant: "demo".to_string(),
+        team: None,
+    };
+    let extra = scope.resolve_uri("state-redis/password")?;
+    secrets_admin::set_secret(&handle, &extra, &SecretValue::new(b"hunter2"))?;
+    let listings = secrets_admin::list_secrets(&handle, &bundle_root, &scope, None)?;
+    let listed = listings
+        .iter()
+        .map(|listing| listing.uri.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(listed, vec![uri.as_str(), extra.as_str()]);
+    Ok(())
+}
+
+#[test]
+fn env_backend_reports_missing_secrets_instead_of_seeding() -> Result<()> {
+    let bundle_temp = tempdir()?;
+    let bundle_root = bundle_temp.path().to_path_buf();
+    let pack_path = bundle_root.join("messaging-telegram.gtpack");
+    write_pack_with_secret_requirements(
+        &pack_path,
+        "messaging-telegram",
+        &["setup_default"],
+        &["telegram_bot_token"],
+    )?;
+    let secrets_dir = bundle_root.join("providers").join("secrets");
+    fs::create_dir_all(&secrets_dir)?;
+    let mut zip = ZipWriter::new(File::create(secrets_dir.join("env.gtpack"))?);
+    zip.start_file::<_, ()>("assets/secrets_backend.json", FileOptions::default())?;
+    zip.write_all(br#"{"backend":"env"}"#)?;
+    zip.finish()?;
+
+    let setup = SecretsSetup::new(&bundle_root, "dev", "demo", Some("default"))?;
+    Runtime::new()?.block_on(setup.ensure_pack_secrets(&pack_path, "messaging-telegram"))?;
+
+    assert!(!dev_store_path::default_path(&bundle_root).exists());
+    assert!(secret_placeholders::unresolved_for_bundle(&bundle_root)?.is_empty());
+    let handle = secrets_gate::resolve_secrets_manager(&bundle_root, "demo", Some("default"))?;
+    assert!(handle.is_read_only());
+    let status = secrets_gate::provider_secrets_status(
+        &handle.manager(),
+        "dev",
+        "demo",
+        Some("default"),
+        &pack_path,
+        "messaging-telegram",
+        handle.dev_store_path.as_deref(),
+        handle.using_env_fallback,
+    )?;
+    assert_eq!(status.missing.len(), 1, "{status:?}");
     Ok(())
 }
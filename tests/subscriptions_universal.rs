@@ -11,6 +11,7 @@ use greentic_operator::demo::runner_host::{FlowOutcome, OperatorContext, RunnerE
 use greentic_operator::messaging_universal::dto::{HttpInV1, HttpOutV1};
 use greentic_operator::messaging_universal::ingress::build_ingress_request;
 use greentic_operator::subscriptions_universal::{
+    reconcile::{DesiredBinding, ReconcileAction, apply_reconcile, plan_reconcile},
     scheduler::Scheduler,
     service::{ProviderRunner, SubscriptionEnsureRequest, SubscriptionService},
     store::{AuthUserRefV1, SubscriptionState, SubscriptionStore},
@@ -250,6 +251,11 @@ fn scheduler_renews_due_subscription() -> Result<()> {
         subscription_id: Some("orig".to_string()),
         expiration_unix_ms: Some(Utc::now().timestamp_millis() - 1_000),
         last_error: None,
+        consecutive_failures: 0,
+        last_attempt_unix_ms: None,
+        next_attempt_unix_ms: None,
+        expired: false,
+        managed: false,
     };
     store.write_state(&state)?;
 
@@ -298,6 +304,11 @@ fn scheduler_delete_removes_state() -> Result<()> {
         subscription_id: Some("to-delete".to_string()),
         expiration_unix_ms: Some(Utc::now().timestamp_millis() + 60_000),
         last_error: None,
+        consecutive_failures: 0,
+        last_attempt_unix_ms: None,
+        next_attempt_unix_ms: None,
+        expired: false,
+        managed: false,
     };
     store.write_state(&state)?;
 
@@ -401,3 +412,240 @@ fn stub_runner_sees_ingress_binding_id() -> Result<()> {
     assert_eq!(captured.tenant_hint.as_deref(), Some("demo"));
     Ok(())
 }
+
+#[derive(Clone)]
+struct FailingRunner;
+
+impl ProviderRunner for FailingRunner {
+    fn invoke(
+        &self,
+        _provider: &str,
+        _op: &str,
+        _payload: &[u8],
+        _context: &OperatorContext,
+    ) -> Result<FlowOutcome> {
+        Ok(FlowOutcome {
+            success: false,
+            output: None,
+            raw: None,
+            error: Some("token revoked".to_string()),
+            mode: RunnerExecutionMode::Exec,
+        })
+    }
+}
+
+#[test]
+fn failing_renewal_backs_off_and_marks_expired() -> Result<()> {
+    let temporary = tempdir()?;
+    let store = SubscriptionStore::new(temporary.path());
+    let state = SubscriptionState {
+        binding_id: "bind-789".to_string(),
+        provider: "messaging.email".to_string(),
+        tenant: "demo".to_string(),
+        team: Some("default".to_string()),
+        resource: None,
+        change_types: vec!["created".to_string()],
+        notification_url: None,
+        client_state: None,
+        user: None,
+        subscription_id: Some("sub-789".to_string()),
+        expiration_unix_ms: Some(Utc::now().timestamp_millis() - 1_000),
+        last_error: None,
+        consecutive_failures: 0,
+        last_attempt_unix_ms: None,
+        next_attempt_unix_ms: None,
+        expired: false,
+        managed: false,
+    };
+    store.write_state(&state)?;
+
+    let service = SubscriptionService::new(
+        FailingRunner,
+        OperatorContext {
+            tenant: "demo".to_string(),
+            team: Some("default".to_string()),
+            correlation_id: None,
+        },
+    );
+    let scheduler = Scheduler::new(service, store.clone());
+    let first = scheduler.renew_due(Duration::from_secs(0))?;
+    assert_eq!((first.failed, first.expired, first.deferred), (1, 1, 0));
+
+    let failed = store
+        .read_state("messaging.email", "demo", Some("default"), "bind-789")?
+        .expect("state missing");
+    assert_eq!(failed.consecutive_failures, 1);
+    assert!(failed.expired);
+    assert_eq!(failed.last_error.as_deref(), Some("token revoked"));
+    assert!(failed.next_attempt_unix_ms.unwrap() > failed.last_attempt_unix_ms.unwrap());
+
+    let second = scheduler.renew_due(Duration::from_secs(0))?;
+    assert_eq!((second.failed, second.deferred), (0, 1));
+    Ok(())
+}
+
+fn stored_binding(binding_id: &str, resource: &str, managed: bool) -> SubscriptionState {
+    SubscriptionState {
+        binding_id: binding_id.to_string(),
+        provider: "messaging.email".to_string(),
+        tenant: "demo".to_string(),
+        team: Some("default".to_string()),
+        resource: Some(resource.to_string()),
+        change_types: vec!["created".to_string()],
+        notification_url: None,
+        client_state: None,
+        user: None,
+        subscription_id: Some(format!("sub-{binding_id}")),
+        expiration_unix_ms: None,
+        last_error: None,
+        consecutive_failures: 0,
+        last_attempt_unix_ms: None,
+        next_attempt_unix_ms: None,
+        expired: false,
+        managed,
+    }
+}
+
+fn desired_binding(
+    binding_id: Option<&str>,
+    resource: &str,
+    change_types: &[&str],
+) -> DesiredBinding {
+    DesiredBinding {
+        provider: "messaging.email".to_string(),
+        explicit_binding_id: binding_id.is_some(),
+        request: SubscriptionEnsureRequest {
+            binding_id: binding_id.unwrap_or("generated").to_string(),
+            resource: Some(resource.to_string()),
+            change_types: change_types.iter().map(|value| value.to_string()).collect(),
+            notification_url: Some("https://example.com/notify".to_string()),
+            client_state: None,
+            user: None,
+            expiration_target_unix_ms: None,
+        },
+    }
+}
+
+#[test]
+fn reconcile_plan_creates_updates_and_prunes() {
+    let current = vec![
+        stored_binding("inbox", "/me/inbox", true),
+        stored_binding("sent", "/me/sent", true),
+        stored_binding("orphan", "/me/drafts", true),
+        stored_binding("manual", "/me/archive", false),
+    ];
+    let desired = vec![
+        desired_binding(None, "/me/inbox", &["created"]),
+        desired_binding(Some("sent"), "/me/sent", &["created", "deleted"]),
+        desired_binding(None, "/me/calendar", &["created"]),
+    ];
+
+    let actions = plan_reconcile(&desired, &current);
+    let rendered = actions.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(actions.len(), 3, "{rendered:?}");
+    assert!(matches!(
+        &actions[0],
+        ReconcileAction::Update { current, .. } if current.binding_id == "sent"
+    ));
+    assert!(matches!(
+        &actions[1],
+        ReconcileAction::Create(desired) if desired.request.resource.as_deref() == Some("/me/calendar")
+    ));
+    assert!(matches!(
+        &actions[2],
+        ReconcileAction::Delete(state) if state.binding_id == "orphan"
+    ));
+}
+
+#[test]
+fn reconcile_apply_replaces_changed_binding() -> Result<()> {
+    let temporary = tempdir()?;
+    let store = SubscriptionStore::new(temporary.path());
+    store.write_state(&stored_binding("inbox", "/me/inbox", true))?;
+    store.write_state(&stored_binding("orphan", "/me/drafts", true))?;
+
+    let runner = FakeRunner::with_response(json!({
+        "subscription": {
+            "subscription_id": "sub-new",
+            "expiration_unix_ms": 1_700_000_000_000i64,
+        }
+    }));
+    let service = SubscriptionService::new(
+        runner.clone(),
+        OperatorContext {
+            tenant: "demo".to_string(),
+            team: Some("default".to_string()),
+            correlation_id: None,
+        },
+    );
+    let scheduler = Scheduler::new(service, store.clone());
+    let desired = vec![desired_binding(Some("inbox"), "/me/focused", &["created"])];
+    let actions = plan_reconcile(&desired, &store.list_states()?);
+    apply_reconcile(&scheduler, &actions)?;
+
+    assert_eq!(
+        runner.calls(),
+        vec![
+            "messaging.email:subscription_delete".to_string(),
+            "messaging.email:subscription_ensure".to_string(),
+            "messaging.email:subscription_delete".to_string(),
+        ]
+    );
+    let states = store.list_states()?;
+    assert_eq!(states.len(), 1);
+    assert_eq!(states[0].binding_id, "inbox");
+    assert_eq!(states[0].resource.as_deref(), Some("/me/focused"));
+    assert!(states[0].managed);
+    Ok(())
+}
+
+#[test]
+fn reconcile_adopts_bindings_from_before_upgrade() -> Result<()> {
+    let temporary = tempdir()?;
+    let store = SubscriptionStore::new(temporary.path());
+    // Bindings written before reconcile existed are all unmanaged.
+    store.write_state(&stored_binding("legacy-inbox", "/me/inbox", false))?;
+    store.write_state(&stored_binding("legacy-sent", "/me/sent", false))?;
+
+    let runner = FakeRunner::with_response(json!({
+        "subscription": {
+            "subscription_id": "sub-new",
+            "expiration_unix_ms": 1_700_000_000_000i64,
+        }
+    }));
+    let service = SubscriptionService::new(
+        runner.clone(),
+        OperatorContext {
+            tenant: "demo".to_string(),
+            team: Some("default".to_string()),
+            correlation_id: None,
+        },
+    );
+    let scheduler = Scheduler::new(service, store.clone());
+    let desired = vec![desired_binding(None, "/me/inbox", &["created"])];
+    let actions = plan_reconcile(&desired, &store.list_states()?);
+    assert_eq!(actions.len(), 1);
+    assert!(matches!(
+        &actions[0],
+        ReconcileAction::Adopt(state) if state.binding_id == "legacy-inbox"
+    ));
+    apply_reconcile(&scheduler, &actions)?;
+    assert!(runner.calls().is_empty());
+
+    let states = store.list_states()?;
+    assert_eq!(states.len(), 2);
+    let inbox = states
+        .iter()
+        .find(|state| state.binding_id == "legacy-inbox")
+        .unwrap();
+    assert!(inbox.managed);
+
+    // Once adopted, the binding is pruned when config drops it.
+    let actions = plan_reconcile(&[], &store.list_states()?);
+    assert_eq!(actions.len(), 1);
+    assert!(matches!(
+        &actions[0],
+        ReconcileAction::Delete(state) if state.binding_id == "legacy-inbox"
+    ));
+    Ok(())
+}
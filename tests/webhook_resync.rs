@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use greentic_operator::config::{DemoConfig, DemoProviderConfig};
+use greentic_operator::providers::{
+    ProviderSetupOptions, WebhookMove, detect_webhook_move, recorded_public_base_url,
+    run_provider_setup,
+};
+use greentic_operator::runtime_state::RuntimePaths;
+use greentic_operator::tunnel::{self, TunnelWatch};
+use greentic_operator::webhook_sync::{WebhookSync, WebhookSyncConfig};
+
+fn write_status(root: &std::path::Path, provider: &str, url: Option<&str>) {
+    let mut status = serde_json::json!({
+        "provider": provider,
+        "setup_path": root.join(format!("{provider}.setup.json")),
+        "updated_at": "2026-01-01T00:00:00Z",
+    });
+    if let Some(url) = url {
+        status["public_base_url"] = serde_json::Value::String(url.to_string());
+    }
+    std::fs::create_dir_all(root).unwrap();
+    std::fs::write(
+        root.join(format!("{provider}.status.json")),
+        serde_json::to_vec_pretty(&status).unwrap(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn detects_webhook_registered_against_previous_url() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path().join("providers");
+    write_status(
+        &root,
+        "messaging-telegram",
+        Some("https://old.trycloudflare.com"),
+    );
+
+    let moved = detect_webhook_move(&root, "messaging-telegram", "https://new.trycloudflare.com");
+    assert_eq!(
+        moved,
+        Some(WebhookMove {
+            provider: "messaging-telegram".to_string(),
+            previous: "https://old.trycloudflare.com".to_string(),
+            current: "https://new.trycloudflare.com".to_string(),
+        })
+    );
+}
+
+#[test]
+fn unchanged_or_unrecorded_urls_are_not_moves() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path().join("providers");
+    write_status(&root, "messaging-slack", Some("https://same.example.com/"));
+    write_status(&root, "messaging-webchat", None);
+
+    assert!(detect_webhook_move(&root, "messaging-slack", "https://same.example.com").is_none());
+    assert!(detect_webhook_move(&root, "messaging-webchat", "https://new.example.com").is_none());
+    assert!(detect_webhook_move(&root, "messaging-teams", "https://new.example.com").is_none());
+    assert_eq!(recorded_public_base_url(&root, "messaging-webchat"), None);
+}
+
+#[test]
+fn moved_tunnel_url_reruns_provider_setup() {
+    let temp = tempfile::tempdir().unwrap();
+    let config_dir = temp.path();
+    let pack_path = config_dir.join("provider-packs").join("msgraph.gtpack");
+    std::fs::create_dir_all(pack_path.parent().unwrap()).unwrap();
+    std::fs::write(&pack_path, "stub").unwrap();
+    let config = DemoConfig {
+        tenant: "demo".to_string(),
+        team: "default".to_string(),
+        services: Default::default(),
+        providers: Some(BTreeMap::from([(
+            "msgraph".to_string(),
+            DemoProviderConfig {
+                pack: Some(pack_path.to_string_lossy().to_string()),
+                setup_flow: Some("setup_default".to_string()),
+                verify_flow: None,
+                allowed_cidrs: Vec::new(),
+            },
+        )])),
+    };
+    let options = ProviderSetupOptions {
+        providers: None,
+        verify_webhooks: false,
+        force_setup: true,
+        skip_setup: false,
+        skip_secrets_init: true,
+        allow_contract_change: false,
+        backup: false,
+        setup_input: None,
+        runner_binary: Some(example_bin("fake_runner")),
+        continue_on_error: true,
+        dry_run: false,
+    };
+    let old_url = "https://old.trycloudflare.com";
+    run_provider_setup(config_dir, &config, None, Some(old_url), options.clone()).unwrap();
+    let paths = RuntimePaths::new(config_dir.join("state"), "demo", "default");
+    let providers_root = paths.runtime_root().join("providers");
+    assert_eq!(
+        recorded_public_base_url(&providers_root, "msgraph").as_deref(),
+        Some(old_url)
+    );
+
+    // The quick tunnel reconnects and prints a new URL to its log.
+    let log_path = config_dir.join("logs").join("cloudflared.log");
+    std::fs::create_dir_all(log_path.parent().unwrap()).unwrap();
+    std::fs::write(&log_path, format!("ready {old_url}\n")).unwrap();
+    std::fs::write(tunnel::public_url_path(&paths), old_url).unwrap();
+    let sync = WebhookSync::start(WebhookSyncConfig {
+        config_dir: config_dir.to_path_buf(),
+        config,
+        dev_settings: None,
+        provider_options: options,
+        poll_interval: Duration::from_millis(50),
+        tunnel: Some(TunnelWatch {
+            log_path: log_path.clone(),
+            url_suffix: Some(".trycloudflare.com".to_string()),
+        }),
+    })
+    .unwrap();
+    let new_url = "https://new.trycloudflare.com";
+    std::fs::write(
+        &log_path,
+        format!("ready {old_url}\nreconnected {new_url}\n"),
+    )
+    .unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while recorded_public_base_url(&providers_root, "msgraph").as_deref() != Some(new_url) {
+        assert!(Instant::now() < deadline, "provider setup was not re-run");
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    sync.stop().unwrap();
+    assert_eq!(
+        tunnel::read_public_url(&paths).unwrap().as_deref(),
+        Some(new_url)
+    );
+}
+
+fn example_bin(name: &str) -> PathBuf {
+    let mut path = std::env::current_exe().unwrap();
+    path.pop();
+    if path.file_name().and_then(|name| name.to_str()) == Some("deps") {
+        path.pop();
+    }
+    let name = if cfg!(windows) {
+        format!("{name}.exe")
+    } else {
+        name.to_string()
+    };
+    let candidate = path.join("examples").join(&name);
+    if !candidate.exists() {
+        let status = Command::new("cargo")
+            .args(["build", "--example", name.trim_end_matches(".exe")])
+            .status()
+            .expect("failed to build example binary");
+        assert!(status.success(), "failed to build example binary");
+    }
+    candidate
+}